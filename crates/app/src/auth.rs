@@ -1,5 +1,5 @@
 use dioxus::prelude::*;
-use shared_types::AuthUser;
+use shared_types::{AuthUser, Role, User, UserTier, TOKEN_REFRESHED_HEADER};
 
 /// Global authentication state.
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -25,6 +25,32 @@ impl AuthState {
     pub fn clear_auth(&mut self) {
         self.current_user.set(None);
     }
+
+    /// Apply an edited `User` record to the session if it is the signed-in
+    /// user, so the sidebar tier badge and tier gates update without waiting
+    /// for the next auth refresh.
+    pub fn sync_user(&mut self, updated: &User) {
+        let synced = self
+            .current_user
+            .read()
+            .as_ref()
+            .and_then(|current| apply_user_update(current, updated));
+        if let Some(user) = synced {
+            self.current_user.set(Some(user));
+        }
+    }
+}
+
+/// The signed-in user with the fields of `updated` applied, or `None` when
+/// `updated` is a different user.
+fn apply_user_update(current: &AuthUser, updated: &User) -> Option<AuthUser> {
+    (current.id == updated.id).then(|| AuthUser {
+        username: updated.username.clone(),
+        display_name: updated.display_name.clone(),
+        role: Role::from_str_or_default(&updated.role),
+        tier: UserTier::from_str_or_default(&updated.tier),
+        ..current.clone()
+    })
 }
 
 /// Hook to access auth state.
@@ -36,7 +62,7 @@ pub fn use_auth() -> AuthState {
 pub fn use_is_admin() -> bool {
     let auth = use_auth();
     let binding = auth.current_user.read();
-    let is_admin = binding.as_ref().map(|u| u.role == "admin").unwrap_or(false);
+    let is_admin = binding.as_ref().map(|u| u.role.is_admin()).unwrap_or(false);
     is_admin
 }
 
@@ -57,3 +83,106 @@ pub fn use_auth_init() {
         }
     });
 }
+
+/// Script that wraps `window.fetch` and notifies Dioxus whenever a response
+/// carries the token-refreshed header. The wrapper is installed once per page.
+fn token_refresh_interceptor_script() -> String {
+    format!(
+        r#"
+        (function() {{
+            if (!window.__tokenRefreshHooked) {{
+                window.__tokenRefreshHooked = true;
+                var originalFetch = window.fetch;
+                window.fetch = function() {{
+                    return originalFetch.apply(this, arguments).then(function(resp) {{
+                        if (resp.headers.get('{TOKEN_REFRESHED_HEADER}')) {{
+                            window.dispatchEvent(new Event('token-refreshed'));
+                        }}
+                        return resp;
+                    }});
+                }};
+            }}
+            window.addEventListener('token-refreshed', function() {{
+                dioxus.send(true);
+            }});
+        }})();
+        "#
+    )
+}
+
+/// Keeps `AuthState` in sync with transparent server-side token refreshes.
+///
+/// When the auth middleware rotates tokens mid-request it tags the response
+/// with `X-Token-Refreshed`. A fetch interceptor forwards that signal here and
+/// the current user is re-fetched so role/tier changes baked into the new
+/// tokens show up on the client. Call this once in the root `App` component.
+pub fn use_token_refresh_sync() {
+    let mut auth = use_auth();
+
+    use_effect(move || {
+        spawn(async move {
+            let mut eval = document::eval(&token_refresh_interceptor_script());
+            while eval.recv::<bool>().await.is_ok() {
+                if let Ok(user) = server::api::get_current_user().await {
+                    auth.current_user.set(user);
+                }
+            }
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auth_user() -> AuthUser {
+        AuthUser {
+            id: 7,
+            username: "neo".to_string(),
+            display_name: "Neo".to_string(),
+            email: "neo@example.com".to_string(),
+            role: Role::Admin,
+            tier: UserTier::Free,
+            avatar_url: Some("avatars/7/original".to_string()),
+            thumbnail_url: None,
+            expires_at: Some(1_700_000_000),
+            email_verified: Some(false),
+        }
+    }
+
+    fn user(id: i64, tier: &str) -> User {
+        User {
+            id,
+            username: "neo".to_string(),
+            display_name: "Thomas Anderson".to_string(),
+            role: "admin".to_string(),
+            tier: tier.to_string(),
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn tier_update_for_current_user_updates_badge_source() {
+        let synced = apply_user_update(&auth_user(), &user(7, "elite")).unwrap();
+        assert_eq!(synced.tier, UserTier::Elite);
+        assert_eq!(synced.display_name, "Thomas Anderson");
+        // Fields `User` doesn't carry are kept
+        assert_eq!(synced.email, "neo@example.com");
+        assert_eq!(synced.avatar_url.as_deref(), Some("avatars/7/original"));
+        assert_eq!(synced.expires_at, Some(1_700_000_000));
+        assert_eq!(synced.email_verified, Some(false));
+    }
+
+    #[test]
+    fn update_for_other_user_is_ignored() {
+        assert!(apply_user_update(&auth_user(), &user(8, "elite")).is_none());
+    }
+
+    #[test]
+    fn interceptor_watches_token_refreshed_header() {
+        let script = token_refresh_interceptor_script();
+        assert!(script.contains("resp.headers.get('x-token-refreshed')"));
+        assert!(script.contains("dioxus.send(true)"));
+    }
+}
@@ -0,0 +1,86 @@
+use dioxus::prelude::*;
+use shared_types::{AppError, AppErrorKind};
+use shared_ui::{consume_toast, ToastOptions};
+
+/// The [`AppError`] carried by a server function error.
+///
+/// Errors that don't carry one (network failures, panics on the server) come
+/// back as an internal error with a generic message.
+pub fn parse_error(err: &ServerFnError) -> AppError {
+    let raw = err.to_string();
+    AppError::from_server_error(&raw)
+        .unwrap_or_else(|| AppError::internal(AppError::friendly_message(&raw)))
+}
+
+/// What to tell the user about `error`.
+///
+/// Validation errors carry a generic message, so the first field error (by
+/// field name) is shown instead when there is one.
+pub fn user_message(error: &AppError) -> String {
+    if error.kind == AppErrorKind::ValidationError {
+        if let Some((_, message)) = error.field_errors.iter().min_by_key(|(field, _)| *field) {
+            return message.clone();
+        }
+    }
+    error.message.clone()
+}
+
+/// Show a server function error as a toast and log the details.
+///
+/// Returns the parsed error so callers can still act on it, e.g. to mark
+/// form fields.
+pub fn report_error(err: &ServerFnError) -> AppError {
+    report(None, err)
+}
+
+/// [`report_error`], prefixing the toast with what was being attempted,
+/// e.g. `"Failed to delete user 4"`.
+pub fn report_error_in(action: &str, err: &ServerFnError) -> AppError {
+    report(Some(action), err)
+}
+
+fn report(action: Option<&str>, err: &ServerFnError) -> AppError {
+    let error = parse_error(err);
+    dioxus::logger::tracing::warn!(kind = ?error.kind, error = %err, "server function failed");
+
+    let message = user_message(&error);
+    let message = match action {
+        Some(action) => format!("{action}: {message}"),
+        None => message,
+    };
+    consume_toast().error(message, ToastOptions::new());
+    error
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validation_error_shows_the_field_message() {
+        let err = ServerFnError::new(
+            r#"{"kind":"ValidationError","message":"Validation failed","field_errors":{"username":"Username is required","display_name":"Display name is too long"}}"#,
+        );
+        let error = parse_error(&err);
+        assert_eq!(error.kind, AppErrorKind::ValidationError);
+        assert_eq!(user_message(&error), "Display name is too long");
+    }
+
+    #[test]
+    fn app_error_message_is_shown_without_the_wrapper() {
+        let err = ServerFnError::new(r#"{"kind":"NotFound","message":"User not found"}"#);
+        let message = user_message(&parse_error(&err));
+        assert_eq!(message, "User not found");
+    }
+
+    #[test]
+    fn unstructured_errors_get_a_generic_message() {
+        let err = ServerFnError::new("connection reset by peer");
+        let error = parse_error(&err);
+        assert_eq!(error.kind, AppErrorKind::InternalError);
+        assert_eq!(
+            user_message(&error),
+            "Something went wrong. Please try again."
+        );
+    }
+}
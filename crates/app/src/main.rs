@@ -1,8 +1,15 @@
 use dioxus::prelude::*;
 
 mod auth;
+mod errors;
+mod request_id;
+mod retry;
 mod routes;
+mod seo;
+mod shortcuts;
+mod theme_sync;
 pub mod tier_gate;
+mod unsaved_guard;
 use auth::{use_auth, AuthState};
 use routes::Route;
 
@@ -19,33 +26,70 @@ const CYBERPUNK_THEME: Asset = asset!("/assets/cyberpunk-theme.css");
 
 fn main() {
     #[cfg(feature = "server")]
-    dioxus::serve(|| async move {
-        server::telemetry::init_telemetry();
-        server::health::record_start_time();
-
-        let pool = server::db::create_pool();
-        server::db::run_migrations(&pool).await;
-        server::s3::ensure_bucket().await;
-        let state = server::db::AppState { pool: pool.clone() };
-
-        let router = dioxus::server::router(App)
-            .merge(server::openapi::api_router(pool))
-            .layer(server::telemetry::OtelTraceLayer)
-            .layer(axum::middleware::from_fn_with_state(
-                state,
-                server::auth::middleware::auth_middleware,
-            ))
-            .layer(tower_http::request_id::PropagateRequestIdLayer::x_request_id())
-            .layer(tower_http::request_id::SetRequestIdLayer::x_request_id(
-                tower_http::request_id::MakeRequestUuid,
-            ));
-        Ok(router)
-    });
+    {
+        server::logging::init_logging();
+
+        // Debug builds serve through dioxus for hot-patching, which doesn't
+        // record connection addresses, so client IPs are unknown in development.
+        if cfg!(debug_assertions) {
+            dioxus::serve(|| async { Ok(server_router().await) });
+        }
+        server::client_ip::run_with_peer_addrs(
+            dioxus::cli_config::fullstack_address_or_localhost(),
+            server_router(),
+        );
+    }
 
     #[cfg(not(feature = "server"))]
     dioxus::launch(App);
 }
 
+/// The full server: the Dioxus app, the REST API and the middleware stack.
+#[cfg(feature = "server")]
+async fn server_router() -> axum::Router {
+    server::telemetry::init_telemetry();
+    server::health::record_start_time();
+    server::auth::cookies::cookie_config();
+    server::client_ip::trusted_proxies();
+
+    let pool = server::db::create_pool();
+    server::db::run_migrations(&pool).await;
+    server::s3::ensure_bucket().await;
+    server::auth::oauth_state::spawn_state_sweeper();
+    server::auth::deletion::spawn_purger(pool.clone());
+    let state = server::db::AppState::new(pool.clone());
+    let drain = server::shutdown::Drain::new();
+    server::shutdown::spawn_shutdown_handler(drain.clone(), pool.clone());
+
+    dioxus::server::router(App)
+        .merge(server::openapi::api_router(pool))
+        .layer(axum::middleware::from_fn_with_state(
+            server::security_headers::SecurityHeadersConfig::from_env(),
+            server::security_headers::security_headers,
+        ))
+        .layer(server::compression::compression_layer())
+        .layer(server::telemetry::OtelTraceLayer)
+        .layer(axum::middleware::from_fn_with_state(
+            state,
+            server::auth::middleware::auth_middleware,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            server::load_shed::ConcurrencyLimit::from_env(),
+            server::load_shed::shed_load,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            drain,
+            server::shutdown::track_requests,
+        ))
+        .layer(tower_http::request_id::PropagateRequestIdLayer::x_request_id())
+        .layer(tower_http::request_id::SetRequestIdLayer::x_request_id(
+            tower_http::request_id::MakeRequestUuid,
+        ))
+        .layer(axum::middleware::from_fn(
+            server::request_id::sanitize_request_id,
+        ))
+}
+
 /// Detect the client platform from compile-time feature flags.
 fn client_platform() -> &'static str {
     if cfg!(feature = "web") {
@@ -72,9 +116,12 @@ fn App() -> Element {
         );
         set_request_headers(headers);
     });
+    request_id::use_request_ids(client_platform());
 
     use_context_provider(AuthState::new);
     auth::use_auth_init();
+    auth::use_token_refresh_sync();
+    theme_sync::use_theme_sync();
 
     // Derive profile state from auth — updates when user logs in/out
     let auth = use_auth();
@@ -92,12 +139,19 @@ fn App() -> Element {
             .map(|u| u.email.clone())
             .unwrap_or_else(|| "guest@cyberapp.io".to_string())
     });
-    let avatar_url = use_memo(move || {
+    // `AuthUser` holds S3 object keys; resolve the thumbnail (or original)
+    // to a short-lived signed URL whenever the key changes.
+    let avatar_key = use_memo(move || {
         auth.current_user
             .read()
             .as_ref()
-            .and_then(|u| u.avatar_url.clone())
+            .and_then(|u| u.thumbnail_url.clone().or_else(|| u.avatar_url.clone()))
     });
+    let signed_avatar = use_resource(move || async move {
+        avatar_key()?;
+        server::api::get_avatar_url().await.ok().flatten()
+    });
+    let avatar_url = use_memo(move || signed_avatar.read().clone().flatten());
 
     use_context_provider(|| ProfileState {
         display_name,
@@ -105,10 +159,35 @@ fn App() -> Element {
         avatar_url,
     });
 
+    // Per-request CSP nonce from the security headers middleware, resolved
+    // during SSR and carried to the client so hydration sees the same value.
+    let csp_nonce = use_server_cached(|| {
+        #[cfg(feature = "server")]
+        {
+            server::security_headers::current_nonce()
+        }
+        #[cfg(not(feature = "server"))]
+        {
+            None::<String>
+        }
+    });
+
+    // Configured theme cookie name (`COOKIE_PREFIX` / `COOKIE_THEME_NAME`).
+    let theme_cookie = use_server_cached(|| {
+        #[cfg(feature = "server")]
+        {
+            Some(server::auth::cookies::cookie_config().names.theme.clone())
+        }
+        #[cfg(not(feature = "server"))]
+        {
+            None::<String>
+        }
+    });
+
     rsx! {
         document::Link { rel: "stylesheet", href: CYBERPUNK_THEME }
-        shared_ui::theme::ThemeSeed {}
-        shared_ui::ToastProvider {
+        shared_ui::theme::ThemeSeed { nonce: csp_nonce, cookie: theme_cookie }
+        shared_ui::ToastProvider { position: shared_ui::ToastPosition::BottomRight,
             Router::<Route> {}
         }
     }
@@ -0,0 +1,60 @@
+use dioxus::prelude::*;
+
+/// Script that wraps `window.fetch` so every same-origin request carries a
+/// fresh `x-request-id` of the form `{platform}-{uuid}`, logged to the
+/// console so a browser log line can be matched with the server's telemetry
+/// span. Requests that already set the header keep theirs. The wrapper is
+/// installed once per page.
+fn request_id_interceptor_script(platform: &str) -> String {
+    format!(
+        r#"
+        (function() {{
+            if (window.__requestIdHooked || !window.crypto || !crypto.randomUUID) {{
+                return;
+            }}
+            window.__requestIdHooked = true;
+            var originalFetch = window.fetch;
+            window.fetch = function(input, init) {{
+                var fromRequest = input instanceof Request;
+                var url = new URL(fromRequest ? input.url : String(input), location.href);
+                var headers = new Headers(
+                    (init && init.headers) || (fromRequest ? input.headers : undefined));
+                if (url.origin !== location.origin || headers.has('x-request-id')) {{
+                    return originalFetch.apply(this, arguments);
+                }}
+                var requestId = '{platform}-' + crypto.randomUUID();
+                headers.set('x-request-id', requestId);
+                console.debug('request', requestId, url.pathname);
+                return originalFetch.call(this, input, Object.assign({{}}, init, {{ headers: headers }}));
+            }};
+        }})();
+        "#
+    )
+}
+
+/// Gives each server function call its own `x-request-id`.
+///
+/// On the web a fetch wrapper stamps every request. Native clients call the
+/// server from Rust, where Dioxus only supports headers shared by all calls,
+/// so they send none and the server mints an id per request. Call this once
+/// in the root `App` component.
+pub fn use_request_ids(platform: &'static str) {
+    use_effect(move || {
+        if cfg!(feature = "web") {
+            document::eval(&request_id_interceptor_script(platform));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interceptor_stamps_each_same_origin_request() {
+        let script = request_id_interceptor_script("web");
+        assert!(script.contains("var requestId = 'web-' + crypto.randomUUID();"));
+        assert!(script.contains("headers.set('x-request-id', requestId)"));
+        assert!(script.contains("url.origin !== location.origin"));
+    }
+}
@@ -0,0 +1,92 @@
+use std::future::Future;
+use std::time::Duration;
+
+use dioxus::prelude::*;
+use shared_types::AppErrorKind;
+
+use crate::errors::parse_error;
+
+/// Calls made by [`with_retry`], including the first.
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Wait before the first retry; doubled for each one after.
+const BASE_DELAY: Duration = Duration::from_millis(300);
+
+/// Whether an error of this kind may go away if the call is repeated, e.g.
+/// while the server restarts during a deploy.
+///
+/// Errors caused by the request itself (validation, auth) never are.
+/// Unstructured errors such as network failures parse as internal errors, so
+/// they are retried too.
+pub fn is_transient(kind: AppErrorKind) -> bool {
+    matches!(
+        kind,
+        AppErrorKind::InternalError
+            | AppErrorKind::DatabaseError
+            | AppErrorKind::ServiceUnavailable
+    )
+}
+
+/// Wait before retry number `retry` (0-based).
+fn backoff(retry: u32) -> Duration {
+    BASE_DELAY * 2u32.pow(retry)
+}
+
+/// Run a server function call, repeating it with exponential backoff while
+/// it fails with a [transient](is_transient) error.
+///
+/// Read any signals the call depends on before calling this, so the
+/// surrounding resource still tracks them.
+pub async fn with_retry<T, F, Fut>(mut call: F) -> Result<T, ServerFnError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, ServerFnError>>,
+{
+    let mut retry = 0;
+    loop {
+        match call().await {
+            Err(err) if retry + 1 < MAX_ATTEMPTS && is_transient(parse_error(&err).kind) => {
+                dioxus::logger::tracing::debug!(error = %err, retry, "retrying server function");
+                futures_timer::Delay::new(backoff(retry)).await;
+                retry += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_server_side_failures_are_retried() {
+        let retried = [
+            AppErrorKind::InternalError,
+            AppErrorKind::DatabaseError,
+            AppErrorKind::ServiceUnavailable,
+        ];
+        let not_retried = [
+            AppErrorKind::BadRequest,
+            AppErrorKind::NotFound,
+            AppErrorKind::ValidationError,
+            AppErrorKind::Unauthorized,
+            AppErrorKind::Forbidden,
+            AppErrorKind::MethodNotAllowed,
+            AppErrorKind::TooManyRequests,
+        ];
+        for kind in retried {
+            assert!(is_transient(kind), "{kind} should be retried");
+        }
+        for kind in not_retried {
+            assert!(!is_transient(kind), "{kind} should not be retried");
+        }
+    }
+
+    #[test]
+    fn backoff_doubles() {
+        assert_eq!(backoff(0), Duration::from_millis(300));
+        assert_eq!(backoff(1), Duration::from_millis(600));
+        assert_eq!(backoff(2), Duration::from_millis(1200));
+    }
+}
@@ -0,0 +1,191 @@
+use crate::tier_gate::TierGate;
+use dioxus::prelude::*;
+use dioxus_free_icons::icons::ld_icons::LdLockOpen;
+use dioxus_free_icons::Icon;
+use server::api::get_premium_analytics;
+use shared_types::{AppError, AppErrorKind, CategoryCount, CategoryRevenue, UserTier};
+use shared_ui::{
+    Badge, BadgeVariant, Button, ButtonVariant, Card, CardContent, CardDescription, CardHeader,
+    CardTitle, Skeleton,
+};
+
+/// Width of a category bar as a percentage of the largest category.
+fn bar_width(value: f64, max: f64) -> f64 {
+    if max <= 0.0 {
+        return 0.0;
+    }
+    (value / max * 100.0).clamp(0.0, 100.0)
+}
+
+/// Premium analytics page: revenue, pricing, category breakdown, and signups.
+#[component]
+pub fn Analytics() -> Element {
+    rsx! {
+        document::Link { rel: "stylesheet", href: asset!("./analytics.css") }
+
+        div { class: "analytics-page",
+            h2 { class: "analytics-page-title", "Analytics" }
+
+            TierGate {
+                required: UserTier::Premium,
+                fallback: rsx! { AnalyticsUpgradePrompt {} },
+                AnalyticsReport {}
+            }
+        }
+    }
+}
+
+/// Loads and renders the analytics data.
+///
+/// The server enforces the tier requirement too; a `Forbidden` response
+/// (e.g. a stale client-side tier) falls back to the upgrade prompt.
+#[component]
+fn AnalyticsReport() -> Element {
+    let mut analytics = use_server_future(|| get_premium_analytics(None))?;
+
+    match analytics() {
+        None => rsx! {
+            Card {
+                CardContent {
+                    div { class: "analytics-page-loading",
+                        Skeleton { style: "height: 2rem; width: 40%;" }
+                        Skeleton { style: "height: 1rem; width: 70%;" }
+                        Skeleton { style: "height: 1rem; width: 55%;" }
+                    }
+                }
+            }
+        },
+        Some(Err(err)) => {
+            let err_str = err.to_string();
+            let forbidden = AppError::from_server_error(&err_str)
+                .is_some_and(|e| e.kind == AppErrorKind::Forbidden);
+
+            if forbidden {
+                rsx! { AnalyticsUpgradePrompt {} }
+            } else {
+                rsx! {
+                    Card {
+                        CardHeader {
+                            CardTitle { "Error" }
+                            CardDescription { "Failed to load analytics." }
+                        }
+                        CardContent {
+                            p { class: "analytics-page-error", "{AppError::friendly_message(&err_str)}" }
+                            Button {
+                                variant: ButtonVariant::Primary,
+                                onclick: move |_| analytics.restart(),
+                                "Retry"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Some(Ok(data)) => rsx! {
+            div { class: "analytics-page-metrics",
+                MetricCard { label: "Total Revenue", value: data.total_revenue.to_string() }
+                MetricCard { label: "Avg Product Price", value: data.avg_product_price.to_string() }
+                MetricCard { label: "New Users (30d)", value: data.users_last_30_days.to_string() }
+            }
+            CategoryBars { categories: data.products_by_category.clone() }
+            RevenueBars { categories: data.revenue_by_category.clone() }
+        },
+    }
+}
+
+/// A single headline number.
+#[component]
+fn MetricCard(label: String, value: String) -> Element {
+    rsx! {
+        Card {
+            CardHeader {
+                CardDescription { "{label}" }
+            }
+            CardContent {
+                span { class: "analytics-page-metric-value", "{value}" }
+            }
+        }
+    }
+}
+
+/// Products per category rendered as a horizontal bar list.
+#[component]
+fn CategoryBars(categories: Vec<CategoryCount>) -> Element {
+    let max = categories.iter().map(|c| c.count).max().unwrap_or(0);
+
+    rsx! {
+        Card {
+            CardHeader {
+                CardTitle { "Products by Category" }
+            }
+            CardContent {
+                if categories.is_empty() {
+                    p { class: "analytics-page-empty", "No products yet." }
+                }
+                div { class: "analytics-bars",
+                    for cat in categories.iter() {
+                        div { key: "{cat.category}", class: "analytics-bar-row",
+                            span { class: "analytics-bar-label", "{cat.category}" }
+                            div { class: "analytics-bar-track",
+                                div {
+                                    class: "analytics-bar-fill",
+                                    style: "width: {bar_width(cat.count as f64, max as f64)}%;",
+                                }
+                            }
+                            Badge { variant: BadgeVariant::Primary, "{cat.count}" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Revenue per category rendered as a horizontal bar list.
+#[component]
+fn RevenueBars(categories: Vec<CategoryRevenue>) -> Element {
+    let max = categories.iter().map(|c| c.revenue).max().unwrap_or_default();
+
+    rsx! {
+        Card {
+            CardHeader {
+                CardTitle { "Revenue by Category" }
+            }
+            CardContent {
+                if categories.is_empty() {
+                    p { class: "analytics-page-empty", "No active products yet." }
+                }
+                div { class: "analytics-bars",
+                    for cat in categories.iter() {
+                        div { key: "{cat.category}", class: "analytics-bar-row",
+                            span { class: "analytics-bar-label", "{cat.category}" }
+                            div { class: "analytics-bar-track",
+                                div {
+                                    class: "analytics-bar-fill",
+                                    style: "width: {bar_width(cat.revenue.cents() as f64, max.cents() as f64)}%;",
+                                }
+                            }
+                            Badge { variant: BadgeVariant::Primary, "{cat.revenue}" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Shown to users whose tier doesn't include analytics.
+#[component]
+fn AnalyticsUpgradePrompt() -> Element {
+    rsx! {
+        div { class: "analytics-upgrade",
+            div { class: "analytics-upgrade-icon",
+                Icon::<LdLockOpen> { icon: LdLockOpen, width: 24, height: 24 }
+            }
+            h3 { class: "analytics-upgrade-title", "Unlock Analytics" }
+            p { class: "analytics-upgrade-description",
+                "Upgrade to Premium to see revenue, pricing, and category insights."
+            }
+        }
+    }
+}
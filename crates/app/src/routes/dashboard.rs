@@ -1,14 +1,16 @@
+use crate::retry::with_retry;
+use crate::routes::Route;
 use crate::tier_gate::TierGate;
 use dioxus::prelude::*;
 use dioxus_free_icons::icons::ld_icons::{LdLock, LdLockOpen};
 use dioxus_free_icons::Icon;
 use server::api::{get_dashboard_stats, get_premium_analytics};
-use shared_types::UserTier;
+use shared_types::{StatsRange, UserTier};
 use shared_ui::{
     AspectRatio, Avatar, AvatarFallback, Badge, BadgeVariant, Button, ButtonVariant, Card,
     CardContent, CardDescription, CardHeader, CardTitle, ContentSide, HoverCard, HoverCardContent,
-    HoverCardTrigger, Progress, ProgressIndicator, Separator, Skeleton, Tooltip, TooltipContent,
-    TooltipTrigger,
+    HoverCardTrigger, Progress, ProgressIndicator, SelectContent, SelectItem, SelectRoot,
+    SelectTrigger, SelectValue, Separator, Skeleton, Tooltip, TooltipContent, TooltipTrigger,
 };
 
 /// Maximum value for progress bar display.
@@ -34,7 +36,11 @@ fn initials_from_name(name: &str) -> String {
 /// Dashboard page displaying stats, progress bars, and recent user activity.
 #[component]
 pub fn Dashboard() -> Element {
-    let mut stats_resource = use_server_future(get_dashboard_stats)?;
+    let mut range = use_signal(StatsRange::default);
+    let mut stats_resource = use_server_future(move || {
+        let range = range();
+        with_retry(move || get_dashboard_stats(Some(range)))
+    })?;
 
     let stats_result = stats_resource();
 
@@ -44,9 +50,37 @@ pub fn Dashboard() -> Element {
         div {
             class: "dashboard-page",
 
-            h2 {
-                class: "dashboard-title",
-                "Dashboard"
+            div {
+                class: "dashboard-header",
+
+                h2 {
+                    class: "dashboard-title",
+                    "Dashboard"
+                }
+
+                SelectRoot::<String> {
+                    default_value: Some(range().as_str().to_string()),
+                    on_value_change: move |val: Option<String>| {
+                        if let Some(selected) = val.as_deref().and_then(StatsRange::parse) {
+                            range.set(selected);
+                        }
+                    },
+                    SelectTrigger {
+                        aria_label: "Stats range",
+                        SelectValue {}
+                    }
+                    SelectContent {
+                        aria_label: "Stats range options",
+                        for (index, option) in StatsRange::ALL.into_iter().enumerate() {
+                            SelectItem::<String> {
+                                key: "{option.as_str()}",
+                                value: option.as_str().to_string(),
+                                index,
+                                "{option.label()}"
+                            }
+                        }
+                    }
+                }
             }
 
             match stats_result {
@@ -262,7 +296,15 @@ fn RecentActivity(stats: shared_types::DashboardStats) -> Element {
     rsx! {
         Card {
             CardHeader {
-                CardTitle { "Recent Activity" }
+                div {
+                    class: "stat-header-row",
+                    CardTitle { "Recent Activity" }
+                    Link {
+                        to: Route::Users { search: String::new() },
+                        class: "recent-activity-link",
+                        "View all users"
+                    }
+                }
                 CardDescription { "Newly registered users." }
             }
             CardContent {
@@ -334,6 +376,15 @@ fn UserRow(user: shared_types::User) -> Element {
                                 class: "hover-card-id",
                                 "ID: {user.id}"
                             }
+                            span {
+                                class: "hover-card-joined",
+                                "Joined {user.joined_date()}"
+                            }
+                            Link {
+                                to: Route::Users { search: user.username.clone() },
+                                class: "recent-activity-link",
+                                "View in Users"
+                            }
                         }
                     }
                 }
@@ -352,7 +403,7 @@ fn UserRow(user: shared_types::User) -> Element {
 /// Premium analytics section — fetches tier-gated data from the server.
 #[component]
 fn AnalyticsSection() -> Element {
-    let analytics = use_server_future(get_premium_analytics)?;
+    let analytics = use_server_future(|| get_premium_analytics(None))?;
     let result = analytics();
 
     rsx! {
@@ -380,11 +431,11 @@ fn AnalyticsSection() -> Element {
                         div { class: "analytics-grid",
                             div { class: "analytics-metric",
                                 span { class: "analytics-metric-label", "Total Revenue" }
-                                span { class: "analytics-metric-value", "${data.total_revenue:.2}" }
+                                span { class: "analytics-metric-value", "{data.total_revenue}" }
                             }
                             div { class: "analytics-metric",
                                 span { class: "analytics-metric-label", "Avg Price" }
-                                span { class: "analytics-metric-value", "${data.avg_product_price:.2}" }
+                                span { class: "analytics-metric-value", "{data.avg_product_price}" }
                             }
                             div { class: "analytics-metric",
                                 span { class: "analytics-metric-label", "New Users (30d)" }
@@ -413,8 +464,6 @@ fn AnalyticsSection() -> Element {
 /// Elite admin panel — quick user count and navigation link.
 #[component]
 fn AdminPanel(total_users: i64) -> Element {
-    use crate::routes::Route;
-
     rsx! {
         Card {
             CardHeader {
@@ -433,7 +482,7 @@ fn AdminPanel(total_users: i64) -> Element {
                     }
                     Separator {}
                     div { class: "admin-actions",
-                        Link { to: Route::Users {},
+                        Link { to: Route::Users { search: String::new() },
                             Button { variant: ButtonVariant::Primary, "Manage Users" }
                         }
                         Link { to: Route::Products {},
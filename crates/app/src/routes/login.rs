@@ -46,25 +46,6 @@ pub fn Login() -> Element {
         loading.set(false);
     };
 
-    let handle_oauth = move |provider: &'static str| {
-        move |_: MouseEvent| {
-            let provider = provider.to_string();
-            spawn(async move {
-                match server::api::oauth_authorize_url(provider).await {
-                    Ok(url) => {
-                        // Navigate to the OAuth provider's authorization page
-                        navigator().push(NavigationTarget::<Route>::External(url));
-                    }
-                    Err(e) => {
-                        error_msg.set(Some(shared_types::AppError::friendly_message(
-                            &e.to_string(),
-                        )));
-                    }
-                }
-            });
-        }
-    };
-
     rsx! {
         document::Link { rel: "stylesheet", href: asset!("./login.css") }
 
@@ -82,28 +63,7 @@ pub fn Login() -> Element {
                         div { class: "auth-error", "{err}" }
                     }
 
-                    // OAuth buttons
-                    div { class: "auth-oauth-buttons",
-                        Button {
-                            variant: ButtonVariant::Outline,
-                            class: "auth-oauth-btn",
-                            onclick: handle_oauth("google"),
-                            "Continue with Google"
-                        }
-                        Button {
-                            variant: ButtonVariant::Outline,
-                            class: "auth-oauth-btn",
-                            onclick: handle_oauth("github"),
-                            "Continue with GitHub"
-                        }
-                    }
-
-                    // Divider
-                    div { class: "auth-divider",
-                        Separator {}
-                        span { class: "auth-divider-text", "or" }
-                        Separator {}
-                    }
+                    OAuthButtons { on_error: move |msg| error_msg.set(Some(msg)) }
 
                     // Email/Password form
                     form { onsubmit: handle_login,
@@ -145,10 +105,71 @@ pub fn Login() -> Element {
                 CardFooter {
                     p { class: "auth-link",
                         "Don't have an account? "
-                        Link { to: Route::Register {}, "Create one" }
+                        Link {
+                            to: Route::Register {
+                                invite: String::new(),
+                            },
+                            "Create one"
+                        }
                     }
                 }
             }
         }
     }
 }
+
+/// Sign-in buttons for the OAuth providers configured on the server.
+///
+/// Renders nothing (including the "or" divider) when no provider is
+/// configured, so password-only deployments don't show dead buttons.
+#[component]
+pub fn OAuthButtons(on_error: EventHandler<String>) -> Element {
+    let providers = use_server_future(server::api::oauth_providers)?;
+    let providers = match providers() {
+        Some(Ok(providers)) if !providers.is_empty() => providers,
+        _ => return rsx! {},
+    };
+
+    let handle_oauth = move |provider: String| {
+        move |_: MouseEvent| {
+            let provider = provider.clone();
+            spawn(async move {
+                match server::api::oauth_authorize_url(provider).await {
+                    Ok(url) => {
+                        // Navigate to the OAuth provider's authorization page
+                        navigator().push(NavigationTarget::<Route>::External(url));
+                    }
+                    Err(e) => {
+                        on_error.call(shared_types::AppError::friendly_message(&e.to_string()));
+                    }
+                }
+            });
+        }
+    };
+
+    rsx! {
+        div { class: "auth-oauth-buttons",
+            for provider in providers {
+                Button {
+                    key: "{provider.id}",
+                    variant: ButtonVariant::Outline,
+                    class: "auth-oauth-btn",
+                    onclick: handle_oauth(provider.id.clone()),
+                    span {
+                        class: "auth-oauth-icon",
+                        "data-icon": "{provider.icon}",
+                        "aria-hidden": "true",
+                        {provider.display_name.chars().next().map(String::from)}
+                    }
+                    "Continue with {provider.display_name}"
+                }
+            }
+        }
+
+        div { class: "auth-divider",
+            Separator {}
+            span { class: "auth-divider-text", "or" }
+            Separator {}
+        }
+    }
+}
@@ -1,28 +1,40 @@
+pub mod analytics;
 pub mod dashboard;
 pub mod login;
 pub mod not_found;
+pub mod product_detail;
 pub mod products;
 pub mod register;
 pub mod settings;
 pub mod users;
 
 use crate::auth::use_auth;
+use crate::errors::report_error;
+use crate::seo::{PageMeta, SeoHead};
+use crate::shortcuts::{use_keyboard_shortcuts, ShortcutHelp, BINDINGS};
+use crate::tier_gate::TierGate;
 use crate::ProfileState;
 use dioxus::prelude::*;
-use dioxus_free_icons::icons::ld_icons::{LdLayoutDashboard, LdPackage, LdSettings, LdUsers};
+use dioxus_free_icons::icons::ld_icons::{
+    LdLayoutDashboard, LdPackage, LdSettings, LdTrendingUp, LdUsers,
+};
 use dioxus_free_icons::Icon;
-use shared_types::UserTier;
+use shared_types::{TierBadgeVariant, UserTier};
 use shared_ui::{
-    Avatar, AvatarFallback, AvatarImage, Badge, BadgeVariant, DropdownMenu, DropdownMenuContent,
-    DropdownMenuItem, DropdownMenuSeparator, DropdownMenuTrigger, Navbar, Separator, Sidebar,
-    SidebarContent, SidebarFooter, SidebarGroup, SidebarGroupContent, SidebarGroupLabel,
-    SidebarHeader, SidebarInset, SidebarMenu, SidebarMenuButton, SidebarMenuItem, SidebarProvider,
-    SidebarRail, SidebarSeparator, SidebarTrigger, Switch, SwitchThumb,
+    use_toast, Avatar, AvatarFallback, AvatarImage, Badge, BadgeVariant, Button, ButtonVariant,
+    Command, CommandPalette, DialogContent, DialogDescription, DialogRoot, DialogTitle,
+    DropdownMenu, DropdownMenuContent, DropdownMenuItem, DropdownMenuSeparator,
+    DropdownMenuTrigger, Input, Navbar, Separator, Sidebar, SidebarContent, SidebarFooter,
+    SidebarGroup, SidebarGroupContent, SidebarGroupLabel, SidebarHeader, SidebarInset, SidebarMenu,
+    SidebarMenuButton, SidebarMenuItem, SidebarProvider, SidebarRail, SidebarSeparator,
+    SidebarTrigger, Skeleton, Switch, SwitchThumb, ToastOptions, TopProgressBar,
 };
 
+use analytics::Analytics;
 use dashboard::Dashboard;
 use login::Login;
 use not_found::NotFound;
+use product_detail::{ProductDetail, ProductMeta};
 use products::Products;
 use register::Register;
 use settings::Settings;
@@ -31,18 +43,23 @@ use users::Users;
 /// Application routes.
 #[derive(Clone, Routable, Debug, PartialEq)]
 pub enum Route {
+    #[layout(MetaLayout)]
     #[route("/login")]
     Login {},
-    #[route("/register")]
-    Register {},
+    #[route("/register?:invite")]
+    Register { invite: String },
     #[layout(AuthGuard)]
     #[layout(AppLayout)]
     #[route("/")]
     Dashboard {},
-    #[route("/users")]
-    Users {},
+    #[route("/users?:search")]
+    Users { search: String },
     #[route("/products")]
     Products {},
+    #[route("/products/:id")]
+    ProductDetail { id: i64 },
+    #[route("/analytics")]
+    Analytics {},
     #[route("/settings")]
     Settings {},
     #[end_layout]
@@ -51,6 +68,23 @@ pub enum Route {
     NotFound { route: Vec<String> },
 }
 
+/// Outermost layout: writes the page title and social meta tags for every
+/// route, including ones behind the auth guard, so link previews work for
+/// visitors who aren't signed in.
+#[component]
+fn MetaLayout() -> Element {
+    let route: Route = use_route();
+
+    rsx! {
+        if let Route::ProductDetail { id } = route {
+            ProductMeta { id }
+        } else {
+            SeoHead { meta: PageMeta::for_route(&route) }
+        }
+        Outlet::<Route> {}
+    }
+}
+
 /// Auth guard layout — redirects to /login if not authenticated.
 #[component]
 fn AuthGuard() -> Element {
@@ -74,24 +108,53 @@ fn AppLayout() -> Element {
     let route: Route = use_route();
     let profile: ProfileState = use_context();
     let mut auth = use_auth();
+    let mut show_rename = use_signal(|| false);
+    // Set while the page being navigated to waits on its data
+    let route_loading = use_signal(|| false);
+    let shortcuts = use_keyboard_shortcuts();
 
-    let mut theme_state = use_context_provider(|| shared_ui::theme::ThemeState {
-        family: Signal::new("cyberpunk".to_string()),
-        is_dark: Signal::new(true),
-    });
+    let theme_state: shared_ui::theme::ThemeState = use_context();
+
+    let mut sign_out = move || {
+        spawn(async move {
+            let _ = server::api::logout().await;
+        });
+        auth.clear_auth();
+        navigator().push(Route::Login {});
+    };
+
+    // Everything the shortcuts do, plus what has no key of its own
+    let mut commands: Vec<Command> = BINDINGS
+        .iter()
+        .map(|binding| {
+            let action = binding.action;
+            Command::new(binding.description, move |_| shortcuts.run(action))
+                .hint(binding.keys.join(" "))
+        })
+        .collect();
+    commands.push(Command::new("Toggle light/dark theme", move |_| {
+        let family = shared_ui::theme::ThemeFamily::from_key(&theme_state.family.peek());
+        let is_dark = *theme_state.is_dark.peek();
+        crate::theme_sync::save_theme(theme_state, family, !is_dark);
+    }));
+    commands.push(Command::new("Edit name", move |_| show_rename.set(true)));
+    commands.push(Command::new("Sign out", move |_| sign_out()));
 
     let page_title = match &route {
         Route::Dashboard {} => "Dashboard",
-        Route::Users {} => "Users",
-        Route::Products {} => "Products",
+        Route::Users { .. } => "Users",
+        Route::Products {} | Route::ProductDetail { .. } => "Products",
+        Route::Analytics {} => "Analytics",
         Route::Settings {} => "Settings",
-        Route::Login {} | Route::Register {} => "Auth",
+        Route::Login {} | Route::Register { .. } => "Auth",
         _ => "",
     };
 
     rsx! {
         document::Link { rel: "stylesheet", href: asset!("./layout.css") }
 
+        TopProgressBar { active: route_loading() }
+
         SidebarProvider { default_open: false,
             Sidebar {
                 SidebarHeader {
@@ -120,8 +183,8 @@ fn AppLayout() -> Element {
                                     }
                                 }
                                 SidebarMenuItem {
-                                    Link { to: Route::Users {},
-                                        SidebarMenuButton { active: matches!(route, Route::Users {}),
+                                    Link { to: Route::Users { search: String::new() },
+                                        SidebarMenuButton { active: matches!(route, Route::Users { .. }),
                                             Icon::<LdUsers> { icon: LdUsers, width: 18, height: 18 }
                                             "Users"
                                         }
@@ -129,12 +192,22 @@ fn AppLayout() -> Element {
                                 }
                                 SidebarMenuItem {
                                     Link { to: Route::Products {},
-                                        SidebarMenuButton { active: matches!(route, Route::Products {}),
+                                        SidebarMenuButton { active: matches!(route, Route::Products {} | Route::ProductDetail { .. }),
                                             Icon::<LdPackage> { icon: LdPackage, width: 18, height: 18 }
                                             "Products"
                                         }
                                     }
                                 }
+                                TierGate { required: UserTier::Premium,
+                                    SidebarMenuItem {
+                                        Link { to: Route::Analytics {},
+                                            SidebarMenuButton { active: matches!(route, Route::Analytics {}),
+                                                Icon::<LdTrendingUp> { icon: LdTrendingUp, width: 18, height: 18 }
+                                                "Analytics"
+                                            }
+                                        }
+                                    }
+                                }
                                 SidebarMenuItem {
                                     Link { to: Route::Settings {},
                                         SidebarMenuButton { active: matches!(route, Route::Settings {}),
@@ -159,8 +232,8 @@ fn AppLayout() -> Element {
                         Switch {
                             checked: (theme_state.is_dark)(),
                             on_checked_change: move |checked: bool| {
-                                theme_state.is_dark.set(checked);
-                                theme_state.apply();
+                                let family = shared_ui::theme::ThemeFamily::from_key(&(theme_state.family)());
+                                crate::theme_sync::save_theme(theme_state, family, checked);
                             },
                             SwitchThumb {}
                         }
@@ -211,10 +284,16 @@ fn AppLayout() -> Element {
                                     },
                                     "Profile"
                                 }
+                                DropdownMenuItem::<String> {
+                                    value: "rename".to_string(),
+                                    index: 1usize,
+                                    on_select: move |_: String| show_rename.set(true),
+                                    "Edit Name"
+                                }
                                 DropdownMenuSeparator {}
                                 DropdownMenuItem::<String> {
                                     value: "docs".to_string(),
-                                    index: 1usize,
+                                    index: 2usize,
                                     div {
                                         onclick: move |_| {
                                             navigator().push(
@@ -230,14 +309,8 @@ fn AppLayout() -> Element {
                                 DropdownMenuSeparator {}
                                 DropdownMenuItem::<String> {
                                     value: "logout".to_string(),
-                                    index: 2usize,
-                                    on_select: move |_: String| {
-                                        spawn(async move {
-                                            let _ = server::api::logout().await;
-                                        });
-                                        auth.clear_auth();
-                                        navigator().push(Route::Login {});
-                                    },
+                                    index: 3usize,
+                                    on_select: move |_: String| sign_out(),
                                     "Sign Out"
                                 }
                             }
@@ -248,10 +321,116 @@ fn AppLayout() -> Element {
                 // Page content
                 div {
                     class: "page-content",
-                    Outlet::<Route> {}
+                    SuspenseBoundary {
+                        fallback: move |_| rsx! { RouteLoading { loading: route_loading } },
+                        Outlet::<Route> {}
+                    }
                 }
             }
         }
+
+        RenameDialog { open: show_rename }
+        ShortcutHelp {}
+        CommandPalette { commands }
+    }
+}
+
+/// Placeholder shown while a route's server data loads. Holds `loading`
+/// true for as long as it is mounted, which drives the top progress bar.
+#[component]
+fn RouteLoading(loading: Signal<bool>) -> Element {
+    let mut loading = loading;
+    use_effect(move || loading.set(true));
+    use_drop(move || loading.set(false));
+
+    rsx! {
+        div {
+            class: "route-loading",
+            Skeleton { class: "route-loading-title" }
+            Skeleton { class: "route-loading-block" }
+        }
+    }
+}
+
+/// Quick edit of the signed-in user's display name, opened from the navbar
+/// avatar menu. Email changes still go through the full profile form in
+/// Settings.
+#[component]
+fn RenameDialog(open: Signal<bool>) -> Element {
+    let profile: ProfileState = use_context();
+    let mut auth = use_auth();
+    let toast = use_toast();
+    let mut name = use_signal(String::new);
+    let mut saving = use_signal(|| false);
+
+    // Start from the current name each time the dialog opens.
+    use_effect(move || {
+        if open() {
+            name.set(profile.display_name.peek().clone());
+        }
+    });
+
+    let save = move |_| {
+        saving.set(true);
+        spawn(async move {
+            match server::api::update_display_name(name()).await {
+                Ok(user) => {
+                    auth.set_user(user);
+                    open.set(false);
+                    toast.success("Display name updated".to_string(), ToastOptions::new());
+                }
+                Err(err) => {
+                    report_error(&err);
+                }
+            }
+            saving.set(false);
+        });
+    };
+
+    rsx! {
+        DialogRoot {
+            open: open(),
+            on_open_change: move |is_open: bool| open.set(is_open),
+            DialogContent {
+                DialogTitle { "Edit Display Name" }
+                DialogDescription { "This is the name shown across the app. To change your email, use Settings." }
+                div {
+                    class: "rename-form",
+                    Input {
+                        value: name(),
+                        placeholder: "Display name",
+                        label: "",
+                        on_input: move |evt: FormEvent| name.set(evt.value()),
+                    }
+                    div {
+                        class: "rename-actions",
+                        Button {
+                            variant: ButtonVariant::Ghost,
+                            onclick: move |_| open.set(false),
+                            "Cancel"
+                        }
+                        Button {
+                            variant: ButtonVariant::Primary,
+                            disabled: saving() || name().trim().is_empty(),
+                            onclick: save,
+                            "Save"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// How long the "Undo" button stays up after deleting a user or product.
+pub const UNDO_DELETE_WINDOW: std::time::Duration = std::time::Duration::from_secs(8);
+
+/// Map a tier to the UI badge variant.
+pub fn tier_badge_variant(tier: &UserTier) -> BadgeVariant {
+    match tier.badge_variant() {
+        TierBadgeVariant::Primary => BadgeVariant::Primary,
+        TierBadgeVariant::Secondary => BadgeVariant::Secondary,
+        TierBadgeVariant::Destructive => BadgeVariant::Destructive,
     }
 }
 
@@ -267,11 +446,9 @@ fn TierBadge() -> Element {
             .unwrap_or(UserTier::Free)
     });
 
-    let (variant, label) = match tier() {
-        UserTier::Free => (BadgeVariant::Secondary, "FREE"),
-        UserTier::Premium => (BadgeVariant::Primary, "PREMIUM"),
-        UserTier::Elite => (BadgeVariant::Destructive, "ELITE"),
-    };
+    let tier = tier();
+    let variant = tier_badge_variant(&tier);
+    let label = tier.display_name().to_uppercase();
 
     rsx! {
         div { class: "sidebar-footer-row sidebar-tier-row",
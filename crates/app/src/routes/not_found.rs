@@ -2,10 +2,60 @@ use dioxus::prelude::*;
 
 use crate::routes::Route;
 
+/// Top-level pages a mistyped URL may have meant, by first path segment.
+fn known_routes() -> [(&'static str, Route); 5] {
+    [
+        ("dashboard", Route::Dashboard {}),
+        (
+            "users",
+            Route::Users {
+                search: String::new(),
+            },
+        ),
+        ("products", Route::Products {}),
+        ("analytics", Route::Analytics {}),
+        ("settings", Route::Settings {}),
+    ]
+}
+
+/// Edits (insertions, deletions, substitutions) needed to turn `a` into `b`.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut row = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            row[j + 1] = substitution.min(prev[j + 1] + 1).min(row[j] + 1);
+        }
+        prev = row;
+    }
+    prev[b.len()]
+}
+
+/// The known page whose name is closest to `segment`, ignoring case, if it
+/// is within two edits and less than half the name differs.
+fn closest_route(segment: &str) -> Option<(&'static str, Route)> {
+    let segment = segment.trim().to_lowercase();
+    if segment.is_empty() {
+        return None;
+    }
+    known_routes()
+        .into_iter()
+        .map(|(name, route)| (levenshtein(&segment, name), name, route))
+        .filter(|(distance, name, _)| *distance <= 2 && distance * 2 < name.len())
+        .min_by_key(|(distance, _, _)| *distance)
+        .map(|(_, name, route)| (name, route))
+}
+
 /// 404 Not Found page.
+///
+/// When the first path segment is a near miss for a known page, e.g.
+/// `/prodcuts`, links to that page as well.
 #[component]
 pub fn NotFound(route: Vec<String>) -> Element {
     let path = format!("/{}", route.join("/"));
+    let suggestion = route.first().and_then(|segment| closest_route(segment));
 
     rsx! {
         document::Link { rel: "stylesheet", href: asset!("./not_found.css") }
@@ -19,6 +69,13 @@ pub fn NotFound(route: Vec<String>) -> Element {
                     code { "{path}" }
                     " could not be found."
                 }
+                if let Some((name, to)) = suggestion {
+                    p { class: "not-found-suggestion",
+                        "Did you mean "
+                        Link { to, "/{name}" }
+                        "?"
+                    }
+                }
                 Link { to: Route::Dashboard {},
                     class: "not-found-link",
                     "Back to Dashboard"
@@ -27,3 +84,38 @@ pub fn NotFound(route: Vec<String>) -> Element {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance_counts_single_character_edits() {
+        assert_eq!(levenshtein("products", "products"), 0);
+        assert_eq!(levenshtein("prodcts", "products"), 1);
+        assert_eq!(levenshtein("prodcuts", "products"), 2);
+        assert_eq!(levenshtein("", "users"), 5);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn near_misses_suggest_the_closest_page() {
+        let name = |segment| closest_route(segment).map(|(name, _)| name);
+        assert_eq!(name("prodcuts"), Some("products"));
+        assert_eq!(name("Setings"), Some("settings"));
+        assert_eq!(name("user"), Some("users"));
+        assert_eq!(name("dashbaord"), Some("dashboard"));
+        assert_eq!(
+            closest_route("analytic").map(|(_, route)| route),
+            Some(Route::Analytics {})
+        );
+    }
+
+    #[test]
+    fn distant_paths_get_no_suggestion() {
+        assert_eq!(closest_route("billing"), None);
+        assert_eq!(closest_route("orders"), None);
+        assert_eq!(closest_route("x"), None);
+        assert_eq!(closest_route(""), None);
+    }
+}
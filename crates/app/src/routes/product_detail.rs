@@ -0,0 +1,77 @@
+use dioxus::prelude::*;
+use server::api::get_product;
+use shared_ui::{Badge, Card, CardContent, CardHeader, CardTitle, Skeleton};
+
+use crate::routes::products::badge_variant_for_status;
+use crate::routes::Route;
+use crate::seo::{PageMeta, SeoHead};
+
+/// Product detail page, the page shared when linking to a single product.
+#[component]
+pub fn ProductDetail(id: i64) -> Element {
+    let product = use_server_future(use_reactive!(|id| get_product(id)))?;
+
+    rsx! {
+        document::Link { rel: "stylesheet", href: asset!("./products.css") }
+
+        div {
+            class: "products-page",
+            Link { to: Route::Products {}, class: "product-back-link", "\u{2190} All products" }
+
+            match &*product.read() {
+                Some(Ok(product)) => rsx! {
+                    Card {
+                        CardHeader {
+                            div {
+                                class: "product-card-header",
+                                CardTitle { "{product.name}" }
+                                Badge {
+                                    variant: badge_variant_for_status(&product.status),
+                                    "{product.status}"
+                                }
+                            }
+                        }
+                        CardContent {
+                            div {
+                                class: "product-card-body",
+                                img {
+                                    class: "product-detail-image",
+                                    src: "/api/v1/products/{product.id}/image",
+                                    alt: "{product.name}",
+                                }
+                                p { class: "product-price", "{product.price}" }
+                                p { class: "product-category", "{product.category}" }
+                                p { class: "product-detail-description", "{product.description}" }
+                            }
+                        }
+                    }
+                },
+                Some(Err(e)) => rsx! {
+                    p {
+                        class: "products-empty-subtitle",
+                        {shared_types::AppError::friendly_message(&e.to_string())}
+                    }
+                },
+                None => rsx! {
+                    Skeleton { style: "height: 240px; width: 100%;" }
+                },
+            }
+        }
+    }
+}
+
+/// Social meta tags for a product page, filled in from the product once it
+/// loads so link previews show its name, description, and image.
+#[component]
+pub fn ProductMeta(id: i64) -> Element {
+    let product = use_server_future(use_reactive!(|id| get_product(id)))?;
+
+    let meta = match &*product.read() {
+        Some(Ok(product)) => PageMeta::for_product(product),
+        _ => PageMeta::for_route(&Route::ProductDetail { id }),
+    };
+
+    rsx! {
+        SeoHead { meta }
+    }
+}
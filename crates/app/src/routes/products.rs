@@ -1,15 +1,28 @@
+use std::collections::HashSet;
+
+use crate::errors::{parse_error, report_error};
+use crate::retry::with_retry;
+use crate::routes::{Route, UNDO_DELETE_WINDOW};
+use crate::shortcuts::Shortcuts;
+use crate::unsaved_guard::use_unsaved_guard;
 use dioxus::prelude::*;
-use server::api::{create_product, delete_product, list_products, update_product};
-use shared_types::Product;
+use server::api::{
+    create_product, delete_product, get_product_history, list_products_page, restore_product,
+    update_product, upload_product_image,
+};
+use shared_types::{
+    parse_price, AuditAction, AuditEntry, Cursor, Money, Product, ProductStatus, MIN_PRODUCT_PRICE,
+};
 use shared_ui::{
-    use_toast, Badge, BadgeVariant, Button, ButtonVariant, Card, CardContent, CardHeader,
-    CardTitle, Collapsible, CollapsibleContent, CollapsibleTrigger, Date, DatePicker,
-    DatePickerCalendar, DatePickerInput, DatePickerPopover, Form, Input, Label, RadioGroup,
-    RadioGroupItem, SelectContent, SelectItem, SelectRoot, SelectTrigger, SelectValue, Separator,
-    Sheet, SheetClose, SheetContent, SheetDescription, SheetFooter, SheetHeader, SheetSide,
-    SheetTitle, Skeleton, SliderRange, SliderRoot, SliderThumb, SliderTrack, SliderValue,
-    TabContent, TabList, TabTrigger, Tabs, Textarea, TextareaVariant, ToastOptions, ToggleGroup,
-    ToggleGroupItem,
+    use_draft, use_form, use_toast, Badge, BadgeVariant, Button, ButtonVariant, Card, CardContent,
+    CardHeader, CardTitle, Collapsible, CollapsibleContent, CollapsibleTrigger, Combobox,
+    ComboboxOption, Date, DatePicker, DatePickerCalendar, DatePickerInput, DatePickerPopover,
+    DateTextInput, DroppedFile, FieldErrors, FileDropzone, FileRejection, Form, FormValues, Input,
+    Label, MaskedValue, MultiSelect, MultiSelectOption, NumberMask, RadioGroup, RadioGroupItem,
+    SelectContent, SelectItem, SelectRoot, SelectTrigger, SelectValue, Separator, Sheet,
+    SheetClose, SheetContent, SheetDescription, SheetFooter, SheetHeader, SheetSide, SheetTitle,
+    Skeleton, SliderRange, SliderRoot, SliderThumb, SliderTrack, SliderValue, Textarea,
+    TextareaVariant, ToastOptions, ToggleGroup, ToggleGroupItem, ToggleSelection,
 };
 
 /// Maximum price bound used by the slider filter.
@@ -18,20 +31,177 @@ const PRICE_SLIDER_MAX: f64 = 1000.0;
 /// Step increment for the price slider.
 const PRICE_SLIDER_STEP: f64 = 10.0;
 
-/// Maps a product status string to the appropriate badge variant.
-fn badge_variant_for_status(status: &str) -> BadgeVariant {
+/// Products fetched per page as the grid scrolls.
+const PRODUCTS_PER_PAGE: i64 = 24;
+
+/// Product images accepted by the server.
+const PRODUCT_IMAGE_ACCEPT: &str = "image/jpeg,image/png,image/webp";
+
+/// Largest product image the upload field will send.
+const PRODUCT_IMAGE_MAX_SIZE: u64 = 5 * 1024 * 1024;
+
+/// URL of a product's uploaded image, if it has one.
+///
+/// The last-modified time is added as a version, so a replaced image isn't
+/// served from the browser's cache of the old one's redirect.
+fn product_image_src(product: &Product) -> Option<String> {
+    product.image_url.as_ref()?;
+    let version: String = product
+        .updated_at
+        .chars()
+        .filter(char::is_ascii_digit)
+        .collect();
+    Some(format!("/api/v1/products/{}/image?v={version}", product.id))
+}
+
+/// Id of the element at the end of the grid that loads the next page when
+/// it scrolls into view.
+const SENTINEL_ID: &str = "products-sentinel";
+
+/// Script that reports once when the sentinel comes within 200px of the
+/// viewport. A new sentinel is mounted for every page, so each page is
+/// requested at most once.
+fn observe_sentinel_script() -> String {
+    format!(
+        r#"
+        const el = document.getElementById('{SENTINEL_ID}');
+        if (!el || !('IntersectionObserver' in window)) return;
+        const observer = new IntersectionObserver((entries) => {{
+            if (entries.some((entry) => entry.isIntersecting)) {{
+                observer.disconnect();
+                dioxus.send(true);
+            }}
+        }}, {{ rootMargin: '200px' }});
+        observer.observe(el);
+        "#
+    )
+}
+
+/// Products from the first page and every page loaded after it, in order.
+fn loaded_products(first: Option<&Cursor<Product>>, more: &[Cursor<Product>]) -> Vec<Product> {
+    first
+        .into_iter()
+        .chain(more)
+        .flat_map(|page| page.items.iter().cloned())
+        .collect()
+}
+
+/// Cursor for the page after the last one loaded; `None` once every product
+/// has been loaded.
+fn next_cursor(first: Option<&Cursor<Product>>, more: &[Cursor<Product>]) -> Option<i64> {
+    match more.last() {
+        Some(page) => page.next_cursor,
+        None => first.and_then(|page| page.next_cursor),
+    }
+}
+
+/// Maps a product status to the appropriate badge variant.
+pub(crate) fn badge_variant_for_status(status: &ProductStatus) -> BadgeVariant {
     match status {
-        "active" => BadgeVariant::Primary,
-        "draft" => BadgeVariant::Secondary,
-        "archived" => BadgeVariant::Outline,
-        _ => BadgeVariant::Secondary,
+        ProductStatus::Active => BadgeVariant::Primary,
+        ProductStatus::Draft => BadgeVariant::Secondary,
+        ProductStatus::Archived => BadgeVariant::Outline,
+    }
+}
+
+/// Default product categories, offered even before any product uses them.
+const DEFAULT_CATEGORIES: [&str; 3] = ["Hardware", "Software", "Service"];
+
+/// Category filter options: "All", then every known category in alphabetical order.
+fn category_options(products: &[Product]) -> Vec<ComboboxOption<String>> {
+    let categories: std::collections::BTreeSet<&str> = DEFAULT_CATEGORIES
+        .into_iter()
+        .chain(products.iter().map(|p| p.category.as_str()))
+        .collect();
+    std::iter::once("All")
+        .chain(categories)
+        .map(|c| ComboboxOption::new(c.to_string(), c))
+        .collect()
+}
+
+/// Product form input that passed client-side validation.
+#[derive(Debug, Clone, PartialEq)]
+struct ProductDraft {
+    name: String,
+    description: String,
+    price: Money,
+    category: String,
+    status: String,
+}
+
+/// Product form fields, blank for a new product or filled from `product`.
+fn product_form_values(product: Option<&Product>) -> Vec<(&'static str, String)> {
+    match product {
+        Some(p) => vec![
+            ("name", p.name.clone()),
+            ("description", p.description.clone()),
+            ("price", p.price.to_decimal_string()),
+            ("category", p.category.clone()),
+            ("status", p.status.as_str().to_string()),
+        ],
+        None => vec![
+            ("name", String::new()),
+            ("description", String::new()),
+            ("price", String::new()),
+            ("category", "Hardware".to_string()),
+            ("status", ProductStatus::Active.as_str().to_string()),
+        ],
     }
 }
 
-/// Filters a product list by status tab, search query, category, price, and date.
+/// Check the product form before it is sent; the server re-validates.
+async fn validate_product_form(values: FormValues) -> Result<ProductDraft, FieldErrors> {
+    check_product_form(&values)
+}
+
+/// The checks behind [`validate_product_form`]. Prices use the same rule as
+/// the server's request validation, so a price that fails here never saves.
+fn check_product_form(values: &FormValues) -> Result<ProductDraft, FieldErrors> {
+    let field = |name: &str| values.get(name).cloned().unwrap_or_default();
+    let mut errors = FieldErrors::new();
+
+    let name = field("name").trim().to_string();
+    if name.is_empty() {
+        errors.insert("name".to_string(), "Name is required".to_string());
+    }
+    let price = parse_price(&field("price")).unwrap_or_else(|message| {
+        errors.insert("price".to_string(), message);
+        MIN_PRODUCT_PRICE
+    });
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    Ok(ProductDraft {
+        name,
+        description: field("description"),
+        price,
+        category: field("category"),
+        status: field("status"),
+    })
+}
+
+/// Status filter options, one per product status.
+fn status_options() -> Vec<MultiSelectOption<ProductStatus>> {
+    ProductStatus::all()
+        .into_iter()
+        .map(|status| {
+            let label = match status {
+                ProductStatus::Active => "Active",
+                ProductStatus::Draft => "Draft",
+                ProductStatus::Archived => "Archived",
+            };
+            MultiSelectOption::new(status, label)
+        })
+        .collect()
+}
+
+/// Filters a product list by status, search query, category, price, and date.
+///
+/// An empty status set matches every status.
 fn filter_products(
     products: &[Product],
-    tab: &str,
+    statuses: &HashSet<ProductStatus>,
     search: &str,
     category: &str,
     price_max: f64,
@@ -40,14 +210,14 @@ fn filter_products(
     let query = search.to_lowercase();
     products
         .iter()
-        .filter(|p| tab == "all" || p.status == tab)
+        .filter(|p| statuses.is_empty() || statuses.contains(&p.status))
         .filter(|p| {
             query.is_empty()
                 || p.name.to_lowercase().contains(&query)
                 || p.description.to_lowercase().contains(&query)
         })
         .filter(|p| category == "All" || p.category == category)
-        .filter(|p| p.price <= price_max)
+        .filter(|p| p.price <= Money::from_f64(price_max))
         .filter(|p| {
             date_after.is_none_or(|d| {
                 let date_str = format!("{}-{:02}-{:02}", d.year(), d.month() as u8, d.day());
@@ -58,113 +228,183 @@ fn filter_products(
         .collect()
 }
 
-/// Products page displaying a filterable, tabbed product catalog with CRUD operations.
+/// Products page displaying a filterable product catalog with CRUD operations.
+///
+/// Products load a page at a time as the grid scrolls. Filters apply to what
+/// has loaded so far and never reset it, so changing them keeps the scroll
+/// position; if they hide everything loaded, more pages keep loading.
 #[component]
 pub fn Products() -> Element {
-    let mut products = use_server_future(list_products)?;
+    let mut products =
+        use_server_future(|| with_retry(|| list_products_page(None, PRODUCTS_PER_PAGE)))?;
+    // Pages after the first, appended as the sentinel scrolls into view.
+    let mut more_pages = use_signal(Vec::<Cursor<Product>>::new);
+    let mut loading_more = use_signal(|| false);
     let toast = use_toast();
 
+    let cursor = use_memo(move || {
+        let first = products.read();
+        let first = first.as_ref().and_then(|r| r.as_ref().ok());
+        next_cursor(first, &more_pages.read())
+    });
+
+    let load_more = move || {
+        let Some(before_id) = cursor() else {
+            return;
+        };
+        if *loading_more.peek() {
+            return;
+        }
+        loading_more.set(true);
+        spawn(async move {
+            match with_retry(|| list_products_page(Some(before_id), PRODUCTS_PER_PAGE)).await {
+                Ok(page) => more_pages.write().push(page),
+                Err(err) => report_error(&err),
+            }
+            loading_more.set(false);
+        });
+    };
+
+    // Start over from the first page, e.g. after a product was saved.
+    let mut reload = move || {
+        more_pages.set(Vec::new());
+        products.restart();
+    };
+
     let mut view_mode = use_signal(|| "grid".to_string());
     let mut search_query = use_signal(String::new);
+    let mut status_filter = use_signal(HashSet::<ProductStatus>::new);
     let mut category_filter = use_signal(|| "All".to_string());
     let mut price_max = use_signal(|| PRICE_SLIDER_MAX);
     let mut date_after = use_signal(|| None::<Date>);
     let mut show_sheet = use_signal(|| false);
     let mut editing_product = use_signal(|| Option::<Product>::None);
+    let mut uploading_image = use_signal(|| false);
 
-    let mut form_name = use_signal(String::new);
-    let mut form_description = use_signal(String::new);
-    let mut form_price = use_signal(String::new);
-    let mut form_category = use_signal(|| "Hardware".to_string());
-    let mut form_status = use_signal(|| "active".to_string());
+    let mut form = use_form(|| product_form_values(None), validate_product_form);
+    // Only a new product keeps a draft; edits start from the saved product.
+    let creating = use_memo(move || show_sheet() && editing_product().is_none());
+    let draft = use_draft("product-create", form, creating);
+    // Drafts survive leaving the page, so only unsaved edits need a guard.
+    let unsaved_edit =
+        use_memo(move || editing_product().is_some() && show_sheet() && form.is_dirty());
+    use_unsaved_guard(unsaved_edit);
 
-    let open_create = move |_| {
+    let mut open_create = move || {
         editing_product.set(None);
-        form_name.set(String::new());
-        form_description.set(String::new());
-        form_price.set(String::new());
-        form_category.set("Hardware".to_string());
-        form_status.set("active".to_string());
+        form.reset(product_form_values(None));
         show_sheet.set(true);
     };
 
+    // `n` anywhere in the app lands here with the sheet requested
+    let Shortcuts {
+        mut new_product, ..
+    } = use_context();
+    use_effect(move || {
+        if new_product() {
+            new_product.set(false);
+            open_create();
+        }
+    });
+
     let handle_save = move |_: FormEvent| {
-        let name = form_name();
-        let description = form_description();
-        let price_str = form_price();
-        let category = form_category();
-        let status = form_status();
         let editing = editing_product();
-
-        spawn(async move {
-            let parsed_price: f64 = price_str.parse().unwrap_or(0.0);
-
+        form.submit(move |draft: ProductDraft| async move {
+            let ProductDraft {
+                name,
+                description,
+                price,
+                category,
+                status,
+            } = draft;
             let result = if let Some(existing) = editing {
-                update_product(
-                    existing.id,
-                    name,
-                    description,
-                    parsed_price,
-                    category,
-                    status,
-                )
-                .await
+                update_product(existing.id, name, description, price, category, status).await
             } else {
-                create_product(name, description, parsed_price, category, status).await
+                create_product(name, description, price, category, status).await
             };
 
             match result {
                 Ok(_) => {
-                    products.restart();
+                    draft.clear();
+                    reload();
                     show_sheet.set(false);
                     toast.success(
                         "Product saved successfully".to_string(),
                         ToastOptions::new(),
                     );
+                    Ok(())
                 }
                 Err(err) => {
-                    toast.error(
-                        shared_types::AppError::friendly_message(&err.to_string()),
-                        ToastOptions::new(),
-                    );
+                    // Field errors are shown on the form; anything else gets a toast.
+                    let error = parse_error(&err);
+                    if error.field_errors.is_empty() {
+                        report_error(&err);
+                    }
+                    Err(error.field_errors)
                 }
             }
         });
     };
 
+    let handle_image = move |product_id: i64, file: DroppedFile| async move {
+        uploading_image.set(true);
+        use base64::Engine as _;
+        let encoded = base64::engine::general_purpose::STANDARD.encode(&file.bytes);
+        match upload_product_image(product_id, encoded, file.content_type).await {
+            Ok(product) => {
+                editing_product.set(Some(product));
+                reload();
+                toast.success("Image uploaded".to_string(), ToastOptions::new());
+            }
+            Err(err) => report_error(&err),
+        }
+        uploading_image.set(false);
+    };
+
     let handle_delete = move |product_id: i64| {
         spawn(async move {
             match delete_product(product_id).await {
                 Ok(()) => {
-                    products.restart();
+                    reload();
                     show_sheet.set(false);
-                    toast.success("Product deleted".to_string(), ToastOptions::new());
+                    toast.success(
+                        "Product deleted".to_string(),
+                        ToastOptions::new().duration(UNDO_DELETE_WINDOW).action(
+                            "Undo",
+                            move |_| {
+                                spawn(async move {
+                                    match restore_product(product_id).await {
+                                        Ok(_) => reload(),
+                                        Err(err) => report_error(&err),
+                                    }
+                                });
+                            },
+                        ),
+                    );
                 }
                 Err(err) => {
-                    toast.error(
-                        shared_types::AppError::friendly_message(&err.to_string()),
-                        ToastOptions::new(),
-                    );
+                    report_error(&err);
                 }
             }
         });
     };
 
-    let product_list = products.read();
-    let all_products: Vec<Product> = match product_list.as_ref() {
-        Some(Ok(list)) => list.clone(),
-        _ => vec![],
-    };
-    let is_loading = product_list.is_none();
+    let first_page = products.read();
+    let all_products = loaded_products(
+        first_page.as_ref().and_then(|r| r.as_ref().ok()),
+        &more_pages.read(),
+    );
+    let is_loading = first_page.is_none();
 
     let query = search_query();
     let cat = category_filter();
     let pmax = price_max();
     let dafter = date_after();
 
-    let filtered_all = filter_products(&all_products, "all", &query, &cat, pmax, dafter);
-    let filtered_active = filter_products(&all_products, "active", &query, &cat, pmax, dafter);
-    let filtered_archived = filter_products(&all_products, "archived", &query, &cat, pmax, dafter);
+    let category_options = category_options(&all_products);
+
+    let statuses = status_filter();
+    let filtered = filter_products(&all_products, &statuses, &query, &cat, pmax, dafter);
 
     rsx! {
         document::Link { rel: "stylesheet", href: asset!("./products.css") }
@@ -181,7 +421,7 @@ pub fn Products() -> Element {
                 }
                 Button {
                     variant: ButtonVariant::Primary,
-                    onclick: open_create,
+                    onclick: move |_| open_create(),
                     "New Product"
                 }
             }
@@ -195,6 +435,7 @@ pub fn Products() -> Element {
                     value: search_query(),
                     placeholder: "Search products...",
                     label: "",
+                    debounce_ms: 150,
                     on_input: move |evt: FormEvent| search_query.set(evt.value()),
                 }
             }
@@ -215,21 +456,16 @@ pub fn Products() -> Element {
                         div {
                             class: "filter-control filter-field",
                             Label { html_for: "category-filter", "Category" }
-                            SelectRoot::<String> {
+                            Combobox::<String> {
+                                id: "category-filter",
+                                options: category_options,
+                                value: Some(cat.clone()),
+                                placeholder: "Search categories...",
                                 on_value_change: move |val: Option<String>| {
                                     if let Some(v) = val {
                                         category_filter.set(v);
                                     }
                                 },
-                                SelectTrigger {
-                                    SelectValue {}
-                                }
-                                SelectContent {
-                                    SelectItem::<String> { value: "All", index: 0usize, "All" }
-                                    SelectItem::<String> { value: "Hardware", index: 1usize, "Hardware" }
-                                    SelectItem::<String> { value: "Software", index: 2usize, "Software" }
-                                    SelectItem::<String> { value: "Service", index: 3usize, "Service" }
-                                }
                             }
                         }
 
@@ -256,6 +492,11 @@ pub fn Products() -> Element {
                         div {
                             class: "filter-control filter-field",
                             Label { html_for: "date-filter", "Created After" }
+                            DateTextInput {
+                                id: "date-filter",
+                                value: date_after(),
+                                on_change: move |val: Option<Date>| date_after.set(val),
+                            }
                             DatePicker {
                                 selected_date: date_after(),
                                 on_value_change: move |val: Option<Date>| {
@@ -275,6 +516,7 @@ pub fn Products() -> Element {
                                 variant: ButtonVariant::Ghost,
                                 onclick: move |_| {
                                     search_query.set(String::new());
+                                    status_filter.set(HashSet::new());
                                     category_filter.set("All".to_string());
                                     price_max.set(PRICE_SLIDER_MAX);
                                     date_after.set(None);
@@ -286,91 +528,70 @@ pub fn Products() -> Element {
                 }
             }
 
-            // Tabs + view toggle row
-            Tabs {
-                horizontal: true,
-                default_value: "all",
+            // Status filter + view toggle row
+            div {
+                class: "products-toolbar",
                 div {
-                    class: "tabs-toolbar",
-                    TabList {
-                        TabTrigger { value: "all", index: 0usize, "All" }
-                        TabTrigger { value: "active", index: 1usize, "Active" }
-                        TabTrigger { value: "archived", index: 2usize, "Archived" }
-                    }
-                    div {
-                        class: "view-toggle-row",
-                        ToggleGroup {
-                            horizontal: true,
-                            default_pressed: std::collections::HashSet::from([0]),
-                            on_pressed_change: move |pressed: std::collections::HashSet<usize>| {
-                                if pressed.contains(&0) {
-                                    view_mode.set("grid".to_string());
-                                } else if pressed.contains(&1) {
-                                    view_mode.set("list".to_string());
-                                }
-                            },
-                            ToggleGroupItem { index: 0usize, "Grid" }
-                            ToggleGroupItem { index: 1usize, "List" }
-                        }
+                    class: "status-filter",
+                    MultiSelect::<ProductStatus> {
+                        options: status_options(),
+                        value: statuses,
+                        placeholder: "All statuses",
+                        aria_label: "Filter by status",
+                        on_change: move |selected: HashSet<ProductStatus>| status_filter.set(selected),
                     }
                 }
-
-                TabContent { value: "all", index: 0usize,
-                    if is_loading {
-                        {render_skeletons()}
-                    } else if filtered_all.is_empty() {
-                        {render_empty_state()}
-                    } else {
-                        ProductGrid {
-                            products: filtered_all.clone(),
-                            view_mode: view_mode(),
-                            editing_product,
-                            form_name,
-                            form_description,
-                            form_price,
-                            form_category,
-                            form_status,
-                            show_sheet,
-                        }
+                div {
+                    class: "view-toggle-row",
+                    ToggleGroup {
+                        horizontal: true,
+                        selection: ToggleSelection::Single,
+                        default_pressed: HashSet::from([0]),
+                        on_select: move |index: usize| {
+                            view_mode.set(if index == 0 { "grid" } else { "list" }.to_string());
+                        },
+                        ToggleGroupItem { index: 0usize, "Grid" }
+                        ToggleGroupItem { index: 1usize, "List" }
                     }
                 }
+            }
 
-                TabContent { value: "active", index: 1usize,
-                    if is_loading {
-                        {render_skeletons()}
-                    } else if filtered_active.is_empty() {
-                        {render_empty_state()}
-                    } else {
-                        ProductGrid {
-                            products: filtered_active.clone(),
-                            view_mode: view_mode(),
-                            editing_product,
-                            form_name,
-                            form_description,
-                            form_price,
-                            form_category,
-                            form_status,
-                            show_sheet,
-                        }
-                    }
+            if is_loading {
+                {render_skeletons(6)}
+            } else if filtered.is_empty() && cursor().is_none() {
+                {render_empty_state()}
+            } else {
+                ProductGrid {
+                    products: filtered,
+                    view_mode: view_mode(),
+                    editing_product,
+                    form,
+                    show_sheet,
                 }
+            }
 
-                TabContent { value: "archived", index: 2usize,
-                    if is_loading {
-                        {render_skeletons()}
-                    } else if filtered_archived.is_empty() {
-                        {render_empty_state()}
+            // Keyed by cursor so each page mounts a fresh sentinel (and
+            // observer); it disappears once the last page is loaded.
+            for before_id in cursor() {
+                div {
+                    key: "{before_id}",
+                    id: SENTINEL_ID,
+                    class: "products-sentinel",
+                    onmounted: move |_| {
+                        spawn(async move {
+                            let mut observer = document::eval(&observe_sentinel_script());
+                            if observer.recv::<bool>().await.is_ok() {
+                                load_more();
+                            }
+                        });
+                    },
+                    if loading_more() {
+                        {render_skeletons(3)}
                     } else {
-                        ProductGrid {
-                            products: filtered_archived.clone(),
-                            view_mode: view_mode(),
-                            editing_product,
-                            form_name,
-                            form_description,
-                            form_price,
-                            form_category,
-                            form_status,
-                            show_sheet,
+                        Button {
+                            variant: ButtonVariant::Ghost,
+                            onclick: move |_| load_more(),
+                            "Load more"
                         }
                     }
                 }
@@ -402,36 +623,58 @@ pub fn Products() -> Element {
                         div {
                             class: "sheet-form",
 
-                            Input {
-                                label: "Name",
-                                value: form_name(),
-                                on_input: move |evt: FormEvent| form_name.set(evt.value()),
-                                placeholder: "Product name",
+                            {
+                                let name = form.register("name");
+                                rsx! {
+                                    Input {
+                                        label: "Name",
+                                        value: name.value,
+                                        on_input: name.on_input,
+                                        placeholder: "Product name",
+                                    }
+                                    if let Some(err) = name.error {
+                                        div { class: "sheet-field-error", "{err}" }
+                                    }
+                                }
                             }
 
-                            Textarea {
-                                variant: TextareaVariant::Default,
-                                value: form_description(),
-                                on_input: move |evt: FormEvent| form_description.set(evt.value()),
-                                placeholder: "Product description",
-                                label: "Description",
+                            {
+                                let description = form.register("description");
+                                rsx! {
+                                    Textarea {
+                                        variant: TextareaVariant::Default,
+                                        value: description.value,
+                                        on_input: description.on_input,
+                                        placeholder: "Product description",
+                                        label: "Description",
+                                    }
+                                }
                             }
 
-                            Input {
-                                label: "Price",
-                                value: form_price(),
-                                on_input: move |evt: FormEvent| form_price.set(evt.value()),
-                                placeholder: "0.00",
+                            {
+                                let price = form.register("price");
+                                rsx! {
+                                    Input {
+                                        label: "Price",
+                                        value: price.value,
+                                        mask: NumberMask::currency(),
+                                        on_value: move |v: MaskedValue| form.set("price", v.raw),
+                                        placeholder: "0.00",
+                                    }
+                                    if let Some(err) = price.error {
+                                        div { class: "sheet-field-error", "{err}" }
+                                    }
+                                }
                             }
 
                             div {
                                 class: "sheet-field",
                                 Label { html_for: "form-category", "Category" }
                                 SelectRoot::<String> {
-                                    default_value: Some(form_category()),
+                                    default_value: Some(form.value("category")),
                                     on_value_change: move |val: Option<String>| {
                                         if let Some(v) = val {
-                                            form_category.set(v);
+                                            form.set("category", v);
                                         }
                                     },
                                     SelectTrigger {
@@ -449,8 +692,8 @@ pub fn Products() -> Element {
                                 class: "sheet-field",
                                 Label { html_for: "form-status", "Status" }
                                 RadioGroup {
-                                    default_value: form_status(),
-                                    on_value_change: move |val: String| form_status.set(val),
+                                    default_value: form.value("status"),
+                                    on_value_change: move |val: String| form.set("status", val),
                                     div {
                                         class: "status-radio-group",
                                         label {
@@ -471,6 +714,42 @@ pub fn Products() -> Element {
                                     }
                                 }
                             }
+
+                            // Images attach to a saved product, so only edits offer one.
+                            if let Some(product) = editing_product() {
+                                div {
+                                    class: "sheet-field",
+                                    Label { html_for: "form-image", "Image" }
+                                    if let Some(src) = product_image_src(&product) {
+                                        img {
+                                            class: "product-image-preview",
+                                            src: "{src}",
+                                            alt: "{product.name}",
+                                        }
+                                    }
+                                    FileDropzone {
+                                        accept: PRODUCT_IMAGE_ACCEPT,
+                                        max_size: PRODUCT_IMAGE_MAX_SIZE,
+                                        disabled: uploading_image(),
+                                        on_file: move |file: DroppedFile| handle_image(product.id, file),
+                                        on_error: move |rejection: FileRejection| {
+                                            toast.error(rejection.to_string(), ToastOptions::new());
+                                        },
+                                        if uploading_image() {
+                                            "Uploading..."
+                                        } else {
+                                            "Drop an image here or click to upload"
+                                        }
+                                    }
+                                }
+                            }
+
+                            if let Some(product) = editing_product() {
+                                ProductHistoryPanel {
+                                    product_id: product.id,
+                                    updated_at: product.updated_at,
+                                }
+                            }
                         }
 
                         Separator {}
@@ -496,7 +775,8 @@ pub fn Products() -> Element {
 
                                 Button {
                                     variant: ButtonVariant::Primary,
-                                    "Save"
+                                    disabled: form.is_submitting(),
+                                    if form.is_submitting() { "Saving..." } else { "Save" }
                                 }
                             }
                         }
@@ -507,17 +787,87 @@ pub fn Products() -> Element {
     }
 }
 
+/// Date and time of a server timestamp, to the minute.
+fn short_timestamp(timestamp: &str) -> &str {
+    timestamp.get(..16).unwrap_or(timestamp)
+}
+
+/// What an audit entry in a product's history did, and who did it.
+fn history_entry_label(entry: &AuditEntry) -> String {
+    let action = match entry.action {
+        AuditAction::ProductCreate => "Created",
+        AuditAction::ProductUpdate => "Updated",
+        AuditAction::ProductDelete => "Deleted",
+        AuditAction::TierChange | AuditAction::UserDelete => "Changed",
+    };
+    match entry.actor_id {
+        Some(id) => format!("{action} by user #{id}"),
+        None => format!("{action} anonymously"),
+    }
+}
+
+/// Collapsible created/updated times of the product being edited, with its
+/// audit entries for the owner and admins. Reloads when `updated_at` moves.
+#[component]
+fn ProductHistoryPanel(product_id: i64, updated_at: String) -> Element {
+    let history = use_resource(use_reactive!(|product_id, updated_at| async move {
+        let _ = updated_at; // only a reload trigger
+        get_product_history(product_id).await
+    }));
+
+    rsx! {
+        div {
+            class: "sheet-field product-history",
+            Collapsible {
+                CollapsibleTrigger {
+                    Button { variant: ButtonVariant::Ghost, "History" }
+                }
+                CollapsibleContent {
+                    match &*history.read() {
+                        Some(Ok(history)) => rsx! {
+                            dl {
+                                class: "product-history-times",
+                                dt { "Created" }
+                                dd { {short_timestamp(&history.created_at)} }
+                                dt { "Last changed" }
+                                dd { {short_timestamp(&history.updated_at)} }
+                            }
+                            if let Some(entries) = &history.entries {
+                                ul {
+                                    class: "product-history-entries",
+                                    for entry in entries.iter() {
+                                        li {
+                                            key: "{entry.id}",
+                                            span { {history_entry_label(entry)} }
+                                            time { {short_timestamp(&entry.created_at)} }
+                                        }
+                                    }
+                                }
+                            }
+                        },
+                        Some(Err(e)) => rsx! {
+                            p {
+                                class: "product-history-error",
+                                {shared_types::AppError::friendly_message(&e.to_string())}
+                            }
+                        },
+                        None => rsx! {
+                            Skeleton { style: "height: 16px; width: 60%;" }
+                        },
+                    }
+                }
+            }
+        }
+    }
+}
+
 /// Grid or list of product cards.
 #[component]
 fn ProductGrid(
     products: Vec<Product>,
     view_mode: String,
     mut editing_product: Signal<Option<Product>>,
-    mut form_name: Signal<String>,
-    mut form_description: Signal<String>,
-    mut form_price: Signal<String>,
-    mut form_category: Signal<String>,
-    mut form_status: Signal<String>,
+    mut form: UseForm<ProductDraft>,
     mut show_sheet: Signal<bool>,
 ) -> Element {
     let is_grid = view_mode == "grid";
@@ -538,20 +888,29 @@ fn ProductGrid(
                         div {
                             class: "product-card-link",
                             onclick: move |_| {
-                                let pp = p.clone();
-                                form_name.set(pp.name.clone());
-                                form_description.set(pp.description.clone());
-                                form_price.set(format!("{:.2}", pp.price));
-                                form_category.set(pp.category.clone());
-                                form_status.set(pp.status.clone());
-                                editing_product.set(Some(pp));
+                                form.reset(product_form_values(Some(&p)));
+                                editing_product.set(Some(p.clone()));
                                 show_sheet.set(true);
                             },
                             Card {
+                                if let Some(src) = product_image_src(product) {
+                                    img {
+                                        class: "product-card-image",
+                                        src: "{src}",
+                                        alt: "{product.name}",
+                                        loading: "lazy",
+                                    }
+                                }
                                 CardHeader {
                                     div {
                                         class: "product-card-header",
-                                        CardTitle { "{product.name}" }
+                                        CardTitle {
+                                            Link {
+                                                to: Route::ProductDetail { id: product.id },
+                                                class: "product-detail-link",
+                                                "{product.name}"
+                                            }
+                                        }
                                         Badge { variant: variant, "{product.status}" }
                                     }
                                 }
@@ -560,7 +919,7 @@ fn ProductGrid(
                                         class: "product-card-body",
                                         p {
                                             class: "product-price",
-                                            "${product.price:.2}"
+                                            "{product.price}"
                                         }
                                         p {
                                             class: "product-category",
@@ -581,12 +940,12 @@ fn ProductGrid(
     }
 }
 
-/// Renders placeholder skeletons while product data is loading.
-fn render_skeletons() -> Element {
+/// Renders `count` placeholder cards while product data is loading.
+fn render_skeletons(count: usize) -> Element {
     rsx! {
         div {
             class: "skeleton-grid",
-            for _ in 0..6 {
+            for _ in 0..count {
                 Card {
                     CardHeader {
                         Skeleton { style: "height: 24px; width: 60%;" }
@@ -621,3 +980,53 @@ fn render_empty_state() -> Element {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn form(price: &str) -> FormValues {
+        product_form_values(None)
+            .into_iter()
+            .chain([("name", "Widget".to_string()), ("price", price.to_string())])
+            .map(|(name, value)| (name.to_string(), value))
+            .collect()
+    }
+
+    #[test]
+    fn non_numeric_price_blocks_save_with_inline_error() {
+        let errors = check_product_form(&form("abc")).unwrap_err();
+        assert_eq!(
+            errors.get("price").map(String::as_str),
+            Some("Price must be a non-negative number")
+        );
+        assert!(!errors.contains_key("name"));
+    }
+
+    #[test]
+    fn negative_price_is_rejected_like_the_server() {
+        assert!(check_product_form(&form("-1")).is_err());
+    }
+
+    #[test]
+    fn history_entries_name_the_action_and_actor() {
+        let entry = AuditEntry {
+            id: 1,
+            actor_id: Some(7),
+            action: AuditAction::ProductUpdate,
+            target: "product:3".to_string(),
+            metadata: Default::default(),
+            created_at: "2026-03-01 12:34:56.789 +00:00:00".to_string(),
+        };
+        assert_eq!(history_entry_label(&entry), "Updated by user #7");
+        assert_eq!(short_timestamp(&entry.created_at), "2026-03-01 12:34");
+        assert_eq!(short_timestamp("today"), "today");
+    }
+
+    #[test]
+    fn valid_price_produces_a_draft() {
+        let draft = check_product_form(&form(" 19.99 ")).unwrap();
+        assert_eq!(draft.price, Money::from_cents(1999));
+        assert_eq!(draft.name, "Widget");
+    }
+}
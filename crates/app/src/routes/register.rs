@@ -1,20 +1,25 @@
 use crate::auth::use_auth;
+use crate::routes::login::OAuthButtons;
 use crate::routes::Route;
 use dioxus::prelude::*;
 use shared_ui::{
-    Button, ButtonVariant, Card, CardContent, CardDescription, CardFooter, CardHeader, CardTitle,
-    Input, Label, Separator,
+    Card, CardContent, CardDescription, CardFooter, CardHeader, CardTitle, Input, Label,
 };
 use std::collections::HashMap;
 
 /// Register page with email/password and OAuth options.
+///
+/// `invite` comes from the `?invite=` query string so invite links can
+/// pre-fill the invite code on invite-only deployments.
 #[component]
-pub fn Register() -> Element {
+pub fn Register(invite: String) -> Element {
     let mut auth = use_auth();
     let mut username = use_signal(String::new);
     let mut email = use_signal(String::new);
     let mut password = use_signal(String::new);
     let mut display_name = use_signal(String::new);
+    let mut invite_token = use_signal(|| invite.clone());
+    use_effect(use_reactive!(|invite| invite_token.set(invite)));
     let mut error_msg = use_signal(|| Option::<String>::None);
     let mut field_errors = use_signal(HashMap::<String, String>::new);
     let mut loading = use_signal(|| false);
@@ -30,7 +35,8 @@ pub fn Register() -> Element {
         error_msg.set(None);
         field_errors.set(HashMap::new());
 
-        match server::api::register(username(), email(), password(), display_name()).await {
+        let invite = Some(invite_token().trim().to_string()).filter(|t| !t.is_empty());
+        match server::api::register(username(), email(), password(), display_name(), invite).await {
             Ok(user) => {
                 auth.set_user(user);
                 navigator().push(Route::Dashboard {});
@@ -48,24 +54,6 @@ pub fn Register() -> Element {
         loading.set(false);
     };
 
-    let handle_oauth = move |provider: &'static str| {
-        move |_: MouseEvent| {
-            let provider = provider.to_string();
-            spawn(async move {
-                match server::api::oauth_authorize_url(provider).await {
-                    Ok(url) => {
-                        navigator().push(NavigationTarget::<Route>::External(url));
-                    }
-                    Err(e) => {
-                        error_msg.set(Some(shared_types::AppError::friendly_message(
-                            &e.to_string(),
-                        )));
-                    }
-                }
-            });
-        }
-    };
-
     rsx! {
         document::Link { rel: "stylesheet", href: asset!("./login.css") }
 
@@ -83,28 +71,7 @@ pub fn Register() -> Element {
                         div { class: "auth-error", "{err}" }
                     }
 
-                    // OAuth buttons
-                    div { class: "auth-oauth-buttons",
-                        Button {
-                            variant: ButtonVariant::Outline,
-                            class: "auth-oauth-btn",
-                            onclick: handle_oauth("google"),
-                            "Continue with Google"
-                        }
-                        Button {
-                            variant: ButtonVariant::Outline,
-                            class: "auth-oauth-btn",
-                            onclick: handle_oauth("github"),
-                            "Continue with GitHub"
-                        }
-                    }
-
-                    // Divider
-                    div { class: "auth-divider",
-                        Separator {}
-                        span { class: "auth-divider-text", "or" }
-                        Separator {}
-                    }
+                    OAuthButtons { on_error: move |msg| error_msg.set(Some(msg)) }
 
                     // Registration form
                     form { onsubmit: handle_register,
@@ -158,6 +125,18 @@ pub fn Register() -> Element {
                                 div { class: "auth-field-error", "{err}" }
                             }
                         }
+                        div { class: "auth-field",
+                            Label { html_for: "invite_token", "Invite Code (if required)" }
+                            Input {
+                                id: "invite_token",
+                                placeholder: "Paste your invite code",
+                                value: invite_token(),
+                                on_input: move |e: FormEvent| invite_token.set(e.value()),
+                            }
+                            if let Some(err) = field_errors().get("invite_token") {
+                                div { class: "auth-field-error", "{err}" }
+                            }
+                        }
                         button {
                             r#type: "submit",
                             class: "auth-submit button",
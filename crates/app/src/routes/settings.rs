@@ -1,19 +1,53 @@
 use crate::auth::use_auth;
+use crate::errors::report_error;
+use crate::routes::Route;
+use crate::theme_sync::save_theme;
+use crate::tier_gate::TierGate;
+use crate::unsaved_guard::{fields_differ, use_unsaved_guard};
 use crate::ProfileState;
 use dioxus::prelude::*;
+use shared_types::{AccountDeletion, LoginMethods, UserPreferences, UserTier};
+use shared_ui::theme::{ThemeFamily, ThemeState};
 use shared_ui::{
     use_toast, Accordion, AccordionContent, AccordionItem, AccordionTrigger, AlertDialogAction,
     AlertDialogActions, AlertDialogCancel, AlertDialogContent, AlertDialogDescription,
     AlertDialogRoot, AlertDialogTitle, Avatar, AvatarFallback, AvatarImage, Badge, BadgeVariant,
     Button, ButtonVariant, Calendar, CalendarGrid, CalendarHeader, CalendarMonthTitle,
     CalendarNavigation, CalendarNextMonthButton, CalendarPreviousMonthButton, CalendarSelectMonth,
-    CalendarSelectYear, Collapsible, CollapsibleContent, CollapsibleTrigger, Date, Form, Input,
-    Label, MenubarContent, MenubarItem, MenubarMenu, MenubarRoot, MenubarSeparator, MenubarTrigger,
-    SelectContent, SelectItem, SelectRoot, SelectTrigger, SelectValue, Separator, Sheet,
-    SheetClose, SheetContent, SheetDescription, SheetFooter, SheetHeader, SheetSide, SheetTitle,
-    Switch, SwitchThumb, Textarea, ToastOptions, Toggle, UtcDateTime,
+    CalendarSelectYear, Collapsible, CollapsibleContent, CollapsibleTrigger, Date, DroppedFile,
+    FileDropzone, FileRejection, Form, Input, Label, MenubarContent, MenubarItem, MenubarMenu,
+    MenubarRoot, MenubarSeparator, MenubarTrigger, SelectContent, SelectItem, SelectRoot,
+    SelectTrigger, SelectValue, Separator, Sheet, SheetClose, SheetContent, SheetDescription,
+    SheetFooter, SheetHeader, SheetSide, SheetTitle, Switch, SwitchThumb, Textarea, ToastOptions,
+    Toggle, UtcDateTime,
 };
 
+/// Avatar uploads accepted by the server.
+const AVATAR_ACCEPT: &str = "image/jpeg,image/png,image/webp";
+
+/// Largest avatar the upload form will send.
+const AVATAR_MAX_SIZE: u64 = 2 * 1024 * 1024;
+
+/// How long preference toggles must settle before they are saved.
+const PREFERENCES_SAVE_DELAY_MS: u32 = 600;
+
+/// The date part of a scheduled deletion, e.g. `2026-03-01`.
+fn deletion_date(deletion: &AccountDeletion) -> &str {
+    deletion
+        .scheduled_for
+        .split_whitespace()
+        .next()
+        .unwrap_or(&deletion.scheduled_for)
+}
+
+/// Wait using the webview's timer, so it works on web, desktop, and mobile.
+async fn sleep_ms(ms: u32) {
+    let _ = document::eval(&format!(
+        "await new Promise(resolve => setTimeout(resolve, {ms})); return null;"
+    ))
+    .await;
+}
+
 /// Settings page with menubar navigation, accordion sections, and advanced collapsible.
 #[component]
 pub fn Settings() -> Element {
@@ -21,13 +55,23 @@ pub fn Settings() -> Element {
     let mut auth = use_auth();
 
     // Theme state (shared with layout via context)
-    let mut theme_state: shared_ui::theme::ThemeState = use_context();
+    let theme_state: ThemeState = use_context();
 
     // Profile state (shared with layout via context)
     let profile: ProfileState = use_context();
     // Local editable signals for form fields, initialized from profile memos
     let mut profile_name = use_signal(move || (profile.display_name)());
     let mut profile_email = use_signal(move || (profile.email)());
+    let profile_dirty = use_memo(move || {
+        fields_differ(
+            &[profile_name().as_str(), profile_email().as_str()],
+            &[
+                (profile.display_name)().as_str(),
+                (profile.email)().as_str(),
+            ],
+        )
+    });
+    use_unsaved_guard(profile_dirty);
 
     // Profile save state
     let mut saving = use_signal(|| false);
@@ -37,17 +81,35 @@ pub fn Settings() -> Element {
     // Avatar upload state
     let mut uploading_avatar = use_signal(|| false);
 
+    // Whether the signed-in user still has to verify their email
+    let needs_verification = use_memo(move || {
+        auth.current_user
+            .read()
+            .as_ref()
+            .is_some_and(|user| user.needs_email_verification())
+    });
+    let mut resending_verification = use_signal(|| false);
+
     // Avatar popup state
     let mut avatar_popup_open = use_signal(|| false);
 
-    // Appearance state
-    let mut animations_enabled = use_signal(|| true);
-    let mut compact_mode = use_signal(|| false);
-
-    // Notification state
-    let mut email_notifs = use_signal(|| true);
-    let mut push_notifs = use_signal(|| false);
-    let mut weekly_digest = use_signal(|| true);
+    // Appearance and notification preferences, loaded from the server
+    let mut preferences = use_signal(UserPreferences::default);
+    let mut preferences_save_seq = use_signal(|| 0u64);
+    use_future(move || async move {
+        if let Ok(saved) = server::api::get_preferences().await {
+            preferences.set(saved);
+        }
+    });
+
+    // Sign-in methods for the security section
+    let mut login_methods = use_signal(LoginMethods::default);
+    let mut unlinking = use_signal(|| false);
+    use_future(move || async move {
+        if let Ok(methods) = server::api::get_login_methods().await {
+            login_methods.set(methods);
+        }
+    });
 
     // Calendar state
     let mut selected_date = use_signal(|| None::<Date>);
@@ -58,11 +120,39 @@ pub fn Settings() -> Element {
     let mut event_title = use_signal(String::new);
     let mut event_notes = use_signal(String::new);
 
-    // Delete account dialog state
+    // Delete account dialog state, and any deletion already scheduled
     let mut delete_dialog_open = use_signal(|| false);
+    let mut pending_deletion = use_signal(|| Option::<AccountDeletion>::None);
+    use_future(move || async move {
+        if let Ok(pending) = server::api::get_account_deletion().await {
+            pending_deletion.set(pending);
+        }
+    });
 
     let toast = use_toast();
 
+    // Apply a change immediately and save it once toggling settles
+    let mut set_preferences = move |mut updated: UserPreferences| {
+        // The theme is saved separately; don't send back a stale copy of it
+        updated.theme_family = (theme_state.family)();
+        updated.theme_dark = (theme_state.is_dark)();
+        preferences.set(updated.clone());
+        let seq = preferences_save_seq() + 1;
+        preferences_save_seq.set(seq);
+        spawn(async move {
+            sleep_ms(PREFERENCES_SAVE_DELAY_MS).await;
+            if preferences_save_seq() != seq {
+                return;
+            }
+            if let Err(e) = server::api::update_preferences(updated).await {
+                toast.error(
+                    shared_types::AppError::friendly_message(&e.to_string()),
+                    ToastOptions::new(),
+                );
+            }
+        });
+    };
+
     rsx! {
         document::Link { rel: "stylesheet", href: asset!("./settings.css") }
 
@@ -137,6 +227,32 @@ pub fn Settings() -> Element {
                         }
                     }
                 }
+
+                MenubarSeparator {}
+
+                MenubarMenu {
+                    index: 3usize,
+                    MenubarTrigger { "Premium" }
+                    MenubarContent {
+                        TierGate {
+                            required: UserTier::Premium,
+                            fallback: rsx! {
+                                MenubarItem { index: 0usize, value: "upgrade",
+                                    on_select: move |_: String| { toast.info("Upgrade to Premium to unlock these features".to_string(), ToastOptions::new()); },
+                                    "Upgrade to unlock"
+                                }
+                            },
+                            MenubarItem { index: 0usize, value: "analytics",
+                                on_select: move |_: String| { navigator().push(Route::Analytics {}); },
+                                "Analytics"
+                            }
+                            MenubarItem { index: 1usize, value: "export",
+                                on_select: move |_: String| { toast.info("Export selected".to_string(), ToastOptions::new()); },
+                                "Export Data"
+                            }
+                        }
+                    }
+                }
             }
 
             Separator {}
@@ -172,49 +288,69 @@ pub fn Settings() -> Element {
                                         }
                                     }
                                 }
-                                label {
-                                    class: if uploading_avatar() { "button avatar-upload-label disabled" } else { "button avatar-upload-label" },
-                                    "data-style": "outline",
-                                    input {
-                                        r#type: "file",
-                                        accept: "image/jpeg,image/png,image/webp",
-                                        class: "avatar-upload-input",
-                                        onchange: move |evt: FormEvent| async move {
-                                            uploading_avatar.set(true);
-                                            let files = evt.files();
-                                            if let Some(file) = files.first() {
-                                                if file.size() > 2 * 1024 * 1024 {
-                                                    toast.error("Avatar must be under 2 MB".to_string(), ToastOptions::new());
-                                                } else {
-                                                    let content_type = file.content_type()
-                                                        .unwrap_or_else(|| "image/jpeg".to_string());
-                                                    match file.read_bytes().await {
-                                                        Ok(bytes) => {
-                                                            use base64::Engine as _;
-                                                            let encoded = base64::engine::general_purpose::STANDARD.encode(&bytes);
-                                                            match server::api::upload_user_avatar(encoded, content_type).await {
-                                                                Ok(user) => {
-                                                                    auth.set_user(user);
-                                                                    toast.success("Avatar uploaded".to_string(), ToastOptions::new());
-                                                                }
-                                                                Err(e) => {
-                                                                    toast.error(
-                                                                        shared_types::AppError::friendly_message(&e.to_string()),
-                                                                        ToastOptions::new(),
-                                                                    );
-                                                                }
-                                                            }
-                                                        }
-                                                        Err(_) => {
-                                                            toast.error("Failed to read file".to_string(), ToastOptions::new());
-                                                        }
+                                FileDropzone {
+                                    class: "avatar-dropzone",
+                                    accept: AVATAR_ACCEPT,
+                                    max_size: AVATAR_MAX_SIZE,
+                                    disabled: uploading_avatar(),
+                                    on_file: move |file: DroppedFile| async move {
+                                        uploading_avatar.set(true);
+                                        use base64::Engine as _;
+                                        let encoded = base64::engine::general_purpose::STANDARD.encode(&file.bytes);
+                                        match server::api::upload_user_avatar(encoded, file.content_type).await {
+                                            Ok(user) => {
+                                                auth.set_user(user);
+                                                toast.success("Avatar uploaded".to_string(), ToastOptions::new());
+                                            }
+                                            Err(e) => {
+                                                toast.error(
+                                                    shared_types::AppError::friendly_message(&e.to_string()),
+                                                    ToastOptions::new(),
+                                                );
+                                            }
+                                        }
+                                        uploading_avatar.set(false);
+                                    },
+                                    on_error: move |rejection: FileRejection| {
+                                        toast.error(rejection.to_string(), ToastOptions::new());
+                                    },
+                                    if uploading_avatar() {
+                                        "Uploading..."
+                                    } else {
+                                        "Drop an image here or click to upload"
+                                    }
+                                }
+                            }
+
+                            if needs_verification() {
+                                div {
+                                    class: "settings-verify-banner",
+                                    p {
+                                        class: "settings-hint",
+                                        "Your email address isn't verified yet. Check your inbox for the verification link."
+                                    }
+                                    Button {
+                                        variant: ButtonVariant::Outline,
+                                        disabled: resending_verification(),
+                                        onclick: move |_| {
+                                            spawn(async move {
+                                                resending_verification.set(true);
+                                                match server::api::resend_verification_email().await {
+                                                    Ok(()) => {
+                                                        toast.success(
+                                                            "Verification email sent".to_string(),
+                                                            ToastOptions::new(),
+                                                        );
+                                                    }
+                                                    Err(err) => {
+                                                        report_error(&err);
                                                     }
                                                 }
-                                            }
-                                            uploading_avatar.set(false);
+                                                resending_verification.set(false);
+                                            });
                                         },
+                                        if resending_verification() { "Sending..." } else { "Resend Verification Email" }
                                     }
-                                    if uploading_avatar() { "Uploading..." } else { "Upload Avatar" }
                                 }
                             }
 
@@ -332,16 +468,22 @@ pub fn Settings() -> Element {
                                     default_value: Some((theme_state.family)()),
                                     on_value_change: move |val: Option<String>| {
                                         if let Some(v) = val {
-                                            theme_state.family.set(v);
-                                            theme_state.apply();
+                                            let is_dark = (theme_state.is_dark)();
+                                            save_theme(theme_state, ThemeFamily::from_key(&v), is_dark);
                                         }
                                     },
                                     SelectTrigger {
                                         SelectValue {}
                                     }
                                     SelectContent {
-                                        SelectItem::<String> { value: "cyberpunk", index: 0usize, "Cyberpunk" }
-                                        SelectItem::<String> { value: "solar", index: 1usize, "Solarized" }
+                                        for (i, family) in ThemeFamily::all().iter().enumerate() {
+                                            SelectItem::<String> {
+                                                key: "{family.as_str()}",
+                                                value: family.as_str().to_string(),
+                                                index: i,
+                                                "{family.display_name()}"
+                                            }
+                                        }
                                     }
                                 }
                             }
@@ -356,9 +498,12 @@ pub fn Settings() -> Element {
                                     "Enable animations"
                                 }
                                 Toggle {
-                                    pressed: Some(animations_enabled()),
+                                    pressed: Some(preferences().animations_enabled),
                                     on_pressed_change: move |val: bool| {
-                                        animations_enabled.set(val);
+                                        set_preferences(UserPreferences {
+                                            animations_enabled: val,
+                                            ..preferences()
+                                        });
                                     },
                                     "Animations"
                                 }
@@ -374,9 +519,12 @@ pub fn Settings() -> Element {
                                     "Compact mode"
                                 }
                                 Switch {
-                                    checked: Some(compact_mode()),
+                                    checked: Some(preferences().compact_mode),
                                     on_checked_change: move |val: bool| {
-                                        compact_mode.set(val);
+                                        set_preferences(UserPreferences {
+                                            compact_mode: val,
+                                            ..preferences()
+                                        });
                                     },
                                     SwitchThumb {}
                                 }
@@ -402,9 +550,12 @@ pub fn Settings() -> Element {
                                     "Email notifications"
                                 }
                                 Switch {
-                                    checked: Some(email_notifs()),
+                                    checked: Some(preferences().email_notifications),
                                     on_checked_change: move |val: bool| {
-                                        email_notifs.set(val);
+                                        set_preferences(UserPreferences {
+                                            email_notifications: val,
+                                            ..preferences()
+                                        });
                                     },
                                     SwitchThumb {}
                                 }
@@ -420,9 +571,12 @@ pub fn Settings() -> Element {
                                     "Push notifications"
                                 }
                                 Switch {
-                                    checked: Some(push_notifs()),
+                                    checked: Some(preferences().push_notifications),
                                     on_checked_change: move |val: bool| {
-                                        push_notifs.set(val);
+                                        set_preferences(UserPreferences {
+                                            push_notifications: val,
+                                            ..preferences()
+                                        });
                                     },
                                     SwitchThumb {}
                                 }
@@ -438,9 +592,12 @@ pub fn Settings() -> Element {
                                     "Weekly digest"
                                 }
                                 Switch {
-                                    checked: Some(weekly_digest()),
+                                    checked: Some(preferences().weekly_digest),
                                     on_checked_change: move |val: bool| {
-                                        weekly_digest.set(val);
+                                        set_preferences(UserPreferences {
+                                            weekly_digest: val,
+                                            ..preferences()
+                                        });
                                     },
                                     SwitchThumb {}
                                 }
@@ -448,6 +605,67 @@ pub fn Settings() -> Element {
                         }
                     }
                 }
+
+                // AccordionItem 3: Security
+                AccordionItem {
+                    index: 3usize,
+
+                    AccordionTrigger { "Security" }
+                    AccordionContent {
+                        div {
+                            class: "settings-section",
+
+                            if login_methods().oauth_providers.is_empty() {
+                                p { class: "settings-hint", "No linked accounts." }
+                            }
+
+                            for provider in login_methods().oauth_providers {
+                                div {
+                                    key: "{provider.as_str()}",
+                                    class: "settings-toggle-row",
+                                    span {
+                                        class: "settings-toggle-label",
+                                        "{provider.display_name()}"
+                                    }
+                                    Button {
+                                        variant: ButtonVariant::Outline,
+                                        disabled: unlinking() || !login_methods().can_unlink(&provider),
+                                        onclick: move |_| {
+                                            let provider = provider.clone();
+                                            async move {
+                                                unlinking.set(true);
+                                                match server::api::unlink_oauth(provider.as_str().to_string()).await {
+                                                    Ok(methods) => {
+                                                        login_methods.set(methods);
+                                                        toast.success(
+                                                            format!("{} unlinked", provider.display_name()),
+                                                            ToastOptions::new(),
+                                                        );
+                                                    }
+                                                    Err(e) => {
+                                                        toast.error(
+                                                            shared_types::AppError::friendly_message(&e.to_string()),
+                                                            ToastOptions::new(),
+                                                        );
+                                                    }
+                                                }
+                                                unlinking.set(false);
+                                            }
+                                        },
+                                        "Unlink"
+                                    }
+                                }
+                            }
+
+                            if !login_methods().has_password && !login_methods().oauth_providers.is_empty() {
+                                p {
+                                    class: "settings-hint",
+                                    "This is your only way to sign in. Set a password before unlinking it."
+                                }
+                            }
+                        }
+                    }
+                }
             }
 
             Separator {}
@@ -515,16 +733,43 @@ pub fn Settings() -> Element {
                         // Danger zone
                         div {
                             class: "danger-zone-stack",
-                            p {
-                                class: "danger-zone-text",
-                                "Irreversible actions that affect your account permanently."
-                            }
-                            Button {
-                                variant: ButtonVariant::Destructive,
-                                onclick: move |_| {
-                                    delete_dialog_open.set(true);
-                                },
-                                "Delete Account"
+                            if let Some(deletion) = pending_deletion() {
+                                p {
+                                    class: "danger-zone-text",
+                                    "Your account will be deleted on {deletion_date(&deletion)}. Signing in is disabled until then."
+                                }
+                                Button {
+                                    variant: ButtonVariant::Outline,
+                                    onclick: move |_| {
+                                        spawn(async move {
+                                            match server::api::cancel_account_deletion().await {
+                                                Ok(()) => {
+                                                    pending_deletion.set(None);
+                                                    toast.success(
+                                                        "Account deletion cancelled".to_string(),
+                                                        ToastOptions::new(),
+                                                    );
+                                                }
+                                                Err(err) => {
+                                                    report_error(&err);
+                                                }
+                                            }
+                                        });
+                                    },
+                                    "Cancel Deletion"
+                                }
+                            } else {
+                                p {
+                                    class: "danger-zone-text",
+                                    "Irreversible actions that affect your account permanently."
+                                }
+                                Button {
+                                    variant: ButtonVariant::Destructive,
+                                    onclick: move |_| {
+                                        delete_dialog_open.set(true);
+                                    },
+                                    "Delete Account"
+                                }
                             }
                         }
                     }
@@ -536,6 +781,7 @@ pub fn Settings() -> Element {
                 open: event_sheet_open(),
                 on_close: move |_| event_sheet_open.set(false),
                 side: SheetSide::Right,
+                mobile_side: SheetSide::Bottom,
 
                 SheetHeader {
                     SheetTitle {
@@ -638,17 +884,30 @@ pub fn Settings() -> Element {
                 AlertDialogContent {
                     AlertDialogTitle { "Delete Account" }
                     AlertDialogDescription {
-                        "This action cannot be undone. This will permanently delete your account and remove all associated data."
+                        "Your account and all associated data will be permanently deleted after a grace period. Until then you won't be able to sign in, and you can cancel the deletion from this page."
                     }
                     AlertDialogActions {
                         AlertDialogCancel { "Cancel" }
                         AlertDialogAction {
                             on_click: move |_| {
-                                toast.error(
-                                    "Account deletion is not available in this demo.".to_string(),
-                                    ToastOptions::new(),
-                                );
                                 delete_dialog_open.set(false);
+                                spawn(async move {
+                                    match server::api::delete_account().await {
+                                        Ok(deletion) => {
+                                            toast.info(
+                                                format!(
+                                                    "Account scheduled for deletion on {}",
+                                                    deletion_date(&deletion)
+                                                ),
+                                                ToastOptions::new(),
+                                            );
+                                            pending_deletion.set(Some(deletion));
+                                        }
+                                        Err(err) => {
+                                            report_error(&err);
+                                        }
+                                    }
+                                });
                             },
                             "Yes, Delete"
                         }
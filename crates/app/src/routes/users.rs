@@ -1,17 +1,22 @@
-use crate::auth::use_is_admin;
+use crate::auth::{use_auth, use_is_admin};
+use crate::errors::{report_error, report_error_in};
+use crate::routes::{tier_badge_variant, UNDO_DELETE_WINDOW};
 use dioxus::prelude::*;
 use dioxus_free_icons::icons::ld_icons::LdEllipsis;
 use dioxus_free_icons::Icon;
-use server::api::{create_user, delete_user, list_users, update_user, update_user_tier};
-use shared_types::User;
+use server::api::{
+    create_user, delete_user, list_users, restore_user, update_user, update_user_tier,
+};
+use shared_types::{ListParams, User, UserTier};
 use shared_ui::{
     use_toast, AlertDialogAction, AlertDialogActions, AlertDialogCancel, AlertDialogContent,
-    AlertDialogDescription, AlertDialogRoot, AlertDialogTitle, Avatar, AvatarFallback, Badge,
-    BadgeVariant, Button, ButtonVariant, Checkbox, CheckboxIndicator, CheckboxState, ContentAlign,
-    ContextMenu, ContextMenuContent, ContextMenuItem, ContextMenuTrigger, DialogContent,
-    DialogDescription, DialogRoot, DialogTitle, Input, Label, PopoverContent, PopoverRoot,
-    PopoverTrigger, SelectContent, SelectItem, SelectItemIndicator, SelectRoot, SelectTrigger,
-    SelectValue, Separator, ToastOptions, Toolbar, ToolbarButton, ToolbarSeparator,
+    AlertDialogDescription, AlertDialogRoot, AlertDialogTitle, Avatar, AvatarFallback, AvatarImage,
+    Badge, Button, ButtonVariant, Checkbox, CheckboxIndicator, CheckboxState, ContentAlign,
+    ContextMenu, ContextMenuContent, ContextMenuItem, ContextMenuTrigger, DataColumn, DataTable,
+    DialogContent, DialogDescription, DialogRoot, DialogTitle, Input, Label, PopoverContent,
+    PopoverRoot, PopoverTrigger, SelectContent, SelectItem, SelectItemIndicator, SelectRoot,
+    SelectTrigger, SelectValue, SortDirection, SortState, ToastOptions, Toolbar, ToolbarButton,
+    ToolbarSeparator,
 };
 
 /// Extract the first two characters of a name as uppercase initials.
@@ -19,30 +24,51 @@ fn initials(name: &str) -> String {
     name.chars().take(2).collect::<String>().to_uppercase()
 }
 
-/// Map a tier string to its badge variant.
-fn tier_badge_variant(tier: &str) -> BadgeVariant {
-    match tier.to_lowercase().as_str() {
-        "premium" => BadgeVariant::Primary,
-        "elite" => BadgeVariant::Destructive,
-        _ => BadgeVariant::Secondary,
-    }
+/// URL of a user's avatar for the table rows.
+///
+/// The avatar endpoint redirects to the 128px thumbnail when one exists and
+/// renders an initials image otherwise. The last-modified time is added as a
+/// version, so a new upload isn't hidden behind the browser's cached redirect.
+fn user_avatar_src(user: &User) -> String {
+    let version: String = user
+        .updated_at
+        .chars()
+        .filter(char::is_ascii_digit)
+        .collect();
+    format!("/api/v1/users/{}/avatar?v={version}", user.id)
+}
+
+/// Case-insensitive match of a search query against username and display name.
+fn matches_search(user: &User, query: &str) -> bool {
+    let query = query.trim().to_lowercase();
+    query.is_empty()
+        || user.username.to_lowercase().contains(&query)
+        || user.display_name.to_lowercase().contains(&query)
 }
 
-/// Format a tier string for display (capitalized).
-fn tier_display(tier: &str) -> &str {
-    match tier.to_lowercase().as_str() {
-        "premium" => "Premium",
-        "elite" => "Elite",
-        _ => "Free",
+/// Order users by the given DataTable column: display name or tier rank.
+fn sort_users(users: &mut [User], sort: &SortState) {
+    match sort.column.as_str() {
+        "tier" => users.sort_by_key(|u| UserTier::from_str_or_default(&u.tier).rank()),
+        _ => users.sort_by_key(|u| u.display_name.to_lowercase()),
+    }
+    if sort.direction == SortDirection::Descending {
+        users.reverse();
     }
 }
 
 /// Users management page with CRUD operations.
+///
+/// `search` comes from the `?search=` query string so other pages (e.g. the
+/// dashboard's recent activity) can deep-link to a pre-filtered list.
 #[component]
-pub fn Users() -> Element {
-    let mut users = use_server_future(list_users)?;
+pub fn Users(search: String) -> Element {
+    let mut users = use_server_future(|| list_users(ListParams::default()))?;
+    let mut search_query = use_signal(|| search.clone());
+    use_effect(use_reactive!(|search| search_query.set(search)));
     let toast = use_toast();
     let is_admin = use_is_admin();
+    let mut auth = use_auth();
 
     let mut show_create_dialog = use_signal(|| false);
     let mut editing_user: Signal<Option<User>> = use_signal(|| None);
@@ -50,6 +76,7 @@ pub fn Users() -> Element {
     let mut selected_ids: Signal<Vec<i64>> = use_signal(Vec::new);
     let mut form_username = use_signal(String::new);
     let mut form_display_name = use_signal(String::new);
+    let mut sort: Signal<Option<SortState>> = use_signal(|| Some(SortState::new("user")));
 
     let has_selection = !selected_ids.read().is_empty();
 
@@ -67,7 +94,8 @@ pub fn Users() -> Element {
             };
 
             match result {
-                Ok(_) => {
+                Ok(saved) => {
+                    auth.sync_user(&saved);
                     let msg = if editing_user.read().is_some() {
                         "User updated"
                     } else {
@@ -79,36 +107,48 @@ pub fn Users() -> Element {
                     users.restart();
                 }
                 Err(err) => {
-                    toast.error(
-                        shared_types::AppError::friendly_message(&err.to_string()),
-                        ToastOptions::new(),
-                    );
+                    report_error(&err);
                 }
             }
         });
     };
 
+    // Deleted users are only soft-deleted, so the toast can bring them back
+    let deleted_toast = move |message: String, ids: Vec<i64>| {
+        let undo = move |_: ()| {
+            let ids = ids.clone();
+            spawn(async move {
+                for id in ids {
+                    if let Err(err) = restore_user(id).await {
+                        report_error_in(&format!("Failed to restore user {id}"), &err);
+                    }
+                }
+                users.restart();
+            });
+        };
+        toast.success(
+            message,
+            ToastOptions::new()
+                .duration(UNDO_DELETE_WINDOW)
+                .action("Undo", undo),
+        );
+    };
+
     // Handle delete of selected users
     let handle_delete_selected = move |_: MouseEvent| {
         let ids = selected_ids.read().clone();
 
         spawn(async move {
-            let mut had_error = false;
-            for id in &ids {
-                if let Err(err) = delete_user(*id).await {
-                    toast.error(
-                        format!(
-                            "Failed to delete user {id}: {}",
-                            shared_types::AppError::friendly_message(&err.to_string())
-                        ),
-                        ToastOptions::new(),
-                    );
-                    had_error = true;
+            let mut deleted = Vec::new();
+            for id in ids {
+                match delete_user(id).await {
+                    Ok(()) => deleted.push(id),
+                    Err(err) => report_error_in(&format!("Failed to delete user {id}"), &err),
                 }
             }
-            if !had_error {
-                let count = ids.len();
-                toast.success(format!("{count} user(s) deleted"), ToastOptions::new());
+            if !deleted.is_empty() {
+                let count = deleted.len();
+                deleted_toast(format!("{count} user(s) deleted"), deleted);
             }
             selected_ids.set(Vec::new());
             show_delete_confirm.set(false);
@@ -116,8 +156,229 @@ pub fn Users() -> Element {
         });
     };
 
+    let query = search_query();
     let user_list = users.read();
-    let user_list = user_list.as_ref().and_then(|r| r.as_ref().ok());
+    let user_list = user_list
+        .as_ref()
+        .and_then(|r| r.as_ref().ok())
+        .map(|list| {
+            let mut list = list
+                .iter()
+                .filter(|u| matches_search(u, &query))
+                .cloned()
+                .collect::<Vec<User>>();
+            if let Some(state) = sort.read().as_ref() {
+                sort_users(&mut list, state);
+            }
+            list
+        });
+    let loading = user_list.is_none();
+    let empty_message = if query.trim().is_empty() {
+        "No users found. Click \"Add User\" to create one.".to_string()
+    } else {
+        format!("No users match \"{query}\".")
+    };
+
+    let columns = vec![
+        DataColumn::new("select", "", move |user: User| {
+            let user_id = user.id;
+            let is_checked = selected_ids.read().contains(&user_id);
+            rsx! {
+                Checkbox {
+                    default_checked: if is_checked { CheckboxState::Checked } else { CheckboxState::Unchecked },
+                    on_checked_change: move |state: CheckboxState| {
+                        let mut ids = selected_ids.write();
+                        match state {
+                            CheckboxState::Checked => {
+                                if !ids.contains(&user_id) {
+                                    ids.push(user_id);
+                                }
+                            }
+                            _ => {
+                                ids.retain(|&id| id != user_id);
+                            }
+                        }
+                    },
+                    CheckboxIndicator {
+                        span { "\u{2713}" }
+                    }
+                }
+            }
+        }),
+        DataColumn::new("user", "User", move |user: User| {
+            let user_id = user.id;
+            let user_for_ctx_edit = user.clone();
+            let display_initials = initials(&user.display_name);
+            let avatar_src = user_avatar_src(&user);
+            rsx! {
+                ContextMenu {
+                    ContextMenuTrigger {
+                        div {
+                            class: "user-cell",
+                            Avatar {
+                                AvatarImage { src: avatar_src }
+                                AvatarFallback { "{display_initials}" }
+                            }
+                            div {
+                                class: "user-info",
+                                span {
+                                    class: "user-display-name",
+                                    "{user.display_name}"
+                                }
+                                span {
+                                    class: "user-username",
+                                    "@{user.username}"
+                                }
+                            }
+                        }
+                    }
+
+                    ContextMenuContent {
+                        ContextMenuItem {
+                            value: "edit",
+                            index: 0usize,
+                            on_select: move |_: String| {
+                                let u = user_for_ctx_edit.clone();
+                                form_username.set(u.username.clone());
+                                form_display_name.set(u.display_name.clone());
+                                editing_user.set(Some(u));
+                                show_create_dialog.set(true);
+                            },
+                            "Edit"
+                        }
+                        ContextMenuItem {
+                            value: "delete",
+                            index: 1usize,
+                            on_select: move |_: String| {
+                                spawn(async move {
+                                    match delete_user(user_id).await {
+                                        Ok(()) => {
+                                            deleted_toast("User deleted".to_string(), vec![user_id]);
+                                            selected_ids.write().retain(|&id| id != user_id);
+                                            users.restart();
+                                        }
+                                        Err(err) => {
+                                            report_error(&err);
+                                        }
+                                    }
+                                });
+                            },
+                            "Delete"
+                        }
+                    }
+                }
+            }
+        })
+        .sortable(),
+        DataColumn::new("tier", "Tier", move |user: User| {
+            let row_user_id = user.id;
+            let tier_str = user.tier;
+            rsx! {
+                div {
+                    class: "user-tier",
+                    if is_admin {
+                        {
+                            let current_tier = tier_str.to_lowercase();
+                            rsx! {
+                                SelectRoot::<String> {
+                                    default_value: current_tier.clone(),
+                                    placeholder: "Tier",
+                                    on_value_change: move |val: Option<String>| {
+                                        if let Some(new_tier) = val {
+                                            spawn(async move {
+                                                match update_user_tier(row_user_id, new_tier.clone()).await {
+                                                    Ok(updated) => {
+                                                        // Refresh the sidebar badge if this is our own row
+                                                        auth.sync_user(&updated);
+                                                        let label = UserTier::from_str_or_default(&new_tier).display_name();
+                                                        toast.success(
+                                                            format!("Tier updated to {label}"),
+                                                            ToastOptions::new(),
+                                                        );
+                                                        users.restart();
+                                                    }
+                                                    Err(err) => {
+                                                        report_error_in("Failed to update tier", &err);
+                                                    }
+                                                }
+                                            });
+                                        }
+                                    },
+                                    SelectTrigger {
+                                        aria_label: "Change tier",
+                                        SelectValue {}
+                                    }
+                                    SelectContent {
+                                        aria_label: "Tier options",
+                                        SelectItem::<String> {
+                                            value: "free",
+                                            index: 0usize,
+                                            "Free"
+                                            SelectItemIndicator { "\u{2713}" }
+                                        }
+                                        SelectItem::<String> {
+                                            value: "premium",
+                                            index: 1usize,
+                                            "Premium"
+                                            SelectItemIndicator { "\u{2713}" }
+                                        }
+                                        SelectItem::<String> {
+                                            value: "elite",
+                                            index: 2usize,
+                                            "Elite"
+                                            SelectItemIndicator { "\u{2713}" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    } else {
+                        {
+                            let tier = UserTier::from_str_or_default(&tier_str);
+                            rsx! {
+                                Badge {
+                                    variant: tier_badge_variant(&tier),
+                                    "{tier.display_name()}"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        })
+        .sortable(),
+        DataColumn::new("actions", "", move |user: User| {
+            rsx! {
+                PopoverRoot {
+                    PopoverTrigger {
+                        Icon::<LdEllipsis> { icon: LdEllipsis, width: 18, height: 18 }
+                    }
+                    PopoverContent {
+                        align: ContentAlign::End,
+                        div {
+                            class: "popover-details",
+                            span {
+                                class: "popover-name",
+                                "{user.display_name}"
+                            }
+                            span {
+                                class: "popover-meta",
+                                "Username: {user.username}"
+                            }
+                            span {
+                                class: "popover-meta",
+                                "ID: {user.id}"
+                            }
+                            span {
+                                class: "popover-meta",
+                                "Joined: {user.joined_date()}"
+                            }
+                        }
+                    }
+                }
+            }
+        }),
+    ];
 
     rsx! {
         document::Link { rel: "stylesheet", href: asset!("./users.css") }
@@ -149,214 +410,29 @@ pub fn Users() -> Element {
                 }
             }
 
-            // User List
+            // Search bar
             div {
-                class: "users-list",
-
-                    if let Some(user_vec) = user_list {
-                        if user_vec.is_empty() {
-                            div {
-                                class: "users-empty",
-                                "No users found. Click \"Add User\" to create one."
-                            }
-                        } else {
-                            for user in user_vec.iter() {
-                                {
-                                    let user_id = user.id;
-                                    let user_clone = user.clone();
-                                    let user_for_edit = user.clone();
-                                    let user_for_ctx_edit = user.clone();
-                                    let display_initials = initials(&user.display_name);
-                                    let is_checked = selected_ids.read().contains(&user_id);
-
-                                    rsx! {
-                                        ContextMenu {
-                                            ContextMenuTrigger {
-                                                div {
-                                                    class: "user-row",
-
-                                                    Checkbox {
-                                                        default_checked: if is_checked { CheckboxState::Checked } else { CheckboxState::Unchecked },
-                                                        on_checked_change: move |state: CheckboxState| {
-                                                            let mut ids = selected_ids.write();
-                                                            match state {
-                                                                CheckboxState::Checked => {
-                                                                    if !ids.contains(&user_id) {
-                                                                        ids.push(user_id);
-                                                                    }
-                                                                }
-                                                                _ => {
-                                                                    ids.retain(|&id| id != user_id);
-                                                                }
-                                                            }
-                                                        },
-                                                        CheckboxIndicator {
-                                                            span { "\u{2713}" }
-                                                        }
-                                                    }
-
-                                                    Avatar {
-                                                        AvatarFallback { "{display_initials}" }
-                                                    }
-
-                                                    div {
-                                                        class: "user-info",
-                                                        span {
-                                                            class: "user-display-name",
-                                                            "{user_clone.display_name}"
-                                                        }
-                                                        span {
-                                                            class: "user-username",
-                                                            "@{user_clone.username}"
-                                                        }
-                                                    }
-
-                                                    {
-                                                        let tier_str = user_clone.tier.clone();
-                                                        let row_user_id = user_id;
-                                                        rsx! {
-                                                            div {
-                                                                class: "user-tier",
-                                                                if is_admin {
-                                                                    {
-                                                                        let current_tier = tier_str.to_lowercase();
-                                                                        rsx! {
-                                                                            SelectRoot::<String> {
-                                                                                default_value: current_tier.clone(),
-                                                                                placeholder: "Tier",
-                                                                                on_value_change: move |val: Option<String>| {
-                                                                                    if let Some(new_tier) = val {
-                                                                                        spawn(async move {
-                                                                                            match update_user_tier(row_user_id, new_tier.clone()).await {
-                                                                                                Ok(_) => {
-                                                                                                    let label = tier_display(&new_tier);
-                                                                                                    toast.success(
-                                                                                                        format!("Tier updated to {label}"),
-                                                                                                        ToastOptions::new(),
-                                                                                                    );
-                                                                                                    users.restart();
-                                                                                                }
-                                                                                                Err(err) => {
-                                                                                                    toast.error(
-                                                                                                        format!("Failed to update tier: {}", shared_types::AppError::friendly_message(&err.to_string())),
-                                                                                                        ToastOptions::new(),
-                                                                                                    );
-                                                                                                }
-                                                                                            }
-                                                                                        });
-                                                                                    }
-                                                                                },
-                                                                                SelectTrigger {
-                                                                                    aria_label: "Change tier",
-                                                                                    SelectValue {}
-                                                                                }
-                                                                                SelectContent {
-                                                                                    aria_label: "Tier options",
-                                                                                    SelectItem::<String> {
-                                                                                        value: "free",
-                                                                                        index: 0usize,
-                                                                                        "Free"
-                                                                                        SelectItemIndicator { "\u{2713}" }
-                                                                                    }
-                                                                                    SelectItem::<String> {
-                                                                                        value: "premium",
-                                                                                        index: 1usize,
-                                                                                        "Premium"
-                                                                                        SelectItemIndicator { "\u{2713}" }
-                                                                                    }
-                                                                                    SelectItem::<String> {
-                                                                                        value: "elite",
-                                                                                        index: 2usize,
-                                                                                        "Elite"
-                                                                                        SelectItemIndicator { "\u{2713}" }
-                                                                                    }
-                                                                                }
-                                                                            }
-                                                                        }
-                                                                    }
-                                                                } else {
-                                                                    Badge {
-                                                                        variant: tier_badge_variant(&tier_str),
-                                                                        "{tier_display(&tier_str)}"
-                                                                    }
-                                                                }
-                                                            }
-                                                        }
-                                                    }
-
-                                                    PopoverRoot {
-                                                        PopoverTrigger {
-                                                            Icon::<LdEllipsis> { icon: LdEllipsis, width: 18, height: 18 }
-                                                        }
-                                                        PopoverContent {
-                                                        align: ContentAlign::End,
-                                                            div {
-                                                                class: "popover-details",
-                                                                span {
-                                                                    class: "popover-name",
-                                                                    "{user_for_edit.display_name}"
-                                                                }
-                                                                span {
-                                                                    class: "popover-meta",
-                                                                    "Username: {user_for_edit.username}"
-                                                                }
-                                                                span {
-                                                                    class: "popover-meta",
-                                                                    "ID: {user_id}"
-                                                                }
-                                                            }
-                                                        }
-                                                    }
-                                                }
-                                            }
-
-                                            ContextMenuContent {
-                                                ContextMenuItem {
-                                                    value: "edit",
-                                                    index: 0usize,
-                                                    on_select: move |_: String| {
-                                                        let u = user_for_ctx_edit.clone();
-                                                        form_username.set(u.username.clone());
-                                                        form_display_name.set(u.display_name.clone());
-                                                        editing_user.set(Some(u));
-                                                        show_create_dialog.set(true);
-                                                    },
-                                                    "Edit"
-                                                }
-                                                ContextMenuItem {
-                                                    value: "delete",
-                                                    index: 1usize,
-                                                    on_select: move |_: String| {
-                                                        spawn(async move {
-                                                            match delete_user(user_id).await {
-                                                                Ok(()) => {
-                                                                    toast.success("User deleted".to_string(), ToastOptions::new());
-                                                                    selected_ids.write().retain(|&id| id != user_id);
-                                                                    users.restart();
-                                                                }
-                                                                Err(err) => {
-                                                                    toast.error(shared_types::AppError::friendly_message(&err.to_string()), ToastOptions::new());
-                                                                }
-                                                            }
-                                                        });
-                                                    },
-                                                    "Delete"
-                                                }
-                                            }
-                                        }
-
-                                        Separator {}
-                                    }
-                                }
-                            }
-                        }
-                    } else {
-                        div {
-                            class: "users-empty",
-                            "Loading users..."
-                        }
-                    }
+                class: "users-search",
+                Input {
+                    value: search_query(),
+                    placeholder: "Search users...",
+                    label: "",
+                    debounce_ms: 150,
+                    on_input: move |evt: FormEvent| search_query.set(evt.value()),
                 }
+            }
+
+            // User List
+            DataTable::<User> {
+                class: "users-table",
+                rows: user_list.unwrap_or_default(),
+                columns,
+                sort: sort(),
+                on_sort: move |state: SortState| sort.set(Some(state)),
+                row_key: move |user: User| user.id.to_string(),
+                loading,
+                empty_message,
+            }
 
             // Create / Edit Dialog
             DialogRoot {
@@ -0,0 +1,269 @@
+use dioxus::prelude::*;
+use shared_types::{Money, Product};
+
+use crate::routes::Route;
+
+/// Site name used in page titles and `og:site_name`.
+pub const SITE_NAME: &str = "Dioxus App";
+
+/// Description for pages that don't have their own.
+const DEFAULT_DESCRIPTION: &str = "A full-stack Rust app built with Dioxus.";
+
+/// Longest description put in meta tags; link previews cut them short anyway.
+const MAX_DESCRIPTION_CHARS: usize = 200;
+
+/// What a page looks like when shared: title, description, and preview image.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageMeta {
+    pub title: String,
+    pub description: String,
+    /// Path of the page, e.g. `/products/3`.
+    pub path: String,
+    /// Preview image, as a path on this site or an absolute URL.
+    pub image: Option<String>,
+    /// Price shown in product previews.
+    pub price: Option<Money>,
+}
+
+impl PageMeta {
+    fn new(title: &str, description: &str, path: String) -> Self {
+        Self {
+            title: title.to_string(),
+            description: description.to_string(),
+            path,
+            image: None,
+            price: None,
+        }
+    }
+
+    /// Defaults for `route`, before any page data has loaded.
+    pub fn for_route(route: &Route) -> Self {
+        let path = route.to_string();
+        match route {
+            Route::Login {} => Self::new("Sign in", "Sign in to your account.", path),
+            Route::Register { .. } => Self::new("Create an account", DEFAULT_DESCRIPTION, path),
+            Route::Dashboard {} => Self::new("Dashboard", DEFAULT_DESCRIPTION, path),
+            Route::Users { .. } => Self::new("Users", DEFAULT_DESCRIPTION, path),
+            Route::Products {} => Self::new("Products", "Browse the product catalog.", path),
+            Route::ProductDetail { .. } => Self::new("Product", DEFAULT_DESCRIPTION, path),
+            Route::Analytics {} => Self::new("Analytics", DEFAULT_DESCRIPTION, path),
+            Route::Settings {} => Self::new("Settings", DEFAULT_DESCRIPTION, path),
+            Route::NotFound { .. } => Self::new("Page not found", DEFAULT_DESCRIPTION, path),
+        }
+    }
+
+    /// Product detail page, previewed with the server-generated product image.
+    pub fn for_product(product: &Product) -> Self {
+        let description = if product.description.trim().is_empty() {
+            format!("{} · {}", product.category, product.price)
+        } else {
+            truncate(product.description.trim(), MAX_DESCRIPTION_CHARS)
+        };
+        Self {
+            title: product.name.clone(),
+            description,
+            path: Route::ProductDetail { id: product.id }.to_string(),
+            image: Some(format!("/api/v1/products/{}/image", product.id)),
+            price: Some(product.price),
+        }
+    }
+
+    /// Text for the `<title>` element.
+    pub fn document_title(&self) -> String {
+        format!("{} | {}", self.title, SITE_NAME)
+    }
+}
+
+/// One `<meta>` tag: Open Graph tags use `property`, Twitter cards use `name`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetaTag {
+    Property(&'static str, String),
+    Name(&'static str, String),
+}
+
+/// Open Graph and Twitter card tags for `meta`, with URLs made absolute
+/// against `base_url`.
+pub fn meta_tags(meta: &PageMeta, base_url: &str) -> Vec<MetaTag> {
+    let image = meta.image.as_deref().map(|i| absolute_url(base_url, i));
+    let mut tags = vec![
+        MetaTag::Name("description", meta.description.clone()),
+        MetaTag::Property("og:site_name", SITE_NAME.to_string()),
+        MetaTag::Property(
+            "og:type",
+            if meta.price.is_some() {
+                "product"
+            } else {
+                "website"
+            }
+            .to_string(),
+        ),
+        MetaTag::Property("og:title", meta.title.clone()),
+        MetaTag::Property("og:description", meta.description.clone()),
+        MetaTag::Property("og:url", absolute_url(base_url, &meta.path)),
+    ];
+    if let Some(image) = &image {
+        tags.push(MetaTag::Property("og:image", image.clone()));
+    }
+    if let Some(price) = meta.price {
+        tags.push(MetaTag::Property(
+            "product:price:amount",
+            price.to_decimal_string(),
+        ));
+        tags.push(MetaTag::Property(
+            "product:price:currency",
+            "USD".to_string(),
+        ));
+    }
+
+    let card = if image.is_some() {
+        "summary_large_image"
+    } else {
+        "summary"
+    };
+    tags.push(MetaTag::Name("twitter:card", card.to_string()));
+    tags.push(MetaTag::Name("twitter:title", meta.title.clone()));
+    tags.push(MetaTag::Name(
+        "twitter:description",
+        meta.description.clone(),
+    ));
+    if let Some(image) = image {
+        tags.push(MetaTag::Name("twitter:image", image));
+    }
+    tags
+}
+
+/// `path` joined onto `base_url`; absolute URLs are returned unchanged.
+fn absolute_url(base_url: &str, path: &str) -> String {
+    if path.starts_with("http://") || path.starts_with("https://") {
+        return path.to_string();
+    }
+    format!("{}{}", base_url.trim_end_matches('/'), path)
+}
+
+/// `text` cut to at most `max` characters, ending in an ellipsis if cut.
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(max - 1).collect();
+    format!("{}…", cut.trim_end())
+}
+
+/// Public origin of the site from `PUBLIC_BASE_URL`, read during SSR.
+fn public_base_url() -> String {
+    #[cfg(feature = "server")]
+    {
+        server::site::SiteConfig::from_env().base_url
+    }
+    #[cfg(not(feature = "server"))]
+    {
+        String::new()
+    }
+}
+
+/// Title, description, Open Graph, and Twitter card tags for the current page.
+///
+/// Rendered during SSR so link previews work without running the app.
+#[component]
+pub fn SeoHead(meta: PageMeta) -> Element {
+    let base_url = use_server_cached(public_base_url);
+    let tags = meta_tags(&meta, &base_url);
+
+    rsx! {
+        document::Title { "{meta.document_title()}" }
+        for tag in tags {
+            {
+                match tag {
+                    MetaTag::Property(property, content) => rsx! {
+                        document::Meta { property: property.to_string(), content }
+                    },
+                    MetaTag::Name(name, content) => rsx! {
+                        document::Meta { name: name.to_string(), content }
+                    },
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared_types::ProductStatus;
+
+    fn product() -> Product {
+        Product {
+            id: 42,
+            name: "Quantum Keyboard".to_string(),
+            description: "Mechanical keyboard with per-key RGB.".to_string(),
+            price: Money::from_cents(14950),
+            category: "Hardware".to_string(),
+            status: ProductStatus::Active,
+            created_at: "2026-01-01 00:00:00".to_string(),
+            updated_at: "2026-01-01 00:00:00".to_string(),
+            image_url: None,
+            is_favorited: None,
+            deleted_at: None,
+        }
+    }
+
+    #[test]
+    fn product_route_gets_product_tags() {
+        let meta = PageMeta::for_product(&product());
+        let tags = meta_tags(&meta, "https://app.example.com/");
+        let expected = [
+            MetaTag::Name(
+                "description",
+                "Mechanical keyboard with per-key RGB.".to_string(),
+            ),
+            MetaTag::Property("og:site_name", SITE_NAME.to_string()),
+            MetaTag::Property("og:type", "product".to_string()),
+            MetaTag::Property("og:title", "Quantum Keyboard".to_string()),
+            MetaTag::Property(
+                "og:description",
+                "Mechanical keyboard with per-key RGB.".to_string(),
+            ),
+            MetaTag::Property("og:url", "https://app.example.com/products/42".to_string()),
+            MetaTag::Property(
+                "og:image",
+                "https://app.example.com/api/v1/products/42/image".to_string(),
+            ),
+            MetaTag::Property("product:price:amount", "149.50".to_string()),
+            MetaTag::Property("product:price:currency", "USD".to_string()),
+            MetaTag::Name("twitter:card", "summary_large_image".to_string()),
+            MetaTag::Name("twitter:title", "Quantum Keyboard".to_string()),
+            MetaTag::Name(
+                "twitter:description",
+                "Mechanical keyboard with per-key RGB.".to_string(),
+            ),
+            MetaTag::Name(
+                "twitter:image",
+                "https://app.example.com/api/v1/products/42/image".to_string(),
+            ),
+        ];
+        assert_eq!(tags, expected);
+        assert_eq!(meta.document_title(), "Quantum Keyboard | Dioxus App");
+    }
+
+    #[test]
+    fn default_route_has_no_image_or_price() {
+        let meta = PageMeta::for_route(&Route::Products {});
+        let tags = meta_tags(&meta, "https://app.example.com");
+        assert!(tags.contains(&MetaTag::Property("og:type", "website".to_string())));
+        assert!(tags.contains(&MetaTag::Name("twitter:card", "summary".to_string())));
+        assert!(!tags
+            .iter()
+            .any(|t| matches!(t, MetaTag::Property("og:image", _))));
+    }
+
+    #[test]
+    fn long_product_description_is_truncated() {
+        let product = Product {
+            description: "a".repeat(500),
+            ..product()
+        };
+        let meta = PageMeta::for_product(&product);
+        assert_eq!(meta.description.chars().count(), MAX_DESCRIPTION_CHARS);
+        assert!(meta.description.ends_with('…'));
+    }
+}
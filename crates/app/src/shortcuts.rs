@@ -0,0 +1,339 @@
+use dioxus::prelude::*;
+use shared_ui::{DialogContent, DialogDescription, DialogRoot, DialogTitle};
+
+use crate::routes::Route;
+
+/// How long the second key of a chord like `g u` may follow the first.
+const CHORD_TIMEOUT_MS: f64 = 1000.0;
+
+/// Search fields `/` focuses; the first one on the page wins.
+const SEARCH_SELECTOR: &str = ".search-bar input, .users-search input";
+
+/// Something a keyboard shortcut does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShortcutAction {
+    GoDashboard,
+    GoUsers,
+    GoProducts,
+    GoSettings,
+    NewProduct,
+    FocusSearch,
+    ToggleHelp,
+}
+
+impl ShortcutAction {
+    /// The page this action navigates to, for the `Go…` actions.
+    fn route(self) -> Option<Route> {
+        match self {
+            ShortcutAction::GoDashboard => Some(Route::Dashboard {}),
+            ShortcutAction::GoUsers => Some(Route::Users {
+                search: String::new(),
+            }),
+            ShortcutAction::GoProducts => Some(Route::Products {}),
+            ShortcutAction::GoSettings => Some(Route::Settings {}),
+            _ => None,
+        }
+    }
+}
+
+/// A key sequence and what it does. `keys` are `KeyboardEvent.key` values
+/// pressed one after another.
+#[derive(Debug, PartialEq)]
+pub struct Binding {
+    pub keys: &'static [&'static str],
+    pub action: ShortcutAction,
+    pub description: &'static str,
+}
+
+/// Every shortcut, in the order the help overlay lists them.
+pub const BINDINGS: &[Binding] = &[
+    Binding {
+        keys: &["g", "d"],
+        action: ShortcutAction::GoDashboard,
+        description: "Go to Dashboard",
+    },
+    Binding {
+        keys: &["g", "u"],
+        action: ShortcutAction::GoUsers,
+        description: "Go to Users",
+    },
+    Binding {
+        keys: &["g", "p"],
+        action: ShortcutAction::GoProducts,
+        description: "Go to Products",
+    },
+    Binding {
+        keys: &["g", "s"],
+        action: ShortcutAction::GoSettings,
+        description: "Go to Settings",
+    },
+    Binding {
+        keys: &["n"],
+        action: ShortcutAction::NewProduct,
+        description: "New product",
+    },
+    Binding {
+        keys: &["/"],
+        action: ShortcutAction::FocusSearch,
+        description: "Focus search",
+    },
+    Binding {
+        keys: &["?"],
+        action: ShortcutAction::ToggleHelp,
+        description: "Show or hide shortcuts",
+    },
+];
+
+/// Result of feeding a key to a [`ChordMatcher`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyMatch {
+    /// The keys so far complete a binding.
+    Action(ShortcutAction),
+    /// The keys so far start a binding; wait for the next one.
+    Pending,
+    NoMatch,
+}
+
+/// Matches key presses against [`Binding`]s, one key at a time.
+#[derive(Debug, Default)]
+pub struct ChordMatcher {
+    pending: Vec<String>,
+    last_at_ms: f64,
+}
+
+impl ChordMatcher {
+    /// Add `key`, pressed at `at_ms`, to the keys pressed so far.
+    ///
+    /// A chord is abandoned when its next key comes more than
+    /// [`CHORD_TIMEOUT_MS`] later or doesn't continue any binding; in the
+    /// latter case the key is matched again on its own, so `g n` still
+    /// opens a new product.
+    pub fn feed(&mut self, bindings: &[Binding], key: &str, at_ms: f64) -> KeyMatch {
+        if at_ms - self.last_at_ms > CHORD_TIMEOUT_MS {
+            self.pending.clear();
+        }
+        self.last_at_ms = at_ms;
+        self.pending.push(key.to_string());
+
+        let pressed = self.pending.iter().map(String::as_str);
+        if let Some(binding) = bindings
+            .iter()
+            .find(|b| b.keys.iter().copied().eq(pressed.clone()))
+        {
+            self.pending.clear();
+            return KeyMatch::Action(binding.action);
+        }
+        let continues_chord = |b: &&Binding| {
+            b.keys.len() > self.pending.len()
+                && b.keys.iter().zip(&self.pending).all(|(a, b)| a == b)
+        };
+        if bindings.iter().any(|b| continues_chord(&b)) {
+            return KeyMatch::Pending;
+        }
+
+        let retry = self.pending.len() > 1;
+        self.pending.clear();
+        if retry {
+            self.feed(bindings, key, at_ms)
+        } else {
+            KeyMatch::NoMatch
+        }
+    }
+}
+
+/// Script forwarding key presses as `[key, timestamp]`.
+///
+/// Keys typed into a field are left alone so shortcuts never eat text;
+/// Escape leaves the field, after which shortcuts work again. Presses with
+/// Ctrl, Alt or Meta are the browser's.
+fn key_listener_script() -> String {
+    r#"
+    if (window.__shortcutListener) {
+        document.removeEventListener('keydown', window.__shortcutListener);
+    }
+    window.__shortcutListener = (event) => {
+        if (event.ctrlKey || event.metaKey || event.altKey || event.repeat) return;
+        const el = event.target;
+        const typing = el && (el.isContentEditable
+            || ['INPUT', 'TEXTAREA', 'SELECT'].includes(el.tagName));
+        if (typing) {
+            if (event.key === 'Escape') el.blur();
+            return;
+        }
+        if (event.key === '/' || event.key === '?') event.preventDefault();
+        dioxus.send([event.key, event.timeStamp]);
+    };
+    document.addEventListener('keydown', window.__shortcutListener);
+    "#
+    .to_string()
+}
+
+fn focus_search_script() -> String {
+    format!(
+        "const el = document.querySelector('{SEARCH_SELECTOR}'); if (el) {{ el.focus(); el.select(); }}"
+    )
+}
+
+/// State shortcuts share with the pages they act on.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Shortcuts {
+    /// Whether the shortcut help overlay is open.
+    pub help_open: Signal<bool>,
+    /// Set by `n`; the Products page opens its "New Product" sheet and
+    /// clears it.
+    pub new_product: Signal<bool>,
+    current_route: Signal<Route>,
+}
+
+impl Shortcuts {
+    /// Do what `action`'s keys do, e.g. when picked from the command palette.
+    pub fn run(mut self, action: ShortcutAction) {
+        if let Some(route) = action.route() {
+            navigator().push(route);
+            return;
+        }
+        match action {
+            ShortcutAction::NewProduct => {
+                self.new_product.set(true);
+                if !matches!(*self.current_route.peek(), Route::Products {}) {
+                    navigator().push(Route::Products {});
+                }
+            }
+            ShortcutAction::FocusSearch => {
+                document::eval(&focus_search_script());
+            }
+            ShortcutAction::ToggleHelp => self.help_open.toggle(),
+            _ => {}
+        }
+    }
+}
+
+/// Listen for the [`BINDINGS`] and act on them. Call once, in the layout
+/// around the signed-in pages; it provides [`Shortcuts`] to them.
+pub fn use_keyboard_shortcuts() -> Shortcuts {
+    let route: Route = use_route();
+    let shortcuts = use_context_provider(|| Shortcuts {
+        help_open: Signal::new(false),
+        new_product: Signal::new(false),
+        current_route: Signal::new(route.clone()),
+    });
+    let mut current_route = shortcuts.current_route;
+    use_effect(use_reactive!(|route| current_route.set(route)));
+
+    use_future(move || async move {
+        let mut matcher = ChordMatcher::default();
+        let mut eval = document::eval(&key_listener_script());
+        while let Ok((key, at_ms)) = eval.recv::<(String, f64)>().await {
+            if let KeyMatch::Action(action) = matcher.feed(BINDINGS, &key, at_ms) {
+                shortcuts.run(action);
+            }
+        }
+    });
+
+    shortcuts
+}
+
+/// Overlay listing the keyboard shortcuts, opened with `?`.
+#[component]
+pub fn ShortcutHelp() -> Element {
+    let Shortcuts { mut help_open, .. } = use_context();
+
+    rsx! {
+        DialogRoot {
+            open: help_open(),
+            on_open_change: move |open: bool| help_open.set(open),
+            DialogContent {
+                DialogTitle { "Keyboard Shortcuts" }
+                DialogDescription { "Shortcuts work anywhere except while typing in a field; press Esc to leave one. Ctrl+K (⌘K on macOS) opens the command palette from anywhere." }
+                dl {
+                    class: "shortcut-help",
+                    for binding in BINDINGS {
+                        dt {
+                            key: "{binding.description}",
+                            for (i, key) in binding.keys.iter().enumerate() {
+                                if i > 0 { " then " }
+                                kbd { "{key}" }
+                            }
+                        }
+                        dd { "{binding.description}" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed_all(matcher: &mut ChordMatcher, keys: &[(&str, f64)]) -> Vec<KeyMatch> {
+        keys.iter()
+            .map(|(key, at)| matcher.feed(BINDINGS, key, *at))
+            .collect()
+    }
+
+    #[test]
+    fn chords_and_single_keys_match() {
+        let mut matcher = ChordMatcher::default();
+        assert_eq!(
+            feed_all(&mut matcher, &[("g", 0.0), ("u", 200.0), ("n", 400.0)]),
+            [
+                KeyMatch::Pending,
+                KeyMatch::Action(ShortcutAction::GoUsers),
+                KeyMatch::Action(ShortcutAction::NewProduct),
+            ]
+        );
+        assert_eq!(
+            matcher.feed(BINDINGS, "/", 500.0),
+            KeyMatch::Action(ShortcutAction::FocusSearch)
+        );
+    }
+
+    #[test]
+    fn broken_chords_fall_back_to_the_last_key() {
+        let mut matcher = ChordMatcher::default();
+        assert_eq!(
+            feed_all(&mut matcher, &[("g", 0.0), ("n", 100.0)]),
+            [
+                KeyMatch::Pending,
+                KeyMatch::Action(ShortcutAction::NewProduct)
+            ]
+        );
+        assert_eq!(
+            feed_all(&mut matcher, &[("g", 200.0), ("x", 300.0), ("p", 400.0)]),
+            [KeyMatch::Pending, KeyMatch::NoMatch, KeyMatch::NoMatch]
+        );
+    }
+
+    #[test]
+    fn chords_time_out() {
+        let mut matcher = ChordMatcher::default();
+        assert_eq!(
+            feed_all(&mut matcher, &[("g", 0.0), ("p", CHORD_TIMEOUT_MS + 1.0)]),
+            [KeyMatch::Pending, KeyMatch::NoMatch]
+        );
+    }
+
+    #[test]
+    fn bindings_are_unambiguous() {
+        for (i, a) in BINDINGS.iter().enumerate() {
+            for b in &BINDINGS[i + 1..] {
+                let shared = a.keys.len().min(b.keys.len());
+                assert_ne!(
+                    a.keys[..shared],
+                    b.keys[..shared],
+                    "{:?} and {:?} overlap",
+                    a.keys,
+                    b.keys
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn navigation_actions_have_routes() {
+        assert_eq!(ShortcutAction::GoProducts.route(), Some(Route::Products {}));
+        assert_eq!(ShortcutAction::NewProduct.route(), None);
+    }
+}
@@ -0,0 +1,50 @@
+use dioxus::prelude::*;
+use shared_ui::theme::{applied_theme, ThemeFamily, ThemeState};
+
+use crate::auth::use_auth;
+
+/// Provide [`ThemeState`] and keep it in step with the saved theme.
+///
+/// The theme cookie is a local cache: `ThemeSeed` applies it before the app
+/// hydrates, and the signals start from whatever it applied. Once a user is
+/// signed in, the theme stored on the server wins and is re-applied, which
+/// also rewrites the cookie.
+pub fn use_theme_sync() {
+    let mut theme = use_context_provider(ThemeState::new);
+    let auth = use_auth();
+
+    use_future(move || async move {
+        if let Some(applied) = applied_theme().await {
+            let (family, is_dark) = ThemeFamily::from_theme(&applied);
+            theme.family.set(family.as_str().to_string());
+            theme.is_dark.set(is_dark);
+        }
+    });
+
+    let user_id = use_memo(move || auth.current_user.read().as_ref().map(|u| u.id));
+    use_effect(move || {
+        if user_id().is_none() {
+            return;
+        }
+        spawn(async move {
+            if let Ok(prefs) = server::api::get_preferences().await {
+                let family = ThemeFamily::from_key(&prefs.theme_family);
+                let unchanged = *theme.family.peek() == family.as_str()
+                    && *theme.is_dark.peek() == prefs.theme_dark;
+                if !unchanged {
+                    theme.set(family, prefs.theme_dark);
+                }
+            }
+        });
+    });
+}
+
+/// Apply a theme change and save it for the signed-in user.
+pub fn save_theme(mut theme: ThemeState, family: ThemeFamily, is_dark: bool) {
+    theme.set(family, is_dark);
+    spawn(async move {
+        if let Err(e) = server::api::update_theme(family.as_str().to_string(), is_dark).await {
+            dioxus::logger::tracing::warn!(error = %e, "failed to save theme");
+        }
+    });
+}
@@ -13,9 +13,28 @@ pub fn use_tier_check(required: &UserTier) -> bool {
 }
 
 /// Conditionally render children based on user tier.
-/// Shows `fallback` if the user's tier is insufficient.
+/// Shows `fallback` (nothing by default) if the user's tier is insufficient.
+///
+/// Reads the current user from [`use_auth`] and compares tiers with
+/// [`UserTier::has_access`], so higher tiers unlock lower-tier content.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// rsx! {
+///     TierGate {
+///         required: UserTier::Premium,
+///         fallback: rsx! { UpgradePrompt { tier_name: "Premium", feature: "Analytics" } },
+///         AnalyticsSection {}
+///     }
+/// }
+/// ```
 #[component]
-pub fn TierGate(required: UserTier, fallback: Element, children: Element) -> Element {
+pub fn TierGate(
+    required: UserTier,
+    #[props(default = VNode::empty())] fallback: Element,
+    children: Element,
+) -> Element {
     let has_access = use_tier_check(&required);
 
     if has_access {
@@ -0,0 +1,105 @@
+use dioxus::prelude::*;
+
+/// Asked before following a link away from unsaved changes. Embedded in a
+/// script as a string literal, so it must not contain `"` or `\`.
+const LEAVE_PROMPT: &str = "You have unsaved changes. Leave this page and discard them?";
+
+/// Whether edited form fields differ from their saved values.
+///
+/// Surrounding whitespace is ignored, as the server trims it on save, so a
+/// stray space doesn't count as an unsaved change.
+pub fn fields_differ(current: &[&str], saved: &[&str]) -> bool {
+    current.len() != saved.len()
+        || current
+            .iter()
+            .zip(saved)
+            .any(|(current, saved)| current.trim() != saved.trim())
+}
+
+/// Script keeping the page-wide guard's flag in step with `dirty`.
+///
+/// The listeners are installed once per page. Links are caught in the
+/// capture phase, before the router's own click handler runs, so declining
+/// the prompt leaves the router untouched; accepting lets the click through.
+fn guard_script(dirty: bool) -> String {
+    format!(
+        r#"
+        if (!window.__unsavedGuard) {{
+            const guard = {{ dirty: false }};
+            window.__unsavedGuard = guard;
+            window.addEventListener('beforeunload', (event) => {{
+                if (!guard.dirty) return;
+                event.preventDefault();
+                event.returnValue = '';
+            }});
+            document.addEventListener('click', (event) => {{
+                if (!guard.dirty || event.defaultPrevented || event.button !== 0) return;
+                if (event.metaKey || event.ctrlKey || event.shiftKey || event.altKey) return;
+                const link = event.target.closest && event.target.closest('a[href]');
+                if (!link || link.target === '_blank') return;
+                if (window.confirm("{LEAVE_PROMPT}")) {{
+                    guard.dirty = false;
+                }} else {{
+                    event.preventDefault();
+                    event.stopImmediatePropagation();
+                }}
+            }}, true);
+        }}
+        window.__unsavedGuard.dirty = {dirty};
+        "#
+    )
+}
+
+/// Ask before the user leaves a form with unsaved changes.
+///
+/// While `is_dirty` is true, following a link (e.g. in the sidebar) asks for
+/// confirmation first, and closing or reloading the tab triggers the
+/// browser's own "leave site?" prompt. Navigation the app starts itself,
+/// such as redirecting after a save, is not interrupted. The guard is lifted
+/// when the calling component unmounts.
+pub fn use_unsaved_guard(is_dirty: impl Readable<Target = bool> + 'static) {
+    use_effect(move || {
+        let dirty = *is_dirty.read();
+        document::eval(&guard_script(dirty));
+    });
+    use_drop(|| {
+        document::eval(&guard_script(false));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unchanged_fields_are_not_dirty() {
+        assert!(!fields_differ(
+            &["Neo", "neo@example.com"],
+            &["Neo", "neo@example.com"]
+        ));
+        assert!(!fields_differ(
+            &[" Neo ", "neo@example.com\n"],
+            &["Neo", "neo@example.com"]
+        ));
+    }
+
+    #[test]
+    fn edited_fields_are_dirty() {
+        assert!(fields_differ(
+            &["Thomas", "neo@example.com"],
+            &["Neo", "neo@example.com"]
+        ));
+        assert!(fields_differ(
+            &["Neo", "neo@matrix.io"],
+            &["Neo", "neo@example.com"]
+        ));
+        assert!(fields_differ(&["Neo", ""], &["Neo", "neo@example.com"]));
+    }
+
+    #[test]
+    fn guard_script_sets_the_flag() {
+        assert!(guard_script(true).contains("__unsavedGuard.dirty = true;"));
+        assert!(guard_script(false).contains("__unsavedGuard.dirty = false;"));
+        assert!(guard_script(true).contains(r#"window.confirm("You have unsaved changes."#));
+    }
+}
@@ -0,0 +1,140 @@
+use shared_types::{
+    AnalyticsScope, AppError, CategoryCount, CategoryRevenue, Money, PremiumAnalytics, Role,
+};
+use sqlx::{Pool, Postgres};
+
+use crate::auth::jwt::Claims;
+use crate::error_convert::SqlxErrorExt;
+
+/// Owner to restrict analytics to for `requested` scope, or `None` for the
+/// whole catalog.
+///
+/// Without an explicit scope admins get the global view and everyone else
+/// their own products. Only admins may ask for `global`.
+pub fn resolve_scope(
+    claims: &Claims,
+    requested: Option<AnalyticsScope>,
+) -> Result<Option<i64>, AppError> {
+    let is_admin = Role::from_str_or_default(&claims.role).is_admin();
+    let scope = requested.unwrap_or(if is_admin {
+        AnalyticsScope::Global
+    } else {
+        AnalyticsScope::Mine
+    });
+    match scope {
+        AnalyticsScope::Mine => Ok(Some(claims.sub)),
+        AnalyticsScope::Global if is_admin => Ok(None),
+        AnalyticsScope::Global => Err(AppError::forbidden(
+            "Admin role required for global analytics",
+        )),
+    }
+}
+
+/// Catalog and signup figures shown on the premium analytics page.
+///
+/// Product figures cover only products created by `owner` when set; the
+/// signup count is always site-wide. Revenue, in total and per category,
+/// counts active products only. Prices are summed as whole cents, so totals
+/// carry no floating-point error.
+pub async fn premium_analytics(
+    pool: &Pool<Postgres>,
+    owner: Option<i64>,
+) -> Result<PremiumAnalytics, AppError> {
+    let total_revenue = sqlx::query_scalar!(
+        r#"SELECT COALESCE(SUM(ROUND(price * 100)), 0)::BIGINT AS "cents!" FROM products WHERE status = 'active' AND deleted_at IS NULL AND ($1::BIGINT IS NULL OR owner_id = $1)"#,
+        owner
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?;
+
+    let avg_product_price = sqlx::query_scalar!(
+        r#"SELECT COALESCE(ROUND(AVG(ROUND(price * 100))), 0)::BIGINT AS "cents!" FROM products WHERE deleted_at IS NULL AND ($1::BIGINT IS NULL OR owner_id = $1)"#,
+        owner
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?;
+
+    let category_rows = sqlx::query!(
+        "SELECT category, COUNT(*) as count FROM products WHERE deleted_at IS NULL AND ($1::BIGINT IS NULL OR owner_id = $1) GROUP BY category ORDER BY count DESC",
+        owner
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?;
+
+    let products_by_category: Vec<CategoryCount> = category_rows
+        .into_iter()
+        .map(|r| CategoryCount {
+            category: r.category,
+            count: r.count.unwrap_or(0),
+        })
+        .collect();
+
+    let users_last_30_days = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM users WHERE deleted_at IS NULL AND created_at >= NOW() - INTERVAL '30 days'"
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?
+    .unwrap_or(0);
+
+    let revenue_rows = sqlx::query!(
+        r#"SELECT category, COALESCE(SUM(ROUND(price * 100)), 0)::BIGINT AS "revenue_cents!" FROM products WHERE status = 'active' AND deleted_at IS NULL AND ($1::BIGINT IS NULL OR owner_id = $1) GROUP BY category ORDER BY 2 DESC, category"#,
+        owner
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?;
+
+    let revenue_by_category: Vec<CategoryRevenue> = revenue_rows
+        .into_iter()
+        .map(|r| CategoryRevenue {
+            category: r.category,
+            revenue: Money::from_cents(r.revenue_cents),
+        })
+        .collect();
+
+    Ok(PremiumAnalytics {
+        total_revenue: Money::from_cents(total_revenue),
+        avg_product_price: Money::from_cents(avg_product_price),
+        products_by_category,
+        users_last_30_days,
+        revenue_by_category,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared_types::AppErrorKind;
+
+    fn claims(role: &str) -> Claims {
+        Claims {
+            sub: 7,
+            email: "a@b.com".to_string(),
+            role: role.to_string(),
+            tier: "premium".to_string(),
+            exp: 0,
+            iat: 0,
+            jti: None,
+        }
+    }
+
+    #[test]
+    fn default_scope_depends_on_role() {
+        assert_eq!(resolve_scope(&claims("user"), None).unwrap(), Some(7));
+        assert_eq!(resolve_scope(&claims("admin"), None).unwrap(), None);
+    }
+
+    #[test]
+    fn only_admins_get_global_scope() {
+        let err = resolve_scope(&claims("user"), Some(AnalyticsScope::Global)).unwrap_err();
+        assert_eq!(err.kind, AppErrorKind::Forbidden);
+        assert_eq!(
+            resolve_scope(&claims("admin"), Some(AnalyticsScope::Mine)).unwrap(),
+            Some(7)
+        );
+    }
+}
@@ -1,89 +1,55 @@
+//! Server functions called by the app.
+//!
+//! # Logging
+//!
+//! Each function runs in a `tracing::instrument` span, which records its
+//! arguments. Arguments holding personal data or secrets are skipped so they
+//! never reach logs or traces: emails, passwords, tokens (invite, OAuth,
+//! refresh, email verification), and uploaded file contents. Usernames, display names, and ids are
+//! recorded. New server functions taking any of the former must `skip` them.
+
 use dioxus::prelude::*;
-use shared_types::{AuthUser, DashboardStats, Product, User};
+use shared_types::{
+    AccountDeletion, AuthUser, Cursor, DashboardStats, ListParams, LoginMethods, Money,
+    OAuthProviderInfo, Product, ProductHistory, User, UserPreferences, UserSortField,
+};
 
 #[cfg(feature = "server")]
-use crate::db::get_db;
+use crate::db::{get_db, UserRecord};
 
 #[cfg(feature = "server")]
 use crate::error_convert::{AppErrorExt, SqlxErrorExt, ValidateRequest};
 
+#[cfg(feature = "server")]
+use crate::telemetry::traced_query;
+
 #[cfg(feature = "server")]
 use shared_types::{
-    CreateProductRequest, CreateUserRequest, UpdateProductRequest, UpdateUserRequest, UserTier,
+    CreateProductRequest, CreateUserRequest, ProductStatus, Role, UpdateProductRequest,
+    UpdateUserRequest,
 };
 
 /// Get premium analytics data. Requires Premium tier or above.
+///
+/// `scope` defaults to the caller's own products, or the whole catalog for
+/// admins; only admins may request [`AnalyticsScope::Global`](shared_types::AnalyticsScope::Global).
 #[cfg_attr(feature = "server", tracing::instrument)]
 #[server]
-pub async fn get_premium_analytics() -> Result<shared_types::PremiumAnalytics, ServerFnError> {
-    use crate::auth::{cookies, jwt};
-    use shared_types::AppError;
-
-    // Extract and validate the caller's tier from the access token
-    let ctx = dioxus::fullstack::FullstackContext::current();
-    let headers = ctx.as_ref().map(|c| c.parts_mut().headers.clone());
-
-    let headers = headers
-        .ok_or_else(|| AppError::unauthorized("Authentication required").into_server_fn_error())?;
-
-    let token = cookies::extract_access_token(&headers)
-        .ok_or_else(|| AppError::unauthorized("Authentication required").into_server_fn_error())?;
-
-    let claims = jwt::validate_access_token(&token)
-        .map_err(|_| AppError::unauthorized("Invalid token").into_server_fn_error())?;
-
-    let user_tier = UserTier::from_str_or_default(&claims.tier);
-    if !user_tier.has_access(&UserTier::Premium) {
-        return Err(
-            AppError::forbidden("Premium tier required for analytics").into_server_fn_error()
-        );
-    }
-
-    let db = get_db().await;
-
-    let total_revenue = sqlx::query_scalar!(
-        "SELECT COALESCE(SUM(price), 0.0) FROM products WHERE status = 'active'"
-    )
-    .fetch_one(db)
-    .await
-    .map_err(|e| e.into_app_error().into_server_fn_error())?
-    .unwrap_or(0.0);
-
-    let avg_product_price = sqlx::query_scalar!("SELECT COALESCE(AVG(price), 0.0) FROM products")
-        .fetch_one(db)
+pub async fn get_premium_analytics(
+    scope: Option<shared_types::AnalyticsScope>,
+) -> Result<shared_types::PremiumAnalytics, ServerFnError> {
+    use crate::auth::extractors::PremiumRequired;
+
+    let headers = dioxus::fullstack::FullstackContext::current()
+        .map(|ctx| ctx.parts_mut().headers.clone())
+        .unwrap_or_default();
+    let tier = PremiumRequired::from_headers(&headers).map_err(|e| e.into_server_fn_error())?;
+
+    let owner =
+        crate::analytics::resolve_scope(&tier.0, scope).map_err(|e| e.into_server_fn_error())?;
+    crate::analytics::premium_analytics(get_db().await, owner)
         .await
-        .map_err(|e| e.into_app_error().into_server_fn_error())?
-        .unwrap_or(0.0);
-
-    let category_rows = sqlx::query!(
-        "SELECT category, COUNT(*) as count FROM products GROUP BY category ORDER BY count DESC"
-    )
-    .fetch_all(db)
-    .await
-    .map_err(|e| e.into_app_error().into_server_fn_error())?;
-
-    let products_by_category: Vec<shared_types::CategoryCount> = category_rows
-        .into_iter()
-        .map(|r| shared_types::CategoryCount {
-            category: r.category,
-            count: r.count.unwrap_or(0),
-        })
-        .collect();
-
-    let users_last_30_days = sqlx::query_scalar!(
-        "SELECT COUNT(*) FROM users WHERE created_at >= NOW() - INTERVAL '30 days'"
-    )
-    .fetch_one(db)
-    .await
-    .map_err(|e| e.into_app_error().into_server_fn_error())?
-    .unwrap_or(0);
-
-    Ok(shared_types::PremiumAnalytics {
-        total_revenue,
-        avg_product_price,
-        products_by_category,
-        users_last_30_days,
-    })
+        .map_err(|e| e.into_server_fn_error())
 }
 
 /// Get a user by ID.
@@ -92,8 +58,8 @@ pub async fn get_premium_analytics() -> Result<shared_types::PremiumAnalytics, S
 pub async fn get_user(user_id: i64) -> Result<User, ServerFnError> {
     let db = get_db().await;
     let user = sqlx::query_as!(
-        User,
-        "SELECT id, username, display_name, role, tier FROM users WHERE id = $1",
+        UserRecord,
+        "SELECT id, username, display_name, email, role, tier, avatar_url, thumbnail_url, created_at, updated_at FROM users WHERE id = $1 AND deleted_at IS NULL",
         user_id
     )
     .fetch_optional(db)
@@ -103,22 +69,30 @@ pub async fn get_user(user_id: i64) -> Result<User, ServerFnError> {
         shared_types::AppError::not_found(format!("User with id {} not found", user_id))
             .into_server_fn_error()
     })?;
-    Ok(user)
+    Ok(user.into())
 }
 
-/// List all users.
+/// Get several users by ID in one call, in the order asked for.
+///
+/// Ids that don't match a user are left out.
 #[cfg_attr(feature = "server", tracing::instrument)]
 #[server]
-pub async fn list_users() -> Result<Vec<User>, ServerFnError> {
-    let db = get_db().await;
-    let users = sqlx::query_as!(
-        User,
-        "SELECT id, username, display_name, role, tier FROM users"
-    )
-    .fetch_all(db)
-    .await
-    .map_err(|e| e.into_app_error().into_server_fn_error())?;
-    Ok(users)
+pub async fn get_users(ids: Vec<i64>) -> Result<Vec<User>, ServerFnError> {
+    use shared_types::BatchGetUsersRequest;
+
+    crate::users::get_many(get_db().await, BatchGetUsersRequest { ids })
+        .await
+        .map_err(|e| e.into_server_fn_error())
+}
+
+/// List users, searched, sorted and paged by `params`; the default params
+/// return every user by id.
+#[cfg_attr(feature = "server", tracing::instrument)]
+#[server]
+pub async fn list_users(params: ListParams<UserSortField>) -> Result<Vec<User>, ServerFnError> {
+    crate::users::list(get_db().await, &params)
+        .await
+        .map_err(|e| e.into_server_fn_error())
 }
 
 /// Create a new user.
@@ -128,14 +102,14 @@ pub async fn create_user(username: String, display_name: String) -> Result<User,
     let req = CreateUserRequest {
         username,
         display_name,
-    };
-    req.validate_request()
-        .map_err(|e| e.into_server_fn_error())?;
+    }
+    .validate_and_normalize()
+    .map_err(|e| e.into_server_fn_error())?;
 
     let db = get_db().await;
     let user = sqlx::query_as!(
-        User,
-        "INSERT INTO users (username, display_name) VALUES ($1, $2) RETURNING id, username, display_name, role, tier",
+        UserRecord,
+        "INSERT INTO users (username, display_name) VALUES ($1, $2) RETURNING id, username, display_name, email, role, tier, avatar_url, thumbnail_url, created_at, updated_at",
         req.username,
         req.display_name
     )
@@ -143,7 +117,7 @@ pub async fn create_user(username: String, display_name: String) -> Result<User,
     .await
     .map_err(|e| e.into_app_error().into_server_fn_error())?;
 
-    Ok(user)
+    Ok(user.into())
 }
 
 /// Update an existing user.
@@ -163,8 +137,8 @@ pub async fn update_user(
 
     let db = get_db().await;
     let user = sqlx::query_as!(
-        User,
-        "UPDATE users SET username = $2, display_name = $3 WHERE id = $1 RETURNING id, username, display_name, role, tier",
+        UserRecord,
+        "UPDATE users SET username = $2, display_name = $3, updated_at = NOW() WHERE id = $1 AND deleted_at IS NULL RETURNING id, username, display_name, email, role, tier, avatar_url, thumbnail_url, created_at, updated_at",
         user_id,
         req.username,
         req.display_name
@@ -172,21 +146,55 @@ pub async fn update_user(
     .fetch_one(db)
     .await
     .map_err(|e| e.into_app_error().into_server_fn_error())?;
-    Ok(user)
+    Ok(user.into())
 }
 
-/// Delete a user by ID.
+/// Soft-delete a user by ID and sign them out. [`restore_user`] undoes it
+/// until the account is purged.
 #[cfg_attr(feature = "server", tracing::instrument)]
 #[server]
 pub async fn delete_user(user_id: i64) -> Result<(), ServerFnError> {
+    use crate::audit;
+    use shared_types::AuditAction;
+
     let db = get_db().await;
-    sqlx::query!("DELETE FROM users WHERE id = $1", user_id)
-        .execute(db)
+    let username = crate::users::soft_delete(db, user_id)
         .await
-        .map_err(|e| e.into_app_error().into_server_fn_error())?;
+        .map_err(|e| e.into_server_fn_error())?;
+
+    if let Some(username) = username {
+        audit::record(
+            db,
+            audit::current_actor(),
+            AuditAction::UserDelete,
+            &audit::user_target(user_id),
+            serde_json::json!({ "username": username }),
+        )
+        .await;
+    }
     Ok(())
 }
 
+/// Restore a soft-deleted user.
+#[cfg_attr(feature = "server", tracing::instrument)]
+#[server]
+pub async fn restore_user(user_id: i64) -> Result<User, ServerFnError> {
+    let db = get_db().await;
+    let user = sqlx::query_as!(
+        UserRecord,
+        "UPDATE users SET deleted_at = NULL, updated_at = NOW() WHERE id = $1 AND deleted_at IS NOT NULL RETURNING id, username, display_name, email, role, tier, avatar_url, thumbnail_url, created_at, updated_at",
+        user_id
+    )
+    .fetch_optional(db)
+    .await
+    .map_err(|e| e.into_app_error().into_server_fn_error())?
+    .ok_or_else(|| {
+        shared_types::AppError::not_found(format!("Deleted user with id {} not found", user_id))
+            .into_server_fn_error()
+    })?;
+    Ok(user.into())
+}
+
 /// Update a user's tier. Requires admin role (verified via JWT).
 #[cfg_attr(feature = "server", tracing::instrument)]
 #[server]
@@ -216,7 +224,7 @@ pub async fn update_user_tier(user_id: i64, tier: String) -> Result<User, Server
     let claims = jwt::validate_access_token(&token)
         .map_err(|_| AppError::unauthorized("Invalid token").into_server_fn_error())?;
 
-    if claims.role != "admin" {
+    if !Role::from_str_or_default(&claims.role).is_admin() {
         return Err(
             AppError::forbidden("Admin role required to change user tiers").into_server_fn_error(),
         );
@@ -224,8 +232,8 @@ pub async fn update_user_tier(user_id: i64, tier: String) -> Result<User, Server
 
     let db = get_db().await;
     let user = sqlx::query_as!(
-        User,
-        "UPDATE users SET tier = $2 WHERE id = $1 RETURNING id, username, display_name, role, tier",
+        UserRecord,
+        "UPDATE users SET tier = $2, updated_at = NOW() WHERE id = $1 AND deleted_at IS NULL RETURNING id, username, display_name, email, role, tier, avatar_url, thumbnail_url, created_at, updated_at",
         user_id,
         tier_lower
     )
@@ -233,7 +241,59 @@ pub async fn update_user_tier(user_id: i64, tier: String) -> Result<User, Server
     .await
     .map_err(|e| e.into_app_error().into_server_fn_error())?;
 
-    Ok(user)
+    crate::audit::record(
+        db,
+        Some(claims.sub),
+        shared_types::AuditAction::TierChange,
+        &crate::audit::user_target(user_id),
+        serde_json::json!({ "tier": tier_lower }),
+    )
+    .await;
+    Ok(user.into())
+}
+
+/// Create a registration invite for `email`. Requires admin role (verified via JWT).
+///
+/// Returns the invite, whose token the admin shares with the invitee.
+#[cfg_attr(feature = "server", tracing::instrument(skip(email)))]
+#[server]
+pub async fn create_invite(email: String) -> Result<shared_types::Invite, ServerFnError> {
+    use crate::auth::{cookies, invites, jwt};
+    use shared_types::{AppError, CreateInviteRequest};
+
+    let req = CreateInviteRequest {
+        email: email.clone(),
+    };
+    req.validate_request()
+        .map_err(|e| e.into_server_fn_error())?;
+
+    let ctx = dioxus::fullstack::FullstackContext::current();
+    let headers = ctx.as_ref().map(|c| c.parts_mut().headers.clone());
+
+    let headers = headers
+        .ok_or_else(|| AppError::unauthorized("Authentication required").into_server_fn_error())?;
+
+    let token = cookies::extract_access_token(&headers)
+        .ok_or_else(|| AppError::unauthorized("Authentication required").into_server_fn_error())?;
+
+    let claims = jwt::validate_access_token(&token)
+        .map_err(|_| AppError::unauthorized("Invalid token").into_server_fn_error())?;
+
+    if !Role::from_str_or_default(&claims.role).is_admin() {
+        return Err(
+            AppError::forbidden("Admin role required to create invites").into_server_fn_error()
+        );
+    }
+
+    let mut conn = get_db()
+        .await
+        .acquire()
+        .await
+        .map_err(|e| e.into_app_error().into_server_fn_error())?;
+
+    invites::create_invite(&mut conn, &email, claims.sub)
+        .await
+        .map_err(|e| e.into_server_fn_error())
 }
 
 /// List all products.
@@ -241,10 +301,13 @@ pub async fn update_user_tier(user_id: i64, tier: String) -> Result<User, Server
 #[server]
 pub async fn list_products() -> Result<Vec<Product>, ServerFnError> {
     let db = get_db().await;
-    let rows = sqlx::query!(
-        "SELECT id, name, description, price, category, status, created_at FROM products ORDER BY id DESC"
+    let rows = traced_query(
+        "products.list",
+        sqlx::query!(
+            "SELECT id, name, description, price, category, status, created_at, updated_at, image_url FROM products WHERE deleted_at IS NULL ORDER BY id DESC"
+        )
+        .fetch_all(db),
     )
-    .fetch_all(db)
     .await
     .map_err(|e| e.into_app_error().into_server_fn_error())?;
 
@@ -254,25 +317,120 @@ pub async fn list_products() -> Result<Vec<Product>, ServerFnError> {
             id: r.id,
             name: r.name,
             description: r.description,
-            price: r.price,
+            price: Money::from_f64(r.price),
             category: r.category,
-            status: r.status,
+            status: ProductStatus::from_str_or_default(&r.status),
             created_at: r.created_at.to_string(),
+            updated_at: r.updated_at.to_string(),
+            image_url: r.image_url,
+            is_favorited: None,
+            deleted_at: None,
         })
         .collect();
     Ok(products)
 }
 
+/// One page of products, newest first, with ids below `before_id` (from the
+/// start when `None`). Pass the returned `next_cursor` as `before_id` to get
+/// the following page.
+#[cfg_attr(feature = "server", tracing::instrument)]
+#[server]
+pub async fn list_products_page(
+    before_id: Option<i64>,
+    limit: i64,
+) -> Result<Cursor<Product>, ServerFnError> {
+    let limit = limit.clamp(1, shared_types::MAX_PAGE_SIZE);
+    let db = get_db().await;
+    // One extra row tells whether another page follows.
+    let rows = traced_query(
+        "products.list_page",
+        sqlx::query!(
+            "SELECT id, name, description, price, category, status, created_at, updated_at, image_url FROM products WHERE deleted_at IS NULL AND ($1::BIGINT IS NULL OR id < $1) ORDER BY id DESC LIMIT $2",
+            before_id,
+            limit + 1
+        )
+        .fetch_all(db),
+    )
+    .await
+    .map_err(|e| e.into_app_error().into_server_fn_error())?;
+
+    let products = rows
+        .into_iter()
+        .map(|r| Product {
+            id: r.id,
+            name: r.name,
+            description: r.description,
+            price: Money::from_f64(r.price),
+            category: r.category,
+            status: ProductStatus::from_str_or_default(&r.status),
+            created_at: r.created_at.to_string(),
+            updated_at: r.updated_at.to_string(),
+            image_url: r.image_url,
+            is_favorited: None,
+            deleted_at: None,
+        })
+        .collect();
+    Ok(Cursor::from_overfetch(products, limit, false, |p| p.id))
+}
+
+/// Get a product by ID.
+#[cfg_attr(feature = "server", tracing::instrument)]
+#[server]
+pub async fn get_product(product_id: i64) -> Result<Product, ServerFnError> {
+    let db = get_db().await;
+    let r = sqlx::query!(
+        "SELECT id, name, description, price, category, status, created_at, updated_at, image_url FROM products WHERE id = $1 AND deleted_at IS NULL",
+        product_id
+    )
+    .fetch_optional(db)
+    .await
+    .map_err(|e| e.into_app_error().into_server_fn_error())?
+    .ok_or_else(|| {
+        shared_types::AppError::not_found(format!("Product with id {} not found", product_id))
+            .into_server_fn_error()
+    })?;
+
+    Ok(Product {
+        id: r.id,
+        name: r.name,
+        description: r.description,
+        price: Money::from_f64(r.price),
+        category: r.category,
+        status: ProductStatus::from_str_or_default(&r.status),
+        created_at: r.created_at.to_string(),
+        updated_at: r.updated_at.to_string(),
+        image_url: r.image_url,
+        is_favorited: None,
+        deleted_at: None,
+    })
+}
+
+/// When a product was created and last changed, and by whom.
+///
+/// Requires a signed-in user. The product's audit entries are only included
+/// for its owner and admins.
+#[cfg_attr(feature = "server", tracing::instrument)]
+#[server]
+pub async fn get_product_history(product_id: i64) -> Result<ProductHistory, ServerFnError> {
+    let claims = require_claims()?;
+    crate::audit::product_history(get_db().await, product_id, &claims)
+        .await
+        .map_err(|e| e.into_server_fn_error())
+}
+
 /// Create a new product.
 #[cfg_attr(feature = "server", tracing::instrument)]
 #[server]
 pub async fn create_product(
     name: String,
     description: String,
-    price: f64,
+    price: Money,
     category: String,
     status: String,
 ) -> Result<Product, ServerFnError> {
+    use crate::audit;
+    use shared_types::AuditAction;
+
     let req = CreateProductRequest {
         name,
         description,
@@ -284,26 +442,41 @@ pub async fn create_product(
         .map_err(|e| e.into_server_fn_error())?;
 
     let db = get_db().await;
+    let actor = audit::current_actor();
     let row = sqlx::query!(
-        "INSERT INTO products (name, description, price, category, status) VALUES ($1, $2, $3, $4, $5) RETURNING id, name, description, price, category, status, created_at",
+        "INSERT INTO products (name, description, price, category, status, owner_id) VALUES ($1, $2, $3, $4, $5, $6) RETURNING id, name, description, price, category, status, created_at, updated_at, image_url",
         req.name,
         req.description,
-        req.price,
+        req.price.to_f64(),
         req.category,
-        req.status
+        req.product_status().as_str(),
+        actor
     )
     .fetch_one(db)
     .await
     .map_err(|e| e.into_app_error().into_server_fn_error())?;
 
+    audit::record(
+        db,
+        actor,
+        AuditAction::ProductCreate,
+        &audit::product_target(row.id),
+        serde_json::json!({ "name": row.name }),
+    )
+    .await;
+
     Ok(Product {
         id: row.id,
         name: row.name,
         description: row.description,
-        price: row.price,
+        price: Money::from_f64(row.price),
         category: row.category,
-        status: row.status,
+        status: ProductStatus::from_str_or_default(&row.status),
         created_at: row.created_at.to_string(),
+        updated_at: row.updated_at.to_string(),
+        image_url: row.image_url,
+        is_favorited: None,
+        deleted_at: None,
     })
 }
 
@@ -314,10 +487,13 @@ pub async fn update_product(
     product_id: i64,
     name: String,
     description: String,
-    price: f64,
+    price: Money,
     category: String,
     status: String,
 ) -> Result<Product, ServerFnError> {
+    use crate::audit;
+    use shared_types::AuditAction;
+
     let req = UpdateProductRequest {
         name,
         description,
@@ -330,128 +506,284 @@ pub async fn update_product(
 
     let db = get_db().await;
     let row = sqlx::query!(
-        "UPDATE products SET name = $2, description = $3, price = $4, category = $5, status = $6 WHERE id = $1 RETURNING id, name, description, price, category, status, created_at",
+        "UPDATE products SET name = $2, description = $3, price = $4, category = $5, status = $6, updated_at = NOW() WHERE id = $1 AND deleted_at IS NULL RETURNING id, name, description, price, category, status, created_at, updated_at, image_url",
         product_id,
         req.name,
         req.description,
-        req.price,
+        req.price.to_f64(),
         req.category,
-        req.status
+        req.product_status().as_str()
     )
-    .fetch_one(db)
+    .fetch_optional(db)
     .await
-    .map_err(|e| e.into_app_error().into_server_fn_error())?;
+    .map_err(|e| e.into_app_error().into_server_fn_error())?
+    .ok_or_else(|| {
+        shared_types::AppError::not_found(format!("Product with id {} not found", product_id))
+            .into_server_fn_error()
+    })?;
+
+    audit::record(
+        db,
+        audit::current_actor(),
+        AuditAction::ProductUpdate,
+        &audit::product_target(product_id),
+        serde_json::json!({ "name": row.name }),
+    )
+    .await;
 
     Ok(Product {
         id: row.id,
         name: row.name,
         description: row.description,
-        price: row.price,
+        price: Money::from_f64(row.price),
         category: row.category,
-        status: row.status,
+        status: ProductStatus::from_str_or_default(&row.status),
         created_at: row.created_at.to_string(),
+        updated_at: row.updated_at.to_string(),
+        image_url: row.image_url,
+        is_favorited: None,
+        deleted_at: None,
     })
 }
 
-/// Delete a product by ID.
-#[cfg_attr(feature = "server", tracing::instrument)]
+/// Upload an image for a product via base64-encoded file data, replacing
+/// any previous one.
+///
+/// Requires a signed-in user. Only JPEG, PNG and WebP images up to 5 MB are
+/// accepted, and the bytes must match `content_type` (see
+/// [`crate::upload_limits::validate_product_image`]).
+#[cfg_attr(feature = "server", tracing::instrument(skip(file_data)))]
 #[server]
-pub async fn delete_product(product_id: i64) -> Result<(), ServerFnError> {
-    let db = get_db().await;
-    sqlx::query!("DELETE FROM products WHERE id = $1", product_id)
-        .execute(db)
-        .await
-        .map_err(|e| e.into_app_error().into_server_fn_error())?;
-    Ok(())
-}
+pub async fn upload_product_image(
+    product_id: i64,
+    file_data: String,
+    content_type: String,
+) -> Result<Product, ServerFnError> {
+    use crate::upload_limits;
+    use shared_types::AppError;
 
-/// Get dashboard statistics.
-#[cfg_attr(feature = "server", tracing::instrument)]
-#[server]
-pub async fn get_dashboard_stats() -> Result<DashboardStats, ServerFnError> {
-    let db = get_db().await;
+    require_claims()?;
+    upload_limits::check_product_image_encoded_len(file_data.len())
+        .map_err(|e| e.into_server_fn_error())?;
 
-    let user_count = sqlx::query_scalar!("SELECT COUNT(*) FROM users")
-        .fetch_one(db)
-        .await
-        .map_err(|e| e.into_app_error().into_server_fn_error())?
-        .unwrap_or(0);
+    let bytes = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, &file_data)
+        .map_err(|e| {
+            AppError::validation(format!("Invalid file data: {}", e), Default::default())
+                .into_server_fn_error()
+        })?;
+    upload_limits::validate_product_image(&content_type, &bytes)
+        .map_err(|e| e.into_server_fn_error())?;
 
-    let product_count = sqlx::query_scalar!("SELECT COUNT(*) FROM products")
-        .fetch_one(db)
-        .await
-        .map_err(|e| e.into_app_error().into_server_fn_error())?
-        .unwrap_or(0);
+    let db = get_db().await;
+    let previous = sqlx::query_scalar!(
+        "SELECT image_url FROM products WHERE id = $1 AND deleted_at IS NULL",
+        product_id
+    )
+    .fetch_optional(db)
+    .await
+    .map_err(|e| e.into_app_error().into_server_fn_error())?
+    .ok_or_else(|| {
+        AppError::not_found(format!("Product with id {} not found", product_id))
+            .into_server_fn_error()
+    })?;
 
-    let active_count = sqlx::query_scalar!("SELECT COUNT(*) FROM products WHERE status = 'active'")
-        .fetch_one(db)
+    let key = crate::s3::upload_object("products", product_id, &content_type, &bytes)
         .await
-        .map_err(|e| e.into_app_error().into_server_fn_error())?
-        .unwrap_or(0);
+        .map_err(|e| AppError::internal(e).into_server_fn_error())?;
 
-    let recent_users = sqlx::query_as!(
-        User,
-        "SELECT id, username, display_name, role, tier FROM users ORDER BY id DESC LIMIT 5"
+    let row = sqlx::query!(
+        "UPDATE products SET image_url = $2, updated_at = NOW() WHERE id = $1 AND deleted_at IS NULL RETURNING id, name, description, price, category, status, created_at, updated_at, image_url",
+        product_id,
+        key
     )
-    .fetch_all(db)
+    .fetch_optional(db)
     .await
     .map_err(|e| e.into_app_error().into_server_fn_error())?;
 
-    Ok(DashboardStats {
-        total_users: user_count,
-        total_products: product_count,
-        active_products: active_count,
-        recent_users,
+    let Some(row) = row else {
+        // Deleted while the upload was in flight
+        crate::s3::delete_replaced_objects(&[Some(key)]).await;
+        return Err(
+            AppError::not_found(format!("Product with id {} not found", product_id))
+                .into_server_fn_error(),
+        );
+    };
+    crate::s3::delete_replaced_objects(&[previous]).await;
+
+    Ok(Product {
+        id: row.id,
+        name: row.name,
+        description: row.description,
+        price: Money::from_f64(row.price),
+        category: row.category,
+        status: ProductStatus::from_str_or_default(&row.status),
+        created_at: row.created_at.to_string(),
+        updated_at: row.updated_at.to_string(),
+        image_url: row.image_url,
+        is_favorited: None,
+        deleted_at: None,
     })
 }
 
-/// Register a new user. Sets HTTP-only auth cookies on success.
-#[cfg_attr(feature = "server", tracing::instrument(skip(password)))]
+/// Soft-delete a product by ID; it can be brought back with [`restore_product`].
+#[cfg_attr(feature = "server", tracing::instrument)]
 #[server]
-pub async fn register(
-    username: String,
-    email: String,
-    password: String,
-    display_name: String,
-) -> Result<AuthUser, ServerFnError> {
-    use crate::auth::{cookies, jwt, password as pw};
-    use shared_types::{AppError, RegisterRequest};
-
-    let req = RegisterRequest {
-        username: username.clone(),
-        email: email.clone(),
-        password: password.clone(),
-        display_name: display_name.clone(),
-    };
-    req.validate_request()
-        .map_err(|e| e.into_server_fn_error())?;
-
-    let password_hash = pw::hash_password(&password)
-        .map_err(|e| AppError::internal(e.to_string()).into_server_fn_error())?;
+pub async fn delete_product(product_id: i64) -> Result<(), ServerFnError> {
+    use crate::audit;
+    use shared_types::AuditAction;
 
     let db = get_db().await;
-    let user = sqlx::query!(
-        "INSERT INTO users (username, email, password_hash, display_name) VALUES ($1, $2, $3, $4) RETURNING id, username, display_name, email, role, tier, avatar_url",
-        username,
-        email,
-        password_hash,
-        display_name
+    let name = sqlx::query_scalar!(
+        "UPDATE products SET deleted_at = NOW(), updated_at = NOW() WHERE id = $1 AND deleted_at IS NULL RETURNING name",
+        product_id
     )
-    .fetch_one(db)
+    .fetch_optional(db)
     .await
     .map_err(|e| e.into_app_error().into_server_fn_error())?;
 
-    let user_email = user.email.unwrap_or_default();
-    let user_role = user.role;
-    let user_tier = UserTier::from_str_or_default(&user.tier);
-
-    let access_token =
-        jwt::create_access_token(user.id, &user_email, &user_role, user_tier.as_str())
-            .map_err(|e| AppError::internal(e.to_string()).into_server_fn_error())?;
+    if let Some(name) = name {
+        audit::record(
+            db,
+            audit::current_actor(),
+            AuditAction::ProductDelete,
+            &audit::product_target(product_id),
+            serde_json::json!({ "name": name }),
+        )
+        .await;
+    }
+    Ok(())
+}
 
-    let (refresh_token, expires_at) =
-        jwt::create_refresh_token(user.id, &user_email, &user_role, user_tier.as_str())
-            .map_err(|e| AppError::internal(e.to_string()).into_server_fn_error())?;
+/// Restore a soft-deleted product.
+#[cfg_attr(feature = "server", tracing::instrument)]
+#[server]
+pub async fn restore_product(product_id: i64) -> Result<Product, ServerFnError> {
+    let db = get_db().await;
+    let row = sqlx::query!(
+        "UPDATE products SET deleted_at = NULL, updated_at = NOW() WHERE id = $1 AND deleted_at IS NOT NULL RETURNING id, name, description, price, category, status, created_at, updated_at, image_url",
+        product_id
+    )
+    .fetch_optional(db)
+    .await
+    .map_err(|e| e.into_app_error().into_server_fn_error())?
+    .ok_or_else(|| {
+        shared_types::AppError::not_found(format!(
+            "Deleted product with id {} not found",
+            product_id
+        ))
+        .into_server_fn_error()
+    })?;
+
+    Ok(Product {
+        id: row.id,
+        name: row.name,
+        description: row.description,
+        price: Money::from_f64(row.price),
+        category: row.category,
+        status: ProductStatus::from_str_or_default(&row.status),
+        created_at: row.created_at.to_string(),
+        updated_at: row.updated_at.to_string(),
+        image_url: row.image_url,
+        is_favorited: None,
+        deleted_at: None,
+    })
+}
+
+/// Get dashboard statistics, counting only records created within `range`
+/// (all time when unset).
+#[cfg_attr(feature = "server", tracing::instrument)]
+#[server]
+pub async fn get_dashboard_stats(
+    range: Option<shared_types::StatsRange>,
+) -> Result<DashboardStats, ServerFnError> {
+    crate::dashboard::stats(get_db().await, range.unwrap_or_default())
+        .await
+        .map_err(|e| e.into_server_fn_error())
+}
+
+/// Register a new user. Sets HTTP-only auth cookies on success.
+///
+/// When `REGISTRATION_MODE=invite`, `invite_token` must be an unused invite
+/// issued for `email`; it is marked used once the account is created.
+#[cfg_attr(
+    feature = "server",
+    tracing::instrument(skip(email, password, invite_token))
+)]
+#[server]
+pub async fn register(
+    username: String,
+    email: String,
+    password: String,
+    display_name: String,
+    invite_token: Option<String>,
+) -> Result<AuthUser, ServerFnError> {
+    use crate::auth::invites::{self, RegistrationMode};
+    use crate::auth::{cookies, jwt, password as pw, verification};
+    use shared_types::{AppError, RegisterRequest};
+
+    let req = RegisterRequest {
+        username,
+        email,
+        password,
+        display_name,
+        invite_token,
+    }
+    .validate_and_normalize()
+    .map_err(|e| e.into_server_fn_error())?;
+
+    let password_hash = pw::hash_password(&req.password)
+        .map_err(|e| AppError::internal(e.to_string()).into_server_fn_error())?;
+
+    let db = get_db().await;
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|e| e.into_app_error().into_server_fn_error())?;
+
+    let invite_id = if RegistrationMode::from_env().requires_invite() {
+        Some(
+            invites::claim_invite(&mut tx, req.invite_token.as_deref(), &req.email)
+                .await
+                .map_err(|e| e.into_server_fn_error())?,
+        )
+    } else {
+        None
+    };
+
+    let user = sqlx::query_as!(
+        UserRecord,
+        "INSERT INTO users (username, email, password_hash, display_name) VALUES ($1, $2, $3, $4) RETURNING id, username, display_name, email, role, tier, avatar_url, thumbnail_url, created_at, updated_at",
+        req.username,
+        req.email,
+        password_hash,
+        req.display_name
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| e.into_app_error().into_server_fn_error())?;
+
+    if let Some(invite_id) = invite_id {
+        invites::record_invitee(&mut tx, invite_id, user.id)
+            .await
+            .map_err(|e| e.into_server_fn_error())?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| e.into_app_error().into_server_fn_error())?;
+
+    if let Err(e) = verification::send_verification_email(db, user.id).await {
+        tracing::warn!(user_id = user.id, error = %e, "failed to send verification email");
+    }
+    let user = AuthUser::from(user).with_email_verified(false);
+
+    let access_token =
+        jwt::create_access_token(user.id, &user.email, user.role.as_str(), user.tier.as_str())
+            .map_err(|e| AppError::internal(e.to_string()).into_server_fn_error())?;
+
+    let (refresh_token, expires_at) =
+        jwt::create_refresh_token(user.id, &user.email, user.role.as_str(), user.tier.as_str())
+            .map_err(|e| AppError::internal(e.to_string()).into_server_fn_error())?;
 
     // Store refresh token for later validation
     sqlx::query!(
@@ -467,86 +799,114 @@ pub async fn register(
     // Schedule cookies to be set by the middleware
     cookies::schedule_auth_cookies(&access_token, &refresh_token);
 
-    Ok(AuthUser {
-        id: user.id,
-        username: user.username,
-        display_name: user.display_name,
-        email: user_email,
-        role: user_role,
-        tier: user_tier,
-        avatar_url: user.avatar_url,
-    })
+    Ok(user)
 }
 
 /// Login with email and password. Sets HTTP-only auth cookies on success.
-#[cfg_attr(feature = "server", tracing::instrument(skip(password)))]
+#[cfg_attr(feature = "server", tracing::instrument(skip(email, password)))]
 #[server]
 pub async fn login(email: String, password: String) -> Result<AuthUser, ServerFnError> {
+    use crate::auth::events::{self as auth_events, ClientInfo};
     use crate::auth::{cookies, jwt, password as pw};
-    use shared_types::{AppError, LoginRequest};
+    use shared_types::{AppError, AuthEventType, LoginRequest};
 
-    let req = LoginRequest {
-        email: email.clone(),
-        password: password.clone(),
-    };
-    req.validate_request()
+    let req = LoginRequest { email, password }
+        .validate_and_normalize()
         .map_err(|e| e.into_server_fn_error())?;
 
+    let client = dioxus::fullstack::FullstackContext::current()
+        .map(|ctx| {
+            let parts = ctx.parts_mut();
+            ClientInfo::from_parts(&parts.headers, &parts.extensions)
+        })
+        .unwrap_or_default();
+
+    // Emails are stored lowercased; LOWER() also matches accounts created
+    // before normalization.
     let db = get_db().await;
-    let user = sqlx::query!(
-        "SELECT id, username, display_name, email, password_hash, role, tier, avatar_url FROM users WHERE email = $1",
-        email
+    let Some(credentials) = traced_query(
+        "users.credentials",
+        sqlx::query!(
+            "SELECT id, password_hash FROM users WHERE LOWER(email) = $1",
+            req.email
+        )
+        .fetch_optional(db),
     )
-    .fetch_optional(db)
     .await
     .map_err(|e| e.into_app_error().into_server_fn_error())?
-    .ok_or_else(|| AppError::unauthorized("Invalid email or password").into_server_fn_error())?;
-
-    let password_hash = user.password_hash.ok_or_else(|| {
-        AppError::unauthorized("Invalid email or password").into_server_fn_error()
-    })?;
+    else {
+        auth_events::record(db, AuthEventType::LoginFailure, None, &client).await;
+        return Err(AppError::unauthorized("Invalid email or password").into_server_fn_error());
+    };
 
-    let valid = pw::verify_password(&password, &password_hash)
-        .map_err(|e| AppError::internal(e.to_string()).into_server_fn_error())?;
+    let valid = match &credentials.password_hash {
+        Some(hash) => pw::verify_password(&req.password, hash)
+            .map_err(|e| AppError::internal(e.to_string()).into_server_fn_error())?,
+        None => false,
+    };
 
     if !valid {
+        auth_events::record(
+            db,
+            AuthEventType::LoginFailure,
+            Some(credentials.id),
+            &client,
+        )
+        .await;
         return Err(AppError::unauthorized("Invalid email or password").into_server_fn_error());
     }
 
-    let user_email = user.email.unwrap_or_default();
-    let user_role = user.role;
-    let user_tier = UserTier::from_str_or_default(&user.tier);
+    if let Err(e) = crate::auth::deletion::ensure_not_pending(db, credentials.id).await {
+        auth_events::record(
+            db,
+            AuthEventType::LoginFailure,
+            Some(credentials.id),
+            &client,
+        )
+        .await;
+        return Err(e.into_server_fn_error());
+    }
+
+    let user: AuthUser = traced_query(
+        "users.get",
+        sqlx::query_as!(
+            UserRecord,
+            "SELECT id, username, display_name, email, role, tier, avatar_url, thumbnail_url, created_at, updated_at FROM users WHERE id = $1",
+            credentials.id
+        )
+        .fetch_one(db),
+    )
+    .await
+    .map_err(|e| e.into_app_error().into_server_fn_error())?
+    .into();
 
     let access_token =
-        jwt::create_access_token(user.id, &user_email, &user_role, user_tier.as_str())
+        jwt::create_access_token(user.id, &user.email, user.role.as_str(), user.tier.as_str())
             .map_err(|e| AppError::internal(e.to_string()).into_server_fn_error())?;
 
     let (refresh_token, expires_at) =
-        jwt::create_refresh_token(user.id, &user_email, &user_role, user_tier.as_str())
+        jwt::create_refresh_token(user.id, &user.email, user.role.as_str(), user.tier.as_str())
             .map_err(|e| AppError::internal(e.to_string()).into_server_fn_error())?;
 
-    sqlx::query!(
-        "INSERT INTO refresh_tokens (user_id, token_hash, expires_at) VALUES ($1, $2, $3)",
-        user.id,
-        refresh_token,
-        expires_at
+    traced_query(
+        "refresh_tokens.insert",
+        sqlx::query!(
+            "INSERT INTO refresh_tokens (user_id, token_hash, expires_at) VALUES ($1, $2, $3)",
+            user.id,
+            refresh_token,
+            expires_at
+        )
+        .execute(db),
     )
-    .execute(db)
     .await
     .map_err(|e| e.into_app_error().into_server_fn_error())?;
 
+    auth_events::record(db, AuthEventType::LoginSuccess, Some(user.id), &client).await;
+
     // Schedule cookies to be set by the middleware
     cookies::schedule_auth_cookies(&access_token, &refresh_token);
 
-    Ok(AuthUser {
-        id: user.id,
-        username: user.username,
-        display_name: user.display_name,
-        email: user_email,
-        role: user_role,
-        tier: user_tier,
-        avatar_url: user.avatar_url,
-    })
+    Ok(user)
 }
 
 /// Get the current authenticated user from cookies. Returns None if not authenticated.
@@ -577,36 +937,45 @@ pub async fn get_current_user() -> Result<Option<AuthUser>, ServerFnError> {
     };
 
     let db = get_db().await;
-    let user = sqlx::query!(
-        "SELECT id, username, display_name, email, role, tier, avatar_url FROM users WHERE id = $1",
+    let user = sqlx::query_as!(
+        UserRecord,
+        "SELECT id, username, display_name, email, role, tier, avatar_url, thumbnail_url, created_at, updated_at FROM users WHERE id = $1",
         claims.sub
     )
     .fetch_optional(db)
     .await
     .map_err(|e| e.into_app_error().into_server_fn_error())?;
 
-    match user {
-        Some(u) => Ok(Some(AuthUser {
-            id: u.id,
-            username: u.username,
-            display_name: u.display_name,
-            email: u.email.unwrap_or_default(),
-            role: u.role,
-            tier: UserTier::from_str_or_default(&u.tier),
-            avatar_url: u.avatar_url,
-        })),
-        None => Ok(None),
-    }
+    let Some(record) = user else {
+        return Ok(None);
+    };
+    let verified = crate::auth::verification::is_verified(db, record.id)
+        .await
+        .map_err(|e| e.into_server_fn_error())?;
+
+    Ok(Some(
+        AuthUser::from(record)
+            .with_expiry(claims.exp)
+            .with_email_verified(verified),
+    ))
 }
 
 /// Logout by revoking all refresh tokens and clearing auth cookies.
 #[cfg_attr(feature = "server", tracing::instrument)]
 #[server]
 pub async fn logout() -> Result<(), ServerFnError> {
+    use crate::auth::events::{self as auth_events, ClientInfo};
     use crate::auth::{cookies, jwt};
+    use shared_types::AuthEventType;
 
     if let Some(ctx) = dioxus::fullstack::FullstackContext::current() {
-        let headers = ctx.parts_mut().headers.clone();
+        let (headers, client) = {
+            let parts = ctx.parts_mut();
+            (
+                parts.headers.clone(),
+                ClientInfo::from_parts(&parts.headers, &parts.extensions),
+            )
+        };
         if let Some(token) = cookies::extract_access_token(&headers) {
             if let Ok(claims) = jwt::validate_access_token(&token) {
                 let db = get_db().await;
@@ -616,6 +985,7 @@ pub async fn logout() -> Result<(), ServerFnError> {
                 )
                 .execute(db)
                 .await;
+                auth_events::record(db, AuthEventType::Logout, Some(claims.sub), &client).await;
             }
         }
     }
@@ -627,7 +997,7 @@ pub async fn logout() -> Result<(), ServerFnError> {
 }
 
 /// Update the current user's profile (display name and email).
-#[cfg_attr(feature = "server", tracing::instrument)]
+#[cfg_attr(feature = "server", tracing::instrument(skip(email)))]
 #[server]
 pub async fn update_profile(
     display_name: String,
@@ -638,11 +1008,11 @@ pub async fn update_profile(
 
     // Validate the request
     let req = UpdateProfileRequest {
-        display_name: display_name.clone(),
-        email: email.clone(),
-    };
-    req.validate_request()
-        .map_err(|e| e.into_server_fn_error())?;
+        display_name,
+        email,
+    }
+    .validate_and_normalize()
+    .map_err(|e| e.into_server_fn_error())?;
 
     // Extract user ID from JWT
     let ctx = dioxus::fullstack::FullstackContext::current();
@@ -657,30 +1027,120 @@ pub async fn update_profile(
     let claims = jwt::validate_access_token(&token)
         .map_err(|_| AppError::unauthorized("Invalid token").into_server_fn_error())?;
 
+    // A changed email has to be verified again
     let db = get_db().await;
-    let user = sqlx::query!(
-        "UPDATE users SET display_name = $2, email = $3 WHERE id = $1 RETURNING id, username, display_name, email, role, tier, avatar_url",
+    let user = sqlx::query_as!(
+        UserRecord,
+        "UPDATE users SET display_name = $2, email = $3, email_verified_at = CASE WHEN LOWER(email) = LOWER($3) THEN email_verified_at END WHERE id = $1 RETURNING id, username, display_name, email, role, tier, avatar_url, thumbnail_url, created_at, updated_at",
         claims.sub,
-        display_name,
-        email
+        req.display_name,
+        req.email
     )
     .fetch_optional(db)
     .await
     .map_err(|e| e.into_app_error().into_server_fn_error())?
     .ok_or_else(|| AppError::not_found("User not found").into_server_fn_error())?;
 
-    Ok(AuthUser {
-        id: user.id,
-        username: user.username,
-        display_name: user.display_name,
-        email: user.email.unwrap_or_default(),
-        role: user.role,
-        tier: UserTier::from_str_or_default(&user.tier),
-        avatar_url: user.avatar_url,
-    })
+    Ok(user.into())
+}
+
+/// Change only the current user's display name; the email is left as is.
+#[cfg_attr(feature = "server", tracing::instrument)]
+#[server]
+pub async fn update_display_name(display_name: String) -> Result<AuthUser, ServerFnError> {
+    use shared_types::UpdateDisplayNameRequest;
+
+    let claims = require_claims()?;
+    crate::profile::update_display_name(
+        get_db().await,
+        claims.sub,
+        UpdateDisplayNameRequest { display_name },
+    )
+    .await
+    .map_err(|e| e.into_server_fn_error())
+}
+
+/// Claims of the signed-in caller, or 401.
+#[cfg(feature = "server")]
+fn require_claims() -> Result<crate::auth::jwt::Claims, ServerFnError> {
+    use crate::auth::{cookies, jwt};
+    use shared_types::AppError;
+
+    let ctx = dioxus::fullstack::FullstackContext::current();
+    let headers = ctx.as_ref().map(|c| c.parts_mut().headers.clone());
+
+    let headers = headers
+        .ok_or_else(|| AppError::unauthorized("Authentication required").into_server_fn_error())?;
+
+    let token = cookies::extract_access_token(&headers)
+        .ok_or_else(|| AppError::unauthorized("Authentication required").into_server_fn_error())?;
+
+    jwt::validate_access_token(&token)
+        .map_err(|_| AppError::unauthorized("Invalid token").into_server_fn_error())
+}
+
+/// Send the current user a new email verification link, replacing the one
+/// sent before. Succeeds without sending anything if the email is already
+/// verified. Limited to a few requests per hour.
+#[cfg_attr(feature = "server", tracing::instrument)]
+#[server]
+pub async fn resend_verification_email() -> Result<(), ServerFnError> {
+    use crate::auth::verification;
+
+    let claims = require_claims()?;
+    verification::check_resend_allowed(claims.sub).map_err(|e| e.into_server_fn_error())?;
+    verification::send_verification_email(get_db().await, claims.sub)
+        .await
+        .map_err(|e| e.into_server_fn_error())?;
+    Ok(())
+}
+
+/// Confirm the email address a verification link was sent to.
+#[cfg_attr(feature = "server", tracing::instrument(skip(token)))]
+#[server]
+pub async fn verify_email(token: String) -> Result<(), ServerFnError> {
+    crate::auth::verification::confirm(get_db().await, &token)
+        .await
+        .map_err(|e| e.into_server_fn_error())?;
+    Ok(())
+}
+
+/// The current user's pending account deletion, if any.
+#[cfg_attr(feature = "server", tracing::instrument)]
+#[server]
+pub async fn get_account_deletion() -> Result<Option<AccountDeletion>, ServerFnError> {
+    let claims = require_claims()?;
+    crate::auth::deletion::pending(get_db().await, claims.sub)
+        .await
+        .map_err(|e| e.into_server_fn_error())
+}
+
+/// Schedule the current user's account for deletion after the grace period.
+/// The account can't be signed in to until then, unless the deletion is
+/// cancelled.
+#[cfg_attr(feature = "server", tracing::instrument)]
+#[server]
+pub async fn delete_account() -> Result<AccountDeletion, ServerFnError> {
+    let claims = require_claims()?;
+    crate::auth::deletion::schedule(get_db().await, claims.sub)
+        .await
+        .map_err(|e| e.into_server_fn_error())
+}
+
+/// Cancel the current user's pending account deletion.
+#[cfg_attr(feature = "server", tracing::instrument)]
+#[server]
+pub async fn cancel_account_deletion() -> Result<(), ServerFnError> {
+    let claims = require_claims()?;
+    crate::auth::deletion::cancel(get_db().await, claims.sub)
+        .await
+        .map_err(|e| e.into_server_fn_error())
 }
 
 /// Upload a user avatar via base64-encoded file data.
+///
+/// Oversized payloads are rejected before decoding, and uploads are rate
+/// limited per user (see [`crate::upload_limits`]).
 #[cfg_attr(feature = "server", tracing::instrument(skip(file_data)))]
 #[server]
 pub async fn upload_user_avatar(
@@ -688,6 +1148,7 @@ pub async fn upload_user_avatar(
     content_type: String,
 ) -> Result<AuthUser, ServerFnError> {
     use crate::auth::{cookies, jwt};
+    use crate::upload_limits;
     use shared_types::AppError;
 
     let allowed = ["image/jpeg", "image/png", "image/webp"];
@@ -699,19 +1160,90 @@ pub async fn upload_user_avatar(
         .into_server_fn_error());
     }
 
+    let limits = upload_limits::avatar_limits();
+    limits
+        .check_encoded_len(file_data.len())
+        .map_err(|e| e.into_server_fn_error())?;
+
+    let ctx = dioxus::fullstack::FullstackContext::current();
+    let headers = ctx
+        .as_ref()
+        .map(|c| c.parts_mut().headers.clone())
+        .ok_or_else(|| AppError::unauthorized("Authentication required").into_server_fn_error())?;
+
+    let token = cookies::extract_access_token(&headers)
+        .ok_or_else(|| AppError::unauthorized("Authentication required").into_server_fn_error())?;
+
+    let claims = jwt::validate_access_token(&token)
+        .map_err(|_| AppError::unauthorized("Invalid token").into_server_fn_error())?;
+
+    upload_limits::check_avatar_upload_rate(claims.sub).map_err(|e| e.into_server_fn_error())?;
+
     let bytes = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, &file_data)
         .map_err(|e| {
             AppError::validation(format!("Invalid file data: {}", e), Default::default())
                 .into_server_fn_error()
         })?;
 
-    if bytes.len() > 2 * 1024 * 1024 {
-        return Err(
-            AppError::validation("Avatar must be under 2 MB", Default::default())
-                .into_server_fn_error(),
-        );
+    limits
+        .check_size(bytes.len())
+        .map_err(|e| e.into_server_fn_error())?;
+
+    let thumbnail = crate::s3::make_thumbnail_async(&bytes)
+        .await
+        .map_err(|e| AppError::validation(e, Default::default()).into_server_fn_error())?;
+
+    let db = get_db().await;
+    let previous = sqlx::query!(
+        "SELECT avatar_url, thumbnail_url, avatar_hash FROM users WHERE id = $1",
+        claims.sub
+    )
+    .fetch_optional(db)
+    .await
+    .map_err(|e| e.into_app_error().into_server_fn_error())?;
+
+    let current = previous
+        .map(|prev| crate::s3::StoredAvatar {
+            original: prev.avatar_url,
+            thumbnail: prev.thumbnail_url,
+            hash: prev.avatar_hash,
+        })
+        .unwrap_or_default();
+
+    let upload = crate::s3::upload_avatar(claims.sub, &content_type, &bytes, &thumbnail, &current)
+        .await
+        .map_err(|e| AppError::internal(e).into_server_fn_error())?;
+
+    let user = sqlx::query_as!(
+        UserRecord,
+        "UPDATE users SET avatar_url = $2, thumbnail_url = $3, avatar_hash = $4, updated_at = NOW() WHERE id = $1 RETURNING id, username, display_name, email, role, tier, avatar_url, thumbnail_url, created_at, updated_at",
+        claims.sub,
+        upload.keys.original,
+        upload.keys.thumbnail,
+        upload.hash
+    )
+    .fetch_one(db)
+    .await
+    .map_err(|e| e.into_app_error().into_server_fn_error())?;
+
+    if !upload.reused {
+        crate::s3::delete_replaced_objects(&[current.original, current.thumbnail]).await;
     }
 
+    Ok(user.into())
+}
+
+/// Get a short-lived signed URL for the current user's avatar.
+///
+/// Prefers the thumbnail when one exists. `users.avatar_url`/`thumbnail_url`
+/// store S3 object keys; the returned URL expires after `S3_PRESIGN_TTL_SECS`,
+/// so clients call this again to refresh it.
+#[cfg_attr(feature = "server", tracing::instrument)]
+#[server]
+pub async fn get_avatar_url() -> Result<Option<String>, ServerFnError> {
+    use crate::auth::{cookies, jwt};
+    use shared_types::AppError;
+
     let ctx = dioxus::fullstack::FullstackContext::current();
     let headers = ctx
         .as_ref()
@@ -724,29 +1256,136 @@ pub async fn upload_user_avatar(
     let claims = jwt::validate_access_token(&token)
         .map_err(|_| AppError::unauthorized("Invalid token").into_server_fn_error())?;
 
-    let avatar_url = crate::s3::upload_avatar(claims.sub, &content_type, &bytes)
+    let db = get_db().await;
+    let avatar = sqlx::query!(
+        "SELECT avatar_url, thumbnail_url FROM users WHERE id = $1",
+        claims.sub
+    )
+    .fetch_optional(db)
+    .await
+    .map_err(|e| e.into_app_error().into_server_fn_error())?
+    .and_then(|row| row.thumbnail_url.or(row.avatar_url));
+
+    match avatar {
+        Some(key) => crate::s3::presigned_url(&key, crate::s3::presign_ttl())
+            .await
+            .map(Some)
+            .map_err(|e| AppError::internal(e).into_server_fn_error()),
+        None => Ok(None),
+    }
+}
+
+/// Generate the initials avatar for a user without an uploaded one.
+///
+/// Returns a `data:image/png;base64,...` URL. The background color is seeded
+/// by the user id, so a user gets the same color on every call.
+#[cfg_attr(feature = "server", tracing::instrument)]
+#[server]
+pub async fn get_fallback_avatar(user_id: i64) -> Result<String, ServerFnError> {
+    use crate::avatar_fallback::{render_fallback_avatar, FALLBACK_AVATAR_SIZE};
+    use shared_types::AppError;
+
+    let db = get_db().await;
+    let display_name = sqlx::query_scalar!("SELECT display_name FROM users WHERE id = $1", user_id)
+        .fetch_optional(db)
         .await
+        .map_err(|e| e.into_app_error().into_server_fn_error())?
+        .ok_or_else(|| {
+            AppError::not_found(format!("User with id {} not found", user_id))
+                .into_server_fn_error()
+        })?;
+
+    let png = render_fallback_avatar(user_id, &display_name, FALLBACK_AVATAR_SIZE)
         .map_err(|e| AppError::internal(e).into_server_fn_error())?;
+    let encoded = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, png);
+    Ok(format!("data:image/png;base64,{}", encoded))
+}
+
+/// Get the current user's notification and appearance preferences.
+///
+/// Returns the defaults until the user saves any.
+#[cfg_attr(feature = "server", tracing::instrument)]
+#[server]
+pub async fn get_preferences() -> Result<UserPreferences, ServerFnError> {
+    use crate::auth::{cookies, jwt};
+    use shared_types::AppError;
+
+    let ctx = dioxus::fullstack::FullstackContext::current();
+    let headers = ctx
+        .as_ref()
+        .map(|c| c.parts_mut().headers.clone())
+        .ok_or_else(|| AppError::unauthorized("Authentication required").into_server_fn_error())?;
+
+    let token = cookies::extract_access_token(&headers)
+        .ok_or_else(|| AppError::unauthorized("Authentication required").into_server_fn_error())?;
+
+    let claims = jwt::validate_access_token(&token)
+        .map_err(|_| AppError::unauthorized("Invalid token").into_server_fn_error())?;
 
     let db = get_db().await;
-    let user = sqlx::query!(
-        "UPDATE users SET avatar_url = $2 WHERE id = $1 RETURNING id, username, display_name, email, role, tier, avatar_url",
-        claims.sub,
-        avatar_url
-    )
-    .fetch_one(db)
-    .await
-    .map_err(|e| e.into_app_error().into_server_fn_error())?;
+    crate::preferences::load_preferences(db, claims.sub)
+        .await
+        .map_err(|e| e.into_server_fn_error())
+}
 
-    Ok(AuthUser {
-        id: user.id,
-        username: user.username,
-        display_name: user.display_name,
-        email: user.email.unwrap_or_default(),
-        role: user.role,
-        tier: UserTier::from_str_or_default(&user.tier),
-        avatar_url: user.avatar_url,
-    })
+/// Save the current user's notification and appearance preferences.
+#[cfg_attr(feature = "server", tracing::instrument)]
+#[server]
+pub async fn update_preferences(prefs: UserPreferences) -> Result<UserPreferences, ServerFnError> {
+    use crate::auth::{cookies, jwt};
+    use shared_types::AppError;
+
+    let ctx = dioxus::fullstack::FullstackContext::current();
+    let headers = ctx
+        .as_ref()
+        .map(|c| c.parts_mut().headers.clone())
+        .ok_or_else(|| AppError::unauthorized("Authentication required").into_server_fn_error())?;
+
+    let token = cookies::extract_access_token(&headers)
+        .ok_or_else(|| AppError::unauthorized("Authentication required").into_server_fn_error())?;
+
+    let claims = jwt::validate_access_token(&token)
+        .map_err(|_| AppError::unauthorized("Invalid token").into_server_fn_error())?;
+
+    let db = get_db().await;
+    crate::preferences::save_preferences(db, claims.sub, &prefs)
+        .await
+        .map_err(|e| e.into_server_fn_error())
+}
+
+/// Save the current user's theme family and dark/light mode.
+///
+/// Only the theme columns are written, so the sidebar toggle can't overwrite
+/// notification settings saved from another page.
+#[cfg_attr(feature = "server", tracing::instrument)]
+#[server]
+pub async fn update_theme(theme_family: String, theme_dark: bool) -> Result<(), ServerFnError> {
+    use crate::auth::{cookies, jwt};
+    use shared_types::AppError;
+
+    let ctx = dioxus::fullstack::FullstackContext::current();
+    let headers = ctx
+        .as_ref()
+        .map(|c| c.parts_mut().headers.clone())
+        .ok_or_else(|| AppError::unauthorized("Authentication required").into_server_fn_error())?;
+
+    let token = cookies::extract_access_token(&headers)
+        .ok_or_else(|| AppError::unauthorized("Authentication required").into_server_fn_error())?;
+
+    let claims = jwt::validate_access_token(&token)
+        .map_err(|_| AppError::unauthorized("Invalid token").into_server_fn_error())?;
+
+    let db = get_db().await;
+    crate::preferences::save_theme(db, claims.sub, &theme_family, theme_dark)
+        .await
+        .map_err(|e| e.into_server_fn_error())
+}
+
+/// OAuth providers configured on this server, for rendering sign-in buttons.
+#[cfg_attr(feature = "server", tracing::instrument)]
+#[server]
+pub async fn oauth_providers() -> Result<Vec<OAuthProviderInfo>, ServerFnError> {
+    Ok(crate::auth::oauth::enabled_providers())
 }
 
 /// Get the OAuth authorization URL for a given provider.
@@ -767,3 +1406,121 @@ pub async fn oauth_authorize_url(provider: String) -> Result<String, ServerFnErr
 
     Ok(url)
 }
+
+/// Get the current user's sign-in methods: password and linked OAuth providers.
+#[cfg_attr(feature = "server", tracing::instrument)]
+#[server]
+pub async fn get_login_methods() -> Result<LoginMethods, ServerFnError> {
+    use crate::auth::{cookies, jwt, oauth};
+    use shared_types::AppError;
+
+    let ctx = dioxus::fullstack::FullstackContext::current();
+    let headers = ctx
+        .as_ref()
+        .map(|c| c.parts_mut().headers.clone())
+        .ok_or_else(|| AppError::unauthorized("Authentication required").into_server_fn_error())?;
+
+    let token = cookies::extract_access_token(&headers)
+        .ok_or_else(|| AppError::unauthorized("Authentication required").into_server_fn_error())?;
+
+    let claims = jwt::validate_access_token(&token)
+        .map_err(|_| AppError::unauthorized("Invalid token").into_server_fn_error())?;
+
+    let db = get_db().await;
+    oauth::login_methods(db, claims.sub)
+        .await
+        .map_err(|e| e.into_server_fn_error())
+}
+
+/// Unlink an OAuth provider from the current user's account.
+///
+/// Refused with `Forbidden` when the provider is the only way to sign in,
+/// i.e. the account has no password set.
+#[cfg_attr(feature = "server", tracing::instrument)]
+#[server]
+pub async fn unlink_oauth(provider: String) -> Result<LoginMethods, ServerFnError> {
+    use crate::auth::{cookies, jwt, oauth};
+    use shared_types::AppError;
+
+    let provider = shared_types::OAuthProvider::parse_provider(&provider).ok_or_else(|| {
+        AppError::validation("Unsupported OAuth provider", Default::default())
+            .into_server_fn_error()
+    })?;
+
+    let ctx = dioxus::fullstack::FullstackContext::current();
+    let headers = ctx
+        .as_ref()
+        .map(|c| c.parts_mut().headers.clone())
+        .ok_or_else(|| AppError::unauthorized("Authentication required").into_server_fn_error())?;
+
+    let token = cookies::extract_access_token(&headers)
+        .ok_or_else(|| AppError::unauthorized("Authentication required").into_server_fn_error())?;
+
+    let claims = jwt::validate_access_token(&token)
+        .map_err(|_| AppError::unauthorized("Invalid token").into_server_fn_error())?;
+
+    let db = get_db().await;
+    oauth::unlink_oauth_user(db, claims.sub, &provider)
+        .await
+        .map_err(|e| e.into_server_fn_error())
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    /// Collects the names of fields recorded on spans with a given name.
+    #[derive(Clone)]
+    struct SpanFields {
+        span: &'static str,
+        fields: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Visit for SpanFields {
+        fn record_debug(&mut self, field: &Field, _value: &dyn std::fmt::Debug) {
+            self.fields.lock().unwrap().push(field.name().to_string());
+        }
+    }
+
+    impl Subscriber for SpanFields {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+            if attrs.metadata().name() == self.span {
+                attrs.record(&mut self.clone());
+            }
+            Id::from_u64(1)
+        }
+
+        fn record(&self, _: &Id, values: &Record<'_>) {
+            values.record(&mut self.clone());
+        }
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+        fn event(&self, _: &Event<'_>) {}
+        fn enter(&self, _: &Id) {}
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[tokio::test]
+    async fn login_span_records_no_credentials() {
+        let recorder = SpanFields {
+            span: "login",
+            fields: Arc::default(),
+        };
+        let _guard = tracing::subscriber::set_default(recorder.clone());
+
+        // An invalid password fails validation before any database access
+        let result = login("neo@matrix.io".to_string(), "short".to_string()).await;
+        assert!(result.is_err());
+
+        let fields = recorder.fields.lock().unwrap();
+        assert!(!fields.iter().any(|f| f == "email" || f == "password"));
+    }
+}
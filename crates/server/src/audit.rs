@@ -0,0 +1,198 @@
+use shared_types::{
+    AppError, AuditAction, AuditEntry, AuditLogQuery, AuditPage, ProductHistory, Role,
+};
+use sqlx::{Pool, Postgres};
+
+use crate::auth::jwt::Claims;
+use crate::error_convert::SqlxErrorExt;
+
+/// Entries per page when the query doesn't set one.
+const DEFAULT_PER_PAGE: i64 = 50;
+
+/// Most entries a single page may hold.
+const MAX_PER_PAGE: i64 = 200;
+
+/// Audit target for a user.
+pub fn user_target(user_id: i64) -> String {
+    format!("user:{user_id}")
+}
+
+/// Audit target for a product.
+pub fn product_target(product_id: i64) -> String {
+    format!("product:{product_id}")
+}
+
+/// The signed-in user behind the current server function call, if any.
+pub fn current_actor() -> Option<i64> {
+    use crate::auth::{cookies, jwt};
+
+    let ctx = dioxus::fullstack::FullstackContext::current()?;
+    let token = cookies::extract_access_token(&ctx.parts_mut().headers)?;
+    jwt::validate_access_token(&token).ok().map(|c| c.sub)
+}
+
+/// Record a privileged action.
+///
+/// Like the auth event log, failures are logged rather than returned: the
+/// action has already happened by the time it is recorded.
+pub async fn record(
+    pool: &Pool<Postgres>,
+    actor_id: Option<i64>,
+    action: AuditAction,
+    target: &str,
+    metadata: serde_json::Value,
+) {
+    let result = sqlx::query!(
+        "INSERT INTO audit_log (actor_id, action, target, metadata) VALUES ($1, $2, $3, $4::TEXT::JSONB)",
+        actor_id,
+        action.as_str(),
+        target,
+        metadata.to_string()
+    )
+    .execute(pool)
+    .await;
+
+    if let Err(e) = result {
+        tracing::warn!(error = %e, action = action.as_str(), target, "failed to record audit entry");
+    }
+}
+
+/// Page number and page size for a query, as `(page, per_page)`.
+fn page_bounds(query: &AuditLogQuery) -> (i64, i64) {
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query
+        .per_page
+        .unwrap_or(DEFAULT_PER_PAGE)
+        .clamp(1, MAX_PER_PAGE);
+    (page, per_page)
+}
+
+/// One page of entries matching `query`, newest first.
+pub async fn list(pool: &Pool<Postgres>, query: &AuditLogQuery) -> Result<AuditPage, AppError> {
+    let (page, per_page) = page_bounds(query);
+
+    let total = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM audit_log WHERE ($1::TEXT IS NULL OR target = $1)"#,
+        query.target
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?;
+
+    let rows = sqlx::query!(
+        r#"SELECT id, actor_id, action, target, metadata::TEXT AS "metadata!", created_at FROM audit_log WHERE ($1::TEXT IS NULL OR target = $1) ORDER BY created_at DESC, id DESC LIMIT $2 OFFSET $3"#,
+        query.target,
+        per_page,
+        (page - 1).saturating_mul(per_page)
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?;
+
+    let entries = rows
+        .into_iter()
+        .filter_map(|row| {
+            Some(AuditEntry {
+                id: row.id,
+                actor_id: row.actor_id,
+                action: AuditAction::parse_action(&row.action)?,
+                target: row.target,
+                metadata: serde_json::from_str(&row.metadata).unwrap_or_default(),
+                created_at: row.created_at.to_string(),
+            })
+        })
+        .collect();
+
+    Ok(AuditPage {
+        entries,
+        page,
+        per_page,
+        total,
+    })
+}
+
+/// Whether `viewer` may see a product's audit entries: its owner and
+/// admins may.
+fn can_view_product_audit(viewer: &Claims, owner_id: Option<i64>) -> bool {
+    owner_id == Some(viewer.sub) || Role::from_str_or_default(&viewer.role).is_admin()
+}
+
+/// When product `product_id` was created and last changed, with its audit
+/// entries if `viewer` owns it or is an admin.
+pub async fn product_history(
+    pool: &Pool<Postgres>,
+    product_id: i64,
+    viewer: &Claims,
+) -> Result<ProductHistory, AppError> {
+    let row = sqlx::query!(
+        "SELECT owner_id, created_at, updated_at FROM products WHERE id = $1 AND deleted_at IS NULL",
+        product_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?
+    .ok_or_else(|| AppError::not_found(format!("Product with id {} not found", product_id)))?;
+
+    let entries = if can_view_product_audit(viewer, row.owner_id) {
+        let query = AuditLogQuery {
+            target: Some(product_target(product_id)),
+            per_page: Some(MAX_PER_PAGE),
+            ..Default::default()
+        };
+        Some(list(pool, &query).await?.entries)
+    } else {
+        None
+    };
+
+    Ok(ProductHistory {
+        product_id,
+        owner_id: row.owner_id,
+        created_at: row.created_at.to_string(),
+        updated_at: row.updated_at.to_string(),
+        entries,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_bounds_are_clamped() {
+        assert_eq!(
+            page_bounds(&AuditLogQuery::default()),
+            (1, DEFAULT_PER_PAGE)
+        );
+
+        let query = AuditLogQuery {
+            page: Some(0),
+            per_page: Some(10_000),
+            ..Default::default()
+        };
+        assert_eq!(page_bounds(&query), (1, MAX_PER_PAGE));
+    }
+
+    #[test]
+    fn targets_name_the_kind_and_id() {
+        assert_eq!(user_target(42), "user:42");
+        assert_eq!(product_target(7), "product:7");
+    }
+
+    #[test]
+    fn only_owner_and_admins_see_product_audit() {
+        let claims = |sub, role: &str| Claims {
+            sub,
+            email: format!("user{sub}@example.com"),
+            role: role.to_string(),
+            tier: "free".to_string(),
+            exp: 0,
+            iat: 0,
+            jti: None,
+        };
+        assert!(can_view_product_audit(&claims(1, "user"), Some(1)));
+        assert!(can_view_product_audit(&claims(2, "admin"), Some(1)));
+        assert!(can_view_product_audit(&claims(2, "admin"), None));
+        assert!(!can_view_product_audit(&claims(2, "user"), Some(1)));
+        assert!(!can_view_product_audit(&claims(2, "user"), None));
+    }
+}
@@ -1,83 +1,258 @@
 use axum::http::{header, HeaderMap, HeaderValue};
-use cookie::Cookie;
-use std::sync::{Arc, Mutex};
+use cookie::time::Duration;
+use cookie::{Cookie, SameSite};
+use std::sync::{Arc, Mutex, OnceLock};
 
 use super::jwt;
 
+/// Default access token cookie name.
 pub const CYBER_ACCESS: &str = "cyber_access";
+/// Default refresh token cookie name.
 pub const CYBER_REFRESH: &str = "cyber_refresh";
+/// Default theme cookie name.
+pub const THEME_COOKIE: &str = "theme";
 
-fn cookie_secure() -> bool {
-    std::env::var("COOKIE_SECURE")
-        .ok()
-        .and_then(|v| v.parse().ok())
-        .unwrap_or(false)
+/// Names of the cookies the app sets.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CookieNames {
+    pub access: String,
+    pub refresh: String,
+    /// Written by the browser (see `shared_ui::theme`), which learns the
+    /// name from `ThemeSeed`.
+    pub theme: String,
 }
 
-fn cookie_domain() -> Option<String> {
-    std::env::var("COOKIE_DOMAIN")
-        .ok()
-        .filter(|d| !d.is_empty())
+impl Default for CookieNames {
+    fn default() -> Self {
+        Self {
+            access: CYBER_ACCESS.to_string(),
+            refresh: CYBER_REFRESH.to_string(),
+            theme: THEME_COOKIE.to_string(),
+        }
+    }
 }
 
-/// Build a Set-Cookie header value for the access token.
-pub fn build_access_cookie(token: &str, max_age_minutes: i64) -> HeaderValue {
-    let mut cookie = Cookie::build((CYBER_ACCESS, token))
-        .http_only(true)
-        .same_site(cookie::SameSite::Lax)
-        .path("/")
-        .max_age(cookie::time::Duration::seconds(max_age_minutes * 60))
-        .secure(cookie_secure());
+impl CookieNames {
+    /// Every name prefixed with `prefix`: `{prefix}access`,
+    /// `{prefix}refresh` and `{prefix}theme`.
+    pub fn with_prefix(prefix: &str) -> Self {
+        Self {
+            access: format!("{prefix}access"),
+            refresh: format!("{prefix}refresh"),
+            theme: format!("{prefix}theme"),
+        }
+    }
 
-    if let Some(domain) = cookie_domain() {
-        cookie = cookie.domain(domain);
+    /// Load from the environment.
+    ///
+    /// - `COOKIE_PREFIX` prefixes every name, e.g. `shop_` gives
+    ///   `shop_access`, `shop_refresh` and `shop_theme`. Unset keeps the
+    ///   defaults (`cyber_access`, `cyber_refresh`, `theme`).
+    /// - `COOKIE_ACCESS_NAME`, `COOKIE_REFRESH_NAME` and `COOKIE_THEME_NAME`
+    ///   override single names.
+    pub fn from_env() -> Result<Self, String> {
+        let var = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let mut names = match var("COOKIE_PREFIX") {
+            Some(prefix) => Self::with_prefix(&prefix),
+            None => Self::default(),
+        };
+        if let Some(name) = var("COOKIE_ACCESS_NAME") {
+            names.access = name;
+        }
+        if let Some(name) = var("COOKIE_REFRESH_NAME") {
+            names.refresh = name;
+        }
+        if let Some(name) = var("COOKIE_THEME_NAME") {
+            names.theme = name;
+        }
+        names.validate()?;
+        Ok(names)
     }
 
-    HeaderValue::from_str(&cookie.build().to_string()).expect("cookie header value should be valid")
+    /// Reject names that are not valid cookie names or that collide.
+    fn validate(&self) -> Result<(), String> {
+        for name in [&self.access, &self.refresh, &self.theme] {
+            if !is_cookie_name(name) {
+                return Err(format!(
+                    "Invalid cookie name {name:?}; use letters, digits, '-' and '_'"
+                ));
+            }
+        }
+        if self.access == self.refresh || self.access == self.theme || self.refresh == self.theme {
+            return Err("Cookie names must be distinct".to_string());
+        }
+        Ok(())
+    }
 }
 
-/// Build a Set-Cookie header value for the refresh token.
-pub fn build_refresh_cookie(token: &str, max_age_days: i64) -> HeaderValue {
-    let mut cookie = Cookie::build((CYBER_REFRESH, token))
-        .http_only(true)
-        .same_site(cookie::SameSite::Lax)
-        .path("/")
-        .max_age(cookie::time::Duration::seconds(max_age_days * 86400))
-        .secure(cookie_secure());
+/// Whether `name` is safe to use as a cookie name, here and in the inline
+/// scripts that read and write the theme cookie.
+fn is_cookie_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
 
-    if let Some(domain) = cookie_domain() {
-        cookie = cookie.domain(domain);
+/// Attributes applied to every auth cookie.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CookieConfig {
+    /// `Domain` attribute; `None` scopes cookies to the exact host.
+    pub domain: Option<String>,
+    pub secure: bool,
+    pub same_site: SameSite,
+    pub names: CookieNames,
+}
+
+impl Default for CookieConfig {
+    fn default() -> Self {
+        Self {
+            domain: None,
+            secure: false,
+            same_site: SameSite::Lax,
+            names: CookieNames::default(),
+        }
     }
+}
 
-    HeaderValue::from_str(&cookie.build().to_string()).expect("cookie header value should be valid")
+impl CookieConfig {
+    /// Build a config, rejecting `SameSite=None` without `Secure`, which
+    /// browsers refuse to store.
+    pub fn new(domain: Option<String>, secure: bool, same_site: SameSite) -> Result<Self, String> {
+        if same_site == SameSite::None && !secure {
+            return Err("COOKIE_SAMESITE=none requires COOKIE_SECURE=true".to_string());
+        }
+        Ok(Self {
+            domain,
+            secure,
+            same_site,
+            names: CookieNames::default(),
+        })
+    }
+
+    /// Use `names` instead of the default cookie names.
+    pub fn with_names(mut self, names: CookieNames) -> Self {
+        self.names = names;
+        self
+    }
+
+    /// Load from the environment.
+    ///
+    /// - `COOKIE_DOMAIN` sets the `Domain` attribute, e.g. `.example.com` to
+    ///   share the session across subdomains (unset or empty = host only).
+    /// - `COOKIE_SECURE` (`true`/`false`, default `false`) sets `Secure`.
+    /// - `COOKIE_SAMESITE` is `strict`, `lax` (default), or `none`.
+    /// - Cookie names as described in [`CookieNames::from_env`].
+    pub fn from_env() -> Result<Self, String> {
+        let domain = std::env::var("COOKIE_DOMAIN")
+            .ok()
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty());
+        let secure = std::env::var("COOKIE_SECURE")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(false);
+        let same_site = match std::env::var("COOKIE_SAMESITE") {
+            Ok(v) => parse_same_site(&v)?,
+            Err(_) => SameSite::Lax,
+        };
+        Ok(Self::new(domain, secure, same_site)?.with_names(CookieNames::from_env()?))
+    }
+
+    /// A cookie named `name` with these attributes, expiring after `max_age`.
+    fn build<'c>(&self, name: &'c str, value: &'c str, max_age: Duration) -> Cookie<'c> {
+        let mut cookie = Cookie::build((name, value))
+            .http_only(true)
+            .same_site(self.same_site)
+            .path("/")
+            .max_age(max_age)
+            .secure(self.secure);
+
+        if let Some(domain) = &self.domain {
+            cookie = cookie.domain(domain.clone());
+        }
+
+        cookie.build()
+    }
 }
 
-/// Build Set-Cookie headers that clear both auth cookies.
-pub fn build_clear_cookies() -> (HeaderValue, HeaderValue) {
-    let access = Cookie::build((CYBER_ACCESS, ""))
-        .http_only(true)
-        .same_site(cookie::SameSite::Lax)
-        .path("/")
-        .max_age(cookie::time::Duration::ZERO)
-        .build();
-
-    let refresh = Cookie::build((CYBER_REFRESH, ""))
-        .http_only(true)
-        .same_site(cookie::SameSite::Lax)
-        .path("/")
-        .max_age(cookie::time::Duration::ZERO)
-        .build();
+/// Parse a `SameSite` value, case-insensitively.
+fn parse_same_site(value: &str) -> Result<SameSite, String> {
+    match value.trim().to_lowercase().as_str() {
+        "strict" => Ok(SameSite::Strict),
+        "lax" | "" => Ok(SameSite::Lax),
+        "none" => Ok(SameSite::None),
+        other => Err(format!(
+            "Invalid COOKIE_SAMESITE value {other:?}; expected strict, lax, or none"
+        )),
+    }
+}
+
+/// Cookie attributes loaded once from the environment.
+///
+/// Panics on invalid settings; call it at startup so a misconfiguration
+/// fails fast instead of on the first login.
+pub fn cookie_config() -> &'static CookieConfig {
+    static CONFIG: OnceLock<CookieConfig> = OnceLock::new();
+    CONFIG.get_or_init(|| CookieConfig::from_env().unwrap_or_else(|e| panic!("{e}")))
+}
+
+fn header_value(cookie: Cookie<'_>) -> HeaderValue {
+    HeaderValue::from_str(&cookie.to_string()).expect("cookie header value should be valid")
+}
+
+/// Build a Set-Cookie header value for the access token.
+pub fn build_access_cookie(
+    config: &CookieConfig,
+    token: &str,
+    max_age_minutes: i64,
+) -> HeaderValue {
+    header_value(config.build(
+        &config.names.access,
+        token,
+        Duration::seconds(max_age_minutes * 60),
+    ))
+}
+
+/// Build a Set-Cookie header value for the refresh token.
+pub fn build_refresh_cookie(config: &CookieConfig, token: &str, max_age_days: i64) -> HeaderValue {
+    header_value(config.build(
+        &config.names.refresh,
+        token,
+        Duration::seconds(max_age_days * 86400),
+    ))
+}
 
+/// Build Set-Cookie headers that clear both auth cookies.
+///
+/// Uses the same attributes as the cookies being cleared; browsers only
+/// replace a cookie whose domain and path match.
+pub fn build_clear_cookies(config: &CookieConfig) -> (HeaderValue, HeaderValue) {
     (
-        HeaderValue::from_str(&access.to_string()).expect("clear cookie should be valid"),
-        HeaderValue::from_str(&refresh.to_string()).expect("clear cookie should be valid"),
+        header_value(config.build(&config.names.access, "", Duration::ZERO)),
+        header_value(config.build(&config.names.refresh, "", Duration::ZERO)),
     )
 }
 
 /// Extract the access token from cookies (preferred) or Bearer header (fallback).
 pub fn extract_access_token(headers: &HeaderMap) -> Option<String> {
+    access_token_from(headers, &cookie_config().names)
+}
+
+/// Extract the refresh token from cookies.
+pub fn extract_refresh_token(headers: &HeaderMap) -> Option<String> {
+    extract_cookie(headers, &cookie_config().names.refresh)
+}
+
+/// [`extract_access_token`] with explicit cookie names.
+fn access_token_from(headers: &HeaderMap, names: &CookieNames) -> Option<String> {
     // Try cookie first
-    if let Some(token) = extract_cookie(headers, CYBER_ACCESS) {
+    if let Some(token) = extract_cookie(headers, &names.access) {
         return Some(token);
     }
 
@@ -93,11 +268,6 @@ pub fn extract_access_token(headers: &HeaderMap) -> Option<String> {
     None
 }
 
-/// Extract the refresh token from cookies.
-pub fn extract_refresh_token(headers: &HeaderMap) -> Option<String> {
-    extract_cookie(headers, CYBER_REFRESH)
-}
-
 /// Parse a specific cookie value from the Cookie header.
 fn extract_cookie(headers: &HeaderMap, name: &str) -> Option<String> {
     for header_value in headers.get_all(header::COOKIE) {
@@ -116,22 +286,23 @@ fn extract_cookie(headers: &HeaderMap, name: &str) -> Option<String> {
 
 /// Set both access and refresh cookies on the response using current JWT expiry config.
 pub fn set_auth_cookies(headers: &mut HeaderMap, access_token: &str, refresh_token: &str) {
+    let config = cookie_config();
     let access_minutes = jwt::access_token_expiry_minutes();
     let refresh_days = jwt::refresh_token_expiry_days();
 
     headers.append(
         header::SET_COOKIE,
-        build_access_cookie(access_token, access_minutes),
+        build_access_cookie(config, access_token, access_minutes),
     );
     headers.append(
         header::SET_COOKIE,
-        build_refresh_cookie(refresh_token, refresh_days),
+        build_refresh_cookie(config, refresh_token, refresh_days),
     );
 }
 
 /// Clear both auth cookies on the response.
 pub fn clear_auth_cookies(headers: &mut HeaderMap) {
-    let (access, refresh) = build_clear_cookies();
+    let (access, refresh) = build_clear_cookies(cookie_config());
     headers.append(header::SET_COOKIE, access);
     headers.append(header::SET_COOKIE, refresh);
 }
@@ -175,3 +346,110 @@ pub fn schedule_clear_cookies() {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_config_is_lax_host_only() {
+        let cookie = build_access_cookie(&CookieConfig::default(), "tok", 15);
+        let cookie = cookie.to_str().unwrap();
+        assert!(cookie.starts_with("cyber_access=tok;"));
+        assert!(cookie.contains("HttpOnly"));
+        assert!(cookie.contains("SameSite=Lax"));
+        assert!(cookie.contains("Path=/"));
+        assert!(cookie.contains("Max-Age=900"));
+        assert!(!cookie.contains("Secure"));
+        assert!(!cookie.contains("Domain"));
+    }
+
+    #[test]
+    fn cross_site_config_sets_domain_secure_and_samesite_none() {
+        let config =
+            CookieConfig::new(Some(".example.com".to_string()), true, SameSite::None).unwrap();
+        let cookie = build_refresh_cookie(&config, "tok", 7);
+        let cookie = cookie.to_str().unwrap();
+        assert!(cookie.starts_with("cyber_refresh=tok;"));
+        assert!(cookie.contains("SameSite=None"));
+        assert!(cookie.contains("Secure"));
+        assert!(cookie.contains("Domain=example.com"));
+        assert!(cookie.contains("Max-Age=604800"));
+    }
+
+    #[test]
+    fn clear_cookies_keep_configured_attributes() {
+        let config =
+            CookieConfig::new(Some("app.example.com".to_string()), true, SameSite::Strict).unwrap();
+        let (access, refresh) = build_clear_cookies(&config);
+        for cookie in [access.to_str().unwrap(), refresh.to_str().unwrap()] {
+            assert!(cookie.contains("Max-Age=0"));
+            assert!(cookie.contains("SameSite=Strict"));
+            assert!(cookie.contains("Secure"));
+            assert!(cookie.contains("Domain=app.example.com"));
+        }
+    }
+
+    /// Send the `Set-Cookie` values back as a `Cookie` header, like a browser.
+    fn echo_cookies(set_cookies: &[HeaderValue]) -> HeaderMap {
+        let pairs: Vec<&str> = set_cookies
+            .iter()
+            .map(|v| v.to_str().unwrap().split(';').next().unwrap())
+            .collect();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            HeaderValue::from_str(&pairs.join("; ")).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn custom_cookie_names_round_trip() {
+        let names = CookieNames::with_prefix("shop_");
+        let config = CookieConfig::default().with_names(names.clone());
+        let access = build_access_cookie(&config, "acc", 15);
+        let refresh = build_refresh_cookie(&config, "ref", 7);
+        assert!(access.to_str().unwrap().starts_with("shop_access=acc;"));
+        assert!(refresh.to_str().unwrap().starts_with("shop_refresh=ref;"));
+
+        let headers = echo_cookies(&[access, refresh]);
+        assert_eq!(access_token_from(&headers, &names), Some("acc".into()));
+        assert_eq!(extract_cookie(&headers, &names.refresh), Some("ref".into()));
+        // Cookies under the default names belong to another app.
+        let defaults = CookieNames::default();
+        assert_eq!(access_token_from(&headers, &defaults), None);
+        assert_eq!(extract_cookie(&headers, &defaults.refresh), None);
+
+        let (access, refresh) = build_clear_cookies(&config);
+        assert!(access.to_str().unwrap().starts_with("shop_access=;"));
+        assert!(refresh.to_str().unwrap().starts_with("shop_refresh=;"));
+    }
+
+    #[test]
+    fn cookie_names_are_validated() {
+        assert!(CookieNames::default().validate().is_ok());
+        assert!(CookieNames::with_prefix("app-1_").validate().is_ok());
+        assert!(CookieNames::with_prefix("a b").validate().is_err());
+        assert!(CookieNames::with_prefix("x';").validate().is_err());
+        let same = CookieNames {
+            refresh: CYBER_ACCESS.to_string(),
+            ..CookieNames::default()
+        };
+        assert!(same.validate().is_err());
+    }
+
+    #[test]
+    fn samesite_none_requires_secure() {
+        assert!(CookieConfig::new(None, false, SameSite::None).is_err());
+        assert!(CookieConfig::new(None, true, SameSite::None).is_ok());
+    }
+
+    #[test]
+    fn samesite_parses_case_insensitively() {
+        assert_eq!(parse_same_site("Strict"), Ok(SameSite::Strict));
+        assert_eq!(parse_same_site(" NONE "), Ok(SameSite::None));
+        assert_eq!(parse_same_site(""), Ok(SameSite::Lax));
+        assert!(parse_same_site("sometimes").is_err());
+    }
+}
@@ -0,0 +1,225 @@
+use std::sync::Once;
+use std::time::Duration;
+
+use shared_types::{AccountDeletion, AppError, AuditAction};
+use sqlx::{Pool, Postgres};
+
+use crate::audit;
+use crate::error_convert::SqlxErrorExt;
+
+/// Days between requesting deletion and the account being purged, when
+/// `ACCOUNT_DELETION_GRACE_DAYS` isn't set.
+const DEFAULT_GRACE_DAYS: i32 = 30;
+
+/// Hours a user soft-deleted by an admin is kept, when
+/// `DELETED_USER_RETENTION_HOURS` isn't set.
+const DEFAULT_RETENTION_HOURS: i32 = 24;
+
+/// How often the purger looks for accounts past their grace period.
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Parse a grace period in days, falling back to the default for missing,
+/// unparseable, or non-positive values.
+fn parse_grace_days(value: Option<&str>) -> i32 {
+    value
+        .and_then(|v| v.trim().parse::<i32>().ok())
+        .filter(|days| *days > 0)
+        .unwrap_or(DEFAULT_GRACE_DAYS)
+}
+
+/// Grace period in days, read from `ACCOUNT_DELETION_GRACE_DAYS`.
+pub fn grace_days() -> i32 {
+    parse_grace_days(std::env::var("ACCOUNT_DELETION_GRACE_DAYS").ok().as_deref())
+}
+
+/// Parse a retention period in hours, falling back to the default for
+/// missing, unparseable, or non-positive values.
+fn parse_retention_hours(value: Option<&str>) -> i32 {
+    value
+        .and_then(|v| v.trim().parse::<i32>().ok())
+        .filter(|hours| *hours > 0)
+        .unwrap_or(DEFAULT_RETENTION_HOURS)
+}
+
+/// How long a user soft-deleted by an admin is kept, in hours, read from
+/// `DELETED_USER_RETENTION_HOURS`.
+///
+/// This is the undo window: the user can be restored until the purger next
+/// runs after it ends, which is up to [`PURGE_INTERVAL`] later.
+pub fn deleted_user_retention_hours() -> i32 {
+    parse_retention_hours(
+        std::env::var("DELETED_USER_RETENTION_HOURS")
+            .ok()
+            .as_deref(),
+    )
+}
+
+/// Schedule `user_id` for deletion after the grace period.
+///
+/// Scheduling an account that is already pending keeps the original date.
+pub async fn schedule(pool: &Pool<Postgres>, user_id: i64) -> Result<AccountDeletion, AppError> {
+    let scheduled_for = sqlx::query_scalar!(
+        r#"UPDATE users SET deletion_scheduled_for = COALESCE(deletion_scheduled_for, NOW() + make_interval(days => $2)) WHERE id = $1 RETURNING deletion_scheduled_for AS "scheduled_for!""#,
+        user_id,
+        grace_days()
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?
+    .ok_or_else(|| AppError::not_found("User not found"))?;
+
+    Ok(AccountDeletion {
+        scheduled_for: scheduled_for.to_string(),
+    })
+}
+
+/// Cancel a pending deletion. Returns 404 when none is scheduled.
+pub async fn cancel(pool: &Pool<Postgres>, user_id: i64) -> Result<(), AppError> {
+    let result = sqlx::query!(
+        "UPDATE users SET deletion_scheduled_for = NULL WHERE id = $1 AND deletion_scheduled_for IS NOT NULL",
+        user_id
+    )
+    .execute(pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found("No account deletion is scheduled"));
+    }
+    Ok(())
+}
+
+/// The pending deletion for `user_id`, if any.
+pub async fn pending(
+    pool: &Pool<Postgres>,
+    user_id: i64,
+) -> Result<Option<AccountDeletion>, AppError> {
+    let scheduled_for = sqlx::query_scalar!(
+        "SELECT deletion_scheduled_for FROM users WHERE id = $1",
+        user_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?
+    .flatten();
+
+    Ok(scheduled_for.map(|at| AccountDeletion {
+        scheduled_for: at.to_string(),
+    }))
+}
+
+/// Refuse to sign in to, or refresh a session of, an account deleted by an
+/// admin.
+pub async fn ensure_not_deleted(pool: &Pool<Postgres>, user_id: i64) -> Result<(), AppError> {
+    let deleted = sqlx::query_scalar!(
+        r#"SELECT deleted_at IS NOT NULL AS "deleted!" FROM users WHERE id = $1"#,
+        user_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?
+    .unwrap_or(false);
+    if deleted {
+        return Err(AppError::forbidden("This account has been deleted."));
+    }
+    Ok(())
+}
+
+/// Refuse to sign in to an account that is pending deletion or was
+/// deleted by an admin.
+///
+/// Only new sign-ins are blocked: a session that was already open keeps
+/// working, so its owner can still cancel from Settings.
+pub async fn ensure_not_pending(pool: &Pool<Postgres>, user_id: i64) -> Result<(), AppError> {
+    ensure_not_deleted(pool, user_id).await?;
+
+    match pending(pool, user_id).await? {
+        Some(deletion) => Err(AppError::forbidden(format!(
+            "This account is scheduled for deletion on {}. Cancel the deletion to sign in again.",
+            deletion.scheduled_for
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Delete every account whose grace period has passed, and every account
+/// soft-deleted more than [`deleted_user_retention_hours`] ago. Returns how
+/// many were deleted.
+pub async fn purge_expired(pool: &Pool<Postgres>) -> Result<u64, AppError> {
+    let purged = sqlx::query!(
+        r#"DELETE FROM users
+        WHERE deletion_scheduled_for <= NOW() OR deleted_at <= NOW() - make_interval(hours => $1)
+        RETURNING id, username, deleted_at IS NOT NULL AS "soft_deleted!""#,
+        deleted_user_retention_hours()
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?;
+
+    for user in &purged {
+        let reason = if user.soft_deleted {
+            "deleted_by_admin"
+        } else {
+            "scheduled_deletion"
+        };
+        audit::record(
+            pool,
+            None,
+            AuditAction::UserDelete,
+            &audit::user_target(user.id),
+            serde_json::json!({ "username": user.username, "reason": reason }),
+        )
+        .await;
+    }
+    Ok(purged.len() as u64)
+}
+
+/// Spawn a background task that purges expired accounts every hour.
+/// Later calls do nothing.
+pub fn spawn_purger(pool: Pool<Postgres>) {
+    static STARTED: Once = Once::new();
+    STARTED.call_once(|| {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(PURGE_INTERVAL);
+            loop {
+                interval.tick().await;
+                match purge_expired(&pool).await {
+                    Ok(0) => {}
+                    Ok(purged) => {
+                        tracing::info!(purged, "purged accounts past their deletion date")
+                    }
+                    Err(e) => tracing::warn!(error = %e, "failed to purge deleted accounts"),
+                }
+            }
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grace_days_falls_back_to_default() {
+        assert_eq!(parse_grace_days(None), DEFAULT_GRACE_DAYS);
+        assert_eq!(parse_grace_days(Some("soon")), DEFAULT_GRACE_DAYS);
+        assert_eq!(parse_grace_days(Some("0")), DEFAULT_GRACE_DAYS);
+        assert_eq!(parse_grace_days(Some("-3")), DEFAULT_GRACE_DAYS);
+    }
+
+    #[test]
+    fn grace_days_reads_positive_values() {
+        assert_eq!(parse_grace_days(Some(" 7 ")), 7);
+    }
+
+    #[test]
+    fn retention_hours_fall_back_to_default() {
+        assert_eq!(parse_retention_hours(None), DEFAULT_RETENTION_HOURS);
+        assert_eq!(
+            parse_retention_hours(Some("a day")),
+            DEFAULT_RETENTION_HOURS
+        );
+        assert_eq!(parse_retention_hours(Some("0")), DEFAULT_RETENTION_HOURS);
+        assert_eq!(parse_retention_hours(Some(" 72 ")), 72);
+    }
+}
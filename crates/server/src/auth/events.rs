@@ -0,0 +1,151 @@
+use std::convert::Infallible;
+
+use axum::extract::FromRequestParts;
+use axum::http::{header, request::Parts, Extensions, HeaderMap};
+use shared_types::{AppError, AuthEvent, AuthEventQuery, AuthEventType};
+use sqlx::{Pool, Postgres};
+
+use crate::client_ip;
+use crate::error_convert::SqlxErrorExt;
+
+/// Events returned when the query doesn't set a limit.
+const DEFAULT_LIMIT: i64 = 100;
+
+/// Most events a single query may return.
+const MAX_LIMIT: i64 = 500;
+
+/// Longest user-agent stored; anything longer is cut.
+const MAX_USER_AGENT_CHARS: usize = 512;
+
+/// Where a request came from, as recorded in the audit log.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ClientInfo {
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl ClientInfo {
+    /// Read the client IP and user-agent from a request.
+    ///
+    /// The IP comes from [`crate::client_ip::client_ip`], which only honors
+    /// forwarded headers set by a trusted proxy.
+    pub fn from_parts(headers: &HeaderMap, extensions: &Extensions) -> Self {
+        let ip_address = client_ip::client_ip(headers, extensions).map(|ip| ip.to_string());
+
+        let user_agent = headers
+            .get(header::USER_AGENT)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(|v| v.chars().take(MAX_USER_AGENT_CHARS).collect());
+
+        Self {
+            ip_address,
+            user_agent,
+        }
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for ClientInfo {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_parts(&parts.headers, &parts.extensions))
+    }
+}
+
+/// Record an authentication event.
+///
+/// Failures are logged rather than returned so a broken audit log never
+/// blocks anyone from signing in or out.
+pub async fn record(
+    pool: &Pool<Postgres>,
+    event_type: AuthEventType,
+    user_id: Option<i64>,
+    client: &ClientInfo,
+) {
+    let result = sqlx::query!(
+        "INSERT INTO auth_events (user_id, event_type, ip_address, user_agent) VALUES ($1, $2, $3, $4)",
+        user_id,
+        event_type.as_str(),
+        client.ip_address,
+        client.user_agent
+    )
+    .execute(pool)
+    .await;
+
+    if let Err(e) = result {
+        tracing::warn!(error = %e, event = event_type.as_str(), "failed to record auth event");
+    }
+}
+
+/// Number of events to return for a requested `limit`.
+fn clamp_limit(limit: Option<i64>) -> i64 {
+    limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT)
+}
+
+/// Most recent events matching `query`, newest first.
+pub async fn list(
+    pool: &Pool<Postgres>,
+    query: &AuthEventQuery,
+) -> Result<Vec<AuthEvent>, AppError> {
+    let event_type = match query.event_type.as_deref() {
+        Some(raw) => Some(
+            AuthEventType::parse_event_type(raw)
+                .ok_or_else(|| AppError::bad_request(format!("Unknown event type: {raw}")))?,
+        ),
+        None => None,
+    };
+
+    let rows = sqlx::query!(
+        "SELECT id, user_id, event_type, ip_address, user_agent, created_at FROM auth_events WHERE ($1::BIGINT IS NULL OR user_id = $1) AND ($2::TEXT IS NULL OR event_type = $2) ORDER BY created_at DESC, id DESC LIMIT $3",
+        query.user_id,
+        event_type.map(|e| e.as_str()),
+        clamp_limit(query.limit)
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| {
+            Some(AuthEvent {
+                id: row.id,
+                user_id: row.user_id,
+                event_type: AuthEventType::parse_event_type(&row.event_type)?,
+                ip_address: row.ip_address,
+                user_agent: row.user_agent,
+                created_at: row.created_at.to_string(),
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::ConnectInfo;
+    use axum::http::HeaderValue;
+    use std::net::SocketAddr;
+
+    #[test]
+    fn client_info_ignores_forwarded_headers_without_a_trusted_proxy() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", HeaderValue::from_static("198.51.100.66"));
+        headers.insert(header::USER_AGENT, HeaderValue::from_static("curl/8.0"));
+        let mut extensions = Extensions::new();
+        extensions.insert(ConnectInfo(SocketAddr::from(([203, 0, 113, 7], 5000))));
+
+        let client = ClientInfo::from_parts(&headers, &extensions);
+        assert_eq!(client.ip_address.as_deref(), Some("203.0.113.7"));
+        assert_eq!(client.user_agent.as_deref(), Some("curl/8.0"));
+    }
+
+    #[test]
+    fn limit_is_clamped() {
+        assert_eq!(clamp_limit(None), DEFAULT_LIMIT);
+        assert_eq!(clamp_limit(Some(0)), 1);
+        assert_eq!(clamp_limit(Some(10_000)), MAX_LIMIT);
+    }
+}
@@ -1,7 +1,11 @@
-use axum::{extract::FromRequestParts, http::request::Parts};
+use axum::{
+    extract::FromRequestParts,
+    http::{request::Parts, HeaderMap},
+};
 use shared_types::{AppError, UserTier};
 
-use super::jwt::Claims;
+use super::cookies;
+use super::jwt::{self, Claims};
 
 /// Extractor that requires authentication. Returns 401 if no valid token.
 pub struct AuthRequired(pub Claims);
@@ -19,21 +23,80 @@ impl<S: Send + Sync> FromRequestParts<S> for AuthRequired {
     }
 }
 
-/// Extractor that optionally extracts auth claims. Never fails.
-pub struct MaybeAuth(pub Option<Claims>);
+/// Extractor for endpoints that adapt to auth state without requiring it.
+///
+/// Yields the claims from the auth middleware when present, otherwise
+/// validates the access token from the request itself, so it also works on
+/// routes outside the middleware. Missing or invalid tokens give `None`
+/// rather than an error.
+pub struct AuthOptional(pub Option<Claims>);
 
-impl<S: Send + Sync> FromRequestParts<S> for MaybeAuth {
+impl<S: Send + Sync> FromRequestParts<S> for AuthOptional {
     type Rejection = std::convert::Infallible;
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        Ok(MaybeAuth(parts.extensions.get::<Claims>().cloned()))
+        if let Some(claims) = parts.extensions.get::<Claims>() {
+            return Ok(AuthOptional(Some(claims.clone())));
+        }
+
+        let claims = cookies::extract_access_token(&parts.headers)
+            .and_then(|token| jwt::validate_access_token(&token).ok());
+        Ok(AuthOptional(claims))
     }
 }
 
-/// Extractor that requires authentication AND a minimum user tier.
+/// Rank of [`UserTier::Free`], for use as a [`TierRequired`] parameter.
+pub const FREE: u8 = 0;
+/// Rank of [`UserTier::Premium`], for use as a [`TierRequired`] parameter.
+pub const PREMIUM: u8 = 1;
+/// Rank of [`UserTier::Elite`], for use as a [`TierRequired`] parameter.
+pub const ELITE: u8 = 2;
+
+/// Extractor that requires authentication AND a minimum user tier, given by
+/// its rank (see [`UserTier::rank`]). Yields the caller's claims.
 /// Returns 401 if unauthenticated, 403 if insufficient tier.
 pub struct TierRequired<const TIER: u8>(pub Claims);
 
+/// Requires Premium tier or above.
+pub type PremiumRequired = TierRequired<PREMIUM>;
+
+/// Requires Elite tier.
+pub type EliteRequired = TierRequired<ELITE>;
+
+impl<const TIER: u8> TierRequired<TIER> {
+    /// The minimum tier this extractor accepts.
+    pub fn required_tier() -> UserTier {
+        match TIER {
+            FREE => UserTier::Free,
+            PREMIUM => UserTier::Premium,
+            _ => UserTier::Elite,
+        }
+    }
+
+    /// Check that `claims` meet the required tier.
+    pub fn check(claims: Claims) -> Result<Self, AppError> {
+        let required_tier = Self::required_tier();
+        let user_tier = UserTier::from_str_or_default(&claims.tier);
+        if !user_tier.has_access(&required_tier) {
+            return Err(AppError::forbidden(format!(
+                "{} tier or higher required",
+                required_tier.display_name()
+            )));
+        }
+        Ok(TierRequired(claims))
+    }
+
+    /// Validate the access token in `headers` and check its tier, for
+    /// server functions that can't use extractors.
+    pub fn from_headers(headers: &HeaderMap) -> Result<Self, AppError> {
+        let token = cookies::extract_access_token(headers)
+            .ok_or_else(|| AppError::unauthorized("Authentication required"))?;
+        let claims = jwt::validate_access_token(&token)
+            .map_err(|_| AppError::unauthorized("Invalid token"))?;
+        Self::check(claims)
+    }
+}
+
 impl<const TIER: u8, S: Send + Sync> FromRequestParts<S> for TierRequired<TIER> {
     type Rejection = AppError;
 
@@ -43,22 +106,37 @@ impl<const TIER: u8, S: Send + Sync> FromRequestParts<S> for TierRequired<TIER>
             .get::<Claims>()
             .cloned()
             .ok_or_else(|| AppError::unauthorized("Authentication required"))?;
+        Self::check(claims)
+    }
+}
 
-        let user_tier = UserTier::from_str_or_default(&claims.tier);
-        let required_tier = match TIER {
-            0 => UserTier::Free,
-            1 => UserTier::Premium,
-            2 => UserTier::Elite,
-            _ => UserTier::Elite,
-        };
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        if !user_tier.has_access(&required_tier) {
-            return Err(AppError::forbidden(format!(
-                "{} tier or higher required",
-                required_tier.as_str()
-            )));
+    fn claims(tier: &str) -> Claims {
+        Claims {
+            sub: 1,
+            email: "tier@test.com".to_string(),
+            role: "user".to_string(),
+            tier: tier.to_string(),
+            exp: 0,
+            iat: 0,
+            jti: None,
         }
+    }
 
-        Ok(TierRequired(claims))
+    #[test]
+    fn free_tier_is_rejected_for_premium() {
+        let err = PremiumRequired::check(claims("free")).err().unwrap();
+        assert_eq!(err.kind, shared_types::AppErrorKind::Forbidden);
+        assert_eq!(err.message, "Premium tier or higher required");
+    }
+
+    #[test]
+    fn higher_tiers_pass_premium() {
+        assert!(PremiumRequired::check(claims("premium")).is_ok());
+        assert!(PremiumRequired::check(claims("elite")).is_ok());
+        assert!(EliteRequired::check(claims("premium")).is_err());
     }
 }
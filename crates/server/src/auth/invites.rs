@@ -0,0 +1,149 @@
+use shared_types::{AppError, Invite};
+use sqlx::PgConnection;
+
+use crate::error_convert::SqlxErrorExt;
+
+/// Who may create an account with email/password.
+///
+/// Read from `REGISTRATION_MODE`: `public` (default) lets anyone register,
+/// `invite` requires an unused invite token issued by an admin.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RegistrationMode {
+    #[default]
+    Public,
+    Invite,
+}
+
+impl RegistrationMode {
+    /// Parse a mode string, defaulting to `Public` for unknown values.
+    pub fn from_str_or_default(s: &str) -> Self {
+        match s.trim().to_lowercase().as_str() {
+            "invite" => RegistrationMode::Invite,
+            _ => RegistrationMode::Public,
+        }
+    }
+
+    /// Load from `REGISTRATION_MODE`.
+    pub fn from_env() -> Self {
+        std::env::var("REGISTRATION_MODE")
+            .map(|v| Self::from_str_or_default(&v))
+            .unwrap_or_default()
+    }
+
+    pub fn requires_invite(&self) -> bool {
+        *self == RegistrationMode::Invite
+    }
+}
+
+/// Generate a random invite token (256 bits, hex-encoded).
+pub fn generate_token() -> String {
+    format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+/// Store a new invite for `email`, issued by the admin `created_by`.
+pub async fn create_invite(
+    conn: &mut PgConnection,
+    email: &str,
+    created_by: i64,
+) -> Result<Invite, AppError> {
+    let row = sqlx::query!(
+        "INSERT INTO invites (email, token, created_by) VALUES ($1, $2, $3) RETURNING id, email, token, created_at",
+        email,
+        generate_token(),
+        created_by
+    )
+    .fetch_one(conn)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?;
+
+    Ok(Invite {
+        id: row.id,
+        email: row.email,
+        token: row.token,
+        created_at: row.created_at.to_string(),
+    })
+}
+
+/// Mark the invite identified by `token` as used by `email`.
+///
+/// Fails with `Forbidden` when the token is missing, unknown, already used,
+/// or issued for a different email. Run inside the registration transaction
+/// so a failed signup doesn't consume the invite.
+pub async fn claim_invite(
+    conn: &mut PgConnection,
+    token: Option<&str>,
+    email: &str,
+) -> Result<i64, AppError> {
+    let token = token
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .ok_or_else(|| AppError::forbidden("An invite is required to register"))?;
+
+    sqlx::query_scalar!(
+        "UPDATE invites SET used_at = NOW() WHERE token = $1 AND used_at IS NULL AND LOWER(email) = LOWER($2) RETURNING id",
+        token,
+        email
+    )
+    .fetch_optional(conn)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?
+    .ok_or_else(|| AppError::forbidden("Invite is invalid or has already been used"))
+}
+
+/// Record which user registered with a claimed invite.
+pub async fn record_invitee(
+    conn: &mut PgConnection,
+    invite_id: i64,
+    user_id: i64,
+) -> Result<(), AppError> {
+    sqlx::query!(
+        "UPDATE invites SET used_by = $2 WHERE id = $1",
+        invite_id,
+        user_id
+    )
+    .execute(conn)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registration_mode_parses_invite() {
+        assert_eq!(
+            RegistrationMode::from_str_or_default("invite"),
+            RegistrationMode::Invite
+        );
+        assert_eq!(
+            RegistrationMode::from_str_or_default(" INVITE "),
+            RegistrationMode::Invite
+        );
+    }
+
+    #[test]
+    fn registration_mode_defaults_to_public() {
+        assert_eq!(
+            RegistrationMode::from_str_or_default("public"),
+            RegistrationMode::Public
+        );
+        assert_eq!(
+            RegistrationMode::from_str_or_default("anything"),
+            RegistrationMode::Public
+        );
+        assert!(!RegistrationMode::default().requires_invite());
+    }
+
+    #[test]
+    fn invite_tokens_are_unique_and_long() {
+        let a = generate_token();
+        assert_eq!(a.len(), 64);
+        assert_ne!(a, generate_token());
+    }
+}
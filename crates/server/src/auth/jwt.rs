@@ -1,6 +1,7 @@
 use chrono::{Duration, Utc};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
+use shared_types::RefreshResponse;
 
 /// JWT claims stored in access and refresh tokens.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -58,6 +59,15 @@ pub fn create_access_token(
     )
 }
 
+/// Wrap a freshly issued access token with its lifetime in seconds, taken
+/// from `JWT_ACCESS_TOKEN_EXPIRY_MINUTES`.
+pub fn refresh_response(access_token: String) -> RefreshResponse {
+    RefreshResponse {
+        access_token,
+        expires_in: access_token_expiry_minutes() * 60,
+    }
+}
+
 pub fn create_refresh_token(
     user_id: i64,
     email: &str,
@@ -111,6 +121,38 @@ mod tests {
         assert_eq!(claims.tier, "free");
     }
 
+    #[test]
+    fn session_expiry_matches_token_exp() {
+        use base64::Engine;
+        use shared_types::{AuthUser, Role, UserTier};
+
+        setup_test_secret();
+        let token = create_access_token(42, "test@example.com", "user", "free").unwrap();
+        let claims = validate_access_token(&token).unwrap();
+        let user = AuthUser {
+            id: 42,
+            username: "test".to_string(),
+            display_name: "Test".to_string(),
+            email: "test@example.com".to_string(),
+            role: Role::User,
+            tier: UserTier::Free,
+            avatar_url: None,
+            thumbnail_url: None,
+            expires_at: None,
+            email_verified: None,
+        }
+        .with_expiry(claims.exp);
+
+        // Read `exp` straight from the token payload.
+        let payload = token.split('.').nth(1).unwrap();
+        let payload = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(payload)
+            .unwrap();
+        let payload: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(user.expires_at, payload["exp"].as_i64());
+        assert_eq!(claims.exp - claims.iat, access_token_expiry_minutes() * 60);
+    }
+
     #[test]
     fn expired_token_rejected() {
         setup_test_secret();
@@ -153,6 +195,17 @@ mod tests {
         assert!(claims.exp > claims.iat);
     }
 
+    #[test]
+    fn refresh_response_reports_access_token_lifetime() {
+        setup_test_secret();
+        let token = create_access_token(7, "r@b.com", "user", "free").unwrap();
+        let claims = validate_access_token(&token).unwrap();
+
+        let response = refresh_response(token);
+        assert_eq!(response.expires_in, access_token_expiry_minutes() * 60);
+        assert_eq!(claims.exp - claims.iat, response.expires_in);
+    }
+
     #[test]
     fn refresh_token_has_later_expiry() {
         setup_test_secret();
@@ -1,9 +1,13 @@
 use axum::extract::{Request, State};
+use axum::http::HeaderValue;
 use axum::middleware::Next;
 use axum::response::Response;
+use shared_types::{AuthEventType, TOKEN_REFRESHED_HEADER};
 use sqlx::{Pool, Postgres};
 
 use super::cookies::{self, CookieSlot, PendingCookieAction};
+use super::deletion;
+use super::events::{self, ClientInfo};
 use super::jwt::{self, validate_access_token};
 
 /// Permissive auth middleware that handles authentication and cookie management.
@@ -14,6 +18,9 @@ use super::jwt::{self, validate_access_token};
 /// 3. Inserts a `CookieSlot` so server functions can schedule cookie changes
 /// 4. After the handler runs, applies any pending cookie actions to the response
 ///
+/// Responses that carry refreshed cookies are tagged with
+/// [`TOKEN_REFRESHED_HEADER`] so the client can resync its auth state.
+///
 /// Does NOT reject unauthenticated requests — downstream handlers decide authorization.
 pub async fn auth_middleware(
     State(pool): State<Pool<Postgres>>,
@@ -51,6 +58,9 @@ pub async fn auth_middleware(
     // Apply cookies from transparent refresh
     if let Some((access, refresh)) = refresh_cookies {
         cookies::set_auth_cookies(response.headers_mut(), &access, &refresh);
+        response
+            .headers_mut()
+            .insert(TOKEN_REFRESHED_HEADER, HeaderValue::from_static("1"));
     }
 
     // Apply any cookie action scheduled by server functions
@@ -95,6 +105,7 @@ async fn try_transparent_refresh(
     if stored.revoked {
         return None;
     }
+    deletion::ensure_not_deleted(pool, claims.sub).await.ok()?;
 
     // Revoke old refresh token
     let _ = sqlx::query!(
@@ -120,6 +131,14 @@ async fn try_transparent_refresh(
     .execute(pool)
     .await;
 
+    events::record(
+        pool,
+        AuthEventType::TokenRefresh,
+        Some(claims.sub),
+        &ClientInfo::from_parts(req.headers(), req.extensions()),
+    )
+    .await;
+
     // Validate the new access token to get fresh claims
     let new_claims = validate_access_token(&new_access).ok()?;
     req.extensions_mut().insert(new_claims);
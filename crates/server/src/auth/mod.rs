@@ -1,8 +1,12 @@
 pub mod cookies;
+pub mod deletion;
+pub mod events;
 pub mod extractors;
+pub mod invites;
 pub mod jwt;
 pub mod middleware;
 pub mod oauth;
 pub mod oauth_callback;
 pub mod oauth_state;
 pub mod password;
+pub mod verification;
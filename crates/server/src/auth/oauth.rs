@@ -1,10 +1,19 @@
+use std::future::Future;
+use std::pin::Pin;
+
 use oauth2::{
-    basic::BasicClient, AuthUrl, ClientId, ClientSecret, CsrfToken, EndpointNotSet, EndpointSet,
-    PkceCodeChallenge, RedirectUrl, Scope, TokenUrl,
+    basic::{BasicClient, BasicErrorResponse, BasicTokenResponse},
+    AsyncHttpClient, AuthUrl, AuthorizationCode, ClientId, ClientSecret, CsrfToken, EndpointNotSet,
+    EndpointSet, HttpRequest, HttpResponse, PkceCodeChallenge, PkceCodeVerifier, RedirectUrl,
+    RequestTokenError, Scope, TokenUrl,
 };
-use shared_types::OAuthProvider;
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use shared_types::{AppError, LoginMethods, OAuthProvider, OAuthProviderInfo};
 
+use super::invites::RegistrationMode;
 use super::oauth_state;
+use crate::error_convert::SqlxErrorExt;
 
 /// Concrete OAuth client type with auth URL, token URL, and redirect URL set.
 type ConfiguredClient =
@@ -35,10 +44,21 @@ const GITHUB_CONFIG: OAuthEnvConfig = OAuthEnvConfig {
     token_url: "https://github.com/login/oauth/access_token",
 };
 
+/// Microsoft identity platform v2 endpoints. The `common` tenant accepts
+/// both work/school (Entra ID) and personal Microsoft accounts.
+const MICROSOFT_CONFIG: OAuthEnvConfig = OAuthEnvConfig {
+    client_id_var: "OAUTH_MICROSOFT_CLIENT_ID",
+    client_secret_var: "OAUTH_MICROSOFT_CLIENT_SECRET",
+    redirect_url_var: "OAUTH_MICROSOFT_REDIRECT_URL",
+    auth_url: "https://login.microsoftonline.com/common/oauth2/v2.0/authorize",
+    token_url: "https://login.microsoftonline.com/common/oauth2/v2.0/token",
+};
+
 fn env_config(provider: &OAuthProvider) -> &'static OAuthEnvConfig {
     match provider {
         OAuthProvider::Google => &GOOGLE_CONFIG,
         OAuthProvider::GitHub => &GITHUB_CONFIG,
+        OAuthProvider::Microsoft => &MICROSOFT_CONFIG,
     }
 }
 
@@ -62,6 +82,34 @@ pub fn build_oauth_client(provider: &OAuthProvider) -> Result<ConfiguredClient,
     Ok(client)
 }
 
+/// Whether every setting `provider` needs is present, looking variables up
+/// with `var`.
+fn is_configured(provider: &OAuthProvider, var: impl Fn(&str) -> Option<String>) -> bool {
+    let config = env_config(provider);
+    [
+        config.client_id_var,
+        config.client_secret_var,
+        config.redirect_url_var,
+    ]
+    .iter()
+    .all(|name| var(name).is_some_and(|v| !v.trim().is_empty()))
+}
+
+/// Providers sign-in buttons should be shown for: those with a client ID,
+/// secret, and redirect URL configured. Unconfigured providers would fail at
+/// [`build_oauth_client`], so they are left out.
+pub fn enabled_providers() -> Vec<OAuthProviderInfo> {
+    enabled_providers_with(|name| std::env::var(name).ok())
+}
+
+fn enabled_providers_with(var: impl Fn(&str) -> Option<String>) -> Vec<OAuthProviderInfo> {
+    OAuthProvider::all()
+        .into_iter()
+        .filter(|provider| is_configured(provider, &var))
+        .map(OAuthProviderInfo::from)
+        .collect()
+}
+
 /// Scopes for each provider.
 fn scopes(provider: &OAuthProvider) -> Vec<Scope> {
     match provider {
@@ -74,6 +122,13 @@ fn scopes(provider: &OAuthProvider) -> Vec<Scope> {
             Scope::new("read:user".to_string()),
             Scope::new("user:email".to_string()),
         ],
+        // `User.Read` lets the access token call Graph's `/me`
+        OAuthProvider::Microsoft => vec![
+            Scope::new("openid".to_string()),
+            Scope::new("email".to_string()),
+            Scope::new("profile".to_string()),
+            Scope::new("User.Read".to_string()),
+        ],
     }
 }
 
@@ -97,11 +152,192 @@ pub async fn get_authorize_url(provider: &OAuthProvider) -> Result<String, Strin
     Ok(url.to_string())
 }
 
+/// Why a request to an OAuth provider failed, which decides what the user
+/// is told.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProviderError {
+    /// The provider is down or overloaded: a 5xx or 429, a network failure,
+    /// or a response that can't be read. Trying again later may work.
+    Unavailable(String),
+    /// The provider refused the request: any other 4xx or an OAuth error
+    /// response, e.g. for an expired or already used authorization code.
+    Rejected(String),
+}
+
+impl ProviderError {
+    /// Classify a non-success HTTP status from the provider.
+    pub fn from_status(status: StatusCode, detail: impl Into<String>) -> Self {
+        if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS {
+            Self::Unavailable(detail.into())
+        } else {
+            Self::Rejected(detail.into())
+        }
+    }
+
+    /// Whether trying again later may succeed.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::Unavailable(_))
+    }
+
+    /// What went wrong, for the logs.
+    pub fn detail(&self) -> &str {
+        match self {
+            Self::Unavailable(detail) | Self::Rejected(detail) => detail,
+        }
+    }
+
+    /// Message for the `?error=` parameter of the login page.
+    pub fn user_message(&self, provider: &OAuthProvider) -> String {
+        let name = provider.display_name();
+        match self {
+            Self::Unavailable(_) => {
+                format!("{name} is not responding right now. Please try again in a few minutes.")
+            }
+            Self::Rejected(_) => {
+                format!("{name} authorization failed. Please try signing in again.")
+            }
+        }
+    }
+}
+
+/// Failure of [`send_token_request`].
+#[derive(Debug)]
+pub enum TokenHttpError {
+    Transport(reqwest::Error),
+    /// A non-2xx response, with its body.
+    Status(StatusCode, String),
+}
+
+impl std::fmt::Display for TokenHttpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Transport(e) => write!(f, "{e}"),
+            Self::Status(status, body) => write!(f, "{status}: {body}"),
+        }
+    }
+}
+
+impl std::error::Error for TokenHttpError {}
+
+/// HTTP client for token requests that keeps the status of non-2xx
+/// responses, which oauth2's own client drops while parsing the body.
+async fn send_token_request(
+    http: &reqwest::Client,
+    request: HttpRequest,
+) -> Result<HttpResponse, TokenHttpError> {
+    let (parts, body) = request.into_parts();
+    let response = http
+        .request(parts.method, parts.uri.to_string())
+        .headers(parts.headers)
+        .body(body)
+        .send()
+        .await
+        .map_err(TokenHttpError::Transport)?;
+
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body = response
+        .bytes()
+        .await
+        .map_err(TokenHttpError::Transport)?
+        .to_vec();
+    if !status.is_success() {
+        return Err(TokenHttpError::Status(
+            status,
+            String::from_utf8_lossy(&body).into_owned(),
+        ));
+    }
+
+    let mut http_response = HttpResponse::new(body);
+    *http_response.status_mut() = status;
+    *http_response.headers_mut() = headers;
+    Ok(http_response)
+}
+
+/// [`send_token_request`] as an oauth2 HTTP client.
+///
+/// A closure borrowing the `reqwest::Client` would make the callback
+/// handler's future non-`Send`, so the client is owned and the request
+/// future boxed as `Send`.
+struct TokenHttpClient(reqwest::Client);
+
+impl<'c> AsyncHttpClient<'c> for TokenHttpClient {
+    type Error = TokenHttpError;
+    type Future = Pin<Box<dyn Future<Output = Result<HttpResponse, TokenHttpError>> + Send + 'c>>;
+
+    fn call(&'c self, request: HttpRequest) -> Self::Future {
+        Box::pin(send_token_request(&self.0, request))
+    }
+}
+
+/// Exchange an authorization code for the provider's access token.
+pub async fn exchange_code(
+    client: &ConfiguredClient,
+    http: &reqwest::Client,
+    code: AuthorizationCode,
+    verifier: PkceCodeVerifier,
+) -> Result<BasicTokenResponse, ProviderError> {
+    client
+        .exchange_code(code)
+        .set_pkce_verifier(verifier)
+        .request_async(&TokenHttpClient(http.clone()))
+        .await
+        .map_err(classify_token_error)
+}
+
+fn classify_token_error(
+    err: RequestTokenError<TokenHttpError, BasicErrorResponse>,
+) -> ProviderError {
+    match err {
+        RequestTokenError::Request(TokenHttpError::Status(status, body)) => {
+            ProviderError::from_status(status, format!("Token endpoint returned {status}: {body}"))
+        }
+        RequestTokenError::Request(TokenHttpError::Transport(e)) => {
+            ProviderError::Unavailable(format!("Token request failed: {e}"))
+        }
+        RequestTokenError::ServerResponse(e) => {
+            ProviderError::Rejected(format!("Token exchange rejected: {e}"))
+        }
+        // GitHub reports a bad code as a 200 carrying an OAuth error body
+        RequestTokenError::Parse(e, body) => {
+            match serde_json::from_slice::<BasicErrorResponse>(&body) {
+                Ok(error) => ProviderError::Rejected(format!("Token exchange rejected: {error}")),
+                Err(_) => ProviderError::Unavailable(format!("Unreadable token response: {e}")),
+            }
+        }
+        RequestTokenError::Other(e) => ProviderError::Unavailable(e),
+    }
+}
+
+/// Send a provider API request and parse its JSON response, classifying
+/// failures. `what` names the resource in error details.
+async fn fetch_json<T: DeserializeOwned>(
+    request: reqwest::RequestBuilder,
+    what: &str,
+) -> Result<T, ProviderError> {
+    let response = request
+        .send()
+        .await
+        .map_err(|e| ProviderError::Unavailable(format!("Failed to fetch {what}: {e}")))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(ProviderError::from_status(
+            status,
+            format!("{what} request returned {status}"),
+        ));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| ProviderError::Unavailable(format!("Failed to parse {what}: {e}")))
+}
+
 /// Google user info from the userinfo endpoint.
 #[derive(Debug, serde::Deserialize)]
 pub struct GoogleUserInfo {
     pub sub: String,
     pub email: Option<String>,
+    pub email_verified: Option<bool>,
     pub name: Option<String>,
     pub picture: Option<String>,
 }
@@ -124,47 +360,70 @@ pub struct GitHubEmail {
     pub verified: bool,
 }
 
+/// Microsoft user info from the Graph `/me` endpoint.
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MicrosoftUserInfo {
+    pub id: String,
+    pub display_name: Option<String>,
+    pub mail: Option<String>,
+}
+
+impl MicrosoftUserInfo {
+    /// Convert to the unified shape. Only `mail` is used as the email, and it
+    /// is never treated as verified: tenant admins can set it to any address,
+    /// and `userPrincipalName` need not be a mailbox at all.
+    pub fn into_oauth_user_info(self) -> OAuthUserInfo {
+        let email = self.mail.unwrap_or_default();
+        OAuthUserInfo {
+            provider: OAuthProvider::Microsoft,
+            provider_id: self.id,
+            display_name: self
+                .display_name
+                .filter(|n| !n.is_empty())
+                .unwrap_or_else(|| "Microsoft User".to_string()),
+            email,
+            email_verified: false,
+            // Graph serves photos as binary data, not a URL
+            avatar_url: None,
+        }
+    }
+}
+
 /// Fetch user info from Google using an access token.
-pub async fn fetch_google_user_info(access_token: &str) -> Result<GoogleUserInfo, String> {
+pub async fn fetch_google_user_info(access_token: &str) -> Result<GoogleUserInfo, ProviderError> {
     let client = reqwest::Client::new();
-    let resp = client
-        .get("https://www.googleapis.com/oauth2/v3/userinfo")
-        .bearer_auth(access_token)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch Google user info: {}", e))?;
-
-    resp.json::<GoogleUserInfo>()
-        .await
-        .map_err(|e| format!("Failed to parse Google user info: {}", e))
+    fetch_json(
+        client
+            .get("https://www.googleapis.com/oauth2/v3/userinfo")
+            .bearer_auth(access_token),
+        "Google user info",
+    )
+    .await
 }
 
 /// Fetch user info from GitHub using an access token.
-pub async fn fetch_github_user_info(access_token: &str) -> Result<GitHubUserInfo, String> {
+pub async fn fetch_github_user_info(access_token: &str) -> Result<GitHubUserInfo, ProviderError> {
     let client = reqwest::Client::new();
-    let mut user_info: GitHubUserInfo = client
-        .get("https://api.github.com/user")
-        .bearer_auth(access_token)
-        .header("User-Agent", "dioxus-app")
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch GitHub user info: {}", e))?
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse GitHub user info: {}", e))?;
+    let mut user_info: GitHubUserInfo = fetch_json(
+        client
+            .get("https://api.github.com/user")
+            .bearer_auth(access_token)
+            .header("User-Agent", "dioxus-app"),
+        "GitHub user info",
+    )
+    .await?;
 
     // If email is not public, fetch from the emails endpoint
     if user_info.email.is_none() {
-        let emails: Vec<GitHubEmail> = client
-            .get("https://api.github.com/user/emails")
-            .bearer_auth(access_token)
-            .header("User-Agent", "dioxus-app")
-            .send()
-            .await
-            .map_err(|e| format!("Failed to fetch GitHub emails: {}", e))?
-            .json()
-            .await
-            .map_err(|e| format!("Failed to parse GitHub emails: {}", e))?;
+        let emails: Vec<GitHubEmail> = fetch_json(
+            client
+                .get("https://api.github.com/user/emails")
+                .bearer_auth(access_token)
+                .header("User-Agent", "dioxus-app"),
+            "GitHub emails",
+        )
+        .await?;
 
         user_info.email = emails
             .into_iter()
@@ -175,61 +434,104 @@ pub async fn fetch_github_user_info(access_token: &str) -> Result<GitHubUserInfo
     Ok(user_info)
 }
 
+/// Fetch user info from Microsoft Graph using an access token.
+pub async fn fetch_microsoft_user_info(access_token: &str) -> Result<OAuthUserInfo, ProviderError> {
+    let client = reqwest::Client::new();
+    let info: MicrosoftUserInfo = fetch_json(
+        client
+            .get("https://graph.microsoft.com/v1.0/me")
+            .bearer_auth(access_token),
+        "Microsoft user info",
+    )
+    .await?;
+
+    Ok(info.into_oauth_user_info())
+}
+
 /// User info unified from any OAuth provider.
 pub struct OAuthUserInfo {
     pub provider: OAuthProvider,
     pub provider_id: String,
     pub email: String,
+    /// Whether the provider vouches that the user owns `email`. Only
+    /// verified emails may link the identity to an existing account.
+    pub email_verified: bool,
     pub display_name: String,
     pub avatar_url: Option<String>,
 }
 
+/// The account an OAuth login signed in to.
+#[derive(Debug)]
+pub struct OAuthAccount {
+    pub id: i64,
+    /// The account's stored email, empty if it has none.
+    pub email: String,
+    pub role: String,
+    pub tier: String,
+}
+
 /// Insert or update a user from an OAuth login.
-/// Returns the user's database ID, role, and tier.
+///
+/// An identity seen before signs in to its account. Otherwise a verified
+/// email links it to the account holding that email, and an unverified one
+/// gets a separate account: its email is stored unverified, or not at all if
+/// another account already has it.
+///
+/// Signing in to an existing account always works, but creating one follows
+/// `registration_mode`: in invite-only mode a new identity is refused with
+/// 403, as it can't carry an invite token through the provider.
 pub async fn upsert_oauth_user(
     db: &sqlx::PgPool,
     info: &OAuthUserInfo,
-) -> Result<(i64, String, String), String> {
+    registration_mode: RegistrationMode,
+) -> Result<OAuthAccount, AppError> {
     let provider_str = info.provider.as_str();
+    let email = info.email.trim().to_lowercase();
 
     // Try to find existing user by OAuth provider + ID
     let existing = sqlx::query!(
-        "SELECT id, role, tier FROM users WHERE oauth_provider = $1 AND oauth_provider_id = $2",
+        "SELECT id, email, role, tier FROM users WHERE oauth_provider = $1 AND oauth_provider_id = $2",
         provider_str,
         info.provider_id
     )
     .fetch_optional(db)
     .await
-    .map_err(|e| format!("DB lookup failed: {}", e))?;
+    .map_err(SqlxErrorExt::into_app_error)?;
 
     if let Some(row) = existing {
         // Update display name and avatar on each login
         sqlx::query!(
-            "UPDATE users SET display_name = $2, avatar_url = $3, updated_at = NOW() WHERE id = $1",
+            "UPDATE users SET display_name = $2, avatar_url = $3, thumbnail_url = NULL, avatar_hash = NULL, updated_at = NOW() WHERE id = $1",
             row.id,
             info.display_name,
             info.avatar_url.as_deref(),
         )
         .execute(db)
         .await
-        .map_err(|e| format!("DB update failed: {}", e))?;
-
-        return Ok((row.id, row.role, row.tier));
+        .map_err(SqlxErrorExt::into_app_error)?;
+
+        return Ok(OAuthAccount {
+            id: row.id,
+            email: row.email.unwrap_or_default(),
+            role: row.role,
+            tier: row.tier,
+        });
     }
 
-    // Check if a user with this email already exists (link OAuth to existing account)
+    // Check if a user with this email already exists. Emails are stored
+    // lowercased; LOWER() also matches accounts created before normalization.
     let by_email = sqlx::query!(
-        "SELECT id, role, tier FROM users WHERE email = $1",
-        info.email
+        "SELECT id, email, role, tier FROM users WHERE LOWER(email) = $1",
+        email
     )
     .fetch_optional(db)
     .await
-    .map_err(|e| format!("DB email lookup failed: {}", e))?;
+    .map_err(SqlxErrorExt::into_app_error)?;
 
-    if let Some(row) = by_email {
+    if let Some(row) = by_email.as_ref().filter(|_| info.email_verified) {
         // Link OAuth provider to existing account
         sqlx::query!(
-            "UPDATE users SET oauth_provider = $2, oauth_provider_id = $3, avatar_url = $4, updated_at = NOW() WHERE id = $1",
+            "UPDATE users SET oauth_provider = $2, oauth_provider_id = $3, avatar_url = $4, thumbnail_url = NULL, avatar_hash = NULL, email_verified_at = COALESCE(email_verified_at, NOW()), updated_at = NOW() WHERE id = $1",
             row.id,
             provider_str,
             info.provider_id,
@@ -237,28 +539,320 @@ pub async fn upsert_oauth_user(
         )
         .execute(db)
         .await
-        .map_err(|e| format!("DB link failed: {}", e))?;
-
-        return Ok((row.id, row.role, row.tier));
+        .map_err(SqlxErrorExt::into_app_error)?;
+
+        return Ok(OAuthAccount {
+            id: row.id,
+            email: row.email.clone().unwrap_or_default(),
+            role: row.role.clone(),
+            tier: row.tier.clone(),
+        });
     }
 
-    // Create new user
-    let username = info.email.split('@').next().unwrap_or("user").to_string();
+    if registration_mode.requires_invite() {
+        return Err(AppError::forbidden("An invite is required to register"));
+    }
 
+    // Create new user, named after the email's local part. Usernames are
+    // unique, so a taken name gets a short random suffix.
+    let base = email.split('@').next().unwrap_or("user");
+    let taken = sqlx::query_scalar!(
+        r#"SELECT EXISTS(SELECT 1 FROM users WHERE username = $1) AS "taken!""#,
+        base
+    )
+    .fetch_one(db)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?;
+    let username = if taken {
+        format!("{base}_{}", &uuid::Uuid::new_v4().simple().to_string()[..6])
+    } else {
+        base.to_string()
+    };
+
+    // An unverified email someone else already has stays off the new account
+    let stored_email = by_email.is_none().then_some(email);
     let row = sqlx::query!(
-        r#"INSERT INTO users (username, email, display_name, oauth_provider, oauth_provider_id, avatar_url)
-           VALUES ($1, $2, $3, $4, $5, $6)
+        r#"INSERT INTO users (username, email, display_name, oauth_provider, oauth_provider_id, avatar_url, email_verified_at)
+           VALUES ($1, $2, $3, $4, $5, $6, CASE WHEN $7 THEN NOW() END)
            RETURNING id, role, tier"#,
         username,
-        info.email,
+        stored_email.as_deref(),
         info.display_name,
         provider_str,
         info.provider_id,
         info.avatar_url.as_deref(),
+        info.email_verified,
     )
     .fetch_one(db)
     .await
-    .map_err(|e| format!("DB insert failed: {}", e))?;
+    .map_err(SqlxErrorExt::into_app_error)?;
+
+    Ok(OAuthAccount {
+        id: row.id,
+        email: stored_email.unwrap_or_default(),
+        role: row.role,
+        tier: row.tier,
+    })
+}
+
+/// Load how `user_id` can sign in: password and linked OAuth providers.
+pub async fn login_methods(db: &sqlx::PgPool, user_id: i64) -> Result<LoginMethods, AppError> {
+    let row = sqlx::query!(
+        "SELECT password_hash IS NOT NULL AS \"has_password!\", oauth_provider FROM users WHERE id = $1",
+        user_id
+    )
+    .fetch_optional(db)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?
+    .ok_or_else(|| AppError::not_found("User not found"))?;
+
+    Ok(LoginMethods {
+        has_password: row.has_password,
+        oauth_providers: row
+            .oauth_provider
+            .as_deref()
+            .and_then(OAuthProvider::parse_provider)
+            .into_iter()
+            .collect(),
+    })
+}
+
+/// Check that `provider` can be unlinked from an account with `methods`.
+///
+/// Fails with `Forbidden` when it is the account's last way to sign in, so
+/// users can't lock themselves out.
+pub fn check_unlink(methods: &LoginMethods, provider: &OAuthProvider) -> Result<(), AppError> {
+    if !methods.oauth_providers.contains(provider) {
+        return Err(AppError::not_found(format!(
+            "{} is not linked to your account",
+            provider.display_name()
+        )));
+    }
+    if !methods.can_unlink(provider) {
+        return Err(AppError::forbidden(format!(
+            "Set a password before unlinking {}, or you won't be able to sign in",
+            provider.display_name()
+        )));
+    }
+    Ok(())
+}
+
+/// Unlink `provider` from `user_id`, keeping at least one way to sign in.
+pub async fn unlink_oauth_user(
+    db: &sqlx::PgPool,
+    user_id: i64,
+    provider: &OAuthProvider,
+) -> Result<LoginMethods, AppError> {
+    let methods = login_methods(db, user_id).await?;
+    check_unlink(&methods, provider)?;
+
+    sqlx::query!(
+        "UPDATE users SET oauth_provider = NULL, oauth_provider_id = NULL, updated_at = NOW() WHERE id = $1 AND oauth_provider = $2",
+        user_id,
+        provider.as_str()
+    )
+    .execute(db)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?;
+
+    login_methods(db, user_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared_types::AppErrorKind;
+    use std::collections::HashMap;
+
+    #[test]
+    fn only_fully_configured_providers_are_enabled() {
+        let env = HashMap::from([
+            ("OAUTH_GITHUB_CLIENT_ID", "id"),
+            ("OAUTH_GITHUB_CLIENT_SECRET", "secret"),
+            (
+                "OAUTH_GITHUB_REDIRECT_URL",
+                "http://localhost/auth/callback/github",
+            ),
+            // Missing the redirect URL
+            ("OAUTH_GOOGLE_CLIENT_ID", "id"),
+            ("OAUTH_GOOGLE_CLIENT_SECRET", "secret"),
+            // Blank values don't count
+            ("OAUTH_MICROSOFT_CLIENT_ID", " "),
+            ("OAUTH_MICROSOFT_CLIENT_SECRET", "secret"),
+            (
+                "OAUTH_MICROSOFT_REDIRECT_URL",
+                "http://localhost/auth/callback/microsoft",
+            ),
+        ]);
+        let providers = enabled_providers_with(|name| env.get(name).map(|v| v.to_string()));
+
+        assert_eq!(providers.len(), 1);
+        assert_eq!(providers[0].provider, OAuthProvider::GitHub);
+        assert_eq!(providers[0].id, "github");
+        assert_eq!(providers[0].display_name, "GitHub");
+        assert!(enabled_providers_with(|_| None).is_empty());
+    }
+
+    /// A stand-in provider on a local port: `/down` fails with 503, `/denied`
+    /// with a 400 OAuth error, `/unauthorized` with 401.
+    async fn mock_provider() -> String {
+        use axum::{http::StatusCode, routing::any, Json, Router};
+
+        let app = Router::new()
+            .route(
+                "/down",
+                any(|| async { (StatusCode::SERVICE_UNAVAILABLE, "upstream timed out") }),
+            )
+            .route(
+                "/denied",
+                any(|| async {
+                    (
+                        StatusCode::BAD_REQUEST,
+                        Json(serde_json::json!({ "error": "invalid_grant" })),
+                    )
+                }),
+            )
+            .route("/unauthorized", any(|| async { StatusCode::UNAUTHORIZED }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{addr}")
+    }
+
+    fn client_with_token_url(token_url: String) -> ConfiguredClient {
+        BasicClient::new(ClientId::new("client".to_string()))
+            .set_client_secret(ClientSecret::new("secret".to_string()))
+            .set_auth_uri(AuthUrl::new("http://localhost/authorize".to_string()).unwrap())
+            .set_token_uri(TokenUrl::new(token_url).unwrap())
+            .set_redirect_uri(RedirectUrl::new("http://localhost/callback".to_string()).unwrap())
+    }
+
+    async fn exchange_at(url: String) -> ProviderError {
+        exchange_code(
+            &client_with_token_url(url),
+            &reqwest::Client::new(),
+            AuthorizationCode::new("code".to_string()),
+            PkceCodeVerifier::new("v".repeat(43)),
+        )
+        .await
+        .unwrap_err()
+    }
+
+    #[tokio::test]
+    async fn token_exchange_failures_are_classified_by_status() {
+        let base = mock_provider().await;
+
+        let down = exchange_at(format!("{base}/down")).await;
+        assert!(down.is_transient(), "{down:?}");
+        assert!(down.detail().contains("503"), "{down:?}");
+
+        let denied = exchange_at(format!("{base}/denied")).await;
+        assert!(!denied.is_transient(), "{denied:?}");
+        assert!(denied.detail().contains("invalid_grant"), "{denied:?}");
+
+        let provider = OAuthProvider::GitHub;
+        assert_eq!(
+            down.user_message(&provider),
+            "GitHub is not responding right now. Please try again in a few minutes."
+        );
+        assert_eq!(
+            denied.user_message(&provider),
+            "GitHub authorization failed. Please try signing in again."
+        );
+    }
 
-    Ok((row.id, row.role, row.tier))
+    #[tokio::test]
+    async fn userinfo_failures_are_classified_by_status() {
+        let base = mock_provider().await;
+        let client = reqwest::Client::new();
+
+        let down = fetch_json::<GoogleUserInfo>(client.get(format!("{base}/down")), "user info")
+            .await
+            .unwrap_err();
+        assert!(matches!(down, ProviderError::Unavailable(_)), "{down:?}");
+
+        let unauthorized =
+            fetch_json::<GoogleUserInfo>(client.get(format!("{base}/unauthorized")), "user info")
+                .await
+                .unwrap_err();
+        assert!(
+            matches!(unauthorized, ProviderError::Rejected(_)),
+            "{unauthorized:?}"
+        );
+        assert_ne!(
+            down.user_message(&OAuthProvider::Google),
+            unauthorized.user_message(&OAuthProvider::Google)
+        );
+    }
+
+    #[test]
+    fn rate_limiting_counts_as_transient() {
+        assert!(ProviderError::from_status(StatusCode::TOO_MANY_REQUESTS, "").is_transient());
+        assert!(ProviderError::from_status(StatusCode::BAD_GATEWAY, "").is_transient());
+        assert!(!ProviderError::from_status(StatusCode::FORBIDDEN, "").is_transient());
+    }
+
+    #[test]
+    fn microsoft_me_response_maps_to_user_info() {
+        let info: MicrosoftUserInfo = serde_json::from_str(
+            r#"{
+                "id": "87d349ed-44d7-43e1-9a83-5f2406dee5bd",
+                "displayName": "Megan Bowen",
+                "mail": "MeganB@contoso.com",
+                "userPrincipalName": "meganb@contoso.onmicrosoft.com"
+            }"#,
+        )
+        .unwrap();
+        let info = info.into_oauth_user_info();
+        assert_eq!(info.provider, OAuthProvider::Microsoft);
+        assert_eq!(info.provider_id, "87d349ed-44d7-43e1-9a83-5f2406dee5bd");
+        assert_eq!(info.email, "MeganB@contoso.com");
+        assert!(!info.email_verified);
+        assert_eq!(info.display_name, "Megan Bowen");
+        assert_eq!(info.avatar_url, None);
+    }
+
+    #[test]
+    fn microsoft_user_without_mail_has_no_email() {
+        let info: MicrosoftUserInfo = serde_json::from_str(
+            r#"{"id": "abc", "displayName": null, "mail": null, "userPrincipalName": "sam@outlook.com"}"#,
+        )
+        .unwrap();
+        let info = info.into_oauth_user_info();
+        assert_eq!(info.email, "");
+        assert_eq!(info.display_name, "Microsoft User");
+    }
+
+    #[test]
+    fn unlinking_last_login_method_is_forbidden() {
+        let methods = LoginMethods {
+            has_password: false,
+            oauth_providers: vec![OAuthProvider::GitHub],
+        };
+        let err = check_unlink(&methods, &OAuthProvider::GitHub).unwrap_err();
+        assert_eq!(err.kind, AppErrorKind::Forbidden);
+        assert_eq!(
+            err.message,
+            "Set a password before unlinking GitHub, or you won't be able to sign in"
+        );
+    }
+
+    #[test]
+    fn unlinking_with_password_set_is_allowed() {
+        let methods = LoginMethods {
+            has_password: true,
+            oauth_providers: vec![OAuthProvider::Google],
+        };
+        assert!(check_unlink(&methods, &OAuthProvider::Google).is_ok());
+    }
+
+    #[test]
+    fn unlinking_provider_that_is_not_linked_is_not_found() {
+        let methods = LoginMethods {
+            has_password: true,
+            oauth_providers: vec![OAuthProvider::Google],
+        };
+        let err = check_unlink(&methods, &OAuthProvider::Microsoft).unwrap_err();
+        assert_eq!(err.kind, AppErrorKind::NotFound);
+    }
 }
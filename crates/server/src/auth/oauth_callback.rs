@@ -3,25 +3,40 @@ use axum::{
     response::{IntoResponse, Redirect, Response},
 };
 use oauth2::{AuthorizationCode, TokenResponse};
-use shared_types::{OAuthProvider, UserTier};
+use shared_types::{AuthEventType, OAuthCallbackParams, OAuthProvider, UserTier};
 use sqlx::{Pool, Postgres};
 
-use super::{cookies, jwt, oauth, oauth_state};
-
-/// Query parameters received from the OAuth provider callback.
-#[derive(Debug, serde::Deserialize)]
-pub struct CallbackQuery {
-    pub code: String,
-    pub state: String,
-}
+use super::events::{self, ClientInfo};
+use super::invites::RegistrationMode;
+use super::{cookies, deletion, jwt, oauth, oauth_state};
 
 /// Axum handler for `/auth/callback/{provider}`.
 /// Exchanges the authorization code for tokens, fetches user info,
 /// upserts the user, creates JWTs, sets HTTP-only cookies, and redirects to `/`.
+/// Sign-ins refused for the account, and successful ones, are recorded as
+/// auth events like password logins.
+#[utoipa::path(
+    get,
+    path = "/auth/callback/{provider}",
+    params(
+        ("provider" = OAuthProvider, Path, description = "Identity provider, case-insensitive (e.g. `github`)"),
+        OAuthCallbackParams
+    ),
+    responses(
+        (status = 303, description = "Redirect to `/` with auth cookies set, or to `/login?error=<message>` if sign-in failed",
+            headers(
+                ("Location" = String, description = "`/` on success, `/login?error=...` on failure"),
+                ("Set-Cookie" = String, description = "Access and refresh token cookies, on success")
+            ))
+    ),
+    tag = "auth"
+)]
 pub async fn oauth_callback(
     State(pool): State<Pool<Postgres>>,
+    State(registration_mode): State<RegistrationMode>,
+    client_info: ClientInfo,
     Path(provider_str): Path<String>,
-    Query(params): Query<CallbackQuery>,
+    Query(params): Query<OAuthCallbackParams>,
 ) -> Result<Response, Response> {
     let error_redirect = |msg: &str| {
         Redirect::to(&format!("/login?error={}", urlencoding::encode(msg))).into_response()
@@ -30,6 +45,24 @@ pub async fn oauth_callback(
     let provider = OAuthProvider::parse_provider(&provider_str)
         .ok_or_else(|| error_redirect("Unknown OAuth provider"))?;
 
+    // Provider outages get a "try again" message, refusals a sign-in failure
+    let provider_error = |e: oauth::ProviderError| {
+        if e.is_transient() {
+            tracing::warn!(
+                provider = provider.as_str(),
+                error = e.detail(),
+                "OAuth provider unavailable"
+            );
+        } else {
+            tracing::info!(
+                provider = provider.as_str(),
+                error = e.detail(),
+                "OAuth provider rejected sign-in"
+            );
+        }
+        error_redirect(&e.user_message(&provider))
+    };
+
     // Verify CSRF state and retrieve PKCE verifier
     let verifier = oauth_state::take_verifier(&params.state)
         .await
@@ -40,12 +73,14 @@ pub async fn oauth_callback(
         .map_err(|e| error_redirect(&format!("OAuth config error: {}", e)))?;
 
     let http_client = reqwest::Client::new();
-    let token_response = client
-        .exchange_code(AuthorizationCode::new(params.code))
-        .set_pkce_verifier(verifier)
-        .request_async(&http_client)
-        .await
-        .map_err(|e| error_redirect(&format!("Token exchange failed: {}", e)))?;
+    let token_response = oauth::exchange_code(
+        &client,
+        &http_client,
+        AuthorizationCode::new(params.code),
+        verifier,
+    )
+    .await
+    .map_err(provider_error)?;
 
     let access_token_str = token_response.access_token().secret();
 
@@ -54,12 +89,13 @@ pub async fn oauth_callback(
         OAuthProvider::Google => {
             let info = oauth::fetch_google_user_info(access_token_str)
                 .await
-                .map_err(|e| error_redirect(&e))?;
+                .map_err(provider_error)?;
 
             oauth::OAuthUserInfo {
                 provider: OAuthProvider::Google,
                 provider_id: info.sub,
                 email: info.email.unwrap_or_default(),
+                email_verified: info.email_verified.unwrap_or(false),
                 display_name: info.name.unwrap_or_else(|| "Google User".to_string()),
                 avatar_url: info.picture,
             }
@@ -67,16 +103,21 @@ pub async fn oauth_callback(
         OAuthProvider::GitHub => {
             let info = oauth::fetch_github_user_info(access_token_str)
                 .await
-                .map_err(|e| error_redirect(&e))?;
+                .map_err(provider_error)?;
 
             oauth::OAuthUserInfo {
                 provider: OAuthProvider::GitHub,
                 provider_id: info.id.to_string(),
                 email: info.email.unwrap_or_default(),
+                // GitHub only exposes verified addresses
+                email_verified: true,
                 display_name: info.name.unwrap_or_else(|| info.login.clone()),
                 avatar_url: info.avatar_url,
             }
         }
+        OAuthProvider::Microsoft => oauth::fetch_microsoft_user_info(access_token_str)
+            .await
+            .map_err(provider_error)?,
     };
 
     if user_info.email.is_empty() {
@@ -86,18 +127,35 @@ pub async fn oauth_callback(
     }
 
     // Upsert user in the database
-    let (user_id, role, tier_str) = oauth::upsert_oauth_user(&pool, &user_info)
-        .await
-        .map_err(|e| error_redirect(&e))?;
+    let account = match oauth::upsert_oauth_user(&pool, &user_info, registration_mode).await {
+        Ok(account) => account,
+        Err(e) => {
+            events::record(&pool, AuthEventType::LoginFailure, None, &client_info).await;
+            return Err(error_redirect(&e.message));
+        }
+    };
+    let user_id = account.id;
+
+    if let Err(e) = deletion::ensure_not_pending(&pool, user_id).await {
+        events::record(
+            &pool,
+            AuthEventType::LoginFailure,
+            Some(user_id),
+            &client_info,
+        )
+        .await;
+        return Err(error_redirect(&e.message));
+    }
 
-    let tier = UserTier::from_str_or_default(&tier_str);
+    let tier = UserTier::from_str_or_default(&account.tier);
 
     // Create JWTs
-    let jwt_access = jwt::create_access_token(user_id, &user_info.email, &role, tier.as_str())
-        .map_err(|e| error_redirect(&format!("JWT error: {}", e)))?;
+    let jwt_access =
+        jwt::create_access_token(user_id, &account.email, &account.role, tier.as_str())
+            .map_err(|e| error_redirect(&format!("JWT error: {}", e)))?;
 
     let (jwt_refresh, expires_at) =
-        jwt::create_refresh_token(user_id, &user_info.email, &role, tier.as_str())
+        jwt::create_refresh_token(user_id, &account.email, &account.role, tier.as_str())
             .map_err(|e| error_redirect(&format!("JWT error: {}", e)))?;
 
     // Store refresh token
@@ -111,6 +169,14 @@ pub async fn oauth_callback(
     .await
     .map_err(|e| error_redirect(&format!("DB error: {}", e)))?;
 
+    events::record(
+        &pool,
+        AuthEventType::LoginSuccess,
+        Some(user_id),
+        &client_info,
+    )
+    .await;
+
     // Build redirect response with auth cookies
     let mut response = Redirect::to("/").into_response();
     cookies::set_auth_cookies(response.headers_mut(), &jwt_access, &jwt_refresh);
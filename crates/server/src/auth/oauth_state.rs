@@ -1,7 +1,7 @@
 use oauth2::PkceCodeVerifier;
 use std::collections::HashMap;
-use std::sync::LazyLock;
-use std::time::Instant;
+use std::sync::{LazyLock, Once};
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 
 /// CSRF state entry with PKCE verifier and creation timestamp.
@@ -10,40 +10,128 @@ struct StateEntry {
     created_at: Instant,
 }
 
-/// TTL for CSRF state entries.
-const STATE_TTL_SECS: u64 = 600;
+/// How long a login may take between redirecting to the provider and the
+/// callback before its CSRF state is rejected.
+const STATE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// How often the background sweep purges abandoned states.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// PKCE verifiers keyed by CSRF state, each valid for [`STATE_TTL`].
+#[derive(Default)]
+struct StateStore {
+    entries: HashMap<String, StateEntry>,
+}
+
+impl StateStore {
+    fn insert(&mut self, state: String, verifier: PkceCodeVerifier, now: Instant) {
+        self.entries.insert(
+            state,
+            StateEntry {
+                verifier,
+                created_at: now,
+            },
+        );
+    }
+
+    /// Remove `state` and return its verifier, unless it has expired.
+    fn take(&mut self, state: &str, now: Instant) -> Option<PkceCodeVerifier> {
+        let entry = self.entries.remove(state)?;
+        if now.duration_since(entry.created_at) > STATE_TTL {
+            return None;
+        }
+        Some(entry.verifier)
+    }
+
+    /// Drop expired entries, returning how many were removed.
+    fn purge_expired(&mut self, now: Instant) -> usize {
+        let before = self.entries.len();
+        self.entries
+            .retain(|_, entry| now.duration_since(entry.created_at) <= STATE_TTL);
+        before - self.entries.len()
+    }
+}
 
 /// In-memory CSRF state store for OAuth flows.
-static STATE_STORE: LazyLock<Mutex<HashMap<String, StateEntry>>> =
-    LazyLock::new(|| Mutex::new(HashMap::new()));
+static STATE_STORE: LazyLock<Mutex<StateStore>> = LazyLock::new(Default::default);
 
 /// Store a CSRF state token with its PKCE verifier.
 pub async fn store_state(state: String, verifier: PkceCodeVerifier) {
-    let mut store = STATE_STORE.lock().await;
-
-    // Prune expired entries while we hold the lock
-    let cutoff = Instant::now() - std::time::Duration::from_secs(STATE_TTL_SECS);
-    store.retain(|_, entry| entry.created_at > cutoff);
-
-    store.insert(
-        state,
-        StateEntry {
-            verifier,
-            created_at: Instant::now(),
-        },
-    );
+    STATE_STORE
+        .lock()
+        .await
+        .insert(state, verifier, Instant::now());
 }
 
 /// Retrieve and remove a PKCE verifier for a given CSRF state token.
-/// Returns None if the state is unknown or expired.
+/// Returns None if the state is unknown or older than 10 minutes.
 pub async fn take_verifier(state: &str) -> Option<PkceCodeVerifier> {
-    let mut store = STATE_STORE.lock().await;
-    let entry = store.remove(state)?;
+    STATE_STORE.lock().await.take(state, Instant::now())
+}
 
-    let elapsed = entry.created_at.elapsed().as_secs();
-    if elapsed > STATE_TTL_SECS {
-        return None;
+/// Remove states from logins that were abandoned before the callback.
+pub async fn purge_expired_states() -> usize {
+    STATE_STORE.lock().await.purge_expired(Instant::now())
+}
+
+/// Spawn a background task that purges expired states every minute, so
+/// abandoned logins don't accumulate. Later calls do nothing.
+pub fn spawn_state_sweeper() {
+    static STARTED: Once = Once::new();
+    STARTED.call_once(|| {
+        tokio::spawn(async {
+            let mut interval = tokio::time::interval(SWEEP_INTERVAL);
+            loop {
+                interval.tick().await;
+                let purged = purge_expired_states().await;
+                if purged > 0 {
+                    tracing::debug!(purged, "purged expired OAuth states");
+                }
+            }
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn verifier() -> PkceCodeVerifier {
+        PkceCodeVerifier::new("a".repeat(43))
     }
 
-    Some(entry.verifier)
+    #[test]
+    fn fresh_state_returns_verifier_once() {
+        let mut store = StateStore::default();
+        let now = Instant::now();
+        store.insert("fresh".to_string(), verifier(), now);
+
+        let taken = store.take("fresh", now + Duration::from_secs(30));
+        assert_eq!(taken.unwrap().secret(), &"a".repeat(43));
+        assert!(store.take("fresh", now).is_none());
+    }
+
+    #[test]
+    fn stale_state_is_rejected() {
+        let mut store = StateStore::default();
+        let now = Instant::now();
+        store.insert("stale".to_string(), verifier(), now);
+
+        assert!(store
+            .take("stale", now + STATE_TTL + Duration::from_secs(1))
+            .is_none());
+    }
+
+    #[test]
+    fn purge_removes_only_expired_states() {
+        let mut store = StateStore::default();
+        let now = Instant::now();
+        store.insert("old".to_string(), verifier(), now);
+        store.insert("new".to_string(), verifier(), now + STATE_TTL);
+
+        let later = now + STATE_TTL + Duration::from_secs(1);
+        assert_eq!(store.purge_expired(later), 1);
+        assert!(store.take("old", later).is_none());
+        assert!(store.take("new", later).is_some());
+    }
 }
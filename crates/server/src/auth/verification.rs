@@ -0,0 +1,164 @@
+use std::{
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+use shared_types::AppError;
+use sqlx::{PgConnection, PgPool};
+
+use crate::auth::invites::generate_token;
+use crate::error_convert::SqlxErrorExt;
+use crate::upload_limits::RateLimiter;
+
+/// How long a verification link stays valid.
+pub const TOKEN_TTL_HOURS: i64 = 48;
+
+/// Verification emails a user may request per hour.
+pub const RESENDS_PER_HOUR: u32 = 3;
+
+/// What [`send_verification_email`] did.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Delivery {
+    /// A fresh link was issued and handed to the mailer.
+    Sent,
+    /// The email is already verified (or the account has none); nothing sent.
+    AlreadyVerified,
+}
+
+/// Count a resend request by `user_id` against `limiter`, failing with
+/// `TooManyRequests` once they are over the limit.
+pub fn check_resend_rate(
+    limiter: &RateLimiter,
+    user_id: i64,
+    now: Instant,
+) -> Result<(), AppError> {
+    limiter.check(user_id, now).map_err(|retry_after| {
+        AppError::too_many_requests(format!(
+            "Too many verification emails requested. Try again in {} minutes.",
+            retry_after.as_secs().div_ceil(60).max(1)
+        ))
+    })
+}
+
+/// Count a resend request by `user_id`, allowing [`RESENDS_PER_HOUR`].
+pub fn check_resend_allowed(user_id: i64) -> Result<(), AppError> {
+    static LIMITER: OnceLock<RateLimiter> = OnceLock::new();
+    let limiter =
+        LIMITER.get_or_init(|| RateLimiter::new(RESENDS_PER_HOUR, Duration::from_secs(60 * 60)));
+    check_resend_rate(limiter, user_id, Instant::now())
+}
+
+/// Issue a verification token for `user_id`'s `email`, replacing any link
+/// sent earlier so only the newest one works.
+pub async fn issue_token(
+    conn: &mut PgConnection,
+    user_id: i64,
+    email: &str,
+) -> Result<String, AppError> {
+    let token = generate_token();
+    sqlx::query!(
+        r#"INSERT INTO email_verifications (user_id, email, token, expires_at)
+           VALUES ($1, $2, $3, NOW() + make_interval(hours => $4))
+           ON CONFLICT (user_id) DO UPDATE
+           SET email = EXCLUDED.email, token = EXCLUDED.token,
+               expires_at = EXCLUDED.expires_at, created_at = NOW()"#,
+        user_id,
+        email,
+        token,
+        TOKEN_TTL_HOURS as i32
+    )
+    .execute(conn)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?;
+    Ok(token)
+}
+
+/// Hand a verification link to the mailer.
+///
+/// Delivery is stubbed until an email provider is configured: only the fact
+/// that a link was issued is logged, never the address or token.
+fn deliver(user_id: i64, _email: &str, _token: &str) {
+    tracing::info!(user_id, "verification email queued (no mailer configured)");
+}
+
+/// Send `user_id` a new verification link, unless their email is already
+/// verified.
+pub async fn send_verification_email(pool: &PgPool, user_id: i64) -> Result<Delivery, AppError> {
+    let row = sqlx::query!(
+        r#"SELECT email, email_verified_at IS NOT NULL AS "verified!" FROM users WHERE id = $1"#,
+        user_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?
+    .ok_or_else(|| AppError::not_found("User not found"))?;
+
+    let Some(email) = row.email.filter(|_| !row.verified) else {
+        return Ok(Delivery::AlreadyVerified);
+    };
+
+    let mut conn = pool.acquire().await.map_err(SqlxErrorExt::into_app_error)?;
+    let token = issue_token(&mut conn, user_id, &email).await?;
+    deliver(user_id, &email, &token);
+    Ok(Delivery::Sent)
+}
+
+/// Mark the email a verification `token` was issued for as verified, using
+/// up the token.
+///
+/// Fails with `BadRequest` when the token is unknown, expired, or was issued
+/// for an address the account no longer has.
+pub async fn confirm(pool: &PgPool, token: &str) -> Result<i64, AppError> {
+    sqlx::query_scalar!(
+        r#"WITH used AS (
+               DELETE FROM email_verifications
+               WHERE token = $1 AND expires_at > NOW()
+               RETURNING user_id, email
+           )
+           UPDATE users SET email_verified_at = NOW(), updated_at = NOW()
+           FROM used
+           WHERE users.id = used.user_id AND LOWER(users.email) = LOWER(used.email)
+           RETURNING users.id"#,
+        token.trim()
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?
+    .ok_or_else(|| AppError::bad_request("Verification link is invalid or has expired"))
+}
+
+/// Whether `user_id` has verified their email.
+pub async fn is_verified(pool: &PgPool, user_id: i64) -> Result<bool, AppError> {
+    sqlx::query_scalar!(
+        r#"SELECT email_verified_at IS NOT NULL AS "verified!" FROM users WHERE id = $1"#,
+        user_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)
+    .map(|verified| verified.unwrap_or(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared_types::AppErrorKind;
+
+    #[test]
+    fn resends_are_limited_per_user() {
+        let limiter = RateLimiter::new(RESENDS_PER_HOUR, Duration::from_secs(60 * 60));
+        let now = Instant::now();
+        for _ in 0..RESENDS_PER_HOUR {
+            assert!(check_resend_rate(&limiter, 1, now).is_ok());
+        }
+
+        let err = check_resend_rate(&limiter, 1, now).unwrap_err();
+        assert_eq!(err.kind, AppErrorKind::TooManyRequests);
+        assert_eq!(
+            err.message,
+            "Too many verification emails requested. Try again in 60 minutes."
+        );
+        assert!(check_resend_rate(&limiter, 2, now).is_ok());
+        assert!(check_resend_rate(&limiter, 1, now + Duration::from_secs(60 * 60)).is_ok());
+    }
+}
@@ -0,0 +1,180 @@
+use image::{ImageFormat, Rgb, RgbImage};
+
+/// Width and height of generated avatars, matching uploaded thumbnails.
+pub const FALLBACK_AVATAR_SIZE: u32 = crate::s3::THUMBNAIL_SIZE;
+
+/// Background colors, picked per user id. All pass WCAG AA with white text.
+const PALETTE: [[u8; 3]; 8] = [
+    [0x00, 0x7a, 0x87], // teal
+    [0x8e, 0x24, 0xaa], // purple
+    [0xc2, 0x18, 0x5b], // magenta
+    [0x1e, 0x5a, 0xc8], // blue
+    [0x2e, 0x7d, 0x32], // green
+    [0xb3, 0x4a, 0x00], // orange
+    [0x45, 0x5a, 0x64], // slate
+    [0x6a, 0x1b, 0x9a], // violet
+];
+
+const TEXT_COLOR: [u8; 3] = [0xff, 0xff, 0xff];
+
+const GLYPH_WIDTH: u32 = 5;
+const GLYPH_HEIGHT: u32 = 7;
+
+/// 5x7 bitmap for an initial; each row's low five bits are pixels, left to right.
+fn glyph(c: char) -> [u8; 7] {
+    match c {
+        'A' => [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'B' => [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E],
+        'C' => [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],
+        'D' => [0x1E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x1E],
+        'E' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+        'F' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+        'G' => [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F],
+        'H' => [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'I' => [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        'J' => [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C],
+        'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F],
+        'M' => [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'P' => [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10],
+        'Q' => [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D],
+        'R' => [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11],
+        'S' => [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],
+        'T' => [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04],
+        'W' => [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A],
+        'X' => [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11],
+        'Y' => [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04],
+        'Z' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F],
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        _ => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04], // '?'
+    }
+}
+
+/// Up to two initials from the first and last words of `name`.
+///
+/// Only ASCII letters and digits can be drawn; when `name` has none, `?` is
+/// used.
+pub fn initials(name: &str) -> String {
+    let firsts: Vec<char> = name
+        .split_whitespace()
+        .filter_map(|word| word.chars().find(char::is_ascii_alphanumeric))
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    match firsts.as_slice() {
+        [] => "?".to_string(),
+        [only] => only.to_string(),
+        [first, .., last] => format!("{first}{last}"),
+    }
+}
+
+/// Background color for `user_id`, stable across calls and restarts.
+pub fn background_color(user_id: i64) -> [u8; 3] {
+    // SplitMix64 finalizer: sequential ids still spread across the palette
+    let mut z = (user_id as u64).wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+    PALETTE[(z % PALETTE.len() as u64) as usize]
+}
+
+/// Render a square PNG of `name`'s initials on the user's background color.
+///
+/// Used for users without an uploaded avatar, so every context (the app,
+/// emails, OG images) shows the same fallback. The color depends only on the
+/// id, so it stays the same when the user renames themselves.
+pub fn render_fallback_avatar(user_id: i64, name: &str, size: u32) -> Result<Vec<u8>, String> {
+    let text: Vec<char> = initials(name).chars().collect();
+    let mut img = RgbImage::from_pixel(size, size, Rgb(background_color(user_id)));
+
+    // Glyphs plus one column of spacing between them, scaled to ~40% height
+    let text_cols = text.len() as u32 * (GLYPH_WIDTH + 1) - 1;
+    let scale = (size * 2 / 5 / GLYPH_HEIGHT).max(1);
+    let left = size.saturating_sub(text_cols * scale) / 2;
+    let top = size.saturating_sub(GLYPH_HEIGHT * scale) / 2;
+
+    for (i, c) in text.iter().enumerate() {
+        let glyph_left = left + i as u32 * (GLYPH_WIDTH + 1) * scale;
+        for (row, bits) in glyph(*c).iter().enumerate() {
+            for col in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - col)) == 0 {
+                    continue;
+                }
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        let x = glyph_left + col * scale + dx;
+                        let y = top + row as u32 * scale + dy;
+                        if x < size && y < size {
+                            img.put_pixel(x, y, Rgb(TEXT_COLOR));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let mut out = std::io::Cursor::new(Vec::new());
+    img.write_to(&mut out, ImageFormat::Png)
+        .map_err(|e| format!("Avatar encoding failed: {}", e))?;
+    Ok(out.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+    #[test]
+    fn initials_use_first_and_last_words() {
+        assert_eq!(initials("Ada Lovelace"), "AL");
+        assert_eq!(initials("grace brewster murray hopper"), "GH");
+        assert_eq!(initials("neo"), "N");
+        assert_eq!(initials("  "), "?");
+        assert_eq!(initials("Zoë (admin)"), "ZA");
+    }
+
+    #[test]
+    fn fallback_is_valid_png_of_requested_size() {
+        let png = render_fallback_avatar(42, "Ada Lovelace", 128).unwrap();
+        assert!(png.starts_with(PNG_SIGNATURE));
+        let decoded = image::load_from_memory_with_format(&png, ImageFormat::Png)
+            .unwrap()
+            .to_rgb8();
+        assert_eq!(decoded.dimensions(), (128, 128));
+        assert_eq!(decoded.get_pixel(0, 0).0, background_color(42));
+        // Initials are drawn somewhere in the middle
+        assert!(decoded.pixels().any(|p| p.0 == TEXT_COLOR));
+    }
+
+    #[test]
+    fn fallback_color_is_stable_per_id() {
+        assert_eq!(background_color(7), background_color(7));
+        assert_eq!(
+            render_fallback_avatar(7, "Neo", 64).unwrap(),
+            render_fallback_avatar(7, "Neo", 64).unwrap()
+        );
+        // Renaming keeps the color, since it depends only on the id
+        let renamed = render_fallback_avatar(7, "Thomas Anderson", 64).unwrap();
+        let renamed = image::load_from_memory(&renamed).unwrap().to_rgb8();
+        assert_eq!(renamed.get_pixel(0, 0).0, background_color(7));
+    }
+
+    #[test]
+    fn fallback_colors_vary_across_ids() {
+        let colors: std::collections::HashSet<_> = (1..=32).map(background_color).collect();
+        assert!(colors.len() > 1);
+    }
+}
@@ -0,0 +1,385 @@
+use std::future::Future;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::OnceLock;
+
+use axum::extract::ConnectInfo;
+use axum::http::{Extensions, HeaderMap};
+use axum::Router;
+use tokio::net::TcpListener;
+
+/// An address range in CIDR notation, e.g. `10.0.0.0/8`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Cidr {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    /// Parse `addr` or `addr/prefix`; a bare address is a single host.
+    fn parse(value: &str) -> Result<Self, String> {
+        let (addr, prefix) = match value.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (value, None),
+        };
+        let network = addr
+            .parse::<IpAddr>()
+            .map_err(|_| format!("invalid address in TRUSTED_PROXIES: {value}"))?
+            .to_canonical();
+        let max = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(p) => p
+                .parse::<u8>()
+                .ok()
+                .filter(|p| *p <= max)
+                .ok_or_else(|| format!("invalid prefix length in TRUSTED_PROXIES: {value}"))?,
+            None => max,
+        };
+        Ok(Self { network, prefix })
+    }
+
+    fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Reverse proxies allowed to report the client address in
+/// `X-Forwarded-For` / `X-Real-IP`.
+///
+/// Forwarded headers are attacker-controlled unless they were set by a proxy
+/// we run, so they are only read when the request arrived from one of these.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TrustedProxies {
+    ranges: Vec<Cidr>,
+    /// Whether the socket peer is trusted whatever its address.
+    trust_peer: bool,
+}
+
+impl TrustedProxies {
+    /// Parse a comma-separated list of addresses and CIDR ranges,
+    /// e.g. `10.0.0.0/8, 127.0.0.1`.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let ranges = value
+            .split(',')
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(Cidr::parse)
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            ranges,
+            trust_peer: false,
+        })
+    }
+
+    /// Also trust whatever connects to the server directly, for platforms
+    /// whose edge proxy has no fixed address range.
+    ///
+    /// Only safe when the server can't be reached except through that proxy.
+    /// The proxy must append to `X-Forwarded-For`, as its last entry is taken
+    /// as the client unless it is in one of the trusted ranges.
+    pub fn trusting_peer(mut self) -> Self {
+        self.trust_peer = true;
+        self
+    }
+
+    /// Read `TRUSTED_PROXIES`, and trust the socket peer when `TRUST_PROXY`
+    /// is `true`; unset trusts no proxy.
+    pub fn from_env() -> Result<Self, String> {
+        let proxies = Self::parse(&std::env::var("TRUSTED_PROXIES").unwrap_or_default())?;
+        let trust_peer = parse_flag(&std::env::var("TRUST_PROXY").unwrap_or_default())
+            .ok_or("TRUST_PROXY must be true or false")?;
+        Ok(if trust_peer {
+            proxies.trusting_peer()
+        } else {
+            proxies
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty() && !self.trust_peer
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        self.ranges.iter().any(|range| range.contains(ip))
+    }
+
+    /// The address of the client that sent a request.
+    ///
+    /// `peer` is the socket address the request arrived on. When it is a
+    /// trusted proxy, `X-Forwarded-For` is walked from the right, skipping
+    /// trusted hops, and the first untrusted hop is the client; entries to its
+    /// left could have been written by anyone. `X-Real-IP` is used when there
+    /// is no `X-Forwarded-For`.
+    ///
+    /// Without a socket address nothing vouches for the headers, so the
+    /// client is unknown. Serve with [`serve_with_peer_addrs`] to record it.
+    pub fn client_ip(&self, headers: &HeaderMap, peer: Option<IpAddr>) -> Option<IpAddr> {
+        let ip = peer?;
+        if !self.trust_peer && !self.contains(ip) {
+            return peer;
+        }
+
+        let forwarded: Vec<&str> = headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .collect();
+
+        if forwarded.is_empty() {
+            return headers
+                .get("x-real-ip")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse::<IpAddr>().ok())
+                .map(|ip| ip.to_canonical())
+                .or(peer);
+        }
+
+        // Closest hop reached so far; a malformed entry ends the walk there.
+        let mut closest = peer;
+        for hop in forwarded.iter().rev() {
+            let Ok(ip) = hop.parse::<IpAddr>().map(|ip| ip.to_canonical()) else {
+                break;
+            };
+            if !self.contains(ip) {
+                return Some(ip);
+            }
+            closest = Some(ip);
+        }
+        closest
+    }
+}
+
+/// Parse a boolean setting; blank is `false`.
+fn parse_flag(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" => Some(true),
+        "false" | "0" | "no" | "" => Some(false),
+        _ => None,
+    }
+}
+
+/// Trusted proxies loaded once from the environment.
+///
+/// Panics on an invalid `TRUSTED_PROXIES` or `TRUST_PROXY`; call it at startup so a
+/// misconfiguration fails fast instead of on the first request.
+pub fn trusted_proxies() -> &'static TrustedProxies {
+    static PROXIES: OnceLock<TrustedProxies> = OnceLock::new();
+    PROXIES.get_or_init(|| TrustedProxies::from_env().unwrap_or_else(|e| panic!("{e}")))
+}
+
+/// The socket address of the connection, when the server records it.
+pub fn peer_ip(extensions: &Extensions) -> Option<IpAddr> {
+    extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_canonical())
+}
+
+/// The client address of a request, using the configured [`trusted_proxies`].
+pub fn client_ip(headers: &HeaderMap, extensions: &Extensions) -> Option<IpAddr> {
+    trusted_proxies().client_ip(headers, peer_ip(extensions))
+}
+
+/// Serve `router` on `listener`, recording each connection's socket address
+/// for [`peer_ip`]. A plain `axum::serve` leaves it out, and every client
+/// address is then unknown.
+pub async fn serve_with_peer_addrs(listener: TcpListener, router: Router) -> std::io::Result<()> {
+    axum::serve(
+        listener,
+        router.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+}
+
+/// Build the router from `make_router` and serve it on `addr` with
+/// [`serve_with_peer_addrs`], on a new multi-threaded runtime. Never returns;
+/// panics if `addr` can't be bound.
+pub fn run_with_peer_addrs(addr: SocketAddr, make_router: impl Future<Output = Router>) -> ! {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .expect("failed to start the tokio runtime");
+    runtime.block_on(async move {
+        let router = make_router.await;
+        let listener = TcpListener::bind(addr)
+            .await
+            .unwrap_or_else(|e| panic!("failed to bind {addr}: {e}"));
+        tracing::info!(%addr, "serving");
+        if let Err(e) = serve_with_peer_addrs(listener, router).await {
+            panic!("server error: {e}");
+        }
+    });
+    unreachable!("the server stopped")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    fn forwarded(chain: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", HeaderValue::from_str(chain).unwrap());
+        headers
+    }
+
+    fn proxies() -> TrustedProxies {
+        TrustedProxies::parse("10.0.0.0/8, 127.0.0.1, fd00::/8").unwrap()
+    }
+
+    #[test]
+    fn untrusted_peer_cannot_spoof_forwarded_headers() {
+        let mut headers = forwarded("198.51.100.66");
+        headers.insert("x-real-ip", HeaderValue::from_static("198.51.100.67"));
+
+        let client = proxies().client_ip(&headers, Some(ip("203.0.113.9")));
+        assert_eq!(client, Some(ip("203.0.113.9")));
+    }
+
+    #[test]
+    fn trusted_chain_yields_first_untrusted_hop() {
+        // The client prepended a fake entry; the proxies appended the rest.
+        let headers = forwarded("198.51.100.66, 203.0.113.9, 10.1.2.3");
+
+        let client = proxies().client_ip(&headers, Some(ip("127.0.0.1")));
+        assert_eq!(client, Some(ip("203.0.113.9")));
+    }
+
+    #[test]
+    fn fully_trusted_chain_yields_leftmost_hop() {
+        let headers = forwarded("10.9.9.9, 10.1.2.3");
+
+        let client = proxies().client_ip(&headers, Some(ip("10.0.0.1")));
+        assert_eq!(client, Some(ip("10.9.9.9")));
+    }
+
+    #[test]
+    fn malformed_hop_stops_the_walk() {
+        let headers = forwarded("203.0.113.9, not-an-ip, 10.1.2.3");
+
+        let client = proxies().client_ip(&headers, Some(ip("10.0.0.1")));
+        assert_eq!(client, Some(ip("10.1.2.3")));
+    }
+
+    #[test]
+    fn real_ip_is_used_without_forwarded_for() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-real-ip", HeaderValue::from_static("203.0.113.9"));
+
+        let client = proxies().client_ip(&headers, Some(ip("::ffff:127.0.0.1")));
+        assert_eq!(client, Some(ip("203.0.113.9")));
+    }
+
+    #[test]
+    fn unknown_peer_never_trusts_headers() {
+        let mut headers = forwarded("203.0.113.9, 10.1.2.3");
+        headers.insert("x-real-ip", HeaderValue::from_static("203.0.113.9"));
+
+        assert_eq!(TrustedProxies::default().client_ip(&headers, None), None);
+        assert_eq!(proxies().client_ip(&headers, None), None);
+        let trusting = TrustedProxies::default().trusting_peer();
+        assert_eq!(trusting.client_ip(&headers, None), None);
+    }
+
+    #[tokio::test]
+    async fn served_requests_carry_the_peer_address() {
+        use axum::routing::get;
+
+        let router = Router::new().route(
+            "/",
+            get(|req: axum::extract::Request| async move {
+                peer_ip(req.extensions())
+                    .map(|ip| ip.to_string())
+                    .unwrap_or_default()
+            }),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve_with_peer_addrs(listener, router));
+
+        let body = reqwest::get(format!("http://{addr}/"))
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert_eq!(body, "127.0.0.1");
+    }
+
+    #[test]
+    fn trusted_peer_yields_the_hop_it_appended() {
+        let proxies = TrustedProxies::default().trusting_peer();
+        // The client prepended two fake entries; the edge proxy appended the real one.
+        let headers = forwarded("198.51.100.66, 192.0.2.1, 203.0.113.9");
+
+        let client = proxies.client_ip(&headers, Some(ip("172.16.5.5")));
+        assert_eq!(client, Some(ip("203.0.113.9")));
+
+        // Hops through internal proxies behind the edge are skipped.
+        let proxies = TrustedProxies::parse("10.0.0.0/8").unwrap().trusting_peer();
+        let headers = forwarded("198.51.100.66, 203.0.113.9, 10.1.2.3, 10.4.5.6");
+        let client = proxies.client_ip(&headers, Some(ip("172.16.5.5")));
+        assert_eq!(client, Some(ip("203.0.113.9")));
+    }
+
+    #[test]
+    fn forwarded_headers_are_ignored_without_proxy_trust() {
+        let mut headers = forwarded("198.51.100.66, 203.0.113.9");
+        headers.insert("x-real-ip", HeaderValue::from_static("198.51.100.67"));
+
+        let proxies = TrustedProxies::default();
+        assert_eq!(
+            proxies.client_ip(&headers, Some(ip("172.16.5.5"))),
+            Some(ip("172.16.5.5"))
+        );
+        assert_eq!(proxies.client_ip(&headers, None), None);
+    }
+
+    #[test]
+    fn proxy_trust_flag_is_parsed() {
+        assert_eq!(parse_flag(" TRUE "), Some(true));
+        assert_eq!(parse_flag("1"), Some(true));
+        assert_eq!(parse_flag(""), Some(false));
+        assert_eq!(parse_flag("false"), Some(false));
+        assert_eq!(parse_flag("sometimes"), None);
+        assert!(!TrustedProxies::default().trusting_peer().is_empty());
+    }
+
+    #[test]
+    fn ranges_match_by_prefix() {
+        let proxies = proxies();
+        assert!(proxies.contains(ip("10.255.0.1")));
+        assert!(proxies.contains(ip("fd12::1")));
+        assert!(!proxies.contains(ip("11.0.0.1")));
+        assert!(!proxies.contains(ip("127.0.0.2")));
+        assert!(TrustedProxies::parse("0.0.0.0/0")
+            .unwrap()
+            .contains(ip("8.8.8.8")));
+    }
+
+    #[test]
+    fn invalid_entries_are_rejected() {
+        assert!(TrustedProxies::parse("10.0.0.0/33").is_err());
+        assert!(TrustedProxies::parse("proxy.internal").is_err());
+        assert!(TrustedProxies::parse("").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,85 @@
+use tower_http::compression::predicate::{And, DefaultPredicate, Predicate, SizeAbove};
+use tower_http::compression::CompressionLayer;
+
+/// Default minimum response size (in bytes) before compression kicks in.
+const DEFAULT_MIN_SIZE: u16 = 1024;
+
+/// Minimum response size eligible for compression.
+///
+/// Reads `COMPRESSION_MIN_SIZE` (bytes), falling back to 1 KiB.
+pub fn min_size() -> u16 {
+    std::env::var("COMPRESSION_MIN_SIZE")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_MIN_SIZE)
+}
+
+/// Response compression layer honoring `Accept-Encoding` (gzip and brotli).
+///
+/// Builds on tower-http's `DefaultPredicate`, which already skips images,
+/// gRPC, and `text/event-stream` so SSE/streaming responses are never buffered,
+/// and adds a minimum-size threshold so tiny responses go out uncompressed.
+pub fn compression_layer() -> CompressionLayer<And<DefaultPredicate, SizeAbove>> {
+    CompressionLayer::new()
+        .gzip(true)
+        .br(true)
+        .compress_when(DefaultPredicate::new().and(SizeAbove::new(min_size())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        Router::new()
+            .route("/large", get(|| async { "x".repeat(16 * 1024) }))
+            .route("/small", get(|| async { "ok" }))
+            .layer(compression_layer())
+    }
+
+    async fn content_encoding(uri: &str, accept: &str) -> Option<String> {
+        let response = app()
+            .oneshot(
+                Request::builder()
+                    .uri(uri)
+                    .header("accept-encoding", accept)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        response
+            .headers()
+            .get("content-encoding")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    }
+
+    #[tokio::test]
+    async fn large_body_is_gzip_compressed() {
+        assert_eq!(
+            content_encoding("/large", "gzip").await.as_deref(),
+            Some("gzip")
+        );
+    }
+
+    #[tokio::test]
+    async fn large_body_is_brotli_compressed() {
+        assert_eq!(
+            content_encoding("/large", "br").await.as_deref(),
+            Some("br")
+        );
+    }
+
+    #[tokio::test]
+    async fn small_body_is_not_compressed() {
+        assert_eq!(content_encoding("/small", "gzip").await, None);
+    }
+
+    #[tokio::test]
+    async fn no_accept_encoding_is_not_compressed() {
+        assert_eq!(content_encoding("/large", "identity").await, None);
+    }
+}
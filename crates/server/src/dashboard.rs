@@ -0,0 +1,71 @@
+use shared_types::{AppError, DashboardStats, StatsRange, User};
+use sqlx::{Pool, Postgres};
+
+use crate::db::UserRecord;
+use crate::error_convert::SqlxErrorExt;
+use crate::telemetry::traced_query;
+
+/// Users listed under recent activity.
+const RECENT_USERS: i64 = 5;
+
+/// Figures shown on the dashboard, counting only records created within
+/// `range`.
+pub async fn stats(pool: &Pool<Postgres>, range: StatsRange) -> Result<DashboardStats, AppError> {
+    let days = range.days().map(|d| d as i32);
+
+    let total_users = traced_query(
+        "users.count",
+        sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM users WHERE deleted_at IS NULL AND ($1::INT IS NULL OR created_at >= NOW() - make_interval(days => $1))",
+            days
+        )
+        .fetch_one(pool),
+    )
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?
+    .unwrap_or(0);
+
+    let total_products = traced_query(
+        "products.count",
+        sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM products WHERE deleted_at IS NULL AND ($1::INT IS NULL OR created_at >= NOW() - make_interval(days => $1))",
+            days
+        )
+        .fetch_one(pool),
+    )
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?
+    .unwrap_or(0);
+
+    let active_products = traced_query(
+        "products.count_active",
+        sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM products WHERE status = 'active' AND deleted_at IS NULL AND ($1::INT IS NULL OR created_at >= NOW() - make_interval(days => $1))",
+            days
+        )
+        .fetch_one(pool),
+    )
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?
+    .unwrap_or(0);
+
+    let recent_users = traced_query(
+        "users.recent",
+        sqlx::query_as!(
+            UserRecord,
+            "SELECT id, username, display_name, email, role, tier, avatar_url, thumbnail_url, created_at, updated_at FROM users WHERE deleted_at IS NULL AND ($1::INT IS NULL OR created_at >= NOW() - make_interval(days => $1)) ORDER BY id DESC LIMIT $2",
+            days,
+            RECENT_USERS
+        )
+        .fetch_all(pool),
+    )
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?;
+
+    Ok(DashboardStats {
+        total_users,
+        total_products,
+        active_products,
+        recent_users: recent_users.into_iter().map(User::from).collect(),
+    })
+}
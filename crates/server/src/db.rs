@@ -1,14 +1,121 @@
 use axum::extract::FromRef;
+use chrono::{DateTime, Utc};
+use shared_types::{AuthUser, Money, Product, ProductStatus, Role, User, UserTier};
+use sqlx::migrate::Migrator;
 use sqlx::postgres::PgPoolOptions;
 use sqlx::{Pool, Postgres};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::OnceLock;
 
+use crate::auth::invites::RegistrationMode;
+use crate::site::SiteConfig;
+
 /// Shared application state passed to Axum handlers via `State`.
 /// Derives `FromRef` so handlers can extract `State<PgPool>` directly.
 #[derive(Clone, FromRef)]
 pub struct AppState {
     pub pool: Pool<Postgres>,
+    pub registration_mode: RegistrationMode,
+    pub site: SiteConfig,
+}
+
+impl AppState {
+    /// Build state for `pool`, reading the remaining settings from the environment.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self {
+            pool,
+            registration_mode: RegistrationMode::from_env(),
+            site: SiteConfig::from_env(),
+        }
+    }
+}
+
+/// Row shape for every `users` query that returns a user.
+///
+/// Queries select this one shape and handlers convert it into whichever
+/// public projection they return: [`User`] for the admin user list, or
+/// [`AuthUser`] for the signed-in user. Timestamps come back as
+/// `TIMESTAMPTZ`; the `User` conversion renders them as strings, matching how
+/// `Product::created_at` is exposed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserRecord {
+    pub id: i64,
+    pub username: String,
+    pub display_name: String,
+    pub email: Option<String>,
+    pub role: String,
+    pub tier: String,
+    pub avatar_url: Option<String>,
+    pub thumbnail_url: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<UserRecord> for User {
+    fn from(record: UserRecord) -> Self {
+        User {
+            id: record.id,
+            username: record.username,
+            display_name: record.display_name,
+            role: record.role,
+            tier: record.tier,
+            created_at: record.created_at.to_string(),
+            updated_at: record.updated_at.to_string(),
+        }
+    }
+}
+
+impl From<UserRecord> for AuthUser {
+    fn from(record: UserRecord) -> Self {
+        AuthUser {
+            id: record.id,
+            username: record.username,
+            display_name: record.display_name,
+            email: record.email.unwrap_or_default(),
+            role: Role::from_str_or_default(&record.role),
+            tier: UserTier::from_str_or_default(&record.tier),
+            avatar_url: record.avatar_url,
+            thumbnail_url: record.thumbnail_url,
+            expires_at: None,
+            email_verified: None,
+        }
+    }
+}
+
+/// Row shape for product list queries, including whether the requesting
+/// user has favorited the product.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProductRecord {
+    pub id: i64,
+    pub name: String,
+    pub description: String,
+    pub price: f64,
+    pub category: String,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub image_url: Option<String>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub is_favorited: bool,
+}
+
+impl ProductRecord {
+    /// The public product; `is_favorited` is only set for signed-in users.
+    pub fn into_product(self, signed_in: bool) -> Product {
+        Product {
+            id: self.id,
+            name: self.name,
+            description: self.description,
+            price: Money::from_f64(self.price),
+            category: self.category,
+            status: ProductStatus::from_str_or_default(&self.status),
+            created_at: self.created_at.to_string(),
+            updated_at: self.updated_at.to_string(),
+            image_url: self.image_url,
+            is_favorited: signed_in.then_some(self.is_favorited),
+            deleted_at: self.deleted_at.map(|d| d.to_string()),
+        }
+    }
 }
 
 /// Pool created lazily — no connections are opened until the first query.
@@ -37,14 +144,55 @@ pub fn create_pool() -> Pool<Postgres> {
         .expect("Failed to create database pool")
 }
 
+/// Migrations embedded from the workspace `migrations/` directory.
+static MIGRATOR: Migrator = sqlx::migrate!("../../migrations");
+
 /// Run database migrations against the given pool.
 pub async fn run_migrations(pool: &Pool<Postgres>) {
-    sqlx::migrate!("../../migrations")
+    MIGRATOR
         .run(pool)
         .await
         .expect("Failed to run database migrations");
 }
 
+/// How far the database schema is behind the migrations this build ships.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MigrationStatus {
+    /// Shipped migrations recorded as applied.
+    pub applied: usize,
+    /// Shipped migrations not applied yet.
+    pub pending: usize,
+}
+
+/// Compare the shipped migrations with those recorded in sqlx's
+/// `_sqlx_migrations` table. A database that was never migrated has every
+/// migration pending.
+pub async fn migration_status(pool: &Pool<Postgres>) -> Result<MigrationStatus, sqlx::Error> {
+    let table_exists =
+        sqlx::query_scalar::<_, bool>("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+            .fetch_one(pool)
+            .await?;
+    let applied: Vec<i64> = if table_exists {
+        sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success")
+            .fetch_all(pool)
+            .await?
+    } else {
+        Vec::new()
+    };
+
+    let shipped = MIGRATOR
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration());
+    let (applied, pending) = shipped.fold((0, 0), |(applied_count, pending), m| {
+        if applied.contains(&m.version) {
+            (applied_count + 1, pending)
+        } else {
+            (applied_count, pending + 1)
+        }
+    });
+    Ok(MigrationStatus { applied, pending })
+}
+
 /// Get or initialize the database connection pool.
 /// Migrations run once on the first call; subsequent calls return immediately.
 ///
@@ -61,3 +209,62 @@ pub async fn get_db() -> &'static Pool<Postgres> {
 
     pool
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record() -> UserRecord {
+        UserRecord {
+            id: 42,
+            username: "trinity".into(),
+            display_name: "Trinity".into(),
+            email: Some("trinity@example.com".into()),
+            role: "admin".into(),
+            tier: "premium".into(),
+            avatar_url: Some("avatars/42/original".into()),
+            thumbnail_url: Some("avatars/42/thumb".into()),
+            created_at: "2026-02-06T12:00:00Z".parse().unwrap(),
+            updated_at: "2026-02-07T08:30:00Z".parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn user_record_into_user_keeps_fields() {
+        let user = User::from(record());
+        assert_eq!(user.id, 42);
+        assert_eq!(user.username, "trinity");
+        assert_eq!(user.display_name, "Trinity");
+        assert_eq!(user.role, "admin");
+        assert_eq!(user.tier, "premium");
+        assert_eq!(user.created_at, "2026-02-06 12:00:00 UTC");
+        assert_eq!(user.joined_date(), "2026-02-06");
+        assert_eq!(user.updated_at, "2026-02-07 08:30:00 UTC");
+    }
+
+    #[test]
+    fn user_record_into_auth_user_keeps_fields() {
+        let user = AuthUser::from(record());
+        assert_eq!(user.id, 42);
+        assert_eq!(user.username, "trinity");
+        assert_eq!(user.display_name, "Trinity");
+        assert_eq!(user.email, "trinity@example.com");
+        assert_eq!(user.role, Role::Admin);
+        assert_eq!(user.tier, UserTier::Premium);
+        assert_eq!(user.avatar_url.as_deref(), Some("avatars/42/original"));
+        assert_eq!(user.thumbnail_url.as_deref(), Some("avatars/42/thumb"));
+    }
+
+    #[test]
+    fn user_record_into_auth_user_defaults_missing_values() {
+        let user = AuthUser::from(UserRecord {
+            email: None,
+            role: "superuser".into(),
+            tier: "gold".into(),
+            ..record()
+        });
+        assert_eq!(user.email, "");
+        assert_eq!(user.role, Role::User);
+        assert_eq!(user.tier, UserTier::Free);
+    }
+}
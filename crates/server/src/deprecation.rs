@@ -0,0 +1,160 @@
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use chrono::{DateTime, Utc};
+
+/// `Deprecation` response header (RFC 9745).
+pub const DEPRECATION: HeaderName = HeaderName::from_static("deprecation");
+
+/// `Sunset` response header (RFC 8594).
+pub const SUNSET: HeaderName = HeaderName::from_static("sunset");
+
+/// How a deprecated endpoint is announced to clients.
+///
+/// Attach it to the routes being retired with
+/// `route_layer(axum::middleware::from_fn_with_state(deprecation, deprecation_headers))`;
+/// routes without the layer are left untouched.
+#[derive(Clone, Debug, Default)]
+pub struct Deprecation {
+    /// When the endpoint was deprecated; `None` sends `Deprecation: true`.
+    pub since: Option<DateTime<Utc>>,
+    /// When the endpoint stops working, sent as `Sunset`.
+    pub sunset: Option<DateTime<Utc>>,
+    /// Replacement to use instead, sent as a `successor-version` link.
+    pub successor: Option<String>,
+    /// Human-readable notice, sent as a `Warning` header.
+    pub message: Option<String>,
+}
+
+impl Deprecation {
+    /// The unversioned `/api/...` routes, kept as an alias while clients
+    /// move to the versioned prefix.
+    ///
+    /// `LEGACY_API_SUNSET` (RFC 3339, e.g. `2027-01-01T00:00:00Z`) sets the
+    /// date the alias will be removed; it is left out when unset or invalid.
+    pub fn legacy_api() -> Self {
+        let sunset = std::env::var("LEGACY_API_SUNSET")
+            .ok()
+            .and_then(|v| DateTime::parse_from_rfc3339(v.trim()).ok())
+            .map(|at| at.with_timezone(&Utc));
+
+        Self {
+            since: None,
+            sunset,
+            successor: Some("/api/v1".to_string()),
+            message: Some("Unversioned /api routes are deprecated; use /api/v1".to_string()),
+        }
+    }
+
+    /// Header values for this deprecation, in the order they are sent.
+    fn headers(&self) -> Vec<(HeaderName, String)> {
+        let mut headers = vec![(
+            DEPRECATION,
+            self.since
+                .map(|at| format!("@{}", at.timestamp()))
+                .unwrap_or_else(|| "true".to_string()),
+        )];
+        if let Some(sunset) = self.sunset {
+            headers.push((SUNSET, http_date(sunset)));
+        }
+        if let Some(successor) = &self.successor {
+            headers.push((
+                header::LINK,
+                format!("<{successor}>; rel=\"successor-version\""),
+            ));
+        }
+        if let Some(message) = &self.message {
+            headers.push((
+                header::WARNING,
+                format!("299 - \"{}\"", message.replace('"', "'")),
+            ));
+        }
+        headers
+    }
+}
+
+/// Format a timestamp as an HTTP-date (RFC 9110), e.g.
+/// `Fri, 01 Jan 2027 00:00:00 GMT`.
+fn http_date(at: DateTime<Utc>) -> String {
+    at.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// Middleware that marks responses from a deprecated route.
+///
+/// Headers the handler already set are kept.
+pub async fn deprecation_headers(
+    State(deprecation): State<Deprecation>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let mut response = next.run(req).await;
+    let headers = response.headers_mut();
+    for (name, value) in deprecation.headers() {
+        if let Ok(value) = HeaderValue::from_str(&value) {
+            headers.entry(name).or_insert(value);
+        }
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get, Router};
+    use chrono::TimeZone;
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        let deprecation = Deprecation {
+            since: Some(Utc.with_ymd_and_hms(2026, 6, 1, 0, 0, 0).unwrap()),
+            sunset: Some(Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap()),
+            successor: Some("/api/v1/ping".to_string()),
+            message: Some("Use /api/v1/ping".to_string()),
+        };
+        Router::new()
+            .route("/api/ping", get(|| async { "pong" }))
+            .route_layer(axum::middleware::from_fn_with_state(
+                deprecation,
+                deprecation_headers,
+            ))
+            .route("/api/v1/ping", get(|| async { "pong" }))
+    }
+
+    async fn send(uri: &str) -> Response {
+        app()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn deprecated_route_announces_its_sunset() {
+        let response = send("/api/ping").await;
+        let headers = response.headers();
+        assert_eq!(headers[DEPRECATION], "@1780272000");
+        assert_eq!(headers[SUNSET], "Fri, 01 Jan 2027 00:00:00 GMT");
+        assert_eq!(
+            headers[header::LINK],
+            "</api/v1/ping>; rel=\"successor-version\""
+        );
+        assert_eq!(headers[header::WARNING], "299 - \"Use /api/v1/ping\"");
+    }
+
+    #[tokio::test]
+    async fn current_route_has_no_deprecation_headers() {
+        let response = send("/api/v1/ping").await;
+        let headers = response.headers();
+        assert!(!headers.contains_key(DEPRECATION));
+        assert!(!headers.contains_key(SUNSET));
+        assert!(!headers.contains_key(header::WARNING));
+    }
+
+    #[test]
+    fn undated_deprecation_is_flagged_true() {
+        let headers = Deprecation::default().headers();
+        assert_eq!(headers, vec![(DEPRECATION, "true".to_string())]);
+    }
+}
@@ -1,10 +1,68 @@
+use std::collections::HashMap;
+
 use dioxus::prelude::ServerFnError;
-use shared_types::AppError;
+use shared_types::{AppError, Normalize};
+
+/// Unique constraints whose violations are the caller's fault, as
+/// `(constraint, field, message)`. They become a `ValidationError` naming the
+/// field, so forms can highlight it, instead of a database error.
+const UNIQUE_FIELDS: &[(&str, &str, &str)] = &[
+    ("users_email_key", "email", "Email already in use"),
+    ("users_username_key", "username", "Username taken"),
+];
+
+/// The field error for a unique violation on `constraint`, if it is one of
+/// [`UNIQUE_FIELDS`].
+fn unique_violation_error(constraint: Option<&str>) -> Option<AppError> {
+    let (_, field, message) = UNIQUE_FIELDS
+        .iter()
+        .find(|(name, _, _)| Some(*name) == constraint)?;
+    Some(AppError::validation(
+        *message,
+        HashMap::from([(field.to_string(), message.to_string())]),
+    ))
+}
+
+/// Shown when the database can't be reached; the underlying error stays in
+/// the server logs.
+const UNAVAILABLE_MESSAGE: &str = "The database is temporarily unavailable. Please try again.";
+
+/// A foreign-key violation (23503) as a `ValidationError`.
+///
+/// Postgres reports deletes of still-referenced rows as "update or delete on
+/// table ..."; anything else is an insert or update pointing at a missing row.
+fn foreign_key_violation_error(message: &str) -> AppError {
+    let message = if message.starts_with("update or delete on table") {
+        "This record is still referenced by other records"
+    } else {
+        "A referenced record does not exist"
+    };
+    AppError::validation(message, HashMap::new())
+}
 
 /// Convert a sqlx::Error into an AppError.
+///
+/// Constraint violations the caller can fix become `ValidationError`s, and
+/// connection failures become an `InternalError` that doesn't leak SQL.
+/// Anything else is a generic database error.
 pub fn sqlx_to_app_error(err: sqlx::Error) -> AppError {
     match &err {
         sqlx::Error::RowNotFound => AppError::not_found("Resource not found"),
+        sqlx::Error::Database(db) if db.is_unique_violation() => {
+            unique_violation_error(db.constraint()).unwrap_or_else(|| {
+                AppError::validation("A record with this value already exists", HashMap::new())
+            })
+        }
+        sqlx::Error::Database(db) if db.is_foreign_key_violation() => {
+            foreign_key_violation_error(db.message())
+        }
+        sqlx::Error::PoolTimedOut
+        | sqlx::Error::PoolClosed
+        | sqlx::Error::Io(_)
+        | sqlx::Error::Tls(_) => {
+            tracing::error!(error = %err, "database connection failed");
+            AppError::internal(UNAVAILABLE_MESSAGE)
+        }
         _ => AppError::database(err.to_string()),
     }
 }
@@ -38,8 +96,24 @@ impl AppErrorExt for AppError {
 }
 
 /// Trait for validating request DTOs before processing.
+///
+/// Failures are `ValidationError`s (422): the body parsed but its values are
+/// invalid. Unparseable bodies are rejected earlier with 400 by
+/// [`AppJson`](crate::extract::AppJson).
 pub trait ValidateRequest {
     fn validate_request(&self) -> Result<(), AppError>;
+
+    /// Normalize the DTO (see [`Normalize`]), then validate it.
+    ///
+    /// Returns the cleaned copy, which handlers should use from then on.
+    fn validate_and_normalize(self) -> Result<Self, AppError>
+    where
+        Self: Normalize + Sized,
+    {
+        let normalized = self.normalize();
+        normalized.validate_request()?;
+        Ok(normalized)
+    }
 }
 
 impl<T: validator::Validate> ValidateRequest for T {
@@ -47,3 +121,232 @@ impl<T: validator::Validate> ValidateRequest for T {
         self.validate().map_err(AppError::from)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared_types::{
+        AppErrorKind, CreateUserRequest, RefreshRequest, RegisterRequest, UpdateProfileRequest,
+    };
+    use sqlx::error::{DatabaseError, ErrorKind};
+    use std::borrow::Cow;
+
+    fn register_request(username: &str, email: &str, display_name: &str) -> RegisterRequest {
+        RegisterRequest {
+            username: username.to_string(),
+            email: email.to_string(),
+            password: "StrongPass1!".to_string(),
+            display_name: display_name.to_string(),
+            invite_token: None,
+        }
+    }
+
+    #[test]
+    fn validate_and_normalize_returns_cleaned_register_request() {
+        let req = register_request("  neo  ", "  Neo@Matrix.IO ", " The One ")
+            .validate_and_normalize()
+            .unwrap();
+
+        assert_eq!(req.username, "neo");
+        assert_eq!(req.email, "neo@matrix.io");
+        assert_eq!(req.display_name, "The One");
+        assert!(req.validate_request().is_ok());
+    }
+
+    #[test]
+    fn validation_runs_on_normalized_input() {
+        // Whitespace padding would satisfy the length rule before trimming
+        let err = register_request("  ab  ", "neo@matrix.io", "Neo")
+            .validate_and_normalize()
+            .unwrap_err();
+        assert_eq!(err.kind, AppErrorKind::ValidationError);
+        assert!(err.field_errors.contains_key("username"));
+
+        let err = CreateUserRequest {
+            username: "trinity".to_string(),
+            display_name: "   ".to_string(),
+        }
+        .validate_and_normalize()
+        .unwrap_err();
+        assert!(err.field_errors.contains_key("display_name"));
+    }
+
+    #[test]
+    fn update_profile_email_is_lowercased() {
+        let req = UpdateProfileRequest {
+            display_name: " Morpheus ".to_string(),
+            email: "MORPHEUS@zion.net".to_string(),
+        }
+        .validate_and_normalize()
+        .unwrap();
+        assert_eq!(req.display_name, "Morpheus");
+        assert_eq!(req.email, "morpheus@zion.net");
+    }
+
+    #[test]
+    fn empty_refresh_token_is_rejected() {
+        let err = RefreshRequest {
+            refresh_token: String::new(),
+        }
+        .validate_request()
+        .unwrap_err();
+        assert_eq!(err.kind, AppErrorKind::ValidationError);
+        assert_eq!(
+            err.field_errors.get("refresh_token").map(String::as_str),
+            Some("Refresh token is required")
+        );
+    }
+
+    #[test]
+    fn known_unique_violations_become_field_errors() {
+        let err = unique_violation_error(Some("users_email_key")).unwrap();
+        assert_eq!(err.kind, AppErrorKind::ValidationError);
+        assert_eq!(
+            err.field_errors.get("email").map(String::as_str),
+            Some("Email already in use")
+        );
+
+        let err = unique_violation_error(Some("users_username_key")).unwrap();
+        assert_eq!(
+            err.field_errors.get("username").map(String::as_str),
+            Some("Username taken")
+        );
+    }
+
+    #[test]
+    fn other_unique_violations_are_not_field_errors() {
+        assert!(unique_violation_error(Some("refresh_tokens_token_hash_key")).is_none());
+        assert!(unique_violation_error(None).is_none());
+    }
+
+    /// A Postgres error as sqlx reports it, without a database.
+    #[derive(Debug)]
+    struct FakeDbError {
+        code: &'static str,
+        message: &'static str,
+        constraint: Option<&'static str>,
+    }
+
+    impl std::fmt::Display for FakeDbError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(self.message)
+        }
+    }
+
+    impl std::error::Error for FakeDbError {}
+
+    impl DatabaseError for FakeDbError {
+        fn message(&self) -> &str {
+            self.message
+        }
+
+        fn constraint(&self) -> Option<&str> {
+            self.constraint
+        }
+
+        fn as_error(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn as_error_mut(&mut self) -> &mut (dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn into_error(self: Box<Self>) -> Box<dyn std::error::Error + Send + Sync + 'static> {
+            self
+        }
+
+        fn code(&self) -> Option<Cow<'_, str>> {
+            Some(Cow::Borrowed(self.code))
+        }
+
+        fn kind(&self) -> ErrorKind {
+            match self.code {
+                "23505" => ErrorKind::UniqueViolation,
+                "23503" => ErrorKind::ForeignKeyViolation,
+                _ => ErrorKind::Other,
+            }
+        }
+    }
+
+    fn db_error(
+        code: &'static str,
+        message: &'static str,
+        constraint: Option<&'static str>,
+    ) -> sqlx::Error {
+        sqlx::Error::Database(Box::new(FakeDbError {
+            code,
+            message,
+            constraint,
+        }))
+    }
+
+    #[test]
+    fn unique_violations_map_to_validation_errors() {
+        let err = db_error(
+            "23505",
+            "duplicate key value violates unique constraint \"users_email_key\"",
+            Some("users_email_key"),
+        )
+        .into_app_error();
+        assert_eq!(err.kind, AppErrorKind::ValidationError);
+        assert!(err.field_errors.contains_key("email"));
+
+        let err = db_error(
+            "23505",
+            "duplicate key value violates unique constraint \"favorites_pkey\"",
+            Some("favorites_pkey"),
+        )
+        .into_app_error();
+        assert_eq!(err.kind, AppErrorKind::ValidationError);
+        assert_eq!(err.message, "A record with this value already exists");
+        assert!(!err.message.contains("favorites_pkey"));
+    }
+
+    #[test]
+    fn foreign_key_violations_map_to_validation_errors() {
+        let err = db_error(
+            "23503",
+            "insert or update on table \"favorites\" violates foreign key constraint \"favorites_product_id_fkey\"",
+            Some("favorites_product_id_fkey"),
+        )
+        .into_app_error();
+        assert_eq!(err.kind, AppErrorKind::ValidationError);
+        assert_eq!(err.message, "A referenced record does not exist");
+
+        let err = db_error(
+            "23503",
+            "update or delete on table \"users\" violates foreign key constraint \"invites_created_by_fkey\" on table \"invites\"",
+            Some("invites_created_by_fkey"),
+        )
+        .into_app_error();
+        assert_eq!(err.kind, AppErrorKind::ValidationError);
+        assert_eq!(
+            err.message,
+            "This record is still referenced by other records"
+        );
+    }
+
+    #[test]
+    fn connection_failures_do_not_leak_details() {
+        for err in [
+            sqlx::Error::PoolTimedOut,
+            sqlx::Error::PoolClosed,
+            sqlx::Error::Io(std::io::Error::other("connection refused to 10.0.0.5:5432")),
+        ] {
+            let err = err.into_app_error();
+            assert_eq!(err.kind, AppErrorKind::InternalError);
+            assert_eq!(err.message, UNAVAILABLE_MESSAGE);
+        }
+    }
+
+    #[test]
+    fn row_not_found_and_other_errors_keep_their_kinds() {
+        assert_eq!(
+            sqlx::Error::RowNotFound.into_app_error().kind,
+            AppErrorKind::NotFound
+        );
+        let err = db_error("22012", "division by zero", None).into_app_error();
+        assert_eq!(err.kind, AppErrorKind::DatabaseError);
+    }
+}
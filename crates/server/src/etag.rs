@@ -0,0 +1,105 @@
+use axum::{
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use shared_types::AppError;
+
+use crate::s3::content_hash;
+
+/// Hex digits of the body hash kept in the tag.
+const TAG_LEN: usize = 32;
+
+/// Weak ETag for a serialized JSON body, e.g. `W/"3f9a…"`.
+///
+/// Weak because the tag identifies the resource's JSON representation, not
+/// the exact bytes on the wire (compression may change those).
+pub fn weak_etag(body: &[u8]) -> String {
+    let hash = content_hash(body);
+    format!("W/\"{}\"", &hash[..TAG_LEN])
+}
+
+/// Whether an `If-None-Match` header value matches `etag`.
+///
+/// Uses weak comparison (RFC 9110 §13.1.2): the `W/` prefix is ignored on
+/// both sides, `*` matches anything, and the value may list several tags.
+pub fn if_none_match(header_value: &str, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    let etag = opaque(etag);
+    header_value
+        .split(',')
+        .any(|candidate| candidate.trim() == "*" || opaque(candidate) == etag)
+}
+
+/// Respond with `value` as JSON and its ETag, or with `304 Not Modified`
+/// when the request's `If-None-Match` already names that ETag.
+pub fn conditional_json<T: Serialize>(
+    headers: &HeaderMap,
+    value: &T,
+) -> Result<Response, AppError> {
+    let body = serde_json::to_vec(value)
+        .map_err(|e| AppError::internal(format!("Failed to serialize response: {e}")))?;
+    let etag = weak_etag(&body);
+    let etag_header = HeaderValue::from_str(&etag)
+        .map_err(|e| AppError::internal(format!("Invalid ETag: {e}")))?;
+
+    let not_modified = headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .any(|v| if_none_match(v, &etag));
+    if not_modified {
+        return Ok((StatusCode::NOT_MODIFIED, [(header::ETAG, etag_header)]).into_response());
+    }
+
+    Ok((
+        [
+            (header::ETAG, etag_header),
+            (
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn etag_is_weak_and_stable() {
+        let etag = weak_etag(br#"{"id":1}"#);
+        assert!(etag.starts_with("W/\"") && etag.ends_with('"'), "{etag}");
+        assert_eq!(etag.len(), TAG_LEN + 4);
+        assert_eq!(etag, weak_etag(br#"{"id":1}"#));
+        assert_ne!(etag, weak_etag(br#"{"id":2}"#));
+    }
+
+    #[test]
+    fn if_none_match_uses_weak_comparison() {
+        let etag = "W/\"abc\"";
+        assert!(if_none_match("W/\"abc\"", etag));
+        assert!(if_none_match("\"abc\"", etag));
+        assert!(if_none_match("\"xyz\", W/\"abc\"", etag));
+        assert!(if_none_match("*", etag));
+        assert!(!if_none_match("W/\"xyz\"", etag));
+        assert!(!if_none_match("", etag));
+    }
+
+    #[test]
+    fn matching_request_gets_not_modified() {
+        let value = serde_json::json!({ "id": 1 });
+        let first = conditional_json(&HeaderMap::new(), &value).unwrap();
+        assert_eq!(first.status(), StatusCode::OK);
+        let etag = first.headers().get(header::ETAG).unwrap().clone();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, etag.clone());
+        let second = conditional_json(&headers, &value).unwrap();
+        assert_eq!(second.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(second.headers().get(header::ETAG), Some(&etag));
+    }
+}
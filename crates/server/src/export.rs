@@ -0,0 +1,194 @@
+use std::borrow::Cow;
+
+use futures_util::{stream, Stream, StreamExt};
+use shared_types::{AppError, Money, Product, ProductStatus, User};
+use sqlx::{Pool, Postgres};
+
+use crate::db::UserRecord;
+use crate::error_convert::SqlxErrorExt;
+
+/// Rows fetched per query while exporting; at most this many are held in
+/// memory at once.
+const BATCH_SIZE: i64 = 500;
+
+/// Header row for the products export, in [`Product`] field order.
+pub const PRODUCT_COLUMNS: [&str; 8] = [
+    "id",
+    "name",
+    "description",
+    "price",
+    "category",
+    "status",
+    "created_at",
+    "deleted_at",
+];
+
+/// Header row for the users export, in [`User`] field order.
+pub const USER_COLUMNS: [&str; 7] = [
+    "id",
+    "username",
+    "display_name",
+    "role",
+    "tier",
+    "created_at",
+    "updated_at",
+];
+
+/// Quote a field if it contains a delimiter, quote, or line break (RFC 4180).
+fn escape(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+/// One CSV line, including the trailing CRLF.
+pub fn csv_row<S: AsRef<str>>(fields: impl IntoIterator<Item = S>) -> String {
+    let mut line = fields
+        .into_iter()
+        .map(|f| escape(f.as_ref()).into_owned())
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push_str("\r\n");
+    line
+}
+
+fn product_row(product: &Product) -> String {
+    csv_row([
+        product.id.to_string(),
+        product.name.clone(),
+        product.description.clone(),
+        product.price.to_decimal_string(),
+        product.category.clone(),
+        product.status.as_str().to_string(),
+        product.created_at.clone(),
+        product.deleted_at.clone().unwrap_or_default(),
+    ])
+}
+
+fn user_row(user: &User) -> String {
+    csv_row([
+        user.id.to_string(),
+        user.username.clone(),
+        user.display_name.clone(),
+        user.role.clone(),
+        user.tier.clone(),
+        user.created_at.clone(),
+        user.updated_at.clone(),
+    ])
+}
+
+/// Prepend the header row to a stream of CSV chunks, and turn errors into
+/// I/O errors so the stream can be used as a response body.
+fn with_header(
+    columns: &[&str],
+    rows: impl Stream<Item = Result<String, AppError>> + Send + 'static,
+) -> impl Stream<Item = Result<String, std::io::Error>> + Send + 'static {
+    stream::once(std::future::ready(Ok(csv_row(columns))))
+        .chain(rows)
+        .map(|chunk| {
+            chunk.map_err(|e| {
+                tracing::error!(error = %e, "CSV export failed mid-stream");
+                std::io::Error::other(e.message)
+            })
+        })
+}
+
+/// Products as CSV, newest first like the product list, fetched in batches.
+///
+/// Soft-deleted products are included only when `include_deleted` is set.
+pub fn products_csv(
+    pool: Pool<Postgres>,
+    include_deleted: bool,
+) -> impl Stream<Item = Result<String, std::io::Error>> + Send + 'static {
+    // `Some(cursor)` while there may be more rows; `cursor` is the last id sent.
+    let rows = stream::try_unfold(Some(None::<i64>), move |state| {
+        let pool = pool.clone();
+        async move {
+            let Some(before) = state else {
+                return Ok(None);
+            };
+            let rows = sqlx::query!(
+                "SELECT id, name, description, price, category, status, created_at, updated_at, deleted_at FROM products WHERE ($1 OR deleted_at IS NULL) AND ($2::BIGINT IS NULL OR id < $2) ORDER BY id DESC LIMIT $3",
+                include_deleted,
+                before,
+                BATCH_SIZE
+            )
+            .fetch_all(&pool)
+            .await
+            .map_err(SqlxErrorExt::into_app_error)?;
+
+            let next = (rows.len() as i64 == BATCH_SIZE).then(|| rows.last().map(|r| r.id));
+            let chunk: String = rows
+                .into_iter()
+                .map(|row| {
+                    product_row(&Product {
+                        id: row.id,
+                        name: row.name,
+                        description: row.description,
+                        price: Money::from_f64(row.price),
+                        category: row.category,
+                        status: ProductStatus::from_str_or_default(&row.status),
+                        created_at: row.created_at.to_string(),
+                        updated_at: row.updated_at.to_string(),
+                        image_url: None,
+                        is_favorited: None,
+                        deleted_at: row.deleted_at.map(|d| d.to_string()),
+                    })
+                })
+                .collect();
+            Ok::<_, AppError>(Some((chunk, next)))
+        }
+    });
+    with_header(&PRODUCT_COLUMNS, rows)
+}
+
+/// Users as CSV in id order, fetched in batches. Deleted users are left out.
+pub fn users_csv(
+    pool: Pool<Postgres>,
+) -> impl Stream<Item = Result<String, std::io::Error>> + Send + 'static {
+    let rows = stream::try_unfold(Some(None::<i64>), move |state| {
+        let pool = pool.clone();
+        async move {
+            let Some(after) = state else {
+                return Ok(None);
+            };
+            let records = sqlx::query_as!(
+                UserRecord,
+                "SELECT id, username, display_name, email, role, tier, avatar_url, thumbnail_url, created_at, updated_at FROM users WHERE deleted_at IS NULL AND ($1::BIGINT IS NULL OR id > $1) ORDER BY id LIMIT $2",
+                after,
+                BATCH_SIZE
+            )
+            .fetch_all(&pool)
+            .await
+            .map_err(SqlxErrorExt::into_app_error)?;
+
+            let next = (records.len() as i64 == BATCH_SIZE).then(|| records.last().map(|r| r.id));
+            let chunk: String = records
+                .into_iter()
+                .map(|record| user_row(&record.into()))
+                .collect();
+            Ok::<_, AppError>(Some((chunk, next)))
+        }
+    });
+    with_header(&USER_COLUMNS, rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_fields_are_left_alone() {
+        assert_eq!(csv_row(["1", "Widget", "9.99"]), "1,Widget,9.99\r\n");
+    }
+
+    #[test]
+    fn fields_with_delimiters_are_quoted() {
+        assert_eq!(
+            csv_row(["a,b", "say \"hi\"", "two\nlines", ""]),
+            "\"a,b\",\"say \"\"hi\"\"\",\"two\nlines\",\r\n"
+        );
+    }
+}
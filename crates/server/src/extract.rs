@@ -0,0 +1,58 @@
+use axum::{
+    extract::{
+        rejection::{JsonRejection, QueryRejection},
+        FromRequest, FromRequestParts, Query, Request,
+    },
+    http::request::Parts,
+    Json,
+};
+use shared_types::AppError;
+
+/// JSON body extractor that rejects with an [`AppError`] instead of axum's
+/// plain-text rejection.
+///
+/// Status convention for request bodies:
+/// - malformed or unparseable input (bad JSON syntax, missing fields, wrong
+///   types, missing `Content-Type`) → `400 BadRequest`
+/// - parseable input that breaks validation rules (`validate_request`) →
+///   `422 ValidationError`
+pub struct AppJson<T>(pub T);
+
+impl<T, S> FromRequest<S> for AppJson<T>
+where
+    Json<T>: FromRequest<S, Rejection = JsonRejection>,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        Json::<T>::from_request(req, state)
+            .await
+            .map(|Json(value)| AppJson(value))
+            .map_err(json_rejection_to_app_error)
+    }
+}
+
+/// Map an axum JSON rejection to a `BadRequest` error carrying its message.
+pub fn json_rejection_to_app_error(rejection: JsonRejection) -> AppError {
+    AppError::bad_request(rejection.body_text())
+}
+
+/// Query string extractor that rejects with a `400 BadRequest` [`AppError`],
+/// e.g. for an unknown sort field, instead of axum's plain-text rejection.
+pub struct AppQuery<T>(pub T);
+
+impl<T, S> FromRequestParts<S> for AppQuery<T>
+where
+    Query<T>: FromRequestParts<S, Rejection = QueryRejection>,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        Query::<T>::from_request_parts(parts, state)
+            .await
+            .map(|Query(value)| AppQuery(value))
+            .map_err(|rejection| AppError::bad_request(rejection.body_text()))
+    }
+}
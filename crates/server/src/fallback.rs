@@ -0,0 +1,23 @@
+use axum::http::{Method, Uri};
+use shared_types::AppError;
+
+/// Fallback for routes that exist but don't support the request method.
+///
+/// Returns the standard `AppError` envelope with a 405 status. Axum appends
+/// the `Allow` header listing the methods the route does support.
+pub async fn method_not_allowed(method: Method, uri: Uri) -> AppError {
+    AppError::method_not_allowed(format!(
+        "Method {} is not allowed for {}",
+        method,
+        uri.path()
+    ))
+}
+
+/// Catch-all for unmatched `/api/*` paths.
+///
+/// Registered as a wildcard route so API clients get a JSON 404 instead of
+/// falling through to the Dioxus SSR handler. Concrete routes (including
+/// server functions) take precedence over the wildcard.
+pub async fn api_not_found(uri: Uri) -> AppError {
+    AppError::not_found(format!("No API route matches {}", uri.path()))
+}
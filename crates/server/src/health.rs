@@ -15,10 +15,19 @@ pub fn record_start_time() {
 /// Health check response.
 #[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct HealthResponse {
+    /// `ok`, or `degraded` while migrations are pending (a half-deployed
+    /// schema) or their status can't be read.
     pub status: String,
     pub db: String,
     pub uptime_seconds: u64,
     pub version: String,
+    /// Migrations shipped with this build that the database has applied;
+    /// omitted when the database can't be queried.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub migrations_applied: Option<usize>,
+    /// Migrations shipped with this build that are not applied yet.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pending_count: Option<usize>,
 }
 
 /// Health check handler.
@@ -26,7 +35,7 @@ pub struct HealthResponse {
     get,
     path = "/health",
     responses(
-        (status = 200, description = "Service is healthy", body = HealthResponse)
+        (status = 200, description = "Service is up; `status` is `degraded` while migrations are pending", body = HealthResponse)
     ),
     tag = "health"
 )]
@@ -39,12 +48,20 @@ pub async fn health_check(State(pool): State<Pool<Postgres>>) -> Json<HealthResp
         Err(e) => format!("error: {e}"),
     };
 
+    let migrations = crate::db::migration_status(&pool).await.ok();
+    let status = match migrations {
+        Some(m) if m.pending == 0 => "ok",
+        _ => "degraded",
+    };
+
     let uptime = START_TIME.get().map(|t| t.elapsed().as_secs()).unwrap_or(0);
 
     Json(HealthResponse {
-        status: "ok".to_string(),
+        status: status.to_string(),
         db: db_status,
         uptime_seconds: uptime,
         version: env!("CARGO_PKG_VERSION").to_string(),
+        migrations_applied: migrations.map(|m| m.applied),
+        pending_count: migrations.map(|m| m.pending),
     })
 }
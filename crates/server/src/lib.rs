@@ -3,18 +3,78 @@ pub mod db;
 
 pub mod api;
 
+#[cfg(feature = "server")]
+pub mod analytics;
+
+#[cfg(feature = "server")]
+pub mod audit;
+
 #[cfg(feature = "server")]
 pub mod rest;
 
 #[cfg(feature = "server")]
 pub mod openapi;
 
+#[cfg(feature = "server")]
+pub mod load_shed;
+
+#[cfg(feature = "server")]
+pub mod logging;
+
+#[cfg(feature = "server")]
+pub mod client_ip;
+
+#[cfg(feature = "server")]
+pub mod compression;
+
+#[cfg(feature = "server")]
+pub mod dashboard;
+
+#[cfg(feature = "server")]
+pub mod deprecation;
+
 #[cfg(feature = "server")]
 pub mod error_convert;
 
+#[cfg(feature = "server")]
+pub mod etag;
+
+#[cfg(feature = "server")]
+pub mod export;
+
+#[cfg(feature = "server")]
+pub mod extract;
+
+#[cfg(feature = "server")]
+pub mod fallback;
+
+#[cfg(feature = "server")]
+pub mod preferences;
+
+#[cfg(feature = "server")]
+pub mod profile;
+
+#[cfg(feature = "server")]
+pub mod request_id;
+
+#[cfg(feature = "server")]
+pub mod security_headers;
+
+#[cfg(feature = "server")]
+pub mod shutdown;
+
+#[cfg(feature = "server")]
+pub mod site;
+
 #[cfg(feature = "server")]
 pub mod telemetry;
 
+#[cfg(feature = "server")]
+pub mod upload_limits;
+
+#[cfg(feature = "server")]
+pub mod users;
+
 #[cfg(feature = "server")]
 pub mod health;
 
@@ -23,3 +83,6 @@ pub mod auth;
 
 #[cfg(feature = "server")]
 pub mod s3;
+
+#[cfg(feature = "server")]
+pub mod avatar_fallback;
@@ -0,0 +1,143 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use shared_types::AppError;
+use tokio::sync::Semaphore;
+
+/// Seconds a shed client is asked to wait before retrying.
+const RETRY_AFTER_SECS: u64 = 1;
+
+/// Path prefix exempt from shedding, so probes still see an overloaded
+/// instance as alive.
+const EXEMPT_PREFIX: &str = "/health";
+
+/// A positive request cap, or `None` for no limit.
+fn parse_max(value: Option<&str>) -> Option<usize> {
+    value
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .filter(|max| *max > 0)
+}
+
+/// Cap on requests handled at once. Requests over the cap are rejected
+/// immediately instead of queueing for the database pool.
+#[derive(Clone, Debug, Default)]
+pub struct ConcurrencyLimit {
+    /// `None` when unlimited.
+    permits: Option<Arc<Semaphore>>,
+}
+
+impl ConcurrencyLimit {
+    pub fn new(max_concurrent: usize) -> Self {
+        Self {
+            permits: Some(Arc::new(Semaphore::new(max_concurrent))),
+        }
+    }
+
+    /// Read the cap from `MAX_CONCURRENT_REQUESTS`. Unset, unparseable, or
+    /// zero means no limit.
+    pub fn from_env() -> Self {
+        parse_max(std::env::var("MAX_CONCURRENT_REQUESTS").ok().as_deref())
+            .map(Self::new)
+            .unwrap_or_default()
+    }
+}
+
+/// Middleware that sheds requests over the [`ConcurrencyLimit`] with a 503
+/// and `Retry-After`. Health checks are always let through.
+pub async fn shed_load(
+    State(limit): State<ConcurrencyLimit>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let Some(permits) = limit.permits else {
+        return next.run(req).await;
+    };
+    if req.uri().path().starts_with(EXEMPT_PREFIX) {
+        return next.run(req).await;
+    }
+
+    let Ok(_permit) = permits.try_acquire_owned() else {
+        tracing::warn!(path = %req.uri().path(), "shedding request over the concurrency limit");
+        let mut response =
+            AppError::service_unavailable("The server is busy. Please try again shortly.")
+                .into_response();
+        response
+            .headers_mut()
+            .insert(header::RETRY_AFTER, HeaderValue::from(RETRY_AFTER_SECS));
+        return response;
+    };
+    next.run(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::StatusCode, routing::get, Router};
+    use tokio::sync::{mpsc, Notify};
+    use tower::ServiceExt;
+
+    /// A router whose `/slow` handler holds its permit until `release` fires,
+    /// reporting on `started` once it is running.
+    fn app(limit: usize, started: mpsc::Sender<()>, release: Arc<Notify>) -> Router {
+        Router::new()
+            .route(
+                "/slow",
+                get(move || {
+                    let (started, release) = (started.clone(), release.clone());
+                    async move {
+                        started.send(()).await.unwrap();
+                        release.notified().await;
+                        "done"
+                    }
+                }),
+            )
+            .route("/health", get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(
+                ConcurrencyLimit::new(limit),
+                shed_load,
+            ))
+    }
+
+    async fn send(app: &Router, uri: &str) -> Response {
+        app.clone()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn requests_over_the_limit_are_shed() {
+        let (started, mut running) = mpsc::channel(1);
+        let release = Arc::new(Notify::new());
+        let app = app(1, started, release.clone());
+
+        let in_flight = tokio::spawn({
+            let app = app.clone();
+            async move { send(&app, "/slow").await.status() }
+        });
+        running.recv().await.unwrap();
+
+        let shed = send(&app, "/slow").await;
+        assert_eq!(shed.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(shed.headers()[header::RETRY_AFTER], "1");
+
+        let health = send(&app, "/health").await;
+        assert_eq!(health.status(), StatusCode::OK);
+
+        release.notify_one();
+        assert_eq!(in_flight.await.unwrap(), StatusCode::OK);
+    }
+
+    #[test]
+    fn missing_or_zero_cap_means_unlimited() {
+        assert_eq!(parse_max(None), None);
+        assert_eq!(parse_max(Some("0")), None);
+        assert_eq!(parse_max(Some("lots")), None);
+        assert_eq!(parse_max(Some(" 64 ")), Some(64));
+    }
+}
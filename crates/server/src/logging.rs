@@ -0,0 +1,93 @@
+use tracing::Subscriber;
+use tracing_subscriber::{fmt, layer::SubscriberExt, EnvFilter};
+
+/// Filter used when `RUST_LOG` is unset or invalid.
+const DEFAULT_FILTER: &str = "info";
+
+/// How log lines are written to stdout.
+///
+/// Read from `LOG_FORMAT`: `json` emits one JSON object per event with the
+/// fields of every enclosing span (request id, user, trace and span ids —
+/// see [`crate::telemetry::OtelTraceLayer`]), for log shippers in
+/// environments without an OTLP collector. Anything else is the
+/// human-readable default.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    #[default]
+    Pretty,
+    Json,
+}
+
+impl LogFormat {
+    /// Parse a format string, defaulting to `Pretty` for unknown values.
+    pub fn from_str_or_default(s: &str) -> Self {
+        match s.trim().to_lowercase().as_str() {
+            "json" => LogFormat::Json,
+            _ => LogFormat::Pretty,
+        }
+    }
+
+    /// Load from `LOG_FORMAT`.
+    pub fn from_env() -> Self {
+        std::env::var("LOG_FORMAT")
+            .map(|v| Self::from_str_or_default(&v))
+            .unwrap_or_default()
+    }
+}
+
+/// Build the subscriber for `format`, filtered by `RUST_LOG`.
+pub fn subscriber(format: LogFormat) -> Box<dyn Subscriber + Send + Sync> {
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+    let registry = tracing_subscriber::registry().with(filter);
+
+    match format {
+        LogFormat::Json => Box::new(
+            registry.with(
+                fmt::layer()
+                    .json()
+                    .with_current_span(true)
+                    .with_span_list(true)
+                    .flatten_event(true),
+            ),
+        ),
+        LogFormat::Pretty => Box::new(registry.with(fmt::layer())),
+    }
+}
+
+/// Install the global `tracing` subscriber in the format from `LOG_FORMAT`.
+///
+/// Call at the very start of `main`, before Dioxus installs its own default
+/// logger, so `#[tracing::instrument]` spans and events go through it. Does
+/// nothing if a subscriber is already set.
+pub fn init_logging() {
+    let _ = dotenvy::dotenv();
+
+    let format = LogFormat::from_env();
+    if tracing::subscriber::set_global_default(subscriber(format)).is_err() {
+        eprintln!("A tracing subscriber is already installed; LOG_FORMAT ignored");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_format_parses_json() {
+        assert_eq!(LogFormat::from_str_or_default(" JSON "), LogFormat::Json);
+        assert_eq!(LogFormat::from_str_or_default("pretty"), LogFormat::Pretty);
+        assert_eq!(LogFormat::from_str_or_default(""), LogFormat::Pretty);
+    }
+
+    #[test]
+    fn subscriber_initializes_in_both_formats() {
+        for format in [LogFormat::Pretty, LogFormat::Json] {
+            tracing::subscriber::with_default(subscriber(format), || {
+                let span = tracing::info_span!("request", request_id = "test-id", user.id = 7);
+                let _entered = span.enter();
+                tracing::info!(status = 200, "request completed");
+            });
+        }
+    }
+}
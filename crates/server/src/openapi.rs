@@ -1,11 +1,21 @@
 use axum::Router;
 use shared_types::{
-    AppError, AppErrorKind, AuthResponse, AuthUser, CreateProductRequest, CreateUserRequest,
-    DashboardStats, LoginRequest, Product, RegisterRequest, UpdateProductRequest,
-    UpdateProfileRequest, UpdateTierRequest, UpdateUserRequest, User, UserTier,
+    AccountDeletion, AnalyticsScope, AppError, AppErrorKind, AuditAction, AuditEntry, AuditPage,
+    AuthEvent, AuthEventType, AuthResponse, AuthUser, BatchGetUsersRequest, CategoryCount,
+    CategoryRevenue, CreateInviteRequest, CreateProductRequest, CreateUserRequest, Cursor,
+    DashboardStats, Invite, LoginMethods, LoginRequest, OAuthCallbackParams, OAuthProvider,
+    OAuthProviderInfo, PremiumAnalytics, Product, ProductHistory, ProductList, RefreshRequest,
+    RefreshResponse, RegisterRequest, Role, SortOrder, StatsRange, UpdateDisplayNameRequest,
+    UpdateProductRequest, UpdateProfileRequest, UpdateTierRequest, UpdateUserRequest, User,
+    UserPreferences, UserSortField, UserTier, VerifyEmailRequest,
 };
 use sqlx::{Pool, Postgres};
-use utoipa::OpenApi;
+use utoipa::openapi::path::{HttpMethod, Operation, OperationBuilder};
+use utoipa::openapi::request_body::RequestBodyBuilder;
+use utoipa::openapi::schema::{Array, ObjectBuilder, Type};
+use utoipa::openapi::security::SecurityRequirement;
+use utoipa::openapi::{ContentBuilder, Ref, RefOr, ResponseBuilder, Schema};
+use utoipa::{Modify, OpenApi};
 use utoipa_scalar::{Scalar, Servable};
 
 use crate::db::AppState;
@@ -17,65 +27,740 @@ use crate::rest;
 #[openapi(
     paths(
         rest::list_users,
+        rest::export_users,
         rest::get_user,
+        rest::batch_get_users,
         rest::create_user,
         rest::update_user,
         rest::delete_user,
+        rest::restore_user,
         rest::update_user_tier,
         rest::list_products,
+        rest::get_product,
+        rest::export_products,
         rest::create_product,
         rest::update_product,
         rest::delete_product,
+        rest::restore_product,
+        rest::get_product_history,
+        rest::get_product_image,
+        rest::favorite_product,
+        rest::unfavorite_product,
         rest::get_dashboard_stats,
+        rest::get_premium_analytics,
         rest::register,
         rest::login,
         rest::logout,
+        rest::list_oauth_providers,
+        rest::oauth_authorize,
+        crate::auth::oauth_callback::oauth_callback,
+        rest::cancel_deletion_with_credentials,
+        rest::create_invite,
+        rest::list_auth_events,
+        rest::list_audit_log,
         rest::upload_avatar,
+        rest::get_user_avatar,
+        rest::get_my_preferences,
+        rest::update_my_preferences,
+        rest::update_my_display_name,
+        rest::get_my_account_deletion,
+        rest::schedule_my_account_deletion,
+        rest::cancel_my_account_deletion,
+        rest::resend_verification_email,
+        rest::verify_email,
         health::health_check,
     ),
     components(schemas(
         User,
         Product,
+        ProductList,
+        Cursor<Product>,
         DashboardStats,
+        StatsRange,
+        PremiumAnalytics,
+        AnalyticsScope,
+        CategoryCount,
+        CategoryRevenue,
         AppError,
         AppErrorKind,
+        AccountDeletion,
         CreateUserRequest,
         UpdateUserRequest,
+        BatchGetUsersRequest,
         CreateProductRequest,
         UpdateProductRequest,
         AuthUser,
         UserTier,
+        UserSortField,
+        SortOrder,
+        Role,
         LoginRequest,
         RegisterRequest,
         AuthResponse,
+        RefreshRequest,
+        RefreshResponse,
+        CreateInviteRequest,
+        Invite,
+        AuthEvent,
+        AuthEventType,
+        AuditEntry,
+        AuditAction,
+        AuditPage,
+        ProductHistory,
         UpdateProfileRequest,
+        UpdateDisplayNameRequest,
+        VerifyEmailRequest,
         UpdateTierRequest,
+        UserPreferences,
+        LoginMethods,
+        OAuthProvider,
+        OAuthProviderInfo,
+        OAuthCallbackParams,
         health::HealthResponse,
     )),
+    modifiers(&ServerFunctions, &StandardErrorResponses),
     tags(
         (name = "auth", description = "Authentication endpoints"),
         (name = "users", description = "User management endpoints"),
         (name = "products", description = "Product management endpoints"),
         (name = "dashboard", description = "Dashboard statistics"),
+        (name = "server-functions", description = "Dioxus server functions called by the app"),
         (name = "health", description = "Health check endpoint")
     )
 )]
 pub struct ApiDoc;
 
+/// What a server function returns.
+enum Returns {
+    Nothing,
+    Text,
+    One(&'static str),
+    Many(&'static str),
+}
+
+/// Documentation for one `#[server]` function in [`crate::api`].
+struct ServerFnDoc {
+    name: &'static str,
+    summary: &'static str,
+    /// Schema matching the JSON arguments object, when there is one.
+    request: Option<&'static str>,
+    returns: Returns,
+    /// Whether the function needs a signed-in caller.
+    authenticated: bool,
+}
+
+const fn server_fn(
+    name: &'static str,
+    summary: &'static str,
+    request: Option<&'static str>,
+    returns: Returns,
+    authenticated: bool,
+) -> ServerFnDoc {
+    ServerFnDoc {
+        name,
+        summary,
+        request,
+        returns,
+        authenticated,
+    }
+}
+
+/// Every server function in [`crate::api`], in source order.
+const SERVER_FUNCTIONS: &[ServerFnDoc] = &[
+    server_fn(
+        "get_premium_analytics",
+        "Premium analytics, scoped by `scope` (Premium tier)",
+        None,
+        Returns::One("PremiumAnalytics"),
+        true,
+    ),
+    server_fn(
+        "get_user",
+        "Get a user by ID",
+        None,
+        Returns::One("User"),
+        false,
+    ),
+    server_fn(
+        "get_users",
+        "Get several users by ID, in request order",
+        Some("BatchGetUsersRequest"),
+        Returns::Many("User"),
+        false,
+    ),
+    server_fn(
+        "list_users",
+        "List users; `params` searches, sorts and pages them (every user by id when empty)",
+        None,
+        Returns::Many("User"),
+        false,
+    ),
+    server_fn(
+        "create_user",
+        "Create a user",
+        Some("CreateUserRequest"),
+        Returns::One("User"),
+        false,
+    ),
+    server_fn(
+        "update_user",
+        "Update a user",
+        None,
+        Returns::One("User"),
+        false,
+    ),
+    server_fn(
+        "delete_user",
+        "Soft-delete a user",
+        None,
+        Returns::Nothing,
+        false,
+    ),
+    server_fn(
+        "restore_user",
+        "Restore a deleted user",
+        None,
+        Returns::One("User"),
+        false,
+    ),
+    server_fn(
+        "update_user_tier",
+        "Change a user's tier (admin)",
+        None,
+        Returns::One("User"),
+        true,
+    ),
+    server_fn(
+        "create_invite",
+        "Invite an email address to register (admin)",
+        Some("CreateInviteRequest"),
+        Returns::One("Invite"),
+        true,
+    ),
+    server_fn(
+        "list_products",
+        "List products",
+        None,
+        Returns::Many("Product"),
+        false,
+    ),
+    server_fn(
+        "list_products_page",
+        "One page of products below `before_id`, with the cursor for the next",
+        None,
+        Returns::One("Cursor_Product"),
+        false,
+    ),
+    server_fn(
+        "get_product",
+        "Get a product by ID",
+        None,
+        Returns::One("Product"),
+        false,
+    ),
+    server_fn(
+        "get_product_history",
+        "When a product was created and last changed; audit entries only for its owner and admins",
+        None,
+        Returns::One("ProductHistory"),
+        true,
+    ),
+    server_fn(
+        "create_product",
+        "Create a product",
+        Some("CreateProductRequest"),
+        Returns::One("Product"),
+        false,
+    ),
+    server_fn(
+        "update_product",
+        "Update a product",
+        None,
+        Returns::One("Product"),
+        false,
+    ),
+    server_fn(
+        "upload_product_image",
+        "Upload a base64-encoded product image (JPEG, PNG or WebP, up to 5 MB)",
+        None,
+        Returns::One("Product"),
+        true,
+    ),
+    server_fn(
+        "delete_product",
+        "Soft-delete a product",
+        None,
+        Returns::Nothing,
+        false,
+    ),
+    server_fn(
+        "restore_product",
+        "Restore a deleted product",
+        None,
+        Returns::One("Product"),
+        false,
+    ),
+    server_fn(
+        "get_dashboard_stats",
+        "Dashboard statistics for `range` (all time when unset)",
+        None,
+        Returns::One("DashboardStats"),
+        false,
+    ),
+    server_fn(
+        "register",
+        "Register and sign in",
+        Some("RegisterRequest"),
+        Returns::One("AuthUser"),
+        false,
+    ),
+    server_fn(
+        "login",
+        "Sign in with email and password",
+        Some("LoginRequest"),
+        Returns::One("AuthUser"),
+        false,
+    ),
+    server_fn(
+        "get_current_user",
+        "The signed-in user, or null",
+        None,
+        Returns::One("AuthUser"),
+        false,
+    ),
+    server_fn(
+        "logout",
+        "Sign out and revoke tokens",
+        None,
+        Returns::Nothing,
+        false,
+    ),
+    server_fn(
+        "update_profile",
+        "Change display name and email",
+        Some("UpdateProfileRequest"),
+        Returns::One("AuthUser"),
+        true,
+    ),
+    server_fn(
+        "update_display_name",
+        "Change only the display name",
+        Some("UpdateDisplayNameRequest"),
+        Returns::One("AuthUser"),
+        true,
+    ),
+    server_fn(
+        "resend_verification_email",
+        "Send a new email verification link (no-op if already verified)",
+        None,
+        Returns::Nothing,
+        true,
+    ),
+    server_fn(
+        "verify_email",
+        "Confirm the email address a verification link was sent to",
+        Some("VerifyEmailRequest"),
+        Returns::Nothing,
+        false,
+    ),
+    server_fn(
+        "get_account_deletion",
+        "Pending account deletion, or null",
+        None,
+        Returns::One("AccountDeletion"),
+        true,
+    ),
+    server_fn(
+        "delete_account",
+        "Schedule account deletion",
+        None,
+        Returns::One("AccountDeletion"),
+        true,
+    ),
+    server_fn(
+        "cancel_account_deletion",
+        "Cancel a scheduled deletion",
+        None,
+        Returns::Nothing,
+        true,
+    ),
+    server_fn(
+        "upload_user_avatar",
+        "Upload a base64-encoded avatar",
+        None,
+        Returns::One("AuthUser"),
+        true,
+    ),
+    server_fn(
+        "get_avatar_url",
+        "Loadable URL for the caller's avatar, or null",
+        None,
+        Returns::Text,
+        true,
+    ),
+    server_fn(
+        "get_fallback_avatar",
+        "Generated initials avatar as a data URL",
+        None,
+        Returns::Text,
+        false,
+    ),
+    server_fn(
+        "get_preferences",
+        "The caller's preferences",
+        None,
+        Returns::One("UserPreferences"),
+        true,
+    ),
+    server_fn(
+        "update_preferences",
+        "Replace the caller's preferences",
+        None,
+        Returns::One("UserPreferences"),
+        true,
+    ),
+    server_fn(
+        "update_theme",
+        "Save the caller's theme",
+        None,
+        Returns::Nothing,
+        true,
+    ),
+    server_fn(
+        "oauth_providers",
+        "OAuth providers configured on this server",
+        None,
+        Returns::Many("OAuthProviderInfo"),
+        false,
+    ),
+    server_fn(
+        "oauth_authorize_url",
+        "Provider URL to start an OAuth sign-in",
+        None,
+        Returns::Text,
+        false,
+    ),
+    server_fn(
+        "get_login_methods",
+        "The caller's password and linked OAuth providers",
+        None,
+        Returns::One("LoginMethods"),
+        true,
+    ),
+    server_fn(
+        "unlink_oauth",
+        "Unlink an OAuth provider",
+        None,
+        Returns::One("LoginMethods"),
+        true,
+    ),
+];
+
+/// Adds the server functions in [`crate::api`] to the document, under the
+/// `server-functions` tag.
+///
+/// Dioxus serves each `#[server]` function as `POST /api/<name>`, taking its
+/// arguments as a JSON object keyed by parameter name. Where that object
+/// matches a request DTO the DTO is referenced as the body.
+pub struct ServerFunctions;
+
+impl ServerFunctions {
+    fn operation(doc: &ServerFnDoc) -> Operation {
+        let json = |schema: RefOr<Schema>| ContentBuilder::new().schema(Some(schema)).build();
+        let response = ResponseBuilder::new().description("Success");
+        let response = match doc.returns {
+            Returns::Nothing => response,
+            Returns::Text => response.content(
+                "application/json",
+                json(ObjectBuilder::new().schema_type(Type::String).into()),
+            ),
+            Returns::One(name) => {
+                response.content("application/json", json(Ref::from_schema_name(name).into()))
+            }
+            Returns::Many(name) => response.content(
+                "application/json",
+                json(Array::new(Ref::from_schema_name(name)).into()),
+            ),
+        };
+
+        let mut operation = OperationBuilder::new()
+            .operation_id(Some(doc.name))
+            .summary(Some(doc.summary))
+            .tag("server-functions")
+            .response("200", response.build());
+        if let Some(request) = doc.request {
+            operation = operation.request_body(Some(
+                RequestBodyBuilder::new()
+                    .content(
+                        "application/json",
+                        json(Ref::from_schema_name(request).into()),
+                    )
+                    .required(Some(utoipa::openapi::Required::True))
+                    .build(),
+            ));
+        }
+        if doc.authenticated {
+            operation = operation.securities(Some([SecurityRequirement::new(
+                "bearer_auth",
+                Vec::<String>::new(),
+            )]));
+        }
+        operation.build()
+    }
+}
+
+impl Modify for ServerFunctions {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        for doc in SERVER_FUNCTIONS {
+            openapi.paths.add_path_operation(
+                format!("/api/{}", doc.name),
+                vec![HttpMethod::Post],
+                Self::operation(doc),
+            );
+        }
+    }
+}
+
+/// Standard error responses, each with an [`AppError`] body.
+const ERROR_RESPONSES: [(&str, &str); 6] = [
+    ("400", "Malformed request body"),
+    ("401", "Not authenticated"),
+    ("403", "Forbidden"),
+    ("404", "Resource not found"),
+    ("422", "Validation error"),
+    ("500", "Internal server error"),
+];
+
+/// Attaches the standard [`AppError`] responses to every documented operation,
+/// so new paths only need to list their success responses:
+///
+/// - `500` on every operation
+/// - `400` and `422` when the operation takes a request body
+/// - `401` and `403` when it requires authentication
+/// - `404` when the path has parameters
+///
+/// Responses a path documents itself are kept as-is.
+pub struct StandardErrorResponses;
+
+impl StandardErrorResponses {
+    fn applicable(path: &str, operation: &Operation) -> Vec<&'static str> {
+        let mut codes = vec!["500"];
+        if operation.request_body.is_some() {
+            codes.extend(["400", "422"]);
+        }
+        if operation.security.as_ref().is_some_and(|s| !s.is_empty()) {
+            codes.extend(["401", "403"]);
+        }
+        if path.contains('{') {
+            codes.push("404");
+        }
+        codes
+    }
+}
+
+impl Modify for StandardErrorResponses {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        for (path, item) in openapi.paths.paths.iter_mut() {
+            let operations = [
+                &mut item.get,
+                &mut item.put,
+                &mut item.post,
+                &mut item.delete,
+                &mut item.patch,
+            ];
+            for operation in operations.into_iter().flatten() {
+                for code in Self::applicable(path, operation) {
+                    let description = ERROR_RESPONSES
+                        .iter()
+                        .find(|(c, _)| *c == code)
+                        .map(|(_, d)| *d)
+                        .unwrap_or_default();
+                    operation
+                        .responses
+                        .responses
+                        .entry(code.to_string())
+                        .or_insert_with(|| {
+                            ResponseBuilder::new()
+                                .description(description)
+                                .content(
+                                    "application/json",
+                                    ContentBuilder::new()
+                                        .schema(Some(Ref::from_schema_name("AppError")))
+                                        .build(),
+                                )
+                                .build()
+                                .into()
+                        });
+                }
+            }
+        }
+    }
+}
+
 /// Build an Axum router that serves the API docs at `/docs`
-/// and the REST API at `/api/*`.
+/// and the REST API at `/api/v1/*` (and its deprecated `/api/*` alias).
 ///
 /// Accepts a `PgPool` to construct `AppState` and apply it via `.with_state()`.
 pub fn api_router(pool: Pool<Postgres>) -> Router {
-    let state = AppState { pool };
+    let state = AppState::new(pool);
 
     Router::new()
         .merge(rest::rest_router())
+        .merge(crate::site::site_router())
         .route("/health", axum::routing::get(health::health_check))
         .route(
             "/auth/callback/{provider}",
             axum::routing::get(crate::auth::oauth_callback::oauth_callback),
         )
+        .method_not_allowed_fallback(crate::fallback::method_not_allowed)
         .with_state(state)
         .merge(Scalar::with_url("/docs", ApiDoc::openapi()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const APP_ERROR_REF: &str = "#/components/schemas/AppError";
+
+    fn spec() -> serde_json::Value {
+        serde_json::to_value(ApiDoc::openapi()).unwrap()
+    }
+
+    #[test]
+    fn every_operation_documents_app_error_responses() {
+        let spec = spec();
+        let paths = spec["paths"].as_object().unwrap();
+        assert!(!paths.is_empty());
+
+        for (path, item) in paths {
+            for (method, operation) in item.as_object().unwrap() {
+                let responses = operation["responses"].as_object().unwrap();
+                let errors: Vec<_> = responses
+                    .iter()
+                    .filter(|(code, _)| code.starts_with('4') || code.starts_with('5'))
+                    .collect();
+                assert!(
+                    responses.contains_key("500"),
+                    "{method} {path} is missing a 500 response"
+                );
+                for (code, response) in errors {
+                    assert_eq!(
+                        response["content"]["application/json"]["schema"]["$ref"], APP_ERROR_REF,
+                        "{method} {path} {code} should reference AppError"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn standard_errors_follow_operation_shape() {
+        let spec = spec();
+        let update_tier = &spec["paths"]["/api/v1/users/{user_id}/tier"]["put"]["responses"];
+        for code in ["400", "401", "403", "404", "422", "500"] {
+            assert!(update_tier.get(code).is_some(), "missing {code}");
+        }
+
+        let list_users = &spec["paths"]["/api/v1/users"]["get"]["responses"];
+        assert!(list_users.get("500").is_some());
+        // Documented by the path itself, for an unknown sort field or order
+        assert!(list_users.get("400").is_some());
+        assert!(list_users.get("401").is_none());
+    }
+
+    #[test]
+    fn server_functions_are_documented_with_their_schemas() {
+        let spec = spec();
+        let schemas = &spec["components"]["schemas"];
+        for name in [
+            "PremiumAnalytics",
+            "CategoryCount",
+            "UpdateProfileRequest",
+            "OAuthCallbackParams",
+        ] {
+            assert!(schemas.get(name).is_some(), "missing {name} schema");
+        }
+
+        let analytics = &spec["paths"]["/api/get_premium_analytics"]["post"];
+        assert_eq!(
+            analytics["responses"]["200"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/PremiumAnalytics"
+        );
+        assert!(analytics["responses"].get("401").is_some());
+
+        let update_profile = &spec["paths"]["/api/update_profile"]["post"];
+        assert_eq!(
+            update_profile["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/UpdateProfileRequest"
+        );
+    }
+
+    #[test]
+    fn every_server_function_schema_is_registered() {
+        let spec = spec();
+        let schemas = &spec["components"]["schemas"];
+        for doc in SERVER_FUNCTIONS {
+            let names = [
+                doc.request,
+                match doc.returns {
+                    Returns::One(name) | Returns::Many(name) => Some(name),
+                    Returns::Nothing | Returns::Text => None,
+                },
+            ];
+            for name in names.into_iter().flatten() {
+                assert!(
+                    schemas.get(name).is_some(),
+                    "{} references unregistered schema {name}",
+                    doc.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn oauth_flow_is_documented() {
+        let spec = spec();
+        assert!(spec["components"]["schemas"].get("OAuthProvider").is_some());
+
+        let authorize = &spec["paths"]["/api/v1/auth/oauth/{provider}/authorize"]["get"];
+        assert!(authorize["responses"].get("303").is_some());
+
+        let callback = &spec["paths"]["/auth/callback/{provider}"]["get"];
+        assert!(callback["responses"]["303"]["headers"]
+            .get("Location")
+            .is_some());
+        let params: Vec<_> = callback["parameters"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| p["name"].as_str().unwrap())
+            .collect();
+        for name in ["provider", "code", "state"] {
+            assert!(params.contains(&name), "callback is missing {name}");
+        }
+    }
+
+    #[test]
+    fn rest_paths_are_versioned() {
+        let spec = spec();
+        let server_fns: Vec<String> = SERVER_FUNCTIONS
+            .iter()
+            .map(|doc| format!("/api/{}", doc.name))
+            .collect();
+        for path in spec["paths"].as_object().unwrap().keys() {
+            let unversioned = path.starts_with("/api/")
+                && !path.starts_with(&format!("{}/", rest::API_PREFIX))
+                && !server_fns.contains(path);
+            assert!(!unversioned, "{path} is not under {}", rest::API_PREFIX);
+        }
+    }
+
+    #[test]
+    fn app_error_schemas_include_examples() {
+        let spec = spec();
+        let schemas = &spec["components"]["schemas"];
+        assert!(schemas["AppError"]["example"].is_object());
+        assert!(schemas["AppErrorKind"]["example"].is_string());
+    }
+}
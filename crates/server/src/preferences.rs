@@ -0,0 +1,98 @@
+use shared_types::{AppError, UserPreferences};
+use sqlx::{Pool, Postgres};
+
+use crate::error_convert::SqlxErrorExt;
+
+/// Load a user's preferences, falling back to the defaults when none are stored.
+pub async fn load_preferences(
+    pool: &Pool<Postgres>,
+    user_id: i64,
+) -> Result<UserPreferences, AppError> {
+    let row = sqlx::query!(
+        "SELECT email_notifications, push_notifications, weekly_digest, animations_enabled, compact_mode, theme_family, theme_dark FROM user_preferences WHERE user_id = $1",
+        user_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?;
+
+    Ok(row
+        .map(|r| UserPreferences {
+            email_notifications: r.email_notifications,
+            push_notifications: r.push_notifications,
+            weekly_digest: r.weekly_digest,
+            animations_enabled: r.animations_enabled,
+            compact_mode: r.compact_mode,
+            theme_family: r.theme_family,
+            theme_dark: r.theme_dark,
+        })
+        .unwrap_or_default())
+}
+
+/// Store a user's preferences, creating their row on first save.
+pub async fn save_preferences(
+    pool: &Pool<Postgres>,
+    user_id: i64,
+    prefs: &UserPreferences,
+) -> Result<UserPreferences, AppError> {
+    let row = sqlx::query!(
+        r#"INSERT INTO user_preferences (user_id, email_notifications, push_notifications, weekly_digest, animations_enabled, compact_mode, theme_family, theme_dark)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (user_id) DO UPDATE SET
+            email_notifications = EXCLUDED.email_notifications,
+            push_notifications = EXCLUDED.push_notifications,
+            weekly_digest = EXCLUDED.weekly_digest,
+            animations_enabled = EXCLUDED.animations_enabled,
+            compact_mode = EXCLUDED.compact_mode,
+            theme_family = EXCLUDED.theme_family,
+            theme_dark = EXCLUDED.theme_dark,
+            updated_at = NOW()
+        RETURNING email_notifications, push_notifications, weekly_digest, animations_enabled, compact_mode, theme_family, theme_dark"#,
+        user_id,
+        prefs.email_notifications,
+        prefs.push_notifications,
+        prefs.weekly_digest,
+        prefs.animations_enabled,
+        prefs.compact_mode,
+        prefs.theme_family,
+        prefs.theme_dark
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?;
+
+    Ok(UserPreferences {
+        email_notifications: row.email_notifications,
+        push_notifications: row.push_notifications,
+        weekly_digest: row.weekly_digest,
+        animations_enabled: row.animations_enabled,
+        compact_mode: row.compact_mode,
+        theme_family: row.theme_family,
+        theme_dark: row.theme_dark,
+    })
+}
+
+/// Store only a user's theme, leaving their other preferences as they are.
+pub async fn save_theme(
+    pool: &Pool<Postgres>,
+    user_id: i64,
+    theme_family: &str,
+    theme_dark: bool,
+) -> Result<(), AppError> {
+    sqlx::query!(
+        r#"INSERT INTO user_preferences (user_id, theme_family, theme_dark)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (user_id) DO UPDATE SET
+            theme_family = EXCLUDED.theme_family,
+            theme_dark = EXCLUDED.theme_dark,
+            updated_at = NOW()"#,
+        user_id,
+        theme_family,
+        theme_dark
+    )
+    .execute(pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?;
+
+    Ok(())
+}
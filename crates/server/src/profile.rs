@@ -0,0 +1,27 @@
+use shared_types::{AppError, AuthUser, UpdateDisplayNameRequest};
+use sqlx::{Pool, Postgres};
+
+use crate::db::UserRecord;
+use crate::error_convert::{SqlxErrorExt, ValidateRequest};
+
+/// Change a user's display name without touching their email.
+pub async fn update_display_name(
+    pool: &Pool<Postgres>,
+    user_id: i64,
+    request: UpdateDisplayNameRequest,
+) -> Result<AuthUser, AppError> {
+    let request = request.validate_and_normalize()?;
+
+    let user = sqlx::query_as!(
+        UserRecord,
+        "UPDATE users SET display_name = $2, updated_at = NOW() WHERE id = $1 RETURNING id, username, display_name, email, role, tier, avatar_url, thumbnail_url, created_at, updated_at",
+        user_id,
+        request.display_name
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?
+    .ok_or_else(|| AppError::not_found("User not found"))?;
+
+    Ok(user.into())
+}
@@ -0,0 +1,124 @@
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+
+/// Header carrying the request id, shared by client and server logs.
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest client-supplied request id the server will adopt.
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Whether a client-supplied request id is safe to put in logs and spans:
+/// non-empty, at most 128 characters, and limited to ASCII letters, digits,
+/// `-`, `_`, `.` and `:` (UUIDs and most tracing id formats fit).
+pub fn is_valid_request_id(value: &HeaderValue) -> bool {
+    let bytes = value.as_bytes();
+    !bytes.is_empty()
+        && bytes.len() <= MAX_REQUEST_ID_LEN
+        && bytes
+            .iter()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b':'))
+}
+
+/// Middleware that keeps a valid client-supplied `x-request-id` and drops
+/// anything else.
+///
+/// `SetRequestIdLayer` only generates an id when the header is missing, so
+/// the client's id flows through to the telemetry span and the response,
+/// letting client and server logs be joined on it. Install this outside
+/// `SetRequestIdLayer` so malformed ids are replaced with a fresh UUID.
+pub async fn sanitize_request_id(mut req: Request, next: Next) -> Response {
+    let invalid = req
+        .headers()
+        .get(&REQUEST_ID_HEADER)
+        .is_some_and(|v| !is_valid_request_id(v));
+    if invalid {
+        req.headers_mut().remove(&REQUEST_ID_HEADER);
+    }
+    next.run(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get, Router};
+    use tower::ServiceExt;
+    use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
+
+    /// Same layer order as the app router; the handler echoes the request id
+    /// the telemetry layer would record on its span.
+    fn app() -> Router {
+        Router::new()
+            .route(
+                "/",
+                get(|req: Request| async move {
+                    crate::telemetry::span_attributes(&req)
+                        .into_iter()
+                        .find(|kv| kv.key.as_str() == "http.request_id")
+                        .map(|kv| kv.value.as_str().into_owned())
+                        .unwrap_or_default()
+                }),
+            )
+            .layer(PropagateRequestIdLayer::new(REQUEST_ID_HEADER))
+            .layer(SetRequestIdLayer::new(REQUEST_ID_HEADER, MakeRequestUuid))
+            .layer(axum::middleware::from_fn(sanitize_request_id))
+    }
+
+    async fn send(request_id: Option<&str>) -> (Option<String>, String) {
+        let mut builder = Request::builder().uri("/");
+        if let Some(id) = request_id {
+            builder = builder.header(REQUEST_ID_HEADER, id);
+        }
+        let response = app()
+            .oneshot(builder.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let header = response
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .map(|v| v.to_str().unwrap().to_string());
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (header, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn client_request_id_reaches_span_and_response() {
+        let client_id = "web-3f2a9c1e-7b4d-4e8a-9f10-2c6d8e0b5a71";
+        let (header, span_id) = send(Some(client_id)).await;
+        assert_eq!(span_id, client_id);
+        assert_eq!(header.as_deref(), Some(client_id));
+    }
+
+    #[tokio::test]
+    async fn missing_request_id_is_generated() {
+        let (header, span_id) = send(None).await;
+        assert!(uuid::Uuid::parse_str(&span_id).is_ok());
+        assert_eq!(header, Some(span_id));
+    }
+
+    #[tokio::test]
+    async fn invalid_request_id_is_replaced() {
+        let (header, span_id) = send(Some("bad id\twith spaces")).await;
+        assert!(uuid::Uuid::parse_str(&span_id).is_ok());
+        assert_eq!(header, Some(span_id));
+    }
+
+    #[test]
+    fn request_id_validation() {
+        assert!(is_valid_request_id(&HeaderValue::from_static(
+            "3f2a9c1e-7b4d-4e8a-9f10-2c6d8e0b5a71"
+        )));
+        assert!(is_valid_request_id(&HeaderValue::from_static(
+            "trace:01.ab_c"
+        )));
+        assert!(!is_valid_request_id(&HeaderValue::from_static("")));
+        assert!(!is_valid_request_id(&HeaderValue::from_static("a b")));
+        let long = "a".repeat(MAX_REQUEST_ID_LEN + 1);
+        assert!(!is_valid_request_id(&HeaderValue::from_str(&long).unwrap()));
+    }
+}
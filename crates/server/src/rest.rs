@@ -1,125 +1,210 @@
 use axum::{
-    extract::{Multipart, Path, State},
-    http::StatusCode,
-    routing::{get, post, put},
+    body::Body,
+    extract::{DefaultBodyLimit, Multipart, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Redirect, Response},
+    routing::{any, get, post, put},
     Json, Router,
 };
 use shared_types::{
-    AppError, AuthResponse, AuthUser, CreateProductRequest, CreateUserRequest, DashboardStats,
-    LoginRequest, Product, RegisterRequest, UpdateProductRequest, UpdateTierRequest,
-    UpdateUserRequest, User, UserTier,
+    AccountDeletion, AnalyticsQuery, AppError, AuditAction, AuditLogQuery, AuditPage, AuthEvent,
+    AuthEventQuery, AuthEventType, AuthResponse, AuthUser, BatchGetUsersRequest,
+    CreateInviteRequest, CreateProductRequest, CreateUserRequest, Cursor, DashboardStats,
+    DashboardStatsQuery, Invite, ListParams, LoginRequest, Money, OAuthProvider, OAuthProviderInfo,
+    Paging, PremiumAnalytics, Product, ProductHistory, ProductList, ProductListQuery,
+    ProductStatus, RegisterRequest, Role, SortOrder, UpdateDisplayNameRequest,
+    UpdateProductRequest, UpdateTierRequest, UpdateUserRequest, User, UserPreferences,
+    UserSortField, VerifyEmailRequest,
 };
 use sqlx::{Pool, Postgres};
+use std::convert::Infallible;
+use tower_http::limit::RequestBodyLimitLayer;
 
-use crate::auth::{extractors::AuthRequired, jwt, password as pw};
-use crate::db::AppState;
+use crate::audit;
+use crate::auth::deletion;
+use crate::auth::events::{self as auth_events, ClientInfo};
+use crate::auth::extractors::{AuthOptional, AuthRequired, PremiumRequired};
+use crate::auth::invites::{self, RegistrationMode};
+use crate::auth::{jwt, oauth, password as pw, verification};
+use crate::db::{AppState, ProductRecord, UserRecord};
+use crate::deprecation::{deprecation_headers, Deprecation};
 use crate::error_convert::{SqlxErrorExt, ValidateRequest};
+use crate::etag;
+use crate::export;
+use crate::extract::{AppJson, AppQuery};
+use crate::telemetry::traced_query;
+use crate::users;
+
+/// Prefix of the current REST API version.
+///
+/// Handlers document their paths relative to it (`context_path`), so the
+/// OpenAPI spec and [`rest_router`] can't drift apart. A breaking change gets
+/// a new prefix; the previous one is kept as an alias for a release.
+pub const API_PREFIX: &str = "/api/v1";
+
+/// Unversioned prefix the API was first served under, kept as a deprecated
+/// alias of [`API_PREFIX`].
+pub const LEGACY_API_PREFIX: &str = "/api";
 
 // ── Users ──────────────────────────────────────────────
 
 #[utoipa::path(
     get,
-    path = "/api/users",
+    context_path = API_PREFIX,
+    path = "/users",
+    params(
+        ("page" = Option<i64>, Query, description = "Page to return, starting at 1 (default 1)"),
+        ("page_size" = Option<i64>, Query, description = "Users per page (at most 100). Without it every user is returned"),
+        ("sort_by" = Option<UserSortField>, Query, description = "Field to sort by (default `id`)"),
+        ("order" = Option<SortOrder>, Query, description = "`asc` (default) or `desc`"),
+        ("search" = Option<String>, Query, description = "Only users whose username or display name contains this, ignoring case")
+    ),
     responses(
         (status = 200, description = "List of users", body = Vec<User>),
+        (status = 400, description = "Unknown sort field or order", body = AppError),
         (status = 500, description = "Internal server error", body = AppError)
     ),
     tag = "users"
 )]
 #[tracing::instrument(skip(pool))]
-pub async fn list_users(State(pool): State<Pool<Postgres>>) -> Result<Json<Vec<User>>, AppError> {
-    let users = sqlx::query_as!(
-        User,
-        "SELECT id, username, display_name, role, tier FROM users"
-    )
-    .fetch_all(&pool)
-    .await
-    .map_err(SqlxErrorExt::into_app_error)?;
+pub async fn list_users(
+    State(pool): State<Pool<Postgres>>,
+    AppQuery(params): AppQuery<ListParams<UserSortField>>,
+) -> Result<Json<Vec<User>>, AppError> {
+    let users = crate::users::list(&pool, &params).await?;
     Ok(Json(users))
 }
 
 #[utoipa::path(
     get,
-    path = "/api/users/{user_id}",
+    context_path = API_PREFIX,
+    path = "/users/export.csv",
+    responses(
+        (status = 200, description = "All users as CSV, in id order", content_type = "text/csv", body = String),
+        (status = 401, description = "Not authenticated", body = AppError),
+        (status = 403, description = "Forbidden — admin role required", body = AppError)
+    ),
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
+#[tracing::instrument(skip(pool, auth))]
+pub async fn export_users(
+    State(pool): State<Pool<Postgres>>,
+    auth: AuthRequired,
+) -> Result<Response, AppError> {
+    if !Role::from_str_or_default(&auth.0.role).is_admin() {
+        return Err(AppError::forbidden("Admin role required to export users"));
+    }
+    Ok(csv_attachment("users.csv", export::users_csv(pool)))
+}
+
+#[utoipa::path(
+    get,
+    context_path = API_PREFIX,
+    path = "/users/{user_id}",
     params(("user_id" = i64, Path, description = "User ID")),
     responses(
         (status = 200, description = "User found", body = User),
+        (status = 304, description = "Not modified — `If-None-Match` matches the current ETag"),
         (status = 404, description = "User not found", body = AppError),
         (status = 500, description = "Internal server error", body = AppError)
     ),
     tag = "users"
 )]
-#[tracing::instrument(skip(pool))]
+#[tracing::instrument(skip(pool, headers))]
 pub async fn get_user(
     State(pool): State<Pool<Postgres>>,
     Path(user_id): Path<i64>,
-) -> Result<Json<User>, AppError> {
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
     let user = sqlx::query_as!(
-        User,
-        "SELECT id, username, display_name, role, tier FROM users WHERE id = $1",
+        UserRecord,
+        "SELECT id, username, display_name, email, role, tier, avatar_url, thumbnail_url, created_at, updated_at FROM users WHERE id = $1 AND deleted_at IS NULL",
         user_id
     )
     .fetch_optional(&pool)
     .await
     .map_err(SqlxErrorExt::into_app_error)?
     .ok_or_else(|| AppError::not_found(format!("User with id {} not found", user_id)))?;
-    Ok(Json(user))
+    etag::conditional_json(&headers, &User::from(user))
+}
+
+#[utoipa::path(
+    post,
+    context_path = API_PREFIX,
+    path = "/users/batch-get",
+    request_body = BatchGetUsersRequest,
+    responses(
+        (status = 200, description = "Matching users in request order; unknown ids are omitted", body = Vec<User>)
+    ),
+    tag = "users"
+)]
+#[tracing::instrument(skip(pool))]
+pub async fn batch_get_users(
+    State(pool): State<Pool<Postgres>>,
+    AppJson(payload): AppJson<BatchGetUsersRequest>,
+) -> Result<Json<Vec<User>>, AppError> {
+    Ok(Json(users::get_many(&pool, payload).await?))
 }
 
 #[utoipa::path(
     post,
-    path = "/api/users",
+    context_path = API_PREFIX,
+    path = "/users",
     request_body = CreateUserRequest,
     responses(
         (status = 201, description = "User created", body = User),
+        (status = 400, description = "Malformed request body", body = AppError),
         (status = 422, description = "Validation error", body = AppError),
         (status = 500, description = "Internal server error", body = AppError)
     ),
     tag = "users"
 )]
-#[tracing::instrument(skip(pool))]
+#[tracing::instrument(skip(pool, payload))]
 pub async fn create_user(
     State(pool): State<Pool<Postgres>>,
-    Json(payload): Json<CreateUserRequest>,
+    AppJson(payload): AppJson<CreateUserRequest>,
 ) -> Result<(StatusCode, Json<User>), AppError> {
-    payload.validate_request()?;
+    let payload = payload.validate_and_normalize()?;
 
     let user = sqlx::query_as!(
-        User,
-        "INSERT INTO users (username, display_name) VALUES ($1, $2) RETURNING id, username, display_name, role, tier",
+        UserRecord,
+        "INSERT INTO users (username, display_name) VALUES ($1, $2) RETURNING id, username, display_name, email, role, tier, avatar_url, thumbnail_url, created_at, updated_at",
         payload.username,
         payload.display_name
     )
     .fetch_one(&pool)
     .await
     .map_err(SqlxErrorExt::into_app_error)?;
-    Ok((StatusCode::CREATED, Json(user)))
+    Ok((StatusCode::CREATED, Json(user.into())))
 }
 
 #[utoipa::path(
     put,
-    path = "/api/users/{user_id}",
+    context_path = API_PREFIX,
+    path = "/users/{user_id}",
     params(("user_id" = i64, Path, description = "User ID")),
     request_body = UpdateUserRequest,
     responses(
         (status = 200, description = "User updated", body = User),
+        (status = 400, description = "Malformed request body", body = AppError),
         (status = 404, description = "User not found", body = AppError),
         (status = 422, description = "Validation error", body = AppError),
         (status = 500, description = "Internal server error", body = AppError)
     ),
     tag = "users"
 )]
-#[tracing::instrument(skip(pool))]
+#[tracing::instrument(skip(pool, payload))]
 pub async fn update_user(
     State(pool): State<Pool<Postgres>>,
     Path(user_id): Path<i64>,
-    Json(payload): Json<UpdateUserRequest>,
+    AppJson(payload): AppJson<UpdateUserRequest>,
 ) -> Result<Json<User>, AppError> {
     payload.validate_request()?;
 
     let user = sqlx::query_as!(
-        User,
-        "UPDATE users SET username = $2, display_name = $3 WHERE id = $1 RETURNING id, username, display_name, role, tier",
+        UserRecord,
+        "UPDATE users SET username = $2, display_name = $3, updated_at = NOW() WHERE id = $1 AND deleted_at IS NULL RETURNING id, username, display_name, email, role, tier, avatar_url, thumbnail_url, created_at, updated_at",
         user_id,
         payload.username,
         payload.display_name
@@ -128,147 +213,433 @@ pub async fn update_user(
     .await
     .map_err(SqlxErrorExt::into_app_error)?
     .ok_or_else(|| AppError::not_found(format!("User with id {} not found", user_id)))?;
-    Ok(Json(user))
+    Ok(Json(user.into()))
 }
 
 #[utoipa::path(
     delete,
-    path = "/api/users/{user_id}",
+    context_path = API_PREFIX,
+    path = "/users/{user_id}",
     params(("user_id" = i64, Path, description = "User ID")),
     responses(
-        (status = 204, description = "User deleted"),
+        (status = 204, description = "User soft-deleted and signed out; restore it with `POST /users/{user_id}/restore` until it is purged"),
         (status = 404, description = "User not found", body = AppError),
         (status = 500, description = "Internal server error", body = AppError)
     ),
     tag = "users"
 )]
-#[tracing::instrument(skip(pool))]
+#[tracing::instrument(skip(pool, auth))]
 pub async fn delete_user(
     State(pool): State<Pool<Postgres>>,
+    auth: AuthOptional,
     Path(user_id): Path<i64>,
 ) -> Result<StatusCode, AppError> {
-    let result = sqlx::query!("DELETE FROM users WHERE id = $1", user_id)
-        .execute(&pool)
-        .await
-        .map_err(SqlxErrorExt::into_app_error)?;
-    if result.rows_affected() > 0 {
-        Ok(StatusCode::NO_CONTENT)
-    } else {
-        Err(AppError::not_found(format!(
-            "User with id {} not found",
-            user_id
-        )))
-    }
+    let username = users::soft_delete(&pool, user_id)
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("User with id {} not found", user_id)))?;
+
+    audit::record(
+        &pool,
+        auth.0.map(|claims| claims.sub),
+        AuditAction::UserDelete,
+        &audit::user_target(user_id),
+        serde_json::json!({ "username": username }),
+    )
+    .await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    context_path = API_PREFIX,
+    path = "/users/{user_id}/restore",
+    params(("user_id" = i64, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "User restored", body = User),
+        (status = 404, description = "No deleted user with this ID", body = AppError),
+        (status = 500, description = "Internal server error", body = AppError)
+    ),
+    tag = "users"
+)]
+#[tracing::instrument(skip(pool))]
+pub async fn restore_user(
+    State(pool): State<Pool<Postgres>>,
+    Path(user_id): Path<i64>,
+) -> Result<Json<User>, AppError> {
+    let user = sqlx::query_as!(
+        UserRecord,
+        "UPDATE users SET deleted_at = NULL, updated_at = NOW() WHERE id = $1 AND deleted_at IS NOT NULL RETURNING id, username, display_name, email, role, tier, avatar_url, thumbnail_url, created_at, updated_at",
+        user_id
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?
+    .ok_or_else(|| AppError::not_found(format!("Deleted user with id {} not found", user_id)))?;
+    Ok(Json(user.into()))
 }
 
 // ── Products ───────────────────────────────────────────
 
 #[utoipa::path(
     get,
-    path = "/api/products",
+    context_path = API_PREFIX,
+    path = "/products",
+    params(ProductListQuery),
     responses(
-        (status = 200, description = "List of products", body = Vec<Product>),
+        (status = 200, description = "Products, newest first; `is_favorited` is set when authenticated. A plain array without `limit`, otherwise a page with `next_cursor`", body = ProductList),
+        (status = 400, description = "Conflicting paging parameters", body = AppError),
+        (status = 401, description = "Not authenticated (include_deleted only)", body = AppError),
+        (status = 403, description = "Forbidden — admin role required for include_deleted", body = AppError),
         (status = 500, description = "Internal server error", body = AppError)
     ),
     tag = "products"
 )]
-#[tracing::instrument(skip(pool))]
+#[tracing::instrument(skip(pool, auth))]
 pub async fn list_products(
     State(pool): State<Pool<Postgres>>,
-) -> Result<Json<Vec<Product>>, AppError> {
-    let rows = sqlx::query!(
-        "SELECT id, name, description, price, category, status, created_at FROM products ORDER BY id DESC"
-    )
-    .fetch_all(&pool)
-    .await
-    .map_err(SqlxErrorExt::into_app_error)?;
+    auth: AuthOptional,
+    Query(query): Query<ProductListQuery>,
+) -> Result<Json<ProductList>, AppError> {
+    let paging = query.paging()?;
+    if query.include_deleted {
+        let claims = auth
+            .0
+            .as_ref()
+            .ok_or_else(|| AppError::unauthorized("Authentication required"))?;
+        if !Role::from_str_or_default(&claims.role).is_admin() {
+            return Err(AppError::forbidden(
+                "Admin role required to view deleted products",
+            ));
+        }
+    }
+
+    let user_id = auth.0.map(|claims| claims.sub);
+    // Pages fetch one extra row to tell whether another page follows.
+    let rows = match paging {
+        Paging::After { limit, id } => {
+            // Nearest newer rows first; the page is flipped to newest first
+            // below, once the extra row is dropped.
+            traced_query(
+                "products.list",
+                sqlx::query_as!(
+                    ProductRecord,
+                    r#"SELECT p.id, p.name, p.description, p.price, p.category, p.status, p.created_at, p.updated_at, p.image_url, p.deleted_at, (f.user_id IS NOT NULL) AS "is_favorited!" FROM products p LEFT JOIN product_favorites f ON f.product_id = p.id AND f.user_id = $1 WHERE ($2 OR p.deleted_at IS NULL) AND p.id > $3 ORDER BY p.id ASC LIMIT $4"#,
+                    user_id,
+                    query.include_deleted,
+                    id,
+                    limit + 1
+                )
+                .fetch_all(&pool),
+            )
+            .await
+            .map_err(SqlxErrorExt::into_app_error)?
+        }
+        _ => {
+            let (limit, offset, before_id) = match paging {
+                Paging::Offset { limit, offset } => (Some(limit + 1), offset, None),
+                Paging::Before { limit, id } => (Some(limit + 1), 0, Some(id)),
+                _ => (None, 0, None),
+            };
+            traced_query(
+                "products.list",
+                sqlx::query_as!(
+                    ProductRecord,
+                    r#"SELECT p.id, p.name, p.description, p.price, p.category, p.status, p.created_at, p.updated_at, p.image_url, p.deleted_at, (f.user_id IS NOT NULL) AS "is_favorited!" FROM products p LEFT JOIN product_favorites f ON f.product_id = p.id AND f.user_id = $1 WHERE ($2 OR p.deleted_at IS NULL) AND ($3::BIGINT IS NULL OR p.id < $3) ORDER BY p.id DESC LIMIT $4 OFFSET $5"#,
+                    user_id,
+                    query.include_deleted,
+                    before_id,
+                    limit,
+                    offset
+                )
+                .fetch_all(&pool),
+            )
+            .await
+            .map_err(SqlxErrorExt::into_app_error)?
+        }
+    };
 
+    let signed_in = user_id.is_some();
     let products: Vec<Product> = rows
         .into_iter()
-        .map(|r| Product {
-            id: r.id,
-            name: r.name,
-            description: r.description,
-            price: r.price,
-            category: r.category,
-            status: r.status,
-            created_at: r.created_at.to_string(),
-        })
+        .map(|r| r.into_product(signed_in))
         .collect();
-    Ok(Json(products))
+    Ok(Json(match paging {
+        Paging::All => ProductList::All(products),
+        Paging::After { limit, .. } => {
+            let mut page = Cursor::from_overfetch(products, limit, true, |p| p.id);
+            page.items.reverse();
+            ProductList::Page(page)
+        }
+        Paging::Offset { limit, .. } | Paging::Before { limit, .. } => {
+            ProductList::Page(Cursor::from_overfetch(products, limit, false, |p| p.id))
+        }
+    }))
+}
+
+#[utoipa::path(
+    get,
+    context_path = API_PREFIX,
+    path = "/products/{product_id}",
+    params(("product_id" = i64, Path, description = "Product ID")),
+    responses(
+        (status = 200, description = "Product found", body = Product),
+        (status = 304, description = "Not modified — `If-None-Match` matches the current ETag"),
+        (status = 404, description = "Product not found", body = AppError),
+        (status = 500, description = "Internal server error", body = AppError)
+    ),
+    tag = "products"
+)]
+#[tracing::instrument(skip(pool, auth, headers))]
+pub async fn get_product(
+    State(pool): State<Pool<Postgres>>,
+    auth: AuthOptional,
+    Path(product_id): Path<i64>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let user_id = auth.0.map(|claims| claims.sub);
+    let row = sqlx::query!(
+        r#"SELECT p.id, p.name, p.description, p.price, p.category, p.status, p.created_at, p.updated_at, p.image_url, (f.user_id IS NOT NULL) AS "is_favorited!" FROM products p LEFT JOIN product_favorites f ON f.product_id = p.id AND f.user_id = $2 WHERE p.id = $1 AND p.deleted_at IS NULL"#,
+        product_id,
+        user_id
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?
+    .ok_or_else(|| AppError::not_found(format!("Product with id {} not found", product_id)))?;
+
+    let product = Product {
+        id: row.id,
+        name: row.name,
+        description: row.description,
+        price: Money::from_f64(row.price),
+        category: row.category,
+        status: ProductStatus::from_str_or_default(&row.status),
+        created_at: row.created_at.to_string(),
+        updated_at: row.updated_at.to_string(),
+        image_url: row.image_url,
+        is_favorited: user_id.map(|_| row.is_favorited),
+        deleted_at: None,
+    };
+    etag::conditional_json(&headers, &product)
+}
+
+#[utoipa::path(
+    put,
+    context_path = API_PREFIX,
+    path = "/products/{product_id}/favorite",
+    params(("product_id" = i64, Path, description = "Product ID")),
+    responses(
+        (status = 204, description = "Product favorited"),
+        (status = 401, description = "Not authenticated", body = AppError),
+        (status = 404, description = "Product not found", body = AppError),
+        (status = 500, description = "Internal server error", body = AppError)
+    ),
+    tag = "products",
+    security(("bearer_auth" = []))
+)]
+#[tracing::instrument(skip(pool, auth))]
+pub async fn favorite_product(
+    State(pool): State<Pool<Postgres>>,
+    auth: AuthRequired,
+    Path(product_id): Path<i64>,
+) -> Result<StatusCode, AppError> {
+    let exists = sqlx::query_scalar!(
+        r#"SELECT EXISTS(SELECT 1 FROM products WHERE id = $1 AND deleted_at IS NULL) AS "exists!""#,
+        product_id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?;
+    if !exists {
+        return Err(AppError::not_found(format!(
+            "Product with id {} not found",
+            product_id
+        )));
+    }
+
+    sqlx::query!(
+        "INSERT INTO product_favorites (user_id, product_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+        auth.0.sub,
+        product_id
+    )
+    .execute(&pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    delete,
+    context_path = API_PREFIX,
+    path = "/products/{product_id}/favorite",
+    params(("product_id" = i64, Path, description = "Product ID")),
+    responses(
+        (status = 204, description = "Product removed from favorites"),
+        (status = 401, description = "Not authenticated", body = AppError),
+        (status = 500, description = "Internal server error", body = AppError)
+    ),
+    tag = "products",
+    security(("bearer_auth" = []))
+)]
+#[tracing::instrument(skip(pool, auth))]
+pub async fn unfavorite_product(
+    State(pool): State<Pool<Postgres>>,
+    auth: AuthRequired,
+    Path(product_id): Path<i64>,
+) -> Result<StatusCode, AppError> {
+    sqlx::query!(
+        "DELETE FROM product_favorites WHERE user_id = $1 AND product_id = $2",
+        auth.0.sub,
+        product_id
+    )
+    .execute(&pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    context_path = API_PREFIX,
+    path = "/products/export.csv",
+    params(ProductListQuery),
+    responses(
+        (status = 200, description = "Products as CSV, newest first", content_type = "text/csv", body = String),
+        (status = 401, description = "Not authenticated", body = AppError),
+        (status = 403, description = "Forbidden — admin role required", body = AppError)
+    ),
+    tag = "products",
+    security(("bearer_auth" = []))
+)]
+#[tracing::instrument(skip(pool, auth))]
+pub async fn export_products(
+    State(pool): State<Pool<Postgres>>,
+    auth: AuthRequired,
+    Query(query): Query<ProductListQuery>,
+) -> Result<Response, AppError> {
+    if !Role::from_str_or_default(&auth.0.role).is_admin() {
+        return Err(AppError::forbidden(
+            "Admin role required to export products",
+        ));
+    }
+    Ok(csv_attachment(
+        "products.csv",
+        export::products_csv(pool, query.include_deleted),
+    ))
+}
+
+/// Stream CSV chunks as a file download named `filename`.
+fn csv_attachment(
+    filename: &str,
+    chunks: impl futures_util::Stream<Item = Result<String, std::io::Error>> + Send + 'static,
+) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{filename}\""),
+            ),
+        ],
+        Body::from_stream(chunks),
+    )
+        .into_response()
 }
 
 #[utoipa::path(
     post,
-    path = "/api/products",
+    context_path = API_PREFIX,
+    path = "/products",
     request_body = CreateProductRequest,
     responses(
         (status = 201, description = "Product created", body = Product),
+        (status = 400, description = "Malformed request body", body = AppError),
         (status = 422, description = "Validation error", body = AppError),
         (status = 500, description = "Internal server error", body = AppError)
     ),
     tag = "products"
 )]
-#[tracing::instrument(skip(pool))]
+#[tracing::instrument(skip(pool, auth))]
 pub async fn create_product(
     State(pool): State<Pool<Postgres>>,
-    Json(payload): Json<CreateProductRequest>,
+    auth: AuthOptional,
+    AppJson(payload): AppJson<CreateProductRequest>,
 ) -> Result<(StatusCode, Json<Product>), AppError> {
     payload.validate_request()?;
 
+    let actor = auth.0.map(|claims| claims.sub);
     let row = sqlx::query!(
-        "INSERT INTO products (name, description, price, category, status) VALUES ($1, $2, $3, $4, $5) RETURNING id, name, description, price, category, status, created_at",
+        "INSERT INTO products (name, description, price, category, status, owner_id) VALUES ($1, $2, $3, $4, $5, $6) RETURNING id, name, description, price, category, status, created_at, updated_at, image_url",
         payload.name,
         payload.description,
-        payload.price,
+        payload.price.to_f64(),
         payload.category,
-        payload.status
+        payload.product_status().as_str(),
+        actor
     )
     .fetch_one(&pool)
     .await
     .map_err(SqlxErrorExt::into_app_error)?;
 
+    audit::record(
+        &pool,
+        actor,
+        AuditAction::ProductCreate,
+        &audit::product_target(row.id),
+        serde_json::json!({ "name": row.name }),
+    )
+    .await;
+
     let product = Product {
         id: row.id,
         name: row.name,
         description: row.description,
-        price: row.price,
+        price: Money::from_f64(row.price),
         category: row.category,
-        status: row.status,
+        status: ProductStatus::from_str_or_default(&row.status),
         created_at: row.created_at.to_string(),
+        updated_at: row.updated_at.to_string(),
+        image_url: row.image_url,
+        is_favorited: None,
+        deleted_at: None,
     };
     Ok((StatusCode::CREATED, Json(product)))
 }
 
 #[utoipa::path(
     put,
-    path = "/api/products/{product_id}",
+    context_path = API_PREFIX,
+    path = "/products/{product_id}",
     params(("product_id" = i64, Path, description = "Product ID")),
     request_body = UpdateProductRequest,
     responses(
         (status = 200, description = "Product updated", body = Product),
+        (status = 400, description = "Malformed request body", body = AppError),
         (status = 404, description = "Product not found", body = AppError),
         (status = 422, description = "Validation error", body = AppError),
         (status = 500, description = "Internal server error", body = AppError)
     ),
     tag = "products"
 )]
-#[tracing::instrument(skip(pool))]
+#[tracing::instrument(skip(pool, auth))]
 pub async fn update_product(
     State(pool): State<Pool<Postgres>>,
+    auth: AuthOptional,
     Path(product_id): Path<i64>,
-    Json(payload): Json<UpdateProductRequest>,
+    AppJson(payload): AppJson<UpdateProductRequest>,
 ) -> Result<Json<Product>, AppError> {
     payload.validate_request()?;
 
     let row = sqlx::query!(
-        "UPDATE products SET name = $2, description = $3, price = $4, category = $5, status = $6 WHERE id = $1 RETURNING id, name, description, price, category, status, created_at",
+        "UPDATE products SET name = $2, description = $3, price = $4, category = $5, status = $6, updated_at = NOW() WHERE id = $1 AND deleted_at IS NULL RETURNING id, name, description, price, category, status, created_at, updated_at, image_url",
         product_id,
         payload.name,
         payload.description,
-        payload.price,
+        payload.price.to_f64(),
         payload.category,
-        payload.status
+        payload.product_status().as_str()
     )
     .fetch_optional(&pool)
     .await
@@ -277,21 +648,35 @@ pub async fn update_product(
         AppError::not_found(format!("Product with id {} not found", product_id))
     })?;
 
+    audit::record(
+        &pool,
+        auth.0.map(|claims| claims.sub),
+        AuditAction::ProductUpdate,
+        &audit::product_target(product_id),
+        serde_json::json!({ "name": row.name }),
+    )
+    .await;
+
     let product = Product {
         id: row.id,
         name: row.name,
         description: row.description,
-        price: row.price,
+        price: Money::from_f64(row.price),
         category: row.category,
-        status: row.status,
+        status: ProductStatus::from_str_or_default(&row.status),
         created_at: row.created_at.to_string(),
+        updated_at: row.updated_at.to_string(),
+        image_url: row.image_url,
+        is_favorited: None,
+        deleted_at: None,
     };
     Ok(Json(product))
 }
 
 #[utoipa::path(
     delete,
-    path = "/api/products/{product_id}",
+    context_path = API_PREFIX,
+    path = "/products/{product_id}",
     params(("product_id" = i64, Path, description = "Product ID")),
     responses(
         (status = 204, description = "Product deleted"),
@@ -300,116 +685,272 @@ pub async fn update_product(
     ),
     tag = "products"
 )]
-#[tracing::instrument(skip(pool))]
+#[tracing::instrument(skip(pool, auth))]
 pub async fn delete_product(
     State(pool): State<Pool<Postgres>>,
+    auth: AuthOptional,
     Path(product_id): Path<i64>,
 ) -> Result<StatusCode, AppError> {
-    let result = sqlx::query!("DELETE FROM products WHERE id = $1", product_id)
-        .execute(&pool)
-        .await
-        .map_err(SqlxErrorExt::into_app_error)?;
-    if result.rows_affected() > 0 {
-        Ok(StatusCode::NO_CONTENT)
-    } else {
-        Err(AppError::not_found(format!(
-            "Product with id {} not found",
-            product_id
-        )))
-    }
-}
+    let name = sqlx::query_scalar!(
+        "UPDATE products SET deleted_at = NOW(), updated_at = NOW() WHERE id = $1 AND deleted_at IS NULL RETURNING name",
+        product_id
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?
+    .ok_or_else(|| AppError::not_found(format!("Product with id {} not found", product_id)))?;
 
-// ── Dashboard ──────────────────────────────────────────
+    audit::record(
+        &pool,
+        auth.0.map(|claims| claims.sub),
+        AuditAction::ProductDelete,
+        &audit::product_target(product_id),
+        serde_json::json!({ "name": name }),
+    )
+    .await;
+    Ok(StatusCode::NO_CONTENT)
+}
 
 #[utoipa::path(
-    get,
-    path = "/api/dashboard/stats",
+    post,
+    context_path = API_PREFIX,
+    path = "/products/{product_id}/restore",
+    params(("product_id" = i64, Path, description = "Product ID")),
     responses(
-        (status = 200, description = "Dashboard statistics", body = DashboardStats),
+        (status = 200, description = "Product restored", body = Product),
+        (status = 404, description = "No deleted product with this ID", body = AppError),
         (status = 500, description = "Internal server error", body = AppError)
     ),
-    tag = "dashboard"
+    tag = "products"
 )]
 #[tracing::instrument(skip(pool))]
-pub async fn get_dashboard_stats(
+pub async fn restore_product(
     State(pool): State<Pool<Postgres>>,
-) -> Result<Json<DashboardStats>, AppError> {
-    let total_users = sqlx::query_scalar!("SELECT COUNT(*) FROM users")
-        .fetch_one(&pool)
-        .await
-        .map_err(SqlxErrorExt::into_app_error)?
-        .unwrap_or(0);
-
-    let total_products = sqlx::query_scalar!("SELECT COUNT(*) FROM products")
-        .fetch_one(&pool)
-        .await
-        .map_err(SqlxErrorExt::into_app_error)?
-        .unwrap_or(0);
-
-    let active_products =
-        sqlx::query_scalar!("SELECT COUNT(*) FROM products WHERE status = 'active'")
-            .fetch_one(&pool)
-            .await
-            .map_err(SqlxErrorExt::into_app_error)?
-            .unwrap_or(0);
-
-    let recent_users = sqlx::query_as!(
-        User,
-        "SELECT id, username, display_name, role, tier FROM users ORDER BY id DESC LIMIT 5"
+    Path(product_id): Path<i64>,
+) -> Result<Json<Product>, AppError> {
+    let row = sqlx::query!(
+        "UPDATE products SET deleted_at = NULL, updated_at = NOW() WHERE id = $1 AND deleted_at IS NOT NULL RETURNING id, name, description, price, category, status, created_at, updated_at, image_url",
+        product_id
     )
-    .fetch_all(&pool)
+    .fetch_optional(&pool)
     .await
-    .map_err(SqlxErrorExt::into_app_error)?;
+    .map_err(SqlxErrorExt::into_app_error)?
+    .ok_or_else(|| {
+        AppError::not_found(format!("Deleted product with id {} not found", product_id))
+    })?;
 
-    Ok(Json(DashboardStats {
-        total_users,
-        total_products,
-        active_products,
-        recent_users,
+    Ok(Json(Product {
+        id: row.id,
+        name: row.name,
+        description: row.description,
+        price: Money::from_f64(row.price),
+        category: row.category,
+        status: ProductStatus::from_str_or_default(&row.status),
+        created_at: row.created_at.to_string(),
+        updated_at: row.updated_at.to_string(),
+        image_url: row.image_url,
+        is_favorited: None,
+        deleted_at: None,
     }))
 }
 
-// ── Auth ───────────────────────────────────────────────
-
 #[utoipa::path(
-    post,
-    path = "/api/auth/register",
-    request_body = RegisterRequest,
+    get,
+    context_path = API_PREFIX,
+    path = "/products/{product_id}/history",
+    params(("product_id" = i64, Path, description = "Product ID")),
     responses(
-        (status = 201, description = "User registered", body = AuthResponse),
-        (status = 422, description = "Validation error (e.g. duplicate email)", body = AppError),
+        (status = 200, description = "When the product was created and last changed; audit entries only for its owner and admins", body = ProductHistory),
+        (status = 401, description = "Not authenticated", body = AppError),
+        (status = 404, description = "Product not found", body = AppError),
         (status = 500, description = "Internal server error", body = AppError)
     ),
-    tag = "auth"
+    tag = "products",
+    security(("bearer_auth" = []))
 )]
-#[tracing::instrument(skip(pool, payload))]
+#[tracing::instrument(skip(pool, auth))]
+pub async fn get_product_history(
+    State(pool): State<Pool<Postgres>>,
+    auth: AuthRequired,
+    Path(product_id): Path<i64>,
+) -> Result<Json<ProductHistory>, AppError> {
+    let history = audit::product_history(&pool, product_id, &auth.0).await?;
+    Ok(Json(history))
+}
+
+/// Width and height of generated product preview images, large enough for
+/// link previews on social sites.
+const PRODUCT_IMAGE_SIZE: u32 = 512;
+
+#[utoipa::path(
+    get,
+    context_path = API_PREFIX,
+    path = "/products/{product_id}/image",
+    params(("product_id" = i64, Path, description = "Product ID")),
+    responses(
+        (status = 200, description = "Generated product image, when none was uploaded", content_type = "image/png"),
+        (status = 307, description = "Redirect to a short-lived URL of the uploaded image"),
+        (status = 404, description = "Product not found", body = AppError),
+        (status = 500, description = "Internal server error", body = AppError)
+    ),
+    tag = "products"
+)]
+#[tracing::instrument(skip(pool))]
+pub async fn get_product_image(
+    State(pool): State<Pool<Postgres>>,
+    Path(product_id): Path<i64>,
+) -> Result<Response, AppError> {
+    let product = sqlx::query!(
+        "SELECT name, image_url FROM products WHERE id = $1 AND deleted_at IS NULL",
+        product_id
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?
+    .ok_or_else(|| AppError::not_found(format!("Product with id {} not found", product_id)))?;
+
+    if let Some(key) = product.image_url {
+        let ttl = crate::s3::presign_ttl();
+        let url = crate::s3::presigned_url(&key, ttl)
+            .await
+            .map_err(AppError::internal)?;
+        // Cache the redirect for at most half the URL's lifetime, so a cached
+        // redirect never points at an expired URL.
+        let cache_control = format!("private, max-age={}", ttl.as_secs() / 2);
+        return Ok((
+            [(header::CACHE_CONTROL, cache_control)],
+            Redirect::temporary(&url),
+        )
+            .into_response());
+    }
+
+    let png = crate::avatar_fallback::render_fallback_avatar(
+        product_id,
+        &product.name,
+        PRODUCT_IMAGE_SIZE,
+    )
+    .map_err(AppError::internal)?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "image/png"),
+            (header::CACHE_CONTROL, "public, max-age=3600"),
+        ],
+        png,
+    )
+        .into_response())
+}
+
+// ── Dashboard ──────────────────────────────────────────
+
+#[utoipa::path(
+    get,
+    context_path = API_PREFIX,
+    path = "/dashboard/stats",
+    params(DashboardStatsQuery),
+    responses(
+        (status = 200, description = "Dashboard statistics for the requested range", body = DashboardStats),
+        (status = 500, description = "Internal server error", body = AppError)
+    ),
+    tag = "dashboard"
+)]
+#[tracing::instrument(skip(pool))]
+pub async fn get_dashboard_stats(
+    State(pool): State<Pool<Postgres>>,
+    Query(query): Query<DashboardStatsQuery>,
+) -> Result<Json<DashboardStats>, AppError> {
+    let stats = crate::dashboard::stats(&pool, query.range.unwrap_or_default()).await?;
+    Ok(Json(stats))
+}
+
+#[utoipa::path(
+    get,
+    context_path = API_PREFIX,
+    path = "/analytics/premium",
+    params(AnalyticsQuery),
+    responses(
+        (status = 200, description = "Premium analytics", body = PremiumAnalytics),
+        (status = 401, description = "Not authenticated", body = AppError),
+        (status = 403, description = "Forbidden — Premium tier required, or global scope without the admin role", body = AppError),
+        (status = 500, description = "Internal server error", body = AppError)
+    ),
+    tag = "dashboard",
+    security(("bearer_auth" = []))
+)]
+#[tracing::instrument(skip(pool, tier))]
+pub async fn get_premium_analytics(
+    State(pool): State<Pool<Postgres>>,
+    tier: PremiumRequired,
+    Query(query): Query<AnalyticsQuery>,
+) -> Result<Json<PremiumAnalytics>, AppError> {
+    let owner = crate::analytics::resolve_scope(&tier.0, query.scope)?;
+    let analytics = crate::analytics::premium_analytics(&pool, owner).await?;
+    Ok(Json(analytics))
+}
+
+// ── Auth ───────────────────────────────────────────────
+
+#[utoipa::path(
+    post,
+    context_path = API_PREFIX,
+    path = "/auth/register",
+    request_body = RegisterRequest,
+    responses(
+        (status = 201, description = "User registered", body = AuthResponse),
+        (status = 400, description = "Malformed request body", body = AppError),
+        (status = 403, description = "Invite required or invalid (invite-only mode)", body = AppError),
+        (status = 422, description = "Validation error (e.g. duplicate email)", body = AppError),
+        (status = 500, description = "Internal server error", body = AppError)
+    ),
+    tag = "auth"
+)]
+#[tracing::instrument(skip(pool, payload))]
 pub async fn register(
     State(pool): State<Pool<Postgres>>,
-    Json(payload): Json<RegisterRequest>,
+    State(registration_mode): State<RegistrationMode>,
+    AppJson(payload): AppJson<RegisterRequest>,
 ) -> Result<(StatusCode, Json<AuthResponse>), AppError> {
+    let payload = payload.validate_and_normalize()?;
+
     let password_hash =
         pw::hash_password(&payload.password).map_err(|e| AppError::internal(e.to_string()))?;
 
-    let user = sqlx::query!(
-        "INSERT INTO users (username, email, password_hash, display_name) VALUES ($1, $2, $3, $4) RETURNING id, username, display_name, email, role, tier, avatar_url",
+    let mut tx = pool.begin().await.map_err(SqlxErrorExt::into_app_error)?;
+
+    let invite_id = if registration_mode.requires_invite() {
+        Some(invites::claim_invite(&mut tx, payload.invite_token.as_deref(), &payload.email).await?)
+    } else {
+        None
+    };
+
+    let user = sqlx::query_as!(
+        UserRecord,
+        "INSERT INTO users (username, email, password_hash, display_name) VALUES ($1, $2, $3, $4) RETURNING id, username, display_name, email, role, tier, avatar_url, thumbnail_url, created_at, updated_at",
         payload.username,
         payload.email,
         password_hash,
         payload.display_name
     )
-    .fetch_one(&pool)
+    .fetch_one(&mut *tx)
     .await
     .map_err(SqlxErrorExt::into_app_error)?;
 
-    let user_email = user.email.unwrap_or_default();
-    let user_tier = UserTier::from_str_or_default(&user.tier);
+    if let Some(invite_id) = invite_id {
+        invites::record_invitee(&mut tx, invite_id, user.id).await?;
+    }
+
+    tx.commit().await.map_err(SqlxErrorExt::into_app_error)?;
+
+    if let Err(e) = verification::send_verification_email(&pool, user.id).await {
+        tracing::warn!(user_id = user.id, error = %e, "failed to send verification email");
+    }
+    let user = AuthUser::from(user).with_email_verified(false);
 
     let access_token =
-        jwt::create_access_token(user.id, &user_email, &user.role, user_tier.as_str())
+        jwt::create_access_token(user.id, &user.email, user.role.as_str(), user.tier.as_str())
             .map_err(|e| AppError::internal(e.to_string()))?;
 
     let (refresh_token, expires_at) =
-        jwt::create_refresh_token(user.id, &user_email, &user.role, user_tier.as_str())
+        jwt::create_refresh_token(user.id, &user.email, user.role.as_str(), user.tier.as_str())
             .map_err(|e| AppError::internal(e.to_string()))?;
 
     sqlx::query!(
@@ -422,101 +963,166 @@ pub async fn register(
     .await
     .map_err(SqlxErrorExt::into_app_error)?;
 
-    let auth_user = AuthUser {
-        id: user.id,
-        username: user.username,
-        display_name: user.display_name,
-        email: user_email,
-        role: user.role,
-        tier: user_tier,
-        avatar_url: user.avatar_url,
-    };
-
     Ok((
         StatusCode::CREATED,
-        Json(AuthResponse {
-            user: auth_user,
-            access_token,
-        }),
+        Json(AuthResponse { user, access_token }),
     ))
 }
 
 #[utoipa::path(
     post,
-    path = "/api/auth/login",
+    context_path = API_PREFIX,
+    path = "/auth/login",
     request_body = LoginRequest,
     responses(
         (status = 200, description = "Login successful", body = AuthResponse),
+        (status = 400, description = "Malformed request body", body = AppError),
         (status = 401, description = "Invalid credentials", body = AppError),
+        (status = 403, description = "Account is scheduled for deletion or was deleted; cancel with `POST /auth/cancel-deletion`", body = AppError),
         (status = 500, description = "Internal server error", body = AppError)
     ),
     tag = "auth"
 )]
-#[tracing::instrument(skip(pool, payload))]
+#[tracing::instrument(skip(pool, client, payload))]
 pub async fn login(
     State(pool): State<Pool<Postgres>>,
-    Json(payload): Json<LoginRequest>,
+    client: ClientInfo,
+    AppJson(payload): AppJson<LoginRequest>,
 ) -> Result<Json<AuthResponse>, AppError> {
-    let user = sqlx::query!(
-        "SELECT id, username, display_name, email, password_hash, role, tier, avatar_url FROM users WHERE email = $1",
-        payload.email
+    let payload = payload.validate_and_normalize()?;
+
+    // Emails are stored lowercased; LOWER() also matches accounts created
+    // before normalization.
+    let Some(credentials) = traced_query(
+        "users.credentials",
+        sqlx::query!(
+            "SELECT id, password_hash FROM users WHERE LOWER(email) = $1",
+            payload.email
+        )
+        .fetch_optional(&pool),
     )
-    .fetch_optional(&pool)
     .await
     .map_err(SqlxErrorExt::into_app_error)?
-    .ok_or_else(|| AppError::unauthorized("Invalid email or password"))?;
-
-    let password_hash = user
-        .password_hash
-        .ok_or_else(|| AppError::unauthorized("Invalid email or password"))?;
+    else {
+        auth_events::record(&pool, AuthEventType::LoginFailure, None, &client).await;
+        return Err(AppError::unauthorized("Invalid email or password"));
+    };
 
-    let valid = pw::verify_password(&payload.password, &password_hash)
-        .map_err(|e| AppError::internal(e.to_string()))?;
+    let valid = match &credentials.password_hash {
+        Some(hash) => pw::verify_password(&payload.password, hash)
+            .map_err(|e| AppError::internal(e.to_string()))?,
+        None => false,
+    };
 
     if !valid {
+        auth_events::record(
+            &pool,
+            AuthEventType::LoginFailure,
+            Some(credentials.id),
+            &client,
+        )
+        .await;
         return Err(AppError::unauthorized("Invalid email or password"));
     }
 
-    let user_email = user.email.unwrap_or_default();
-    let user_tier = UserTier::from_str_or_default(&user.tier);
+    if let Err(e) = deletion::ensure_not_pending(&pool, credentials.id).await {
+        auth_events::record(
+            &pool,
+            AuthEventType::LoginFailure,
+            Some(credentials.id),
+            &client,
+        )
+        .await;
+        return Err(e);
+    }
+
+    let user: AuthUser = traced_query(
+        "users.get",
+        sqlx::query_as!(
+            UserRecord,
+            "SELECT id, username, display_name, email, role, tier, avatar_url, thumbnail_url, created_at, updated_at FROM users WHERE id = $1",
+            credentials.id
+        )
+        .fetch_one(&pool),
+    )
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?
+    .into();
 
     let access_token =
-        jwt::create_access_token(user.id, &user_email, &user.role, user_tier.as_str())
+        jwt::create_access_token(user.id, &user.email, user.role.as_str(), user.tier.as_str())
             .map_err(|e| AppError::internal(e.to_string()))?;
 
     let (refresh_token, expires_at) =
-        jwt::create_refresh_token(user.id, &user_email, &user.role, user_tier.as_str())
+        jwt::create_refresh_token(user.id, &user.email, user.role.as_str(), user.tier.as_str())
             .map_err(|e| AppError::internal(e.to_string()))?;
 
-    sqlx::query!(
-        "INSERT INTO refresh_tokens (user_id, token_hash, expires_at) VALUES ($1, $2, $3)",
-        user.id,
-        refresh_token,
-        expires_at
+    traced_query(
+        "refresh_tokens.insert",
+        sqlx::query!(
+            "INSERT INTO refresh_tokens (user_id, token_hash, expires_at) VALUES ($1, $2, $3)",
+            user.id,
+            refresh_token,
+            expires_at
+        )
+        .execute(&pool),
     )
-    .execute(&pool)
     .await
     .map_err(SqlxErrorExt::into_app_error)?;
 
-    let auth_user = AuthUser {
-        id: user.id,
-        username: user.username,
-        display_name: user.display_name,
-        email: user_email,
-        role: user.role,
-        tier: user_tier,
-        avatar_url: user.avatar_url,
+    auth_events::record(&pool, AuthEventType::LoginSuccess, Some(user.id), &client).await;
+
+    Ok(Json(AuthResponse { user, access_token }))
+}
+
+#[utoipa::path(
+    post,
+    context_path = API_PREFIX,
+    path = "/auth/cancel-deletion",
+    request_body = LoginRequest,
+    responses(
+        (status = 204, description = "Deletion cancelled; the account can sign in again"),
+        (status = 400, description = "Malformed request body", body = AppError),
+        (status = 401, description = "Invalid credentials", body = AppError),
+        (status = 404, description = "No deletion is scheduled", body = AppError),
+        (status = 422, description = "Validation error", body = AppError),
+        (status = 500, description = "Internal server error", body = AppError)
+    ),
+    tag = "auth"
+)]
+#[tracing::instrument(skip(pool, payload))]
+pub async fn cancel_deletion_with_credentials(
+    State(pool): State<Pool<Postgres>>,
+    AppJson(payload): AppJson<LoginRequest>,
+) -> Result<StatusCode, AppError> {
+    let payload = payload.validate_and_normalize()?;
+
+    let credentials = sqlx::query!(
+        "SELECT id, password_hash FROM users WHERE LOWER(email) = $1",
+        payload.email
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?
+    .ok_or_else(|| AppError::unauthorized("Invalid email or password"))?;
+
+    let valid = match &credentials.password_hash {
+        Some(hash) => pw::verify_password(&payload.password, hash)
+            .map_err(|e| AppError::internal(e.to_string()))?,
+        None => false,
     };
+    if !valid {
+        return Err(AppError::unauthorized("Invalid email or password"));
+    }
 
-    Ok(Json(AuthResponse {
-        user: auth_user,
-        access_token,
-    }))
+    deletion::cancel(&pool, credentials.id).await?;
+    Ok(StatusCode::NO_CONTENT)
 }
 
 #[utoipa::path(
     post,
-    path = "/api/auth/logout",
+    context_path = API_PREFIX,
+    path = "/auth/logout",
     responses(
         (status = 204, description = "Logged out"),
         (status = 401, description = "Not authenticated", body = AppError),
@@ -525,9 +1131,10 @@ pub async fn login(
     tag = "auth",
     security(("bearer_auth" = []))
 )]
-#[tracing::instrument(skip(pool, auth))]
+#[tracing::instrument(skip(pool, client, auth))]
 pub async fn logout(
     State(pool): State<Pool<Postgres>>,
+    client: ClientInfo,
     auth: AuthRequired,
 ) -> Result<StatusCode, AppError> {
     sqlx::query!(
@@ -538,16 +1145,57 @@ pub async fn logout(
     .await
     .map_err(SqlxErrorExt::into_app_error)?;
 
+    auth_events::record(&pool, AuthEventType::Logout, Some(auth.0.sub), &client).await;
+
     Ok(StatusCode::NO_CONTENT)
 }
 
+#[utoipa::path(
+    get,
+    context_path = API_PREFIX,
+    path = "/auth/providers",
+    responses(
+        (status = 200, description = "OAuth providers configured on this server, in display order", body = Vec<OAuthProviderInfo>)
+    ),
+    tag = "auth"
+)]
+#[tracing::instrument]
+pub async fn list_oauth_providers() -> Json<Vec<OAuthProviderInfo>> {
+    Json(oauth::enabled_providers())
+}
+
+#[utoipa::path(
+    get,
+    context_path = API_PREFIX,
+    path = "/auth/oauth/{provider}/authorize",
+    params(("provider" = OAuthProvider, Path, description = "Identity provider, case-insensitive (e.g. `github`)")),
+    responses(
+        (status = 303, description = "Redirect to the provider's sign-in page; it returns to `/auth/callback/{provider}`"),
+        (status = 422, description = "Unsupported provider", body = AppError),
+        (status = 500, description = "Provider is not configured", body = AppError)
+    ),
+    tag = "auth"
+)]
+#[tracing::instrument]
+pub async fn oauth_authorize(Path(provider): Path<String>) -> Result<Redirect, AppError> {
+    let provider = OAuthProvider::parse_provider(&provider)
+        .ok_or_else(|| AppError::validation("Unsupported OAuth provider", Default::default()))?;
+
+    let url = oauth::get_authorize_url(&provider)
+        .await
+        .map_err(AppError::internal)?;
+    Ok(Redirect::to(&url))
+}
+
 #[utoipa::path(
     put,
-    path = "/api/users/{user_id}/tier",
+    context_path = API_PREFIX,
+    path = "/users/{user_id}/tier",
     params(("user_id" = i64, Path, description = "User ID")),
     request_body = UpdateTierRequest,
     responses(
         (status = 200, description = "Tier updated", body = User),
+        (status = 400, description = "Malformed request body", body = AppError),
         (status = 401, description = "Not authenticated", body = AppError),
         (status = 403, description = "Forbidden — admin role required", body = AppError),
         (status = 404, description = "User not found", body = AppError),
@@ -562,9 +1210,9 @@ pub async fn update_user_tier(
     State(pool): State<Pool<Postgres>>,
     auth: AuthRequired,
     Path(user_id): Path<i64>,
-    Json(payload): Json<UpdateTierRequest>,
+    AppJson(payload): AppJson<UpdateTierRequest>,
 ) -> Result<Json<User>, AppError> {
-    if auth.0.role != "admin" {
+    if !Role::from_str_or_default(&auth.0.role).is_admin() {
         return Err(AppError::forbidden(
             "Admin role required to change user tiers",
         ));
@@ -580,8 +1228,8 @@ pub async fn update_user_tier(
     }
 
     let user = sqlx::query_as!(
-        User,
-        "UPDATE users SET tier = $2 WHERE id = $1 RETURNING id, username, display_name, role, tier",
+        UserRecord,
+        "UPDATE users SET tier = $2, updated_at = NOW() WHERE id = $1 AND deleted_at IS NULL RETURNING id, username, display_name, email, role, tier, avatar_url, thumbnail_url, created_at, updated_at",
         user_id,
         tier_lower
     )
@@ -590,20 +1238,304 @@ pub async fn update_user_tier(
     .map_err(SqlxErrorExt::into_app_error)?
     .ok_or_else(|| AppError::not_found(format!("User with id {} not found", user_id)))?;
 
+    audit::record(
+        &pool,
+        Some(auth.0.sub),
+        AuditAction::TierChange,
+        &audit::user_target(user_id),
+        serde_json::json!({ "tier": tier_lower }),
+    )
+    .await;
+    Ok(Json(user.into()))
+}
+
+// ── Invites ────────────────────────────────────────────
+
+#[utoipa::path(
+    post,
+    context_path = API_PREFIX,
+    path = "/invites",
+    request_body = CreateInviteRequest,
+    responses(
+        (status = 201, description = "Invite created", body = Invite),
+        (status = 400, description = "Malformed request body", body = AppError),
+        (status = 401, description = "Not authenticated", body = AppError),
+        (status = 403, description = "Forbidden — admin role required", body = AppError),
+        (status = 422, description = "Validation error", body = AppError),
+        (status = 500, description = "Internal server error", body = AppError)
+    ),
+    tag = "auth",
+    security(("bearer_auth" = []))
+)]
+#[tracing::instrument(skip(pool, auth, payload))]
+pub async fn create_invite(
+    State(pool): State<Pool<Postgres>>,
+    auth: AuthRequired,
+    AppJson(payload): AppJson<CreateInviteRequest>,
+) -> Result<(StatusCode, Json<Invite>), AppError> {
+    if !Role::from_str_or_default(&auth.0.role).is_admin() {
+        return Err(AppError::forbidden("Admin role required to create invites"));
+    }
+    payload.validate_request()?;
+
+    let mut conn = pool.acquire().await.map_err(SqlxErrorExt::into_app_error)?;
+    let invite = invites::create_invite(&mut conn, &payload.email, auth.0.sub).await?;
+
+    Ok((StatusCode::CREATED, Json(invite)))
+}
+
+// ── Auth events ────────────────────────────────────────
+
+#[utoipa::path(
+    get,
+    context_path = API_PREFIX,
+    path = "/admin/auth-events",
+    params(AuthEventQuery),
+    responses(
+        (status = 200, description = "Matching authentication events, newest first", body = Vec<AuthEvent>),
+        (status = 400, description = "Unknown event type", body = AppError),
+        (status = 401, description = "Not authenticated", body = AppError),
+        (status = 403, description = "Forbidden — admin role required", body = AppError),
+        (status = 500, description = "Internal server error", body = AppError)
+    ),
+    tag = "auth",
+    security(("bearer_auth" = []))
+)]
+#[tracing::instrument(skip(pool, auth))]
+pub async fn list_auth_events(
+    State(pool): State<Pool<Postgres>>,
+    auth: AuthRequired,
+    Query(query): Query<AuthEventQuery>,
+) -> Result<Json<Vec<AuthEvent>>, AppError> {
+    if !Role::from_str_or_default(&auth.0.role).is_admin() {
+        return Err(AppError::forbidden(
+            "Admin role required to view auth events",
+        ));
+    }
+
+    let events = auth_events::list(&pool, &query).await?;
+    Ok(Json(events))
+}
+
+#[utoipa::path(
+    get,
+    context_path = API_PREFIX,
+    path = "/audit",
+    params(AuditLogQuery),
+    responses(
+        (status = 200, description = "One page of audit entries, newest first", body = AuditPage),
+        (status = 401, description = "Not authenticated", body = AppError),
+        (status = 403, description = "Forbidden — admin role required", body = AppError),
+        (status = 500, description = "Internal server error", body = AppError)
+    ),
+    tag = "auth",
+    security(("bearer_auth" = []))
+)]
+#[tracing::instrument(skip(pool, auth))]
+pub async fn list_audit_log(
+    State(pool): State<Pool<Postgres>>,
+    auth: AuthRequired,
+    Query(query): Query<AuditLogQuery>,
+) -> Result<Json<AuditPage>, AppError> {
+    if !Role::from_str_or_default(&auth.0.role).is_admin() {
+        return Err(AppError::forbidden(
+            "Admin role required to view the audit log",
+        ));
+    }
+
+    let page = audit::list(&pool, &query).await?;
+    Ok(Json(page))
+}
+
+// ── Preferences ─────────────────────────────────────────
+
+#[utoipa::path(
+    get,
+    context_path = API_PREFIX,
+    path = "/users/me/preferences",
+    responses(
+        (status = 200, description = "Current user's preferences (defaults if never saved)", body = UserPreferences),
+        (status = 401, description = "Not authenticated", body = AppError),
+        (status = 500, description = "Internal server error", body = AppError)
+    ),
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
+#[tracing::instrument(skip(pool, auth))]
+pub async fn get_my_preferences(
+    State(pool): State<Pool<Postgres>>,
+    auth: AuthRequired,
+) -> Result<Json<UserPreferences>, AppError> {
+    let prefs = crate::preferences::load_preferences(&pool, auth.0.sub).await?;
+    Ok(Json(prefs))
+}
+
+#[utoipa::path(
+    put,
+    context_path = API_PREFIX,
+    path = "/users/me/preferences",
+    request_body = UserPreferences,
+    responses(
+        (status = 200, description = "Preferences saved", body = UserPreferences),
+        (status = 400, description = "Malformed request body", body = AppError),
+        (status = 401, description = "Not authenticated", body = AppError),
+        (status = 500, description = "Internal server error", body = AppError)
+    ),
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
+#[tracing::instrument(skip(pool, auth))]
+pub async fn update_my_preferences(
+    State(pool): State<Pool<Postgres>>,
+    auth: AuthRequired,
+    AppJson(payload): AppJson<UserPreferences>,
+) -> Result<Json<UserPreferences>, AppError> {
+    let prefs = crate::preferences::save_preferences(&pool, auth.0.sub, &payload).await?;
+    Ok(Json(prefs))
+}
+
+#[utoipa::path(
+    put,
+    context_path = API_PREFIX,
+    path = "/users/me/display-name",
+    request_body = UpdateDisplayNameRequest,
+    responses(
+        (status = 200, description = "Display name changed; email untouched", body = AuthUser),
+        (status = 400, description = "Malformed request body", body = AppError),
+        (status = 401, description = "Not authenticated", body = AppError),
+        (status = 422, description = "Display name is empty", body = AppError),
+        (status = 500, description = "Internal server error", body = AppError)
+    ),
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
+#[tracing::instrument(skip(pool, auth))]
+pub async fn update_my_display_name(
+    State(pool): State<Pool<Postgres>>,
+    auth: AuthRequired,
+    AppJson(payload): AppJson<UpdateDisplayNameRequest>,
+) -> Result<Json<AuthUser>, AppError> {
+    let user = crate::profile::update_display_name(&pool, auth.0.sub, payload).await?;
     Ok(Json(user))
 }
 
-// ── Avatar Upload ───────────────────────────────────────
+#[utoipa::path(
+    get,
+    context_path = API_PREFIX,
+    path = "/users/me/deletion",
+    responses(
+        (status = 200, description = "The pending deletion, or null if the account is active", body = Option<AccountDeletion>),
+        (status = 401, description = "Not authenticated", body = AppError),
+        (status = 500, description = "Internal server error", body = AppError)
+    ),
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
+#[tracing::instrument(skip(pool, auth))]
+pub async fn get_my_account_deletion(
+    State(pool): State<Pool<Postgres>>,
+    auth: AuthRequired,
+) -> Result<Json<Option<AccountDeletion>>, AppError> {
+    Ok(Json(deletion::pending(&pool, auth.0.sub).await?))
+}
+
+#[utoipa::path(
+    post,
+    context_path = API_PREFIX,
+    path = "/users/me/deletion",
+    responses(
+        (status = 200, description = "Account scheduled for deletion; sign-in is blocked until then", body = AccountDeletion),
+        (status = 401, description = "Not authenticated", body = AppError),
+        (status = 500, description = "Internal server error", body = AppError)
+    ),
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
+#[tracing::instrument(skip(pool, auth))]
+pub async fn schedule_my_account_deletion(
+    State(pool): State<Pool<Postgres>>,
+    auth: AuthRequired,
+) -> Result<Json<AccountDeletion>, AppError> {
+    Ok(Json(deletion::schedule(&pool, auth.0.sub).await?))
+}
+
+#[utoipa::path(
+    delete,
+    context_path = API_PREFIX,
+    path = "/users/me/deletion",
+    responses(
+        (status = 204, description = "Deletion cancelled"),
+        (status = 401, description = "Not authenticated", body = AppError),
+        (status = 404, description = "No deletion is scheduled", body = AppError),
+        (status = 500, description = "Internal server error", body = AppError)
+    ),
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
+#[tracing::instrument(skip(pool, auth))]
+pub async fn cancel_my_account_deletion(
+    State(pool): State<Pool<Postgres>>,
+    auth: AuthRequired,
+) -> Result<StatusCode, AppError> {
+    deletion::cancel(&pool, auth.0.sub).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
 
-const MAX_AVATAR_SIZE: usize = 2 * 1024 * 1024; // 2 MB
+#[utoipa::path(
+    post,
+    context_path = API_PREFIX,
+    path = "/users/me/verification-email",
+    responses(
+        (status = 204, description = "Verification link sent, or the email is already verified"),
+        (status = 401, description = "Not authenticated", body = AppError),
+        (status = 429, description = "Too many verification emails requested", body = AppError),
+        (status = 500, description = "Internal server error", body = AppError)
+    ),
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
+#[tracing::instrument(skip(pool, auth))]
+pub async fn resend_verification_email(
+    State(pool): State<Pool<Postgres>>,
+    auth: AuthRequired,
+) -> Result<StatusCode, AppError> {
+    verification::check_resend_allowed(auth.0.sub)?;
+    verification::send_verification_email(&pool, auth.0.sub).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
 
 #[utoipa::path(
     post,
-    path = "/api/users/me/avatar",
+    context_path = API_PREFIX,
+    path = "/auth/verify-email",
+    request_body = VerifyEmailRequest,
+    responses(
+        (status = 204, description = "Email verified"),
+        (status = 400, description = "Link is invalid or has expired", body = AppError),
+        (status = 500, description = "Internal server error", body = AppError)
+    ),
+    tag = "auth"
+)]
+#[tracing::instrument(skip(pool, payload))]
+pub async fn verify_email(
+    State(pool): State<Pool<Postgres>>,
+    AppJson(payload): AppJson<VerifyEmailRequest>,
+) -> Result<StatusCode, AppError> {
+    verification::confirm(&pool, &payload.token).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ── Avatar Upload ───────────────────────────────────────
+
+#[utoipa::path(
+    post,
+    context_path = API_PREFIX,
+    path = "/users/me/avatar",
     responses(
         (status = 200, description = "Avatar uploaded", body = AuthUser),
         (status = 401, description = "Not authenticated", body = AppError),
-        (status = 422, description = "Validation error", body = AppError),
+        (status = 422, description = "Validation error, including \"File too large\"", body = AppError),
+        (status = 429, description = "Too many uploads", body = AppError),
         (status = 500, description = "Internal server error", body = AppError)
     ),
     tag = "users",
@@ -615,13 +1547,16 @@ pub async fn upload_avatar(
     auth: AuthRequired,
     mut multipart: Multipart,
 ) -> Result<Json<AuthUser>, AppError> {
+    crate::upload_limits::check_avatar_upload_rate(auth.0.sub)?;
+    let limits = crate::upload_limits::avatar_limits();
+
     let mut file_bytes: Option<Vec<u8>> = None;
     let mut content_type: Option<String> = None;
 
     while let Some(field) = multipart
         .next_field()
         .await
-        .map_err(|e| AppError::validation(e.to_string(), Default::default()))?
+        .map_err(|e| limits.multipart_error(e))?
     {
         let ct = field
             .content_type()
@@ -636,20 +1571,10 @@ pub async fn upload_avatar(
             ));
         }
 
-        let data = field
-            .bytes()
-            .await
-            .map_err(|e| AppError::internal(e.to_string()))?;
-
-        if data.len() > MAX_AVATAR_SIZE {
-            return Err(AppError::validation(
-                "Avatar must be under 2 MB",
-                Default::default(),
-            ));
-        }
+        let data = limits.read_field(field).await?;
 
         content_type = Some(ct);
-        file_bytes = Some(data.to_vec());
+        file_bytes = Some(data);
         break;
     }
 
@@ -657,14 +1582,37 @@ pub async fn upload_avatar(
         file_bytes.ok_or_else(|| AppError::validation("No file provided", Default::default()))?;
     let ct = content_type.unwrap_or_default();
 
-    let avatar_url = crate::s3::upload_avatar(auth.0.sub, &ct, &bytes)
+    let thumbnail = crate::s3::make_thumbnail_async(&bytes)
+        .await
+        .map_err(|e| AppError::validation(e, Default::default()))?;
+
+    let previous = sqlx::query!(
+        "SELECT avatar_url, thumbnail_url, avatar_hash FROM users WHERE id = $1",
+        auth.0.sub
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?;
+
+    let current = previous
+        .map(|prev| crate::s3::StoredAvatar {
+            original: prev.avatar_url,
+            thumbnail: prev.thumbnail_url,
+            hash: prev.avatar_hash,
+        })
+        .unwrap_or_default();
+
+    let upload = crate::s3::upload_avatar(auth.0.sub, &ct, &bytes, &thumbnail, &current)
         .await
         .map_err(|e| AppError::internal(e))?;
 
-    let user = sqlx::query!(
-        "UPDATE users SET avatar_url = $2 WHERE id = $1 RETURNING id, username, display_name, email, role, tier, avatar_url",
+    let user = sqlx::query_as!(
+        UserRecord,
+        "UPDATE users SET avatar_url = $2, thumbnail_url = $3, avatar_hash = $4, updated_at = NOW() WHERE id = $1 RETURNING id, username, display_name, email, role, tier, avatar_url, thumbnail_url, created_at, updated_at",
         auth.0.sub,
-        avatar_url
+        upload.keys.original,
+        upload.keys.thumbnail,
+        upload.hash
     )
     .fetch_one(&pool)
     .await
@@ -673,34 +1621,155 @@ pub async fn upload_avatar(
         e.into_app_error()
     })?;
 
-    Ok(Json(AuthUser {
-        id: user.id,
-        username: user.username,
-        display_name: user.display_name,
-        email: user.email.unwrap_or_default(),
-        role: user.role,
-        tier: UserTier::from_str_or_default(&user.tier),
-        avatar_url: user.avatar_url,
-    }))
+    if !upload.reused {
+        crate::s3::delete_replaced_objects(&[current.original, current.thumbnail]).await;
+    }
+
+    Ok(Json(user.into()))
+}
+
+#[utoipa::path(
+    get,
+    context_path = API_PREFIX,
+    path = "/users/{user_id}/avatar",
+    params(("user_id" = i64, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "Generated initials avatar", content_type = "image/png"),
+        (status = 307, description = "Redirect to the uploaded avatar"),
+        (status = 404, description = "User not found", body = AppError),
+        (status = 500, description = "Internal server error", body = AppError)
+    ),
+    tag = "users"
+)]
+#[tracing::instrument(skip(pool))]
+pub async fn get_user_avatar(
+    State(pool): State<Pool<Postgres>>,
+    Path(user_id): Path<i64>,
+) -> Result<Response, AppError> {
+    let row = sqlx::query!(
+        "SELECT display_name, avatar_url, thumbnail_url FROM users WHERE id = $1",
+        user_id
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?
+    .ok_or_else(|| AppError::not_found(format!("User with id {} not found", user_id)))?;
+
+    if let Some(key) = row.thumbnail_url.or(row.avatar_url) {
+        let url = crate::s3::presigned_url(&key, crate::s3::presign_ttl())
+            .await
+            .map_err(AppError::internal)?;
+        return Ok(Redirect::temporary(&url).into_response());
+    }
+
+    let png = crate::avatar_fallback::render_fallback_avatar(
+        user_id,
+        &row.display_name,
+        crate::avatar_fallback::FALLBACK_AVATAR_SIZE,
+    )
+    .map_err(AppError::internal)?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "image/png"),
+            (header::CACHE_CONTROL, "public, max-age=3600"),
+        ],
+        png,
+    )
+        .into_response())
 }
 
 /// Build the REST API router with all resource routes.
+///
+/// Routes are served under [`API_PREFIX`], and under [`LEGACY_API_PREFIX`]
+/// with deprecation headers (see [`Deprecation::legacy_api`]) until clients
+/// have moved over.
+///
+/// Unsupported methods on known routes return a JSON `AppError` with an `Allow` header,
+/// and unknown `/api/*` paths return a JSON 404.
 pub fn rest_router() -> Router<AppState> {
+    let legacy = api_routes().route_layer(axum::middleware::from_fn_with_state(
+        Deprecation::legacy_api(),
+        deprecation_headers,
+    ));
+
+    Router::new()
+        .nest(API_PREFIX, with_not_found(api_routes()))
+        .nest(LEGACY_API_PREFIX, with_not_found(legacy))
+        .method_not_allowed_fallback(crate::fallback::method_not_allowed)
+}
+
+/// Unmatched paths under a prefix get a JSON 404 instead of the app.
+fn with_not_found(routes: Router<AppState>) -> Router<AppState> {
+    routes
+        .route("/{*path}", any(crate::fallback::api_not_found))
+        .method_not_allowed_fallback(crate::fallback::method_not_allowed)
+}
+
+/// Resource routes, relative to the API prefix.
+fn api_routes() -> Router<AppState> {
     Router::new()
-        .route("/api/users", get(list_users).post(create_user))
+        .route("/users", get(list_users).post(create_user))
+        .route("/users/export.csv", get(export_users))
+        .route("/users/batch-get", post(batch_get_users))
         .route(
-            "/api/users/{user_id}",
+            "/users/{user_id}",
             get(get_user).put(update_user).delete(delete_user),
         )
-        .route("/api/users/{user_id}/tier", put(update_user_tier))
-        .route("/api/products", get(list_products).post(create_product))
+        .route("/users/{user_id}/restore", post(restore_user))
+        .route("/users/{user_id}/tier", put(update_user_tier))
+        .route("/users/{user_id}/avatar", get(get_user_avatar))
+        .route("/products", get(list_products).post(create_product))
+        .route("/products/export.csv", get(export_products))
+        .route(
+            "/products/{product_id}",
+            get(get_product).put(update_product).delete(delete_product),
+        )
+        .route("/products/{product_id}/restore", post(restore_product))
+        .route("/products/{product_id}/history", get(get_product_history))
+        .route("/products/{product_id}/image", get(get_product_image))
+        .route(
+            "/products/{product_id}/favorite",
+            put(favorite_product).delete(unfavorite_product),
+        )
+        .route("/dashboard/stats", get(get_dashboard_stats))
+        .route("/analytics/premium", get(get_premium_analytics))
+        .route(
+            "/users/me/avatar",
+            post(upload_avatar)
+                .layer::<_, Infallible>(DefaultBodyLimit::disable())
+                .layer::<_, Infallible>(RequestBodyLimitLayer::new(
+                    crate::upload_limits::avatar_limits().max_body_bytes(),
+                ))
+                .layer::<_, Infallible>(axum::middleware::from_fn(
+                    crate::upload_limits::oversized_body_as_app_error,
+                )),
+        )
+        .route(
+            "/users/me/preferences",
+            get(get_my_preferences).put(update_my_preferences),
+        )
+        .route("/users/me/display-name", put(update_my_display_name))
+        .route(
+            "/users/me/deletion",
+            get(get_my_account_deletion)
+                .post(schedule_my_account_deletion)
+                .delete(cancel_my_account_deletion),
+        )
+        .route(
+            "/users/me/verification-email",
+            post(resend_verification_email),
+        )
+        .route("/auth/register", post(register))
+        .route("/auth/verify-email", post(verify_email))
+        .route("/auth/login", post(login))
+        .route("/auth/logout", post(logout))
+        .route("/auth/providers", get(list_oauth_providers))
+        .route("/auth/oauth/{provider}/authorize", get(oauth_authorize))
         .route(
-            "/api/products/{product_id}",
-            put(update_product).delete(delete_product),
+            "/auth/cancel-deletion",
+            post(cancel_deletion_with_credentials),
         )
-        .route("/api/dashboard/stats", get(get_dashboard_stats))
-        .route("/api/users/me/avatar", post(upload_avatar))
-        .route("/api/auth/register", post(register))
-        .route("/api/auth/login", post(login))
-        .route("/api/auth/logout", post(logout))
+        .route("/invites", post(create_invite))
+        .route("/admin/auth-events", get(list_auth_events))
+        .route("/audit", get(list_audit_log))
 }
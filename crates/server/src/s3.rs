@@ -1,8 +1,15 @@
+use std::time::Duration;
+
 use aws_sdk_s3::{
     config::{Credentials, Region},
+    presigning::PresigningConfig,
     primitives::ByteStream,
     Client,
 };
+use sha2::{Digest, Sha256};
+
+/// Default lifetime of presigned URLs (15 minutes).
+const DEFAULT_PRESIGN_TTL_SECS: u64 = 900;
 
 /// Read an env var, trying the primary name first then a fallback.
 fn env_or(primary: &str, fallback: &str) -> Option<String> {
@@ -39,11 +46,16 @@ pub fn s3_client() -> Client {
         .expect("AWS_SECRET_ACCESS_KEY or S3_SECRET_KEY must be set");
     let region = env_or("AWS_REGION", "S3_REGION").unwrap_or_else(|| "us-east-1".to_string());
 
-    let creds = Credentials::new(&access_key, &secret_key, None, None, "env");
+    build_client(&endpoint, &access_key, &secret_key, &region)
+}
+
+/// Build a path-style S3 client for an explicit endpoint and static credentials.
+fn build_client(endpoint: &str, access_key: &str, secret_key: &str, region: &str) -> Client {
+    let creds = Credentials::new(access_key, secret_key, None, None, "env");
 
     let config = aws_sdk_s3::Config::builder()
-        .endpoint_url(&endpoint)
-        .region(Region::new(region))
+        .endpoint_url(endpoint)
+        .region(Region::new(region.to_string()))
         .credentials_provider(creds)
         .force_path_style(true)
         .behavior_version_latest()
@@ -94,56 +106,626 @@ pub async fn ensure_bucket() {
     }
 }
 
-/// Build the public URL for an object.
+/// Image types accepted for avatar and product image uploads.
+pub const IMAGE_CONTENT_TYPES: [&str; 3] = ["image/jpeg", "image/png", "image/webp"];
+
+/// Edge length of generated avatar thumbnails, in pixels.
+pub const THUMBNAIL_SIZE: u32 = 128;
+
+/// Object keys written for a single avatar upload.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AvatarKeys {
+    pub original: String,
+    pub thumbnail: String,
+}
+
+/// A user's current avatar, as recorded on their row.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StoredAvatar {
+    pub original: Option<String>,
+    pub thumbnail: Option<String>,
+    /// [`content_hash`] of the original, when it was uploaded here.
+    pub hash: Option<String>,
+}
+
+impl StoredAvatar {
+    /// The stored keys, if they hold an upload with this content hash.
+    fn keys_for(&self, hash: &str) -> Option<AvatarKeys> {
+        if self.hash.as_deref() != Some(hash) {
+            return None;
+        }
+        match (&self.original, &self.thumbnail) {
+            (Some(original), Some(thumbnail))
+                if is_object_key(original) && is_object_key(thumbnail) =>
+            {
+                Some(AvatarKeys {
+                    original: original.clone(),
+                    thumbnail: thumbnail.clone(),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Result of [`upload_avatar`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AvatarUpload {
+    pub keys: AvatarKeys,
+    /// [`content_hash`] of the uploaded bytes, stored alongside the keys.
+    pub hash: String,
+    /// Whether the bytes matched the current avatar, so nothing was written
+    /// and `keys` are the existing ones.
+    pub reused: bool,
+}
+
+/// Hex-encoded SHA-256 of an upload, used to spot re-uploads of the same file.
+pub fn content_hash(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Largest width or height of an uploaded image. A small compressed file can
+/// declare huge dimensions, so the byte limit alone doesn't bound decoding.
+pub const MAX_IMAGE_DIMENSION: u32 = 4096;
+
+/// Most memory the decoder may allocate for a single upload.
+const MAX_DECODE_ALLOC_BYTES: u64 = 128 * 1024 * 1024;
+
+/// Decoder limits for uploaded images.
+fn decode_limits() -> image::Limits {
+    let mut limits = image::Limits::default();
+    limits.max_image_width = Some(MAX_IMAGE_DIMENSION);
+    limits.max_image_height = Some(MAX_IMAGE_DIMENSION);
+    limits.max_alloc = Some(MAX_DECODE_ALLOC_BYTES);
+    limits
+}
+
+/// Decode an uploaded image and produce a square PNG thumbnail.
 ///
-/// Tigris uses virtual-hosted style: `https://{bucket}.fly.storage.tigris.dev/{key}`
-/// MinIO uses path style: `http://localhost:9000/{bucket}/{key}`
+/// The image is scaled to cover [`THUMBNAIL_SIZE`] and center-cropped, so the
+/// aspect ratio is preserved. Fails if the bytes aren't a decodable image or
+/// exceed [`MAX_IMAGE_DIMENSION`].
 ///
-/// We detect Tigris by checking if the endpoint contains `tigris`.
-fn public_url(bucket: &str, key: &str) -> String {
-    let endpoint = endpoint();
-    if endpoint.contains("tigris") {
-        // Virtual-hosted style for Tigris
-        let host = endpoint
-            .trim_start_matches("https://")
-            .trim_start_matches("http://");
-        format!("https://{}.{}/{}", bucket, host, key)
-    } else {
-        // Path style for MinIO / generic S3
-        format!("{}/{}/{}", endpoint, bucket, key)
-    }
+/// This is CPU-bound; async handlers should use [`make_thumbnail_async`].
+pub fn make_thumbnail(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let mut reader = image::ImageReader::new(std::io::Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| format!("Invalid image: {}", e))?;
+    reader.limits(decode_limits());
+    let img = reader
+        .decode()
+        .map_err(|e| format!("Invalid image: {}", e))?;
+    // Crop before scaling: scaling a very wide or tall image to cover the
+    // thumbnail first would allocate a far larger intermediate image.
+    let side = img.width().min(img.height());
+    let square = img.crop_imm(
+        (img.width() - side) / 2,
+        (img.height() - side) / 2,
+        side,
+        side,
+    );
+    let thumb = square.resize_exact(
+        THUMBNAIL_SIZE,
+        THUMBNAIL_SIZE,
+        image::imageops::FilterType::Lanczos3,
+    );
+
+    let mut out = std::io::Cursor::new(Vec::new());
+    thumb
+        .write_to(&mut out, image::ImageFormat::Png)
+        .map_err(|e| format!("Thumbnail encoding failed: {}", e))?;
+    Ok(out.into_inner())
 }
 
-/// Upload avatar bytes to S3 and return the public URL.
+/// [`make_thumbnail`] on the blocking thread pool, so decoding a large image
+/// doesn't stall other requests on the async runtime.
+pub async fn make_thumbnail_async(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let bytes = bytes.to_vec();
+    tokio::task::spawn_blocking(move || make_thumbnail(&bytes))
+        .await
+        .map_err(|e| format!("Thumbnail task failed: {}", e))?
+}
+
+/// Upload the original avatar and its thumbnail to S3 and return both keys.
 ///
-/// Objects are stored at `{user_id}/{uuid}.{ext}`.
+/// Objects are stored at `avatars/{user_id}/{uuid}/original` and
+/// `avatars/{user_id}/{uuid}/thumb`. Only the keys are persisted; use
+/// [`presigned_url`] to get a URL the browser can load.
+///
+/// When `bytes` are identical to the `current` avatar, nothing is written
+/// and the existing keys are returned with `reused` set.
 pub async fn upload_avatar(
     user_id: i64,
     content_type: &str,
     bytes: &[u8],
+    thumbnail: &[u8],
+    current: &StoredAvatar,
+) -> Result<AvatarUpload, String> {
+    let bucket = bucket_name();
+    let client = s3_client();
+    let prefix = unique_prefix("avatars", user_id);
+
+    let (client, bucket) = (&client, bucket.as_str());
+    store_avatar_once(
+        &prefix,
+        content_type,
+        bytes,
+        thumbnail,
+        current,
+        move |key, ct, body| put_object(client, bucket, key, ct, body),
+    )
+    .await
+}
+
+/// Upload one object for record `id` under `prefix` and return its key.
+///
+/// Objects are stored at `{prefix}/{id}/{uuid}`, e.g.
+/// `products/42/9b1d…`, so a replacement never overwrites an object that a
+/// cached URL may still point to. Only the key is persisted; use
+/// [`presigned_url`] to get a URL the browser can load.
+pub async fn upload_object(
+    prefix: &str,
+    id: i64,
+    content_type: &str,
+    bytes: &[u8],
 ) -> Result<String, String> {
     let bucket = bucket_name();
+    let client = s3_client();
+    let key = unique_prefix(prefix, id);
+
+    let (client, bucket) = (&client, bucket.as_str());
+    store_object(key, content_type, bytes, move |key, ct, body| {
+        put_object(client, bucket, key, ct, body)
+    })
+    .await
+}
+
+/// A fresh `{prefix}/{id}/{uuid}` key prefix.
+fn unique_prefix(prefix: &str, id: i64) -> String {
+    format!("{}/{}/{}", prefix, id, uuid::Uuid::new_v4())
+}
+
+/// Reject content types other than [`IMAGE_CONTENT_TYPES`].
+fn check_content_type(content_type: &str) -> Result<(), String> {
+    if !IMAGE_CONTENT_TYPES.contains(&content_type) {
+        return Err(format!("Unsupported content type: {}", content_type));
+    }
+    Ok(())
+}
+
+/// Write `bytes` at `key` using `put`.
+async fn store_object<F, Fut>(
+    key: String,
+    content_type: &str,
+    bytes: &[u8],
+    put: F,
+) -> Result<String, String>
+where
+    F: Fn(String, String, Vec<u8>) -> Fut,
+    Fut: std::future::Future<Output = Result<(), String>>,
+{
+    check_content_type(content_type)?;
+    put(key.clone(), content_type.to_string(), bytes.to_vec()).await?;
+    Ok(key)
+}
+
+/// [`store_avatar`], skipped when `bytes` hash the same as `current`.
+async fn store_avatar_once<F, Fut>(
+    prefix: &str,
+    content_type: &str,
+    bytes: &[u8],
+    thumbnail: &[u8],
+    current: &StoredAvatar,
+    put: F,
+) -> Result<AvatarUpload, String>
+where
+    F: Fn(String, String, Vec<u8>) -> Fut,
+    Fut: std::future::Future<Output = Result<(), String>>,
+{
+    let hash = content_hash(bytes);
+    if let Some(keys) = current.keys_for(&hash) {
+        return Ok(AvatarUpload {
+            keys,
+            hash,
+            reused: true,
+        });
+    }
 
-    let ext = match content_type {
-        "image/jpeg" => "jpg",
-        "image/png" => "png",
-        "image/webp" => "webp",
-        _ => return Err(format!("Unsupported content type: {}", content_type)),
+    let keys = store_avatar(prefix, content_type, bytes, thumbnail, put).await?;
+    Ok(AvatarUpload {
+        keys,
+        hash,
+        reused: false,
+    })
+}
+
+/// Write the original and thumbnail objects under `prefix` using `put`.
+async fn store_avatar<F, Fut>(
+    prefix: &str,
+    content_type: &str,
+    bytes: &[u8],
+    thumbnail: &[u8],
+    put: F,
+) -> Result<AvatarKeys, String>
+where
+    F: Fn(String, String, Vec<u8>) -> Fut,
+    Fut: std::future::Future<Output = Result<(), String>>,
+{
+    check_content_type(content_type)?;
+
+    let keys = AvatarKeys {
+        original: format!("{}/original", prefix),
+        thumbnail: format!("{}/thumb", prefix),
     };
 
-    let file_id = uuid::Uuid::new_v4();
-    let key = format!("{}/{}.{}", user_id, file_id, ext);
+    put(
+        keys.original.clone(),
+        content_type.to_string(),
+        bytes.to_vec(),
+    )
+    .await?;
+    put(
+        keys.thumbnail.clone(),
+        "image/png".to_string(),
+        thumbnail.to_vec(),
+    )
+    .await?;
 
-    let client = s3_client();
+    Ok(keys)
+}
+
+async fn put_object(
+    client: &Client,
+    bucket: &str,
+    key: String,
+    content_type: String,
+    body: Vec<u8>,
+) -> Result<(), String> {
     client
         .put_object()
-        .bucket(&bucket)
-        .key(&key)
+        .bucket(bucket)
+        .key(key)
         .content_type(content_type)
-        .body(ByteStream::from(bytes.to_vec()))
+        .body(ByteStream::from(body))
         .send()
         .await
         .map_err(|e| format!("S3 upload failed: {}", e))?;
+    Ok(())
+}
+
+/// Whether a stored avatar value is an S3 object key rather than an absolute URL.
+fn is_object_key(value: &str) -> bool {
+    !(value.starts_with("http://") || value.starts_with("https://"))
+}
+
+/// Delete an object from the bucket.
+pub async fn delete_object(key: &str) -> Result<(), String> {
+    s3_client()
+        .delete_object()
+        .bucket(bucket_name())
+        .key(key)
+        .send()
+        .await
+        .map_err(|e| format!("S3 delete failed: {}", e))?;
+    Ok(())
+}
+
+/// Remove the objects of an avatar or product image that has just been
+/// replaced.
+///
+/// Missing keys (first upload) and absolute URLs (OAuth provider avatars) are
+/// skipped. Failures are logged rather than returned so a successful upload
+/// isn't reported as failed because cleanup didn't go through.
+pub async fn delete_replaced_objects(previous: &[Option<String>]) {
+    remove_objects(previous, |key| async move { delete_object(&key).await }).await
+}
 
-    Ok(public_url(&bucket, &key))
+async fn remove_objects<F, Fut>(keys: &[Option<String>], delete: F)
+where
+    F: Fn(String) -> Fut,
+    Fut: std::future::Future<Output = Result<(), String>>,
+{
+    for key in keys.iter().flatten().filter(|k| is_object_key(k)) {
+        if let Err(e) = delete(key.clone()).await {
+            tracing::warn!("Failed to delete replaced object '{}': {}", key, e);
+        }
+    }
+}
+
+/// Lifetime of presigned URLs, from `S3_PRESIGN_TTL_SECS` (default 15 minutes).
+pub fn presign_ttl() -> Duration {
+    let secs = std::env::var("S3_PRESIGN_TTL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_PRESIGN_TTL_SECS);
+    Duration::from_secs(secs)
+}
+
+/// Return a short-lived signed GET URL for a stored object.
+///
+/// `stored` is the value of a column such as `users.avatar_url` or
+/// `products.image_url`: normally an object key, but absolute URLs (OAuth
+/// provider avatars, rows written before keys were stored) are returned
+/// unchanged.
+pub async fn presigned_url(stored: &str, ttl: Duration) -> Result<String, String> {
+    if !is_object_key(stored) {
+        return Ok(stored.to_string());
+    }
+    presign_get(&s3_client(), &bucket_name(), stored, ttl).await
+}
+
+/// Presign a GET request for `key` in `bucket`.
+async fn presign_get(
+    client: &Client,
+    bucket: &str,
+    key: &str,
+    ttl: Duration,
+) -> Result<String, String> {
+    let config =
+        PresigningConfig::expires_in(ttl).map_err(|e| format!("Invalid presign TTL: {}", e))?;
+
+    let request = client
+        .get_object()
+        .bucket(bucket)
+        .key(key)
+        .presigned(config)
+        .await
+        .map_err(|e| format!("S3 presign failed: {}", e))?;
+
+    Ok(request.uri().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn presigned_url_targets_key_on_endpoint() {
+        let client = build_client(
+            "http://mock-s3.local:9000",
+            "test-key",
+            "test-secret",
+            "us-east-1",
+        );
+        let url = presign_get(&client, "avatars", "42/abc.png", Duration::from_secs(300))
+            .await
+            .unwrap();
+
+        assert!(url.starts_with("http://mock-s3.local:9000/avatars/42/abc.png?"));
+        assert!(url.contains("X-Amz-Expires=300"));
+        assert!(url.contains("X-Amz-Signature="));
+        assert!(url.contains("X-Amz-Credential=test-key"));
+    }
+
+    fn sample_png(width: u32, height: u32) -> Vec<u8> {
+        let img = image::RgbImage::from_fn(width, height, |x, y| {
+            image::Rgb([(x % 256) as u8, (y % 256) as u8, 128])
+        });
+        let mut out = std::io::Cursor::new(Vec::new());
+        img.write_to(&mut out, image::ImageFormat::Png).unwrap();
+        out.into_inner()
+    }
+
+    #[test]
+    fn thumbnail_is_square_center_crop() {
+        let thumb = make_thumbnail(&sample_png(300, 150)).unwrap();
+        let decoded = image::load_from_memory(&thumb).unwrap();
+        assert_eq!(decoded.width(), THUMBNAIL_SIZE);
+        assert_eq!(decoded.height(), THUMBNAIL_SIZE);
+    }
+
+    #[test]
+    fn thumbnail_rejects_non_images() {
+        assert!(make_thumbnail(b"not an image").is_err());
+    }
+
+    #[test]
+    fn thumbnail_rejects_oversized_dimensions() {
+        let wide = sample_png(MAX_IMAGE_DIMENSION + 1, 1);
+        assert!(make_thumbnail(&wide).is_err());
+        assert!(make_thumbnail(&sample_png(MAX_IMAGE_DIMENSION, 1)).is_ok());
+    }
+
+    #[tokio::test]
+    async fn thumbnail_can_be_made_off_the_runtime() {
+        let thumb = make_thumbnail_async(&sample_png(64, 32)).await.unwrap();
+        assert_eq!(
+            image::load_from_memory(&thumb).unwrap().width(),
+            THUMBNAIL_SIZE
+        );
+        assert!(make_thumbnail_async(b"not an image").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn store_avatar_writes_original_and_thumbnail() {
+        use std::sync::Mutex;
+
+        let original = sample_png(64, 64);
+        let thumb = make_thumbnail(&original).unwrap();
+        let written: Mutex<Vec<(String, String, usize)>> = Mutex::new(Vec::new());
+
+        let keys = store_avatar(
+            "avatars/7/abc",
+            "image/png",
+            &original,
+            &thumb,
+            |k, ct, b| {
+                written.lock().unwrap().push((k, ct, b.len()));
+                async { Ok(()) }
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(keys.original, "avatars/7/abc/original");
+        assert_eq!(keys.thumbnail, "avatars/7/abc/thumb");
+
+        let written = written.into_inner().unwrap();
+        assert_eq!(
+            written,
+            vec![
+                (
+                    keys.original.clone(),
+                    "image/png".to_string(),
+                    original.len()
+                ),
+                (keys.thumbnail.clone(), "image/png".to_string(), thumb.len()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn store_object_writes_key_and_rejects_other_types() {
+        use std::sync::Mutex;
+
+        let written: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+        let put = |k: String, ct: String, _b: Vec<u8>| {
+            written.lock().unwrap().push((k, ct));
+            async { Ok(()) }
+        };
+
+        let key = store_object("products/7/abc".to_string(), "image/webp", b"img", put)
+            .await
+            .unwrap();
+        assert_eq!(key, "products/7/abc");
+        assert!(
+            store_object("products/7/def".to_string(), "image/gif", b"img", put)
+                .await
+                .is_err()
+        );
+        assert_eq!(
+            written.into_inner().unwrap(),
+            vec![("products/7/abc".to_string(), "image/webp".to_string())]
+        );
+    }
+
+    #[test]
+    fn unique_prefix_nests_id_under_prefix() {
+        let prefix = unique_prefix("products", 42);
+        assert!(prefix.starts_with("products/42/"), "{prefix}");
+        assert_ne!(prefix, unique_prefix("products", 42));
+    }
+
+    #[tokio::test]
+    async fn cleanup_skips_missing_keys_urls_and_tolerates_errors() {
+        use std::sync::Mutex;
+
+        let attempted = Mutex::new(Vec::new());
+        remove_objects(
+            &[
+                None,
+                Some("https://avatars.githubusercontent.com/u/1".to_string()),
+                Some("avatars/7/old/original".to_string()),
+            ],
+            |k| {
+                attempted.lock().unwrap().push(k);
+                async { Err("boom".to_string()) }
+            },
+        )
+        .await;
+
+        assert_eq!(
+            attempted.into_inner().unwrap(),
+            vec!["avatars/7/old/original".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn identical_reupload_writes_nothing() {
+        use std::sync::Mutex;
+
+        let original = sample_png(32, 32);
+        let thumb = make_thumbnail(&original).unwrap();
+        let puts = Mutex::new(Vec::new());
+        let put = |k: String, _ct: String, _b: Vec<u8>| {
+            puts.lock().unwrap().push(k);
+            async { Ok(()) }
+        };
+
+        let first = store_avatar_once(
+            "avatars/7/first",
+            "image/png",
+            &original,
+            &thumb,
+            &StoredAvatar::default(),
+            put,
+        )
+        .await
+        .unwrap();
+        assert!(!first.reused);
+
+        let current = StoredAvatar {
+            original: Some(first.keys.original.clone()),
+            thumbnail: Some(first.keys.thumbnail.clone()),
+            hash: Some(first.hash.clone()),
+        };
+        let second = store_avatar_once(
+            "avatars/7/second",
+            "image/png",
+            &original,
+            &thumb,
+            &current,
+            put,
+        )
+        .await
+        .unwrap();
+
+        assert!(second.reused);
+        assert_eq!(second.keys, first.keys);
+        assert_eq!(
+            puts.into_inner().unwrap(),
+            vec![first.keys.original, first.keys.thumbnail],
+            "the second upload should not touch storage"
+        );
+    }
+
+    #[tokio::test]
+    async fn changed_bytes_or_provider_avatar_are_uploaded() {
+        let original = sample_png(32, 32);
+        let thumb = make_thumbnail(&original).unwrap();
+        let put = |_k: String, _ct: String, _b: Vec<u8>| async { Ok(()) };
+
+        let different = StoredAvatar {
+            original: Some("avatars/7/old/original".to_string()),
+            thumbnail: Some("avatars/7/old/thumb".to_string()),
+            hash: Some(content_hash(b"some other image")),
+        };
+        let provider = StoredAvatar {
+            original: Some("https://avatars.githubusercontent.com/u/1".to_string()),
+            thumbnail: None,
+            hash: Some(content_hash(&original)),
+        };
+        for current in [different, provider] {
+            let upload = store_avatar_once(
+                "avatars/7/new",
+                "image/png",
+                &original,
+                &thumb,
+                &current,
+                put,
+            )
+            .await
+            .unwrap();
+            assert!(!upload.reused);
+            assert_eq!(upload.keys.original, "avatars/7/new/original");
+        }
+    }
+
+    #[test]
+    fn content_hash_is_hex_sha256() {
+        assert_eq!(
+            content_hash(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[tokio::test]
+    async fn absolute_avatar_urls_pass_through() {
+        let url = "https://avatars.githubusercontent.com/u/1";
+        let resolved = presigned_url(url, Duration::from_secs(60)).await.unwrap();
+        assert_eq!(resolved, url);
+    }
 }
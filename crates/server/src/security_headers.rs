@@ -0,0 +1,311 @@
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+
+/// Default Content-Security-Policy for app pages.
+///
+/// Routes rely on inline `style` attributes and the Dioxus runtime injects
+/// inline scripts and evaluates JS, so `'unsafe-inline'`/`'unsafe-eval'` are
+/// allowed until a stricter policy is configured.
+pub const DEFAULT_CSP: &str = "default-src 'self'; \
+    script-src 'self' 'unsafe-inline' 'unsafe-eval' 'wasm-unsafe-eval'; \
+    style-src 'self' 'unsafe-inline'; \
+    img-src 'self' data: blob: https:; \
+    font-src 'self' data:; \
+    connect-src 'self' ws: wss:; \
+    frame-ancestors 'none'; \
+    base-uri 'self'; \
+    form-action 'self'";
+
+/// Placeholder in the CSP template that is replaced with the per-request nonce.
+pub const NONCE_PLACEHOLDER: &str = "{nonce}";
+
+/// Default HSTS max-age (one year).
+const DEFAULT_HSTS_MAX_AGE: u64 = 31_536_000;
+
+/// Per-request CSP nonce, stored in request extensions.
+///
+/// Server-rendered components read it through [`current_nonce`] and attach it
+/// to inline `<script>` tags so a `script-src 'nonce-{nonce}'` policy can be
+/// used without `'unsafe-inline'`.
+#[derive(Clone, Debug)]
+pub struct CspNonce(pub String);
+
+impl CspNonce {
+    /// Generate a fresh random nonce (128 bits, hex-encoded).
+    pub fn generate() -> Self {
+        Self(uuid::Uuid::new_v4().simple().to_string())
+    }
+}
+
+/// Read the CSP nonce for the request currently being rendered.
+///
+/// Returns `None` outside of a request or when the middleware isn't installed.
+pub fn current_nonce() -> Option<String> {
+    let ctx = dioxus::fullstack::FullstackContext::current()?;
+    let parts = ctx.parts_mut();
+    parts.extensions.get::<CspNonce>().map(|n| n.0.clone())
+}
+
+/// Security header settings, loaded once at startup.
+#[derive(Clone, Debug)]
+pub struct SecurityHeadersConfig {
+    /// CSP template for HTML responses; `{nonce}` is substituted per request.
+    pub content_security_policy: Option<String>,
+    pub hsts_max_age: u64,
+}
+
+impl Default for SecurityHeadersConfig {
+    fn default() -> Self {
+        Self {
+            content_security_policy: Some(DEFAULT_CSP.to_string()),
+            hsts_max_age: DEFAULT_HSTS_MAX_AGE,
+        }
+    }
+}
+
+impl SecurityHeadersConfig {
+    /// Load from the environment.
+    ///
+    /// - `CONTENT_SECURITY_POLICY` overrides [`DEFAULT_CSP`]; an empty value
+    ///   disables the header. Use `{nonce}` (e.g. `script-src 'nonce-{nonce}'`)
+    ///   for a strict nonce-based policy.
+    /// - `HSTS_MAX_AGE` sets the HSTS max-age in seconds.
+    pub fn from_env() -> Self {
+        let content_security_policy = match std::env::var("CONTENT_SECURITY_POLICY") {
+            Ok(csp) if csp.trim().is_empty() => None,
+            Ok(csp) => Some(csp),
+            Err(_) => Some(DEFAULT_CSP.to_string()),
+        };
+        let hsts_max_age = std::env::var("HSTS_MAX_AGE")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_HSTS_MAX_AGE);
+
+        Self {
+            content_security_policy,
+            hsts_max_age,
+        }
+    }
+}
+
+/// Whether the request arrived over TLS, either directly or via a
+/// TLS-terminating proxy that sets `X-Forwarded-Proto`.
+fn is_tls(req: &Request) -> bool {
+    req.uri().scheme_str() == Some("https")
+        || req
+            .headers()
+            .get("x-forwarded-proto")
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|proto| proto.eq_ignore_ascii_case("https"))
+}
+
+fn is_html(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("text/html"))
+}
+
+/// Insert a header unless the handler already set one.
+fn set_default(headers: &mut HeaderMap, name: header::HeaderName, value: HeaderValue) {
+    headers.entry(name).or_insert(value);
+}
+
+/// Middleware that adds security headers to every response.
+///
+/// - `X-Content-Type-Options`, `Referrer-Policy`, and `X-Frame-Options` on all responses
+/// - `Strict-Transport-Security` only when the request came in over TLS
+/// - `Content-Security-Policy` on HTML responses, except the `/docs` UI
+///   which loads its own scripts from a CDN
+///
+/// A fresh [`CspNonce`] is inserted into the request extensions before the
+/// handler runs and substituted into the CSP template.
+pub async fn security_headers(
+    State(config): State<SecurityHeadersConfig>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let tls = is_tls(&req);
+    let is_docs = req.uri().path().starts_with("/docs");
+    let nonce = CspNonce::generate();
+    req.extensions_mut().insert(nonce.clone());
+
+    let mut response = next.run(req).await;
+    let html = is_html(response.headers());
+    let headers = response.headers_mut();
+
+    set_default(
+        headers,
+        header::X_CONTENT_TYPE_OPTIONS,
+        HeaderValue::from_static("nosniff"),
+    );
+    set_default(
+        headers,
+        header::REFERRER_POLICY,
+        HeaderValue::from_static("strict-origin-when-cross-origin"),
+    );
+    set_default(
+        headers,
+        header::X_FRAME_OPTIONS,
+        HeaderValue::from_static("DENY"),
+    );
+
+    if tls {
+        if let Ok(value) = HeaderValue::from_str(&format!(
+            "max-age={}; includeSubDomains",
+            config.hsts_max_age
+        )) {
+            set_default(headers, header::STRICT_TRANSPORT_SECURITY, value);
+        }
+    }
+
+    if html && !is_docs {
+        if let Some(value) = config
+            .content_security_policy
+            .as_deref()
+            .map(|csp| csp.replace(NONCE_PLACEHOLDER, &nonce.0))
+            .and_then(|csp| HeaderValue::from_str(&csp).ok())
+        {
+            set_default(headers, header::CONTENT_SECURITY_POLICY, value);
+        }
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::StatusCode, response::Html, routing::get, Json, Router};
+    use dioxus::prelude::*;
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        app_with(SecurityHeadersConfig::default())
+    }
+
+    fn app_with(config: SecurityHeadersConfig) -> Router {
+        Router::new()
+            .route("/", get(|| async { Html("<html><body>app</body></html>") }))
+            .route(
+                "/docs",
+                get(|| async { Html("<html><body>docs</body></html>") }),
+            )
+            .route("/api/ping", get(|| async { Json("pong") }))
+            .layer(axum::middleware::from_fn_with_state(
+                config,
+                security_headers,
+            ))
+    }
+
+    async fn send(uri: &str, forwarded_proto: Option<&str>) -> Response {
+        send_to(app(), uri, forwarded_proto).await
+    }
+
+    async fn send_to(app: Router, uri: &str, forwarded_proto: Option<&str>) -> Response {
+        let mut builder = Request::builder().uri(uri);
+        if let Some(proto) = forwarded_proto {
+            builder = builder.header("x-forwarded-proto", proto);
+        }
+        app.oneshot(builder.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn app_response_has_security_headers() {
+        let response = send("/", None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let headers = response.headers();
+        assert_eq!(headers[header::X_CONTENT_TYPE_OPTIONS], "nosniff");
+        assert_eq!(
+            headers[header::REFERRER_POLICY],
+            "strict-origin-when-cross-origin"
+        );
+        assert_eq!(headers[header::X_FRAME_OPTIONS], "DENY");
+        assert!(headers.contains_key(header::CONTENT_SECURITY_POLICY));
+        assert!(!headers.contains_key(header::STRICT_TRANSPORT_SECURITY));
+    }
+
+    #[tokio::test]
+    async fn hsts_set_behind_tls_proxy() {
+        let response = send("/", Some("https")).await;
+        let hsts = response.headers()[header::STRICT_TRANSPORT_SECURITY]
+            .to_str()
+            .unwrap();
+        assert!(hsts.starts_with("max-age="));
+    }
+
+    #[tokio::test]
+    async fn csp_skipped_for_json_and_docs() {
+        let response = send("/api/ping", None).await;
+        assert!(!response
+            .headers()
+            .contains_key(header::CONTENT_SECURITY_POLICY));
+        assert_eq!(
+            response.headers()[header::X_CONTENT_TYPE_OPTIONS],
+            "nosniff"
+        );
+
+        let response = send("/docs", None).await;
+        assert!(!response
+            .headers()
+            .contains_key(header::CONTENT_SECURITY_POLICY));
+    }
+
+    /// Renders an inline script with the request's nonce, read the way the
+    /// app's root component reads it.
+    #[component]
+    fn NoncedPage() -> Element {
+        let nonce = use_server_cached(current_nonce).unwrap_or_default();
+        rsx! {
+            script { nonce: "{nonce}", "seed()" }
+        }
+    }
+
+    #[tokio::test]
+    async fn csp_nonce_matches_injected_script() {
+        // Rendered by the same SSR handler as the app's pages
+        use dioxus::server::{DioxusRouterExt, ServeConfig};
+        let app = Router::new()
+            .serve_api_application(ServeConfig::new(), NoncedPage)
+            .layer(axum::middleware::from_fn_with_state(
+                SecurityHeadersConfig {
+                    content_security_policy: Some("script-src 'self' 'nonce-{nonce}'".to_string()),
+                    ..Default::default()
+                },
+                security_headers,
+            ));
+        let response = send_to(app, "/", None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let csp = response.headers()[header::CONTENT_SECURITY_POLICY]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let nonce = csp
+            .split("'nonce-")
+            .nth(1)
+            .and_then(|rest| rest.split('\'').next())
+            .unwrap()
+            .to_string();
+        assert!(!nonce.is_empty());
+        assert!(!csp.contains(NONCE_PLACEHOLDER));
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains(&format!("nonce=\"{nonce}\"")));
+    }
+
+    #[test]
+    fn nonces_are_unique() {
+        assert_ne!(CspNonce::generate().0, CspNonce::generate().0);
+    }
+}
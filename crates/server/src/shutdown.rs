@@ -0,0 +1,249 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use shared_types::AppError;
+use sqlx::PgPool;
+use tokio::sync::Notify;
+
+/// Grace period for in-flight requests when `SHUTDOWN_TIMEOUT_SECS` is unset.
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+
+/// Seconds a client turned away during shutdown is asked to wait; by then
+/// the load balancer should be routing to another instance.
+const RETRY_AFTER_SECS: u64 = 1;
+
+/// How long to wait for in-flight requests once a shutdown signal arrives.
+///
+/// Read from `SHUTDOWN_TIMEOUT_SECS`; unset or unparseable means
+/// [`DEFAULT_TIMEOUT_SECS`], and `0` skips waiting.
+pub fn shutdown_timeout() -> Duration {
+    parse_timeout(std::env::var("SHUTDOWN_TIMEOUT_SECS").ok().as_deref())
+}
+
+fn parse_timeout(value: Option<&str>) -> Duration {
+    let secs = value
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_TIMEOUT_SECS);
+    Duration::from_secs(secs)
+}
+
+#[derive(Debug, Default)]
+struct DrainState {
+    draining: AtomicBool,
+    in_flight: AtomicUsize,
+    idle: Notify,
+}
+
+/// Tracks requests in flight so shutdown can wait for them, and turns new
+/// ones away once it has started.
+#[derive(Clone, Debug, Default)]
+pub struct Drain {
+    state: Arc<DrainState>,
+}
+
+/// Counts one request as in flight until dropped.
+struct InFlight(Arc<DrainState>);
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        if self.0.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.idle.notify_waiters();
+        }
+    }
+}
+
+impl Drain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop accepting requests. Those already running are left to finish.
+    pub fn start(&self) {
+        self.state.draining.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.state.draining.load(Ordering::SeqCst)
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.state.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Wait until no requests are in flight, for at most `timeout`.
+    /// Returns whether every request finished in time.
+    pub async fn wait_idle(&self, timeout: Duration) -> bool {
+        tokio::time::timeout(timeout, async {
+            loop {
+                let idle = self.state.idle.notified();
+                if self.in_flight() == 0 {
+                    return;
+                }
+                idle.await;
+            }
+        })
+        .await
+        .is_ok()
+    }
+
+    fn enter(&self) -> InFlight {
+        self.state.in_flight.fetch_add(1, Ordering::SeqCst);
+        InFlight(self.state.clone())
+    }
+}
+
+/// Middleware that counts requests for [`Drain`] and, once shutdown has
+/// started, answers new ones with a 503, `Retry-After` and
+/// `Connection: close` so clients reconnect elsewhere.
+pub async fn track_requests(State(drain): State<Drain>, req: Request, next: Next) -> Response {
+    if drain.is_draining() {
+        let mut response =
+            AppError::service_unavailable("The server is shutting down. Please try again shortly.")
+                .into_response();
+        let headers = response.headers_mut();
+        headers.insert(header::RETRY_AFTER, HeaderValue::from(RETRY_AFTER_SECS));
+        headers.insert(header::CONNECTION, HeaderValue::from_static("close"));
+        return response;
+    }
+
+    let _in_flight = drain.enter();
+    next.run(req).await
+}
+
+/// Resolve on SIGINT (Ctrl-C) or, on Unix, SIGTERM.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!(error = %e, "failed to listen for Ctrl-C");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "failed to listen for SIGTERM");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+/// Spawn the task that shuts the server down cleanly on SIGTERM/SIGINT.
+///
+/// New requests are refused, in-flight ones get up to
+/// [`shutdown_timeout`] to finish, buffered spans are flushed to the
+/// collector and the pool's connections are closed before the process exits.
+pub fn spawn_shutdown_handler(drain: Drain, pool: PgPool) {
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        let timeout = shutdown_timeout();
+        tracing::info!(
+            in_flight = drain.in_flight(),
+            timeout_secs = timeout.as_secs(),
+            "shutdown signal received, draining requests"
+        );
+
+        drain.start();
+        if !drain.wait_idle(timeout).await {
+            tracing::warn!(
+                in_flight = drain.in_flight(),
+                "shutdown timeout elapsed with requests still in flight"
+            );
+        }
+
+        crate::telemetry::shutdown().await;
+        pool.close().await;
+        tracing::info!("shutdown complete");
+        std::process::exit(0);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::StatusCode, routing::get, Router};
+    use tokio::sync::mpsc;
+    use tower::ServiceExt;
+
+    fn app(drain: Drain, started: mpsc::Sender<()>, release: Arc<Notify>) -> Router {
+        Router::new()
+            .route(
+                "/slow",
+                get(move || {
+                    let (started, release) = (started.clone(), release.clone());
+                    async move {
+                        started.send(()).await.unwrap();
+                        release.notified().await;
+                        "done"
+                    }
+                }),
+            )
+            .route("/fast", get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(drain, track_requests))
+    }
+
+    async fn send(app: &Router, uri: &str) -> Response {
+        app.clone()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn draining_refuses_new_requests_and_waits_for_running_ones() {
+        let drain = Drain::new();
+        let (started, mut running) = mpsc::channel(1);
+        let release = Arc::new(Notify::new());
+        let app = app(drain.clone(), started, release.clone());
+
+        let in_flight = tokio::spawn({
+            let app = app.clone();
+            async move { send(&app, "/slow").await.status() }
+        });
+        running.recv().await.unwrap();
+        assert_eq!(drain.in_flight(), 1);
+
+        drain.start();
+        let refused = send(&app, "/fast").await;
+        assert_eq!(refused.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(refused.headers()[header::CONNECTION], "close");
+        assert!(!drain.wait_idle(Duration::from_millis(20)).await);
+
+        release.notify_one();
+        assert!(drain.wait_idle(Duration::from_secs(5)).await);
+        assert_eq!(in_flight.await.unwrap(), StatusCode::OK);
+        assert_eq!(drain.in_flight(), 0);
+    }
+
+    #[test]
+    fn timeout_defaults_when_unset_or_invalid() {
+        let default = Duration::from_secs(DEFAULT_TIMEOUT_SECS);
+        assert_eq!(parse_timeout(None), default);
+        assert_eq!(parse_timeout(Some("soon")), default);
+        assert_eq!(parse_timeout(Some(" 30 ")), Duration::from_secs(30));
+        assert_eq!(parse_timeout(Some("0")), Duration::ZERO);
+    }
+}
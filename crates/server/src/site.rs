@@ -0,0 +1,189 @@
+use axum::{extract::State, http::header, response::IntoResponse, routing::get, Router};
+
+use crate::db::AppState;
+
+/// Public origin used when `PUBLIC_BASE_URL` is unset.
+const DEFAULT_BASE_URL: &str = "http://localhost:8080";
+
+/// Pages anyone can open without signing in. Mirrors the app routes outside
+/// its auth guard; everything else redirects to `/login` for crawlers.
+pub const PUBLIC_PATHS: &[&str] = &["/login", "/register"];
+
+/// Paths disallowed in `robots.txt` by default: the API and the pages that
+/// require signing in.
+const DEFAULT_ROBOTS_DISALLOW: &[&str] =
+    &["/api/", "/users", "/products", "/analytics", "/settings"];
+
+/// Public site settings for `sitemap.xml`, `robots.txt`, and absolute links.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SiteConfig {
+    /// Origin the site is served from, without a trailing slash.
+    pub base_url: String,
+    /// `Allow` lines in `robots.txt`.
+    pub robots_allow: Vec<String>,
+    /// `Disallow` lines in `robots.txt`.
+    pub robots_disallow: Vec<String>,
+}
+
+impl Default for SiteConfig {
+    fn default() -> Self {
+        Self {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            robots_allow: vec!["/".to_string()],
+            robots_disallow: DEFAULT_ROBOTS_DISALLOW
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl SiteConfig {
+    /// Build settings from environment variables.
+    ///
+    /// - `PUBLIC_BASE_URL` sets the site origin (default `http://localhost:8080`).
+    /// - `ROBOTS_ALLOW` and `ROBOTS_DISALLOW` are comma-separated path lists
+    ///   that replace the defaults; set one to an empty string for no lines.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            base_url: std::env::var("PUBLIC_BASE_URL")
+                .map(|v| v.trim().trim_end_matches('/').to_string())
+                .unwrap_or(defaults.base_url),
+            robots_allow: std::env::var("ROBOTS_ALLOW")
+                .map(|v| parse_paths(&v))
+                .unwrap_or(defaults.robots_allow),
+            robots_disallow: std::env::var("ROBOTS_DISALLOW")
+                .map(|v| parse_paths(&v))
+                .unwrap_or(defaults.robots_disallow),
+        }
+    }
+
+    /// Absolute URL for `path` on this site.
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+}
+
+/// Split a comma-separated path list, dropping empty entries.
+fn parse_paths(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Escape text for an XML element.
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Sitemap listing every public page.
+pub fn render_sitemap(site: &SiteConfig) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+    for path in PUBLIC_PATHS {
+        xml.push_str(&format!(
+            "  <url><loc>{}</loc></url>\n",
+            xml_escape(&site.url(path))
+        ));
+    }
+    xml.push_str("</urlset>\n");
+    xml
+}
+
+/// `robots.txt` with the configured rules and a link to the sitemap.
+pub fn render_robots(site: &SiteConfig) -> String {
+    let mut robots = String::from("User-agent: *\n");
+    for path in &site.robots_allow {
+        robots.push_str(&format!("Allow: {path}\n"));
+    }
+    for path in &site.robots_disallow {
+        robots.push_str(&format!("Disallow: {path}\n"));
+    }
+    robots.push_str(&format!("\nSitemap: {}\n", site.url("/sitemap.xml")));
+    robots
+}
+
+/// `GET /sitemap.xml`
+pub async fn sitemap(State(site): State<SiteConfig>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "application/xml; charset=utf-8")],
+        render_sitemap(&site),
+    )
+}
+
+/// `GET /robots.txt`
+pub async fn robots(State(site): State<SiteConfig>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+        render_robots(&site),
+    )
+}
+
+/// Routes for `/sitemap.xml` and `/robots.txt`.
+pub fn site_router() -> Router<AppState> {
+    Router::new()
+        .route("/sitemap.xml", get(sitemap))
+        .route("/robots.txt", get(robots))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn site() -> SiteConfig {
+        SiteConfig {
+            base_url: "https://app.example.com".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sitemap_lists_public_pages_only() {
+        let xml = render_sitemap(&site());
+        assert!(xml.contains("<loc>https://app.example.com/login</loc>"));
+        assert!(xml.contains("<loc>https://app.example.com/register</loc>"));
+        assert!(!xml.contains("/settings"));
+        assert!(!xml.contains("/users"));
+    }
+
+    #[test]
+    fn robots_uses_configured_rules() {
+        let robots = render_robots(&SiteConfig {
+            robots_allow: parse_paths("/, /login"),
+            robots_disallow: parse_paths("/private"),
+            ..site()
+        });
+        assert_eq!(
+            robots,
+            "User-agent: *\nAllow: /\nAllow: /login\nDisallow: /private\n\n\
+             Sitemap: https://app.example.com/sitemap.xml\n"
+        );
+    }
+
+    #[test]
+    fn robots_disallows_signed_in_pages_by_default() {
+        let robots = render_robots(&site());
+        for path in DEFAULT_ROBOTS_DISALLOW {
+            assert!(robots.contains(&format!("Disallow: {path}\n")));
+        }
+    }
+
+    #[test]
+    fn base_url_is_escaped_in_sitemap() {
+        let xml = render_sitemap(&SiteConfig {
+            base_url: "https://example.com/a&b".to_string(),
+            ..Default::default()
+        });
+        assert!(xml.contains("https://example.com/a&amp;b/login"));
+    }
+}
@@ -1,21 +1,29 @@
 use axum::{body::Body, http::Request, response::Response};
 use opentelemetry::{
     global,
-    trace::{SpanKind, TraceContextExt, Tracer},
+    trace::{FutureExt, SpanKind, Status, TraceContextExt, Tracer},
     Context, KeyValue,
 };
 use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::SdkTracerProvider;
 use std::{
     future::Future,
     pin::Pin,
+    sync::OnceLock,
     task::{Context as TaskContext, Poll},
+    time::Instant,
 };
 use tower::{Layer, Service};
+use tracing::Instrument;
 
 use crate::auth::jwt::Claims;
 
 const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// The provider registered by [`init_telemetry`], kept so [`shutdown`] can
+/// flush it. The global registry only hands out tracers.
+static PROVIDER: OnceLock<SdkTracerProvider> = OnceLock::new();
+
 /// Set up the OpenTelemetry TracerProvider and register it globally.
 ///
 /// Must be called inside a Tokio runtime (the tonic exporter needs it).
@@ -51,8 +59,7 @@ pub fn init_telemetry() {
     // Enable TLS with system root certs for HTTPS endpoints (e.g. SigNoz Cloud)
     if endpoint.starts_with("https://") {
         builder = builder.with_tls_config(
-            opentelemetry_otlp::tonic_types::transport::ClientTlsConfig::new()
-                .with_native_roots(),
+            opentelemetry_otlp::tonic_types::transport::ClientTlsConfig::new().with_native_roots(),
         );
     }
 
@@ -76,11 +83,12 @@ pub fn init_telemetry() {
         .with_attribute(KeyValue::new("deployment.environment", environment))
         .build();
 
-    let provider = opentelemetry_sdk::trace::SdkTracerProvider::builder()
+    let provider = SdkTracerProvider::builder()
         .with_batch_exporter(exporter)
         .with_resource(resource)
         .build();
 
+    let _ = PROVIDER.set(provider.clone());
     global::set_tracer_provider(provider);
 
     let mode = if std::env::var("SIGNOZ_INGESTION_KEY")
@@ -94,6 +102,28 @@ pub fn init_telemetry() {
     eprintln!("Telemetry initialized v{APP_VERSION} — exporting to {endpoint} ({mode})");
 }
 
+/// Export any spans still buffered by the batch exporter, then shut the
+/// provider down. Call once while the server is stopping; spans started
+/// afterwards are dropped. Does nothing when telemetry was never initialized.
+pub async fn shutdown() {
+    let Some(provider) = PROVIDER.get().cloned() else {
+        return;
+    };
+    // Both calls block until the exporter's background thread answers.
+    let result = tokio::task::spawn_blocking(move || {
+        if let Err(e) = provider.force_flush() {
+            eprintln!("Failed to flush telemetry: {e}");
+        }
+        provider.shutdown()
+    })
+    .await;
+    match result {
+        Ok(Ok(())) => eprintln!("Telemetry flushed and shut down"),
+        Ok(Err(e)) => eprintln!("Failed to shut down telemetry: {e}"),
+        Err(e) => eprintln!("Telemetry shutdown task failed: {e}"),
+    }
+}
+
 /// Detect client platform from User-Agent and optional X-Client-Platform header.
 ///
 /// Priority: explicit `X-Client-Platform` header > User-Agent heuristic.
@@ -128,10 +158,147 @@ fn detect_platform(ua: &str, explicit: Option<&str>) -> &'static str {
     }
 }
 
+/// Span attributes for an incoming request.
+///
+/// `client.ip` is resolved by [`crate::client_ip::client_ip`], so it only
+/// reflects forwarded headers set by a trusted proxy.
+///
+/// `http.request_id` is the `x-request-id` header as it reaches the trace
+/// layer — the client's own id when it sent a valid one (see
+/// [`crate::request_id`]), otherwise the one generated by `SetRequestIdLayer`.
+pub fn span_attributes(req: &Request<Body>) -> Vec<KeyValue> {
+    let user_agent = req
+        .headers()
+        .get("user-agent")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("unknown")
+        .to_string();
+    let explicit_platform = req
+        .headers()
+        .get("x-client-platform")
+        .and_then(|v| v.to_str().ok());
+    let client_platform = detect_platform(&user_agent, explicit_platform);
+
+    let request_id = req
+        .headers()
+        .get("x-request-id")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+
+    // Extract auth claims if the auth middleware already ran
+    let auth_attrs: Vec<KeyValue> = if let Some(claims) = req.extensions().get::<Claims>() {
+        vec![
+            KeyValue::new("user.id", claims.sub),
+            KeyValue::new("user.email", claims.email.clone()),
+            KeyValue::new("user.role", claims.role.clone()),
+            KeyValue::new("user.tier", claims.tier.clone()),
+            KeyValue::new("auth.status", "authenticated"),
+        ]
+    } else {
+        vec![KeyValue::new("auth.status", "anonymous")]
+    };
+
+    let mut attributes = vec![
+        KeyValue::new("http.method", req.method().to_string()),
+        KeyValue::new("http.target", req.uri().path().to_string()),
+        KeyValue::new("http.user_agent", user_agent),
+        KeyValue::new("client.platform", client_platform),
+        KeyValue::new("http.request_id", request_id),
+    ];
+    if let Some(ip) = crate::client_ip::client_ip(req.headers(), req.extensions()) {
+        attributes.push(KeyValue::new("client.ip", ip.to_string()));
+    }
+    attributes.extend(auth_attrs);
+    attributes
+}
+
+/// `tracing` span for a request, carrying the ids of its OpenTelemetry span.
+///
+/// Events logged while handling the request (including those from
+/// `#[tracing::instrument]` spans nested inside it) carry these fields, so
+/// JSON log lines can be matched to traces (see [`crate::logging`]).
+fn request_log_span(req: &Request<Body>, cx: &Context) -> tracing::Span {
+    let request_id = req
+        .headers()
+        .get("x-request-id")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    let span = tracing::info_span!(
+        "request",
+        request_id,
+        method = %req.method(),
+        path = req.uri().path(),
+        status = tracing::field::Empty,
+        user.id = tracing::field::Empty,
+        trace_id = tracing::field::Empty,
+        span_id = tracing::field::Empty,
+    );
+    if let Some(claims) = req.extensions().get::<Claims>() {
+        span.record("user.id", claims.sub);
+    }
+    let otel = cx.span().span_context().clone();
+    if otel.is_valid() {
+        span.record("trace_id", otel.trace_id().to_string());
+        span.record("span_id", otel.span_id().to_string());
+    }
+    span
+}
+
+/// Run a database query inside a child span of the current request span.
+///
+/// The span is named `db {operation}` and records the operation label and
+/// duration, never the SQL text or bound values, so no user data reaches
+/// the collector. Label operations as `table.action`, e.g. `products.list`.
+///
+/// ```ignore
+/// let rows = traced_query("products.list", sqlx::query!("...").fetch_all(&pool)).await;
+/// ```
+pub async fn traced_query<T, E>(
+    operation: &'static str,
+    query: impl Future<Output = Result<T, E>>,
+) -> Result<T, E> {
+    query_span(&global::tracer("dioxus-app"), operation, query).await
+}
+
+async fn query_span<Tr, T, E>(
+    tracer: &Tr,
+    operation: &'static str,
+    query: impl Future<Output = Result<T, E>>,
+) -> Result<T, E>
+where
+    Tr: Tracer,
+    Tr::Span: Send + Sync + 'static,
+{
+    let span = tracer
+        .span_builder(format!("db {operation}"))
+        .with_kind(SpanKind::Client)
+        .with_attributes([
+            KeyValue::new("db.system.name", "postgresql"),
+            KeyValue::new("db.operation.name", operation),
+        ])
+        .start_with_context(tracer, &Context::current());
+    let cx = Context::current_with_span(span);
+
+    let started = Instant::now();
+    let result = query.with_context(cx.clone()).await;
+    let elapsed_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+    let span = cx.span();
+    span.set_attribute(KeyValue::new("db.duration_ms", elapsed_ms));
+    if result.is_err() {
+        span.set_status(Status::error("query failed"));
+    }
+    span.end();
+    tracing::debug!(operation, duration_ms = elapsed_ms, "db query");
+    result
+}
+
 /// Tower layer that creates an OpenTelemetry span for each HTTP request.
 ///
 /// Captures: method, path, user-agent, client platform, request ID,
-/// response status, and authenticated user info (if present).
+/// response status, and authenticated user info (if present). Also opens a
+/// matching `tracing` span for local logs.
 #[derive(Clone)]
 pub struct OtelTraceLayer;
 
@@ -165,47 +332,7 @@ where
         let tracer = global::tracer("dioxus-app");
         let method = req.method().to_string();
         let path = req.uri().path().to_string();
-
-        let user_agent = req
-            .headers()
-            .get("user-agent")
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or("unknown")
-            .to_string();
-        let explicit_platform = req
-            .headers()
-            .get("x-client-platform")
-            .and_then(|v| v.to_str().ok());
-        let client_platform = detect_platform(&user_agent, explicit_platform);
-
-        let request_id = req
-            .headers()
-            .get("x-request-id")
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or("")
-            .to_string();
-
-        // Extract auth claims if the auth middleware already ran
-        let auth_attrs: Vec<KeyValue> = if let Some(claims) = req.extensions().get::<Claims>() {
-            vec![
-                KeyValue::new("user.id", claims.sub),
-                KeyValue::new("user.email", claims.email.clone()),
-                KeyValue::new("user.role", claims.role.clone()),
-                KeyValue::new("user.tier", claims.tier.clone()),
-                KeyValue::new("auth.status", "authenticated"),
-            ]
-        } else {
-            vec![KeyValue::new("auth.status", "anonymous")]
-        };
-
-        let mut attributes = vec![
-            KeyValue::new("http.method", method.clone()),
-            KeyValue::new("http.target", path.clone()),
-            KeyValue::new("http.user_agent", user_agent),
-            KeyValue::new("client.platform", client_platform),
-            KeyValue::new("http.request_id", request_id),
-        ];
-        attributes.extend(auth_attrs);
+        let attributes = span_attributes(&req);
 
         // Use path as the route name (strip hashes for server fn endpoints)
         let route = path
@@ -219,17 +346,25 @@ where
             .start(&tracer);
 
         let cx = Context::current_with_span(span);
+        let log_span = request_log_span(&req, &cx);
         let mut inner = self.inner.clone();
 
         let guard = cx.clone().attach();
-        let future = inner.call(req);
+        let future = log_span.in_scope(|| inner.call(req));
         drop(guard);
 
         Box::pin(async move {
-            let response = future.await?;
+            // Keep the request span current while the handler runs, so
+            // spans it starts (see `traced_query`) become its children.
+            let response = future
+                .instrument(log_span.clone())
+                .with_context(cx.clone())
+                .await?;
 
             let span = cx.span();
             let status = response.status();
+            log_span.record("status", status.as_u16());
+            log_span.in_scope(|| tracing::info!("request completed"));
             span.set_attribute(KeyValue::new("http.status_code", status.as_u16() as i64));
 
             if status.is_server_error() {
@@ -242,3 +377,56 @@ where
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::trace::{SpanId, TracerProvider as _};
+    use opentelemetry_sdk::trace::InMemorySpanExporter;
+
+    #[tokio::test]
+    async fn query_span_is_a_child_of_the_request_span() {
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let tracer = provider.tracer("test");
+
+        let request = Context::current_with_span(tracer.start("GET /api/products"));
+        let rows = query_span(&tracer, "products.list", async {
+            Ok::<_, String>(vec![1, 2])
+        })
+        .with_context(request.clone())
+        .await;
+        assert_eq!(rows.unwrap(), vec![1, 2]);
+        let failed = query_span(&tracer, "users.get", async { Err::<(), _>("boom") })
+            .with_context(request.clone())
+            .await;
+        assert!(failed.is_err());
+        request.span().end();
+
+        let spans = exporter.get_finished_spans().unwrap();
+        let find = |name: &str| spans.iter().find(|s| s.name == name).unwrap();
+        let request = find("GET /api/products");
+        let list = find("db products.list");
+        assert_eq!(list.parent_span_id, request.span_context.span_id());
+        assert_ne!(list.parent_span_id, SpanId::INVALID);
+        assert_eq!(
+            list.span_context.trace_id(),
+            request.span_context.trace_id()
+        );
+        assert_eq!(list.span_kind, SpanKind::Client);
+        let attribute = |key: &str| {
+            list.attributes
+                .iter()
+                .find(|kv| kv.key.as_str() == key)
+                .map(|kv| kv.value.clone())
+        };
+        assert_eq!(
+            attribute("db.operation.name").map(|v| v.to_string()),
+            Some("products.list".to_string())
+        );
+        assert!(attribute("db.duration_ms").is_some());
+        assert_eq!(find("db users.get").status, Status::error("query failed"));
+    }
+}
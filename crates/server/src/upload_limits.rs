@@ -0,0 +1,461 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::{
+        multipart::{Field, MultipartError},
+        Request,
+    },
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use shared_types::AppError;
+
+/// Default largest decoded avatar (2 MB).
+const DEFAULT_MAX_AVATAR_BYTES: usize = 2 * 1024 * 1024;
+
+/// Largest decoded product image (5 MB).
+pub const MAX_PRODUCT_IMAGE_BYTES: usize = 5 * 1024 * 1024;
+
+/// Default avatar uploads allowed per user per minute.
+const DEFAULT_AVATAR_UPLOADS_PER_MINUTE: u32 = 5;
+
+/// Room allowed on top of the image for multipart boundaries and headers.
+const MULTIPART_OVERHEAD_BYTES: usize = 64 * 1024;
+
+/// Users tracked before idle entries are swept from a [`RateLimiter`].
+const RATE_LIMITER_SWEEP_THRESHOLD: usize = 1024;
+
+/// Size and rate limits for avatar uploads.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AvatarLimits {
+    /// Largest decoded image accepted, in bytes.
+    pub max_bytes: usize,
+    /// Uploads allowed per user in any one-minute window.
+    pub uploads_per_minute: u32,
+}
+
+impl Default for AvatarLimits {
+    fn default() -> Self {
+        Self {
+            max_bytes: DEFAULT_MAX_AVATAR_BYTES,
+            uploads_per_minute: DEFAULT_AVATAR_UPLOADS_PER_MINUTE,
+        }
+    }
+}
+
+impl AvatarLimits {
+    /// Build limits from environment variables.
+    ///
+    /// - `AVATAR_MAX_BYTES` overrides the 2 MB image limit.
+    /// - `AVATAR_UPLOADS_PER_MINUTE` overrides the per-user upload rate.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let max_bytes = std::env::var("AVATAR_MAX_BYTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.max_bytes);
+        let uploads_per_minute = std::env::var("AVATAR_UPLOADS_PER_MINUTE")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.uploads_per_minute);
+
+        Self {
+            max_bytes,
+            uploads_per_minute,
+        }
+    }
+
+    /// Longest base64 string that can decode to at most `max_bytes`.
+    pub fn max_encoded_len(&self) -> usize {
+        self.max_bytes.div_ceil(3) * 4
+    }
+
+    /// Reject a base64 payload that is too long to be a valid avatar,
+    /// before spending time and memory decoding it.
+    pub fn check_encoded_len(&self, len: usize) -> Result<(), AppError> {
+        if len > self.max_encoded_len() {
+            return Err(self.too_large());
+        }
+        Ok(())
+    }
+
+    /// Reject decoded image data over `max_bytes`.
+    pub fn check_size(&self, len: usize) -> Result<(), AppError> {
+        if len > self.max_bytes {
+            return Err(self.too_large());
+        }
+        Ok(())
+    }
+
+    /// Largest avatar upload request body: the image plus multipart framing.
+    ///
+    /// Enforced before the handler runs, so oversized bodies are cut off
+    /// while streaming rather than after buffering.
+    pub fn max_body_bytes(&self) -> usize {
+        self.max_bytes + MULTIPART_OVERHEAD_BYTES
+    }
+
+    /// Error for an upload body over [`max_body_bytes`](Self::max_body_bytes).
+    pub fn body_too_large(&self) -> AppError {
+        AppError::validation(
+            "File too large",
+            [("avatar".to_string(), self.too_large().message)].into(),
+        )
+    }
+
+    /// A multipart read failure, reporting a body cut off by the upload
+    /// limit the same way as one rejected up front.
+    pub fn multipart_error(&self, err: MultipartError) -> AppError {
+        if err.status() == StatusCode::PAYLOAD_TOO_LARGE {
+            self.body_too_large()
+        } else {
+            AppError::validation(err.to_string(), Default::default())
+        }
+    }
+
+    /// Read an uploaded file field chunk by chunk, failing as soon as it
+    /// grows past `max_bytes` rather than buffering the rest of it.
+    pub async fn read_field(&self, mut field: Field<'_>) -> Result<Vec<u8>, AppError> {
+        let mut data = Vec::new();
+        while let Some(chunk) = field.chunk().await.map_err(|e| self.multipart_error(e))? {
+            if data.len() + chunk.len() > self.max_bytes {
+                return Err(self.body_too_large());
+            }
+            data.extend_from_slice(&chunk);
+        }
+        Ok(data)
+    }
+
+    fn too_large(&self) -> AppError {
+        AppError::validation(
+            format!("Avatar must be under {}", format_bytes(self.max_bytes)),
+            Default::default(),
+        )
+    }
+}
+
+/// Human-readable limit, e.g. `2 MB` or `512 KB`.
+fn format_bytes(bytes: usize) -> String {
+    const KB: usize = 1024;
+    const MB: usize = 1024 * KB;
+    if bytes >= MB && bytes.is_multiple_of(MB) {
+        format!("{} MB", bytes / MB)
+    } else if bytes >= KB {
+        format!("{} KB", bytes / KB)
+    } else {
+        format!("{bytes} bytes")
+    }
+}
+
+/// Middleware for upload routes that turns the bare 413 from the body limit
+/// into the standard [`AppError`] response.
+pub async fn oversized_body_as_app_error(req: Request, next: Next) -> Response {
+    let response = next.run(req).await;
+    if response.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        return avatar_limits().body_too_large().into_response();
+    }
+    response
+}
+
+/// Sliding-window rate limiter keyed by user id.
+///
+/// Allows `limit` hits per key in any `window`. State is in memory, so each
+/// server instance enforces the limit separately.
+#[derive(Debug)]
+pub struct RateLimiter {
+    limit: u32,
+    window: Duration,
+    hits: Mutex<HashMap<i64, VecDeque<Instant>>>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: u32, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+            hits: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record a hit for `key` at `now` if it is under the limit.
+    ///
+    /// Returns how long until the next hit would be allowed otherwise.
+    pub fn check(&self, key: i64, now: Instant) -> Result<(), Duration> {
+        let mut hits = self.hits.lock().unwrap_or_else(|e| e.into_inner());
+        if hits.len() > RATE_LIMITER_SWEEP_THRESHOLD {
+            hits.retain(|_, times| {
+                times
+                    .back()
+                    .is_some_and(|t| now.duration_since(*t) < self.window)
+            });
+        }
+
+        let times = hits.entry(key).or_default();
+        while times
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= self.window)
+        {
+            times.pop_front();
+        }
+        if times.len() >= self.limit as usize {
+            let oldest = times.front().copied().unwrap_or(now);
+            return Err(self.window.saturating_sub(now.duration_since(oldest)));
+        }
+        times.push_back(now);
+        Ok(())
+    }
+}
+
+/// Reject a base64 product image too long to decode to at most
+/// [`MAX_PRODUCT_IMAGE_BYTES`], before decoding it.
+pub fn check_product_image_encoded_len(len: usize) -> Result<(), AppError> {
+    if len > MAX_PRODUCT_IMAGE_BYTES.div_ceil(3) * 4 {
+        return Err(product_image_too_large());
+    }
+    Ok(())
+}
+
+/// Validate a decoded product image: a JPEG, PNG or WebP of at most
+/// [`MAX_PRODUCT_IMAGE_BYTES`] whose bytes really are that type, so a
+/// mislabelled upload can't be served with the wrong content type.
+pub fn validate_product_image(content_type: &str, bytes: &[u8]) -> Result<(), AppError> {
+    if !crate::s3::IMAGE_CONTENT_TYPES.contains(&content_type) {
+        return Err(AppError::validation(
+            "Only JPEG, PNG, and WebP images are allowed",
+            Default::default(),
+        ));
+    }
+    if bytes.len() > MAX_PRODUCT_IMAGE_BYTES {
+        return Err(product_image_too_large());
+    }
+    let sniffed = image::guess_format(bytes).ok().map(|f| f.to_mime_type());
+    if sniffed != Some(content_type) {
+        return Err(AppError::validation(
+            format!("File is not a valid {content_type} image"),
+            Default::default(),
+        ));
+    }
+    Ok(())
+}
+
+fn product_image_too_large() -> AppError {
+    AppError::validation(
+        format!(
+            "Product image must be under {}",
+            format_bytes(MAX_PRODUCT_IMAGE_BYTES)
+        ),
+        Default::default(),
+    )
+}
+
+/// Avatar limits for this process, read from the environment once.
+pub fn avatar_limits() -> &'static AvatarLimits {
+    static LIMITS: OnceLock<AvatarLimits> = OnceLock::new();
+    LIMITS.get_or_init(AvatarLimits::from_env)
+}
+
+/// Count an avatar upload by `user_id`, failing with `TooManyRequests` once
+/// they exceed `AVATAR_UPLOADS_PER_MINUTE`.
+pub fn check_avatar_upload_rate(user_id: i64) -> Result<(), AppError> {
+    static LIMITER: OnceLock<RateLimiter> = OnceLock::new();
+    let limiter = LIMITER.get_or_init(|| {
+        RateLimiter::new(avatar_limits().uploads_per_minute, Duration::from_secs(60))
+    });
+    limiter
+        .check(user_id, Instant::now())
+        .map_err(|retry_after| {
+            AppError::too_many_requests(format!(
+                "Too many avatar uploads. Try again in {} seconds.",
+                retry_after.as_secs().max(1)
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared_types::AppErrorKind;
+
+    #[test]
+    fn max_encoded_len_covers_base64_of_max_bytes() {
+        let limits = AvatarLimits::default();
+        let encoded = base64::Engine::encode(
+            &base64::engine::general_purpose::STANDARD,
+            vec![0u8; limits.max_bytes],
+        );
+        assert_eq!(encoded.len(), limits.max_encoded_len());
+        assert!(limits.check_encoded_len(encoded.len()).is_ok());
+    }
+
+    #[test]
+    fn oversized_encoded_string_is_rejected_before_decoding() {
+        let limits = AvatarLimits::default();
+        let err = limits
+            .check_encoded_len(limits.max_encoded_len() + 4)
+            .unwrap_err();
+        assert_eq!(err.kind, AppErrorKind::ValidationError);
+        assert_eq!(err.message, "Avatar must be under 2 MB");
+    }
+
+    #[test]
+    fn decoded_size_limit_follows_config() {
+        let limits = AvatarLimits {
+            max_bytes: 512 * 1024,
+            ..Default::default()
+        };
+        assert!(limits.check_size(512 * 1024).is_ok());
+        let err = limits.check_size(512 * 1024 + 1).unwrap_err();
+        assert_eq!(err.message, "Avatar must be under 512 KB");
+    }
+
+    #[test]
+    fn oversized_body_names_the_avatar_field() {
+        let limits = AvatarLimits::default();
+        assert!(limits.max_body_bytes() > limits.max_bytes);
+
+        let err = limits.body_too_large();
+        assert_eq!(err.kind, AppErrorKind::ValidationError);
+        assert_eq!(err.message, "File too large");
+        assert_eq!(
+            err.field_errors.get("avatar").map(String::as_str),
+            Some("Avatar must be under 2 MB")
+        );
+    }
+
+    fn png_bytes() -> Vec<u8> {
+        let mut out = std::io::Cursor::new(Vec::new());
+        image::RgbImage::new(2, 2)
+            .write_to(&mut out, image::ImageFormat::Png)
+            .unwrap();
+        out.into_inner()
+    }
+
+    #[test]
+    fn product_image_must_be_an_allowed_type_matching_its_bytes() {
+        let png = png_bytes();
+        assert!(validate_product_image("image/png", &png).is_ok());
+
+        let err = validate_product_image("image/gif", &png).unwrap_err();
+        assert_eq!(err.kind, AppErrorKind::ValidationError);
+        assert_eq!(err.message, "Only JPEG, PNG, and WebP images are allowed");
+
+        let err = validate_product_image("image/jpeg", &png).unwrap_err();
+        assert_eq!(err.message, "File is not a valid image/jpeg image");
+        assert!(validate_product_image("image/png", b"<svg></svg>").is_err());
+    }
+
+    #[test]
+    fn product_image_is_capped_at_5_mb() {
+        let mut big = png_bytes();
+        big.resize(MAX_PRODUCT_IMAGE_BYTES + 1, 0);
+        let err = validate_product_image("image/png", &big).unwrap_err();
+        assert_eq!(err.message, "Product image must be under 5 MB");
+
+        let encoded_max = MAX_PRODUCT_IMAGE_BYTES.div_ceil(3) * 4;
+        assert!(check_product_image_encoded_len(encoded_max).is_ok());
+        assert!(check_product_image_encoded_len(encoded_max + 4).is_err());
+    }
+
+    #[tokio::test]
+    async fn oversized_field_is_rejected_while_streaming() {
+        use axum::{body::Body, extract::Multipart, routing::post, Router};
+        use futures_util::StreamExt;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use tower::ServiceExt;
+
+        const CHUNK: usize = 1024;
+        let limits = AvatarLimits {
+            max_bytes: 4 * CHUNK,
+            ..Default::default()
+        };
+        let app = Router::new().route(
+            "/upload",
+            post(move |mut multipart: Multipart| async move {
+                let field = multipart.next_field().await.unwrap().unwrap();
+                limits
+                    .read_field(field)
+                    .await
+                    .map(|data| data.len().to_string())
+            }),
+        );
+
+        // A field that never ends, arriving a chunk at a time like a slow
+        // client; only an early abort lets the request finish.
+        let sent = Arc::new(AtomicUsize::new(0));
+        let head = "--b\r\nContent-Disposition: form-data; name=\"avatar\"; filename=\"a.png\"\r\nContent-Type: image/png\r\n\r\n";
+        let body = futures_util::stream::once(async move {
+            Ok::<_, std::io::Error>(bytes_of(head.as_bytes()))
+        })
+        .chain(
+            futures_util::stream::repeat_with({
+                let sent = sent.clone();
+                move || {
+                    sent.fetch_add(1, Ordering::Relaxed);
+                    Ok(bytes_of(&[0u8; CHUNK]))
+                }
+            })
+            .then(|chunk| async {
+                tokio::task::yield_now().await;
+                chunk
+            }),
+        );
+
+        let response = app
+            .oneshot(
+                Request::post("/upload")
+                    .header("content-type", "multipart/form-data; boundary=b")
+                    .body(Body::from_stream(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(sent.load(Ordering::Relaxed) <= 8, "read past the limit");
+    }
+
+    fn bytes_of(data: &[u8]) -> axum::body::Bytes {
+        axum::body::Bytes::copy_from_slice(data)
+    }
+
+    #[test]
+    fn rate_limiter_blocks_after_limit_until_window_passes() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(60));
+        let start = Instant::now();
+        assert!(limiter.check(1, start).is_ok());
+        assert!(limiter.check(1, start + Duration::from_secs(10)).is_ok());
+
+        let retry_after = limiter
+            .check(1, start + Duration::from_secs(20))
+            .unwrap_err();
+        assert_eq!(retry_after, Duration::from_secs(40));
+
+        // The first hit has aged out of the window
+        assert!(limiter.check(1, start + Duration::from_secs(60)).is_ok());
+    }
+
+    #[test]
+    fn rate_limiter_tracks_users_separately() {
+        let limiter = RateLimiter::new(1, Duration::from_secs(60));
+        let now = Instant::now();
+        assert!(limiter.check(1, now).is_ok());
+        assert!(limiter.check(1, now).is_err());
+        assert!(limiter.check(2, now).is_ok());
+    }
+
+    #[test]
+    fn upload_rate_error_is_too_many_requests() {
+        let user_id = -7;
+        let limit = avatar_limits().uploads_per_minute;
+        for _ in 0..limit {
+            check_avatar_upload_rate(user_id).unwrap();
+        }
+        let err = check_avatar_upload_rate(user_id).unwrap_err();
+        assert_eq!(err.kind, AppErrorKind::TooManyRequests);
+        assert!(err.message.starts_with("Too many avatar uploads"));
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::{HashMap, HashSet};
+
+use shared_types::{AppError, BatchGetUsersRequest, ListParams, User, UserSortField};
+use sqlx::{Pool, Postgres};
+
+use crate::db::UserRecord;
+use crate::error_convert::{SqlxErrorExt, ValidateRequest};
+
+/// `users` in the order their ids appear in `ids`, each once. Ids with no
+/// matching user are skipped.
+fn in_request_order(ids: &[i64], users: Vec<User>) -> Vec<User> {
+    let mut by_id: HashMap<i64, User> = users.into_iter().map(|u| (u.id, u)).collect();
+    ids.iter().filter_map(|id| by_id.remove(id)).collect()
+}
+
+/// Look up several users in one query.
+///
+/// Results follow the order of the requested ids; duplicate ids are returned
+/// once and ids that don't exist are left out.
+pub async fn get_many(
+    pool: &Pool<Postgres>,
+    request: BatchGetUsersRequest,
+) -> Result<Vec<User>, AppError> {
+    request.validate_request()?;
+    let mut seen = HashSet::new();
+    let ids: Vec<i64> = request
+        .ids
+        .into_iter()
+        .filter(|id| seen.insert(*id))
+        .collect();
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let records = sqlx::query_as!(
+        UserRecord,
+        "SELECT id, username, display_name, email, role, tier, avatar_url, thumbnail_url, created_at, updated_at FROM users WHERE id = ANY($1) AND deleted_at IS NULL",
+        &ids
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?;
+
+    Ok(in_request_order(
+        &ids,
+        records.into_iter().map(User::from).collect(),
+    ))
+}
+
+/// Soft-delete a live user and sign them out everywhere by revoking their
+/// refresh tokens; access tokens already issued lapse at their expiry.
+/// Returns the username, or `None` when there is no live user with this id.
+pub async fn soft_delete(pool: &Pool<Postgres>, user_id: i64) -> Result<Option<String>, AppError> {
+    let mut tx = pool.begin().await.map_err(SqlxErrorExt::into_app_error)?;
+    let username = sqlx::query_scalar!(
+        "UPDATE users SET deleted_at = NOW(), updated_at = NOW() WHERE id = $1 AND deleted_at IS NULL RETURNING username",
+        user_id
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?;
+
+    if username.is_some() {
+        sqlx::query!(
+            "UPDATE refresh_tokens SET revoked = TRUE WHERE user_id = $1 AND revoked = FALSE",
+            user_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(SqlxErrorExt::into_app_error)?;
+    }
+    tx.commit().await.map_err(SqlxErrorExt::into_app_error)?;
+    Ok(username)
+}
+
+/// Users matching `params`: filtered by `search` on username and display
+/// name, sorted, and paged when `page_size` is set.
+///
+/// The sort field and order are bound as their fixed names, so the SQL never
+/// contains caller-provided text. Ties fall back to id in the same order.
+pub async fn list(
+    pool: &Pool<Postgres>,
+    params: &ListParams<UserSortField>,
+) -> Result<Vec<User>, AppError> {
+    let (limit, offset) = params
+        .limit_offset()
+        .map_or((None, 0), |(limit, offset)| (Some(limit), offset));
+
+    let records = sqlx::query_as!(
+        UserRecord,
+        r#"SELECT id, username, display_name, email, role, tier, avatar_url, thumbnail_url, created_at, updated_at FROM users
+        WHERE deleted_at IS NULL AND ($1::TEXT IS NULL OR username ILIKE $1 OR display_name ILIKE $1)
+        ORDER BY
+            CASE WHEN $3::TEXT = 'asc' THEN CASE $2::TEXT WHEN 'username' THEN username WHEN 'display_name' THEN display_name END END ASC,
+            CASE WHEN $3::TEXT = 'desc' THEN CASE $2::TEXT WHEN 'username' THEN username WHEN 'display_name' THEN display_name END END DESC,
+            CASE WHEN $2::TEXT = 'created_at' AND $3::TEXT = 'asc' THEN created_at END ASC,
+            CASE WHEN $2::TEXT = 'created_at' AND $3::TEXT = 'desc' THEN created_at END DESC,
+            CASE WHEN $3::TEXT = 'asc' THEN id END ASC,
+            id DESC
+        LIMIT $4 OFFSET $5"#,
+        params.search_pattern(),
+        params.sort_by.as_str(),
+        params.order.as_str(),
+        limit,
+        offset
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(SqlxErrorExt::into_app_error)?;
+
+    Ok(records.into_iter().map(User::from).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(id: i64) -> User {
+        User {
+            id,
+            username: format!("user{id}"),
+            display_name: format!("User {id}"),
+            role: "user".to_string(),
+            tier: "free".to_string(),
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn users_follow_the_requested_order() {
+        let users = in_request_order(&[3, 99, 1, 3], vec![user(1), user(3)]);
+        let ids: Vec<i64> = users.iter().map(|u| u.id).collect();
+        assert_eq!(ids, vec![3, 1]);
+    }
+}
@@ -8,8 +8,12 @@
 mod common;
 
 use axum::http::StatusCode;
-use common::{delete, get, post_json, put_json, test_app};
-use shared_types::{AppError, Product, User};
+use common::{
+    delete, get, get_bytes, get_if_none_match, post_json, put_json, send, test_app,
+    test_app_with_site,
+};
+use server::site::SiteConfig;
+use shared_types::{AppError, Money, Product, ProductList, User};
 
 #[tokio::test]
 async fn health_check_returns_ok() {
@@ -19,6 +23,27 @@ async fn health_check_returns_ok() {
     assert_eq!(status, StatusCode::OK);
     assert!(body.contains("\"status\":\"ok\""));
     assert!(body.contains("\"db\":\"connected\""));
+    assert!(body.contains("\"pending_count\":0"));
+}
+
+#[tokio::test]
+async fn no_migrations_pending_after_run_migrations() {
+    let pool = server::db::create_pool();
+    server::db::run_migrations(&pool).await;
+
+    let status = server::db::migration_status(&pool).await.unwrap();
+    assert_eq!(status.pending, 0);
+    let shipped = std::fs::read_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/../../migrations"))
+        .unwrap()
+        .filter(|e| {
+            e.as_ref()
+                .unwrap()
+                .file_name()
+                .to_string_lossy()
+                .ends_with(".sql")
+        })
+        .count();
+    assert_eq!(status.applied, shipped);
 }
 
 #[tokio::test]
@@ -55,6 +80,39 @@ async fn create_and_get_user() {
     assert_eq!(status, StatusCode::NO_CONTENT);
 }
 
+#[tokio::test]
+async fn batch_get_users_keeps_request_order_and_skips_missing_ids() {
+    let ts = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let app = test_app().await;
+
+    let mut ids = Vec::new();
+    for n in 0..2 {
+        let json = serde_json::json!({
+            "username": format!("batch_get_{n}_{ts}"),
+            "display_name": "Batch User"
+        });
+        let (status, body) = post_json(&app, "/api/users", &json.to_string()).await;
+        assert_eq!(status, StatusCode::CREATED);
+        ids.push(serde_json::from_str::<User>(&body).unwrap().id);
+    }
+
+    // Later user first, with ids that don't exist around and between them
+    let json = serde_json::json!({ "ids": [-1, ids[1], i64::MAX, ids[0]] });
+    let (status, body) = post_json(&app, "/api/users/batch-get", &json.to_string()).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let users: Vec<User> = serde_json::from_str(&body).unwrap();
+    let returned: Vec<i64> = users.iter().map(|u| u.id).collect();
+    assert_eq!(returned, vec![ids[1], ids[0]]);
+
+    for id in ids {
+        delete(&app, &format!("/api/users/{id}")).await;
+    }
+}
+
 #[tokio::test]
 async fn list_users() {
     let app = test_app().await;
@@ -64,6 +122,89 @@ async fn list_users() {
     let _users: Vec<User> = serde_json::from_str(&body).unwrap();
 }
 
+#[tokio::test]
+async fn list_users_searches_sorts_and_pages() {
+    let app = test_app().await;
+    let tag = format!("sortcheck{}", std::process::id());
+    let mut ids = Vec::new();
+    for name in ["Charlie", "alice", "Bob"] {
+        let json = serde_json::json!({
+            "username": format!("{tag}_{}", name.to_lowercase()),
+            "display_name": format!("{name} {tag}"),
+        });
+        let (_, body) = post_json(&app, "/api/v1/users", &json.to_string()).await;
+        ids.push(serde_json::from_str::<User>(&body).unwrap().id);
+    }
+
+    let names = |body: &str| -> Vec<String> {
+        serde_json::from_str::<Vec<User>>(body)
+            .unwrap()
+            .into_iter()
+            .map(|u| u.display_name.split(' ').next().unwrap().to_string())
+            .collect()
+    };
+    let uri = format!("/api/v1/users?search={tag}&sort_by=username&order=desc");
+    let (status, body) = get(&app, &uri).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(names(&body), ["Charlie", "Bob", "alice"]);
+
+    let uri = format!(
+        "/api/v1/users?search={}&sort_by=username&page=2&page_size=2",
+        tag.to_uppercase()
+    );
+    let (_, body) = get(&app, &uri).await;
+    assert_eq!(names(&body), ["Charlie"]);
+
+    let (status, body) = get(&app, "/api/v1/users?sort_by=password").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(serde_json::from_str::<AppError>(&body).is_ok(), "{body}");
+
+    for id in ids {
+        delete(&app, &format!("/api/v1/users/{id}")).await;
+    }
+}
+
+#[tokio::test]
+async fn legacy_api_prefix_serves_the_same_routes_as_v1() {
+    let ts = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+
+    let app = test_app().await;
+    let json = serde_json::json!({
+        "username": format!("versioned_{ts}"),
+        "display_name": "Versioned"
+    });
+    let (status, body) = post_json(&app, "/api/v1/users", &json.to_string()).await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+    let user: User = serde_json::from_str(&body).unwrap();
+
+    // Other tests add users concurrently, so the lists are only compared by
+    // shape; a single user must match byte for byte.
+    let (v1_status, v1_headers, v1_list) = send(&app, "GET", "/api/v1/users").await;
+    let (legacy_status, legacy_headers, legacy_list) = send(&app, "GET", "/api/users").await;
+    assert_eq!(v1_status, StatusCode::OK);
+    assert_eq!(legacy_status, v1_status);
+    assert!(serde_json::from_str::<Vec<User>>(&v1_list).is_ok());
+    assert!(serde_json::from_str::<Vec<User>>(&legacy_list).is_ok());
+
+    let (_, v1_user_headers, v1_user) =
+        send(&app, "GET", &format!("/api/v1/users/{}", user.id)).await;
+    let (_, _, legacy_user) = send(&app, "GET", &format!("/api/users/{}", user.id)).await;
+    assert_eq!(legacy_user, v1_user);
+
+    assert!(!v1_headers.contains_key("deprecation"));
+    assert!(!v1_user_headers.contains_key("deprecation"));
+    assert_eq!(legacy_headers["deprecation"], "true");
+    assert_eq!(
+        legacy_headers["link"],
+        "</api/v1>; rel=\"successor-version\""
+    );
+
+    delete(&app, &format!("/api/v1/users/{}", user.id)).await;
+}
+
 #[tokio::test]
 async fn update_user() {
     let ts = std::time::SystemTime::now()
@@ -112,6 +253,44 @@ async fn get_nonexistent_user_returns_404() {
     assert_eq!(err.kind, shared_types::AppErrorKind::NotFound);
 }
 
+#[tokio::test]
+async fn user_without_upload_gets_generated_avatar() {
+    let ts = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let username = format!("avatar_test_{ts}");
+
+    let app = test_app().await;
+
+    let json = serde_json::json!({
+        "username": username,
+        "display_name": "Ada Lovelace"
+    });
+    let (_, body) = post_json(&app, "/api/users", &json.to_string()).await;
+    let user: User = serde_json::from_str(&body).unwrap();
+    let uri = format!("/api/users/{}/avatar", user.id);
+
+    let (status, headers, png) = get_bytes(&app, &uri).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers["content-type"], "image/png");
+    assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+
+    // Same user, same image
+    let (_, _, again) = get_bytes(&app, &uri).await;
+    assert_eq!(png, again);
+
+    // Clean up
+    delete(&app, &format!("/api/users/{}", user.id)).await;
+}
+
+#[tokio::test]
+async fn avatar_for_nonexistent_user_returns_404() {
+    let app = test_app().await;
+    let (status, _) = get(&app, "/api/users/999999/avatar").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
 #[tokio::test]
 async fn delete_nonexistent_user_returns_404() {
     let app = test_app().await;
@@ -133,7 +312,7 @@ async fn create_and_get_product() {
 
     let product: Product = serde_json::from_str(&body).unwrap();
     assert_eq!(product.name, "Test Widget");
-    assert_eq!(product.price, 29.99);
+    assert_eq!(product.price, Money::from_cents(2999));
 
     // Clean up
     delete(&app, &format!("/api/products/{}", product.id)).await;
@@ -148,6 +327,70 @@ async fn list_products() {
     let _products: Vec<Product> = serde_json::from_str(&body).unwrap();
 }
 
+#[tokio::test]
+async fn cursor_pages_cover_every_product_exactly_once() {
+    let app = test_app().await;
+    let mut created = Vec::new();
+    for i in 0..5 {
+        let json = serde_json::json!({
+            "name": format!("Paged {i}"),
+            "description": "",
+            "price": 1.0,
+            "category": "Hardware",
+            "status": "active"
+        });
+        let (_, body) = post_json(&app, "/api/v1/products", &json.to_string()).await;
+        created.push(serde_json::from_str::<Product>(&body).unwrap().id);
+    }
+    let newest = *created.last().unwrap();
+    let oldest = created[0];
+
+    // Walk down from just above the newest product until past the oldest.
+    // Other tests add products concurrently, so only ours are checked.
+    let mut seen = Vec::new();
+    let mut cursor = newest + 1;
+    loop {
+        let (status, body) = get(
+            &app,
+            &format!("/api/v1/products?limit=2&before_id={cursor}"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        let ProductList::Page(page) = serde_json::from_str(&body).unwrap() else {
+            panic!("expected a page: {body}");
+        };
+        assert!(page.items.len() <= 2);
+        seen.extend(page.items.iter().map(|p| p.id));
+        match page.next_cursor {
+            Some(next) if next > oldest => cursor = next,
+            _ => break,
+        }
+    }
+    assert!(seen.windows(2).all(|w| w[0] > w[1]), "{seen:?}");
+    let ours: Vec<i64> = seen.into_iter().filter(|id| created.contains(id)).collect();
+    let mut expected = created.clone();
+    expected.reverse();
+    assert_eq!(ours, expected);
+
+    // Paging up from below the oldest finds the same products.
+    let (_, body) = get(
+        &app,
+        &format!("/api/v1/products?limit=5&after_id={}", oldest - 1),
+    )
+    .await;
+    let ProductList::Page(page) = serde_json::from_str(&body).unwrap() else {
+        panic!("expected a page: {body}");
+    };
+    assert_eq!(page.items.last().map(|p| p.id), Some(oldest));
+
+    let (status, _) = get(&app, "/api/v1/products?limit=2&before_id=10&after_id=1").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    for id in created {
+        delete(&app, &format!("/api/v1/products/{id}")).await;
+    }
+}
+
 #[tokio::test]
 async fn update_product() {
     let app = test_app().await;
@@ -165,19 +408,88 @@ async fn update_product() {
     let (status, body) = put_json(
         &app,
         &format!("/api/products/{}", product.id),
-        r#"{"name":"Updated Name","description":"new desc","price":20.0,"category":"Software","status":"inactive"}"#,
+        r#"{"name":"Updated Name","description":"new desc","price":20.0,"category":"Software","status":"archived"}"#,
     )
     .await;
     assert_eq!(status, StatusCode::OK);
 
     let updated: Product = serde_json::from_str(&body).unwrap();
     assert_eq!(updated.name, "Updated Name");
-    assert_eq!(updated.price, 20.0);
+    assert_eq!(updated.price, Money::from_cents(2000));
 
     // Clean up
     delete(&app, &format!("/api/products/{}", product.id)).await;
 }
 
+#[tokio::test]
+async fn get_user_supports_conditional_requests() {
+    let ts = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+
+    let app = test_app().await;
+    let json = serde_json::json!({
+        "username": format!("etag_{ts}"),
+        "display_name": "ETag"
+    });
+    let (_, body) = post_json(&app, "/api/v1/users", &json.to_string()).await;
+    let user: User = serde_json::from_str(&body).unwrap();
+    let uri = format!("/api/v1/users/{}", user.id);
+
+    let (status, headers, body) = send(&app, "GET", &uri).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(serde_json::from_str::<User>(&body).unwrap(), user);
+    let etag = headers["etag"].to_str().unwrap().to_string();
+    assert!(etag.starts_with("W/\""), "{etag}");
+
+    let (status, headers, body) = get_if_none_match(&app, &uri, &etag).await;
+    assert_eq!(status, StatusCode::NOT_MODIFIED);
+    assert_eq!(headers["etag"], etag.as_str());
+    assert!(body.is_empty());
+
+    // A change to the user invalidates the tag
+    let json = serde_json::json!({
+        "username": format!("etag_{ts}"),
+        "display_name": "ETag Renamed"
+    });
+    put_json(&app, &uri, &json.to_string()).await;
+    let (status, headers, _) = get_if_none_match(&app, &uri, &etag).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_ne!(headers["etag"], etag.as_str());
+
+    delete(&app, &uri).await;
+}
+
+#[tokio::test]
+async fn get_product_supports_conditional_requests() {
+    let app = test_app().await;
+    let (_, body) = post_json(
+        &app,
+        "/api/v1/products",
+        r#"{"name":"Tagged","description":"desc","price":5.0,"category":"Hardware","status":"active"}"#,
+    )
+    .await;
+    let product: Product = serde_json::from_str(&body).unwrap();
+    let uri = format!("/api/v1/products/{}", product.id);
+
+    let (status, headers, body) = send(&app, "GET", &uri).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(serde_json::from_str::<Product>(&body).unwrap(), product);
+    let etag = headers["etag"].to_str().unwrap().to_string();
+
+    let (status, _, body) = get_if_none_match(&app, &uri, &etag).await;
+    assert_eq!(status, StatusCode::NOT_MODIFIED);
+    assert!(body.is_empty());
+
+    let (status, _, _) = get_if_none_match(&app, &uri, "W/\"stale\"").await;
+    assert_eq!(status, StatusCode::OK);
+
+    delete(&app, &uri).await;
+    let (status, _) = get(&app, &uri).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
 #[tokio::test]
 async fn delete_nonexistent_product_returns_404() {
     let app = test_app().await;
@@ -196,6 +508,41 @@ async fn dashboard_stats() {
     assert!(stats.total_products >= 0);
 }
 
+#[tokio::test]
+async fn dashboard_stats_30_day_range_excludes_older_users() {
+    let ts = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let app = test_app().await;
+
+    let json = serde_json::json!({
+        "username": format!("olduser_{ts}"),
+        "display_name": "Old User"
+    });
+    let (status, body) = post_json(&app, "/api/users", &json.to_string()).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let old_user: User = serde_json::from_str(&body).unwrap();
+
+    let pool = server::db::create_pool();
+    sqlx::query("UPDATE users SET created_at = NOW() - INTERVAL '40 days' WHERE id = $1")
+        .bind(old_user.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let (status, body) = get(&app, "/api/dashboard/stats?range=30d").await;
+    assert_eq!(status, StatusCode::OK);
+    let last_30: shared_types::DashboardStats = serde_json::from_str(&body).unwrap();
+
+    let (status, body) = get(&app, "/api/dashboard/stats?range=all").await;
+    assert_eq!(status, StatusCode::OK);
+    let all_time: shared_types::DashboardStats = serde_json::from_str(&body).unwrap();
+
+    assert!(last_30.total_users < all_time.total_users);
+    assert!(last_30.recent_users.iter().all(|u| u.id != old_user.id));
+}
+
 #[tokio::test]
 async fn validation_rejects_short_username() {
     let app = test_app().await;
@@ -245,6 +592,49 @@ async fn validation_rejects_negative_price() {
     assert!(err.field_errors.contains_key("price"));
 }
 
+#[tokio::test]
+async fn malformed_json_body_returns_400() {
+    let app = test_app().await;
+
+    let (status, body) = post_json(&app, "/api/users", r#"{"username":"neo","#).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let err: AppError = serde_json::from_str(&body).unwrap();
+    assert_eq!(err.kind, shared_types::AppErrorKind::BadRequest);
+}
+
+#[tokio::test]
+async fn wrongly_typed_json_body_returns_400() {
+    let app = test_app().await;
+
+    let (status, body) = post_json(
+        &app,
+        "/api/products",
+        r#"{"name":"Widget","description":"desc","price":"cheap","category":"Hardware","status":"active"}"#,
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let err: AppError = serde_json::from_str(&body).unwrap();
+    assert_eq!(err.kind, shared_types::AppErrorKind::BadRequest);
+}
+
+#[tokio::test]
+async fn parseable_but_invalid_body_returns_422() {
+    let app = test_app().await;
+
+    let (status, body) = post_json(
+        &app,
+        "/api/users",
+        r#"{"username":"neo","display_name":""}"#,
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    let err: AppError = serde_json::from_str(&body).unwrap();
+    assert_eq!(err.kind, shared_types::AppErrorKind::ValidationError);
+}
+
 #[tokio::test]
 async fn update_nonexistent_user_returns_404() {
     let app = test_app().await;
@@ -343,3 +733,235 @@ async fn delete_product_actually_removes() {
     let products: Vec<Product> = serde_json::from_str(&body).unwrap();
     assert!(!products.iter().any(|p| p.id == product.id));
 }
+
+#[tokio::test]
+async fn unsupported_method_returns_405_envelope() {
+    let app = test_app().await;
+    let (status, headers, body) = send(&app, "POST", "/api/products/1").await;
+
+    assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+    let err: AppError = serde_json::from_str(&body).unwrap();
+    assert_eq!(err.kind, shared_types::AppErrorKind::MethodNotAllowed);
+
+    let allow = headers
+        .get(axum::http::header::ALLOW)
+        .and_then(|v| v.to_str().ok())
+        .unwrap();
+    assert!(allow.contains("PUT"));
+    assert!(allow.contains("DELETE"));
+    assert!(!allow.contains("POST"));
+}
+
+#[tokio::test]
+async fn create_product_invalid_status_is_rejected() {
+    let app = test_app().await;
+
+    let (status, body) = post_json(
+        &app,
+        "/api/products",
+        r#"{"name":"Widget","description":"desc","price":10.0,"category":"Hardware","status":"bogus"}"#,
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let err: AppError = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        err.field_errors.get("status").map(String::as_str),
+        Some("Status must be one of: active, draft, archived")
+    );
+}
+
+#[tokio::test]
+async fn unknown_api_path_returns_json_404() {
+    let app = test_app().await;
+    let (status, body) = get(&app, "/api/does-not-exist").await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let err: AppError = serde_json::from_str(&body).unwrap();
+    assert_eq!(err.kind, shared_types::AppErrorKind::NotFound);
+}
+
+#[tokio::test]
+async fn dashboard_recent_users_match_user_list_projection() {
+    use serde_json::Value;
+
+    let app = test_app().await;
+
+    // Ensure at least one user exists for both endpoints to return
+    let ts = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let json = serde_json::json!({
+        "username": format!("projection_{ts}"),
+        "display_name": "Projection"
+    });
+    let (_, body) = post_json(&app, "/api/users", &json.to_string()).await;
+    let user: User = serde_json::from_str(&body).unwrap();
+
+    let (_, body) = get(&app, "/api/dashboard/stats").await;
+    let stats: Value = serde_json::from_str(&body).unwrap();
+    let (_, body) = get(&app, "/api/users").await;
+    let users: Value = serde_json::from_str(&body).unwrap();
+
+    let keys = |v: &Value| {
+        let mut keys: Vec<String> = v.as_object().unwrap().keys().cloned().collect();
+        keys.sort();
+        keys
+    };
+    let recent = &stats["recent_users"][0];
+    let listed = &users.as_array().unwrap()[0];
+    assert_eq!(keys(recent), keys(listed));
+    assert!(keys(recent).contains(&"tier".to_string()));
+    assert!(keys(recent).contains(&"role".to_string()));
+
+    // Clean up
+    delete(&app, &format!("/api/users/{}", user.id)).await;
+}
+
+#[tokio::test]
+async fn sitemap_lists_public_pages() {
+    let app = test_app_with_site(SiteConfig {
+        base_url: "https://app.example.com".to_string(),
+        ..Default::default()
+    })
+    .await;
+    let (status, body) = get(&app, "/sitemap.xml").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("<loc>https://app.example.com/login</loc>"));
+    assert!(body.contains("<loc>https://app.example.com/register</loc>"));
+    assert!(!body.contains("/settings"));
+}
+
+#[tokio::test]
+async fn robots_reflects_configuration() {
+    let app = test_app_with_site(SiteConfig {
+        base_url: "https://app.example.com".to_string(),
+        robots_allow: vec!["/".to_string()],
+        robots_disallow: vec!["/api/".to_string(), "/admin".to_string()],
+    })
+    .await;
+    let (status, body) = get(&app, "/robots.txt").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("Allow: /\n"));
+    assert!(body.contains("Disallow: /api/\n"));
+    assert!(body.contains("Disallow: /admin\n"));
+    assert!(body.contains("Sitemap: https://app.example.com/sitemap.xml"));
+}
+
+#[tokio::test]
+async fn soft_deleted_product_can_be_restored() {
+    let app = test_app().await;
+    let (_, body) = post_json(
+        &app,
+        "/api/products",
+        r#"{"name":"Restore Me","description":"","price":3.0,"category":"Hardware","status":"active"}"#,
+    )
+    .await;
+    let product: Product = serde_json::from_str(&body).unwrap();
+    let listed = |body: &str| {
+        serde_json::from_str::<Vec<Product>>(body)
+            .unwrap()
+            .iter()
+            .any(|p| p.id == product.id)
+    };
+
+    let (status, _) = delete(&app, &format!("/api/products/{}", product.id)).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (_, body) = get(&app, "/api/products").await;
+    assert!(!listed(&body));
+
+    // Deleting or editing it again finds nothing.
+    let (status, _) = delete(&app, &format!("/api/products/{}", product.id)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = put_json(
+        &app,
+        &format!("/api/products/{}", product.id),
+        r#"{"name":"Edited","description":"","price":3.0,"category":"Hardware","status":"active"}"#,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _, body) = send(
+        &app,
+        "POST",
+        &format!("/api/products/{}/restore", product.id),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let restored: Product = serde_json::from_str(&body).unwrap();
+    assert_eq!(restored.name, "Restore Me");
+    assert_eq!(restored.deleted_at, None);
+
+    let (_, body) = get(&app, "/api/products").await;
+    assert!(listed(&body));
+
+    delete(&app, &format!("/api/products/{}", product.id)).await;
+}
+
+#[tokio::test]
+async fn restoring_a_live_product_returns_404() {
+    let app = test_app().await;
+    let (_, body) = post_json(
+        &app,
+        "/api/products",
+        r#"{"name":"Still Here","description":"","price":1.0,"category":"Hardware","status":"active"}"#,
+    )
+    .await;
+    let product: Product = serde_json::from_str(&body).unwrap();
+
+    let (status, _, _) = send(
+        &app,
+        "POST",
+        &format!("/api/products/{}/restore", product.id),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    delete(&app, &format!("/api/products/{}", product.id)).await;
+}
+
+#[tokio::test]
+async fn soft_deleted_user_can_be_restored() {
+    let ts = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let username = format!("restore_me_{ts}");
+    let app = test_app().await;
+    let json = serde_json::json!({ "username": username, "display_name": "Restore Me" });
+    let (_, body) = post_json(&app, "/api/users", &json.to_string()).await;
+    let user: User = serde_json::from_str(&body).unwrap();
+    let search = format!("/api/users?search={username}");
+    let listed = |body: &str| {
+        serde_json::from_str::<Vec<User>>(body)
+            .unwrap()
+            .iter()
+            .any(|u| u.id == user.id)
+    };
+
+    let (status, _) = delete(&app, &format!("/api/users/{}", user.id)).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (_, body) = get(&app, &search).await;
+    assert!(!listed(&body));
+    let (status, _) = delete(&app, &format!("/api/users/{}", user.id)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let restore = format!("/api/users/{}/restore", user.id);
+    let (status, _, body) = send(&app, "POST", &restore).await;
+    assert_eq!(status, StatusCode::OK);
+    let restored: User = serde_json::from_str(&body).unwrap();
+    assert_eq!(restored.username, username);
+
+    let (_, body) = get(&app, &search).await;
+    assert!(listed(&body));
+    let (status, _) = get(&app, &format!("/api/users/{}", user.id)).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Only deleted users can be restored
+    let (status, _, _) = send(&app, "POST", &restore).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    delete(&app, &format!("/api/users/{}", user.id)).await;
+}
@@ -7,11 +7,24 @@
 
 mod common;
 
-use axum::http::StatusCode;
+use axum::{
+    body::Body,
+    extract::ConnectInfo,
+    http::{header, Request, StatusCode},
+};
 use common::{
-    get, post_json, post_json_with_auth, put_json_with_auth, register_test_user, test_app_with_auth,
+    delete_with_auth, get, get_with_auth, post_json, post_json_with_auth, put_json,
+    put_json_with_auth, register_test_user, test_app, test_app_with_auth,
+    test_app_with_registration_mode,
+};
+use server::auth::invites::RegistrationMode;
+use shared_types::{
+    AccountDeletion, AppError, AuditAction, AuditPage, AuthEvent, AuthEventType, AuthResponse,
+    AuthUser, Invite, Money, OAuthProviderInfo, Product, ProductHistory, UserPreferences,
+    TOKEN_REFRESHED_HEADER,
 };
-use shared_types::{AppError, AuthResponse};
+use std::net::SocketAddr;
+use tower::ServiceExt;
 
 /// Generate a unique username + email pair for test isolation.
 fn unique_suffix(prefix: &str) -> (String, String) {
@@ -104,7 +117,7 @@ async fn register_duplicate_email_returns_error() {
 
     // Second registration with same email fails
     let (status, _) = register_test_user(&app, &username2, &email, "Pass5678!").await;
-    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
 }
 
 #[tokio::test]
@@ -159,6 +172,153 @@ async fn update_tier_non_admin_returns_403() {
     assert_eq!(err.kind, shared_types::AppErrorKind::Forbidden);
 }
 
+#[tokio::test]
+async fn premium_analytics_rejects_free_tier() {
+    let app = test_app_with_auth().await;
+    let (username, email) = unique_suffix("freetier");
+    let (_, body) = register_test_user(&app, &username, &email, "FreePass1!").await;
+    let resp: AuthResponse = serde_json::from_str(&body).unwrap();
+
+    let (status, body) = get_with_auth(&app, "/api/analytics/premium", &resp.access_token).await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let err: AppError = serde_json::from_str(&body).unwrap();
+    assert_eq!(err.message, "Premium tier or higher required");
+}
+
+#[tokio::test]
+async fn premium_analytics_allows_premium_tier() {
+    let app = test_app_with_auth().await;
+    let (username, email) = unique_suffix("premiumtier");
+    let (_, body) = register_test_user(&app, &username, &email, "PremPass1!").await;
+    let resp: AuthResponse = serde_json::from_str(&body).unwrap();
+    let token =
+        server::auth::jwt::create_access_token(resp.user.id, &email, "user", "premium").unwrap();
+
+    let (status, body) = get_with_auth(&app, "/api/analytics/premium", &token).await;
+
+    assert_eq!(status, StatusCode::OK);
+    let _analytics: shared_types::PremiumAnalytics = serde_json::from_str(&body).unwrap();
+}
+
+#[tokio::test]
+async fn premium_analytics_scopes_differ() {
+    let app = test_app_with_auth().await;
+    let (username, email) = unique_suffix("scope");
+    let (_, body) = register_test_user(&app, &username, &email, "ScopePass1!").await;
+    let resp: AuthResponse = serde_json::from_str(&body).unwrap();
+    let user_token =
+        server::auth::jwt::create_access_token(resp.user.id, &email, "user", "premium").unwrap();
+
+    // Admin tokens carry the tier too; global analytics still need Premium
+    let admin = admin_token(&app).await;
+    let admin_claims = server::auth::jwt::validate_access_token(&admin).unwrap();
+    let admin_premium = server::auth::jwt::create_access_token(
+        admin_claims.sub,
+        &admin_claims.email,
+        "admin",
+        "premium",
+    )
+    .unwrap();
+
+    // One product each in a category nobody else uses
+    let category = format!("Scope {username}");
+    for (token, price) in [(&user_token, 42.0), (&admin_premium, 10.0)] {
+        let json = serde_json::json!({
+            "name": "Scoped product",
+            "description": "",
+            "price": price,
+            "category": category,
+            "status": "active"
+        });
+        let (status, _) =
+            post_json_with_auth(&app, "/api/products", &json.to_string(), token).await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+    let count_in = |analytics: &shared_types::PremiumAnalytics| {
+        analytics
+            .products_by_category
+            .iter()
+            .find(|c| c.category == category)
+            .map(|c| c.count)
+    };
+
+    let (status, body) =
+        get_with_auth(&app, "/api/analytics/premium?scope=mine", &user_token).await;
+    assert_eq!(status, StatusCode::OK);
+    let mine: shared_types::PremiumAnalytics = serde_json::from_str(&body).unwrap();
+    assert_eq!(count_in(&mine), Some(1));
+    assert_eq!(mine.avg_product_price, Money::from_cents(4200));
+    assert_eq!(mine.total_revenue, Money::from_cents(4200));
+
+    let (status, body) =
+        get_with_auth(&app, "/api/analytics/premium?scope=global", &admin_premium).await;
+    assert_eq!(status, StatusCode::OK);
+    let global: shared_types::PremiumAnalytics = serde_json::from_str(&body).unwrap();
+    assert_eq!(count_in(&global), Some(2));
+    assert!(global.total_revenue >= Money::from_cents(5200));
+
+    // Global scope is admin only
+    let (status, _) = get_with_auth(&app, "/api/analytics/premium?scope=global", &user_token).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn premium_analytics_sums_revenue_per_category() {
+    let app = test_app_with_auth().await;
+    let (username, email) = unique_suffix("revenue");
+    let (_, body) = register_test_user(&app, &username, &email, "RevenuePass1!").await;
+    let resp: AuthResponse = serde_json::from_str(&body).unwrap();
+    let token =
+        server::auth::jwt::create_access_token(resp.user.id, &email, "user", "premium").unwrap();
+
+    let hardware = format!("Hardware {username}");
+    let software = format!("Software {username}");
+    let stickers = format!("Stickers {username}");
+    let seeded = [
+        (&hardware, 19.5, "active"),
+        (&hardware, 30.0, "active"),
+        (&hardware, 100.0, "draft"),
+        (&software, 12.25, "active"),
+        // 0.1 + 0.2 is not 0.3 in floating point
+        (&stickers, 0.1, "active"),
+        (&stickers, 0.2, "active"),
+    ];
+    for (category, price, status) in seeded {
+        let json = serde_json::json!({
+            "name": "Revenue product",
+            "description": "",
+            "price": price,
+            "category": category,
+            "status": status
+        });
+        let (status, _) =
+            post_json_with_auth(&app, "/api/products", &json.to_string(), &token).await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+
+    let (status, body) = get_with_auth(&app, "/api/analytics/premium?scope=mine", &token).await;
+    assert_eq!(status, StatusCode::OK);
+    let analytics: shared_types::PremiumAnalytics = serde_json::from_str(&body).unwrap();
+
+    let revenue: Vec<_> = analytics
+        .revenue_by_category
+        .iter()
+        .map(|c| (c.category.as_str(), c.revenue))
+        .collect();
+    // Drafts don't count; highest revenue first, summed to the exact cent
+    assert_eq!(
+        revenue,
+        vec![
+            (hardware.as_str(), Money::from_cents(4950)),
+            (software.as_str(), Money::from_cents(1225)),
+            (stickers.as_str(), Money::from_cents(30)),
+        ]
+    );
+    assert_eq!(analytics.total_revenue, Money::from_cents(6205));
+    assert_eq!(analytics.total_revenue.to_string(), "$62.05");
+}
+
 #[tokio::test]
 async fn health_includes_version() {
     let app = test_app_with_auth().await;
@@ -168,3 +328,1118 @@ async fn health_includes_version() {
     assert!(body.contains("\"version\""));
     assert!(body.contains("\"uptime_seconds\""));
 }
+
+/// Mint and store a refresh token for `user` the way login does.
+async fn store_refresh_token(user: &AuthUser) -> String {
+    let (refresh_token, expires_at) = server::auth::jwt::create_refresh_token(
+        user.id,
+        &user.email,
+        user.role.as_str(),
+        user.tier.as_str(),
+    )
+    .unwrap();
+    let pool = server::db::create_pool();
+    sqlx::query("INSERT INTO refresh_tokens (user_id, token_hash, expires_at) VALUES ($1, $2, $3)")
+        .bind(user.id)
+        .bind(&refresh_token)
+        .bind(expires_at)
+        .execute(&pool)
+        .await
+        .unwrap();
+    refresh_token
+}
+
+/// Send a request with an invalid access cookie and `refresh_token`, which
+/// triggers a transparent refresh.
+async fn send_with_refresh_cookie(
+    app: &axum::Router,
+    refresh_token: &str,
+) -> axum::http::Response<Body> {
+    app.clone()
+        .oneshot(
+            Request::builder()
+                .uri("/health")
+                .header(
+                    header::COOKIE,
+                    format!("cyber_access=expired; cyber_refresh={refresh_token}"),
+                )
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn transparent_refresh_sets_token_refreshed_header() {
+    let app = test_app_with_auth().await;
+    let (username, email) = unique_suffix("refreshhdr");
+
+    let (_, body) = register_test_user(&app, &username, &email, "RefreshPass1!").await;
+    let resp: AuthResponse = serde_json::from_str(&body).unwrap();
+    let refresh_token = store_refresh_token(&resp.user).await;
+
+    let response = send_with_refresh_cookie(&app, &refresh_token).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[TOKEN_REFRESHED_HEADER], "1");
+    assert!(response.headers().contains_key(header::SET_COOKIE));
+}
+
+#[tokio::test]
+async fn transparent_refresh_stops_for_deleted_accounts() {
+    let app = test_app_with_auth().await;
+    let user = register_and_schedule_deletion(&app, "refreshgrace", "GracePass1!").await;
+
+    // A pending deletion only blocks new sign-ins; the open session can still
+    // cancel it from Settings
+    let refresh_token = store_refresh_token(&user.user).await;
+    let response = send_with_refresh_cookie(&app, &refresh_token).await;
+    assert_eq!(response.headers()[TOKEN_REFRESHED_HEADER], "1");
+
+    // An account deleted by an admin can't refresh, even with a live token
+    let pool = server::db::create_pool();
+    sqlx::query("UPDATE users SET deleted_at = NOW() WHERE id = $1")
+        .bind(user.user.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let refresh_token = store_refresh_token(&user.user).await;
+    let response = send_with_refresh_cookie(&app, &refresh_token).await;
+    assert!(!response.headers().contains_key(TOKEN_REFRESHED_HEADER));
+    assert!(!response.headers().contains_key(header::SET_COOKIE));
+}
+
+#[tokio::test]
+async fn soft_deleting_a_user_ends_their_sessions() {
+    let app = test_app_with_auth().await;
+    let (username, email) = unique_suffix("softdelete");
+    let (_, body) = register_test_user(&app, &username, &email, "DeletePass1!").await;
+    let resp: AuthResponse = serde_json::from_str(&body).unwrap();
+    let refresh_token = store_refresh_token(&resp.user).await;
+
+    let (status, _, _) =
+        common::send(&app, "DELETE", &format!("/api/users/{}", resp.user.id)).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    let pool = server::db::create_pool();
+    let live: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM refresh_tokens WHERE user_id = $1 AND revoked = FALSE",
+    )
+    .bind(resp.user.id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(live, 0);
+    let response = send_with_refresh_cookie(&app, &refresh_token).await;
+    assert!(!response.headers().contains_key(TOKEN_REFRESHED_HEADER));
+}
+
+#[tokio::test]
+async fn anonymous_request_does_not_set_token_refreshed_header() {
+    let app = test_app_with_auth().await;
+    let (_, headers, _) = common::send(&app, "GET", "/health").await;
+    assert!(!headers.contains_key(TOKEN_REFRESHED_HEADER));
+}
+
+/// Register a user, promote them to admin, and return an admin access token.
+async fn admin_token(app: &axum::Router) -> String {
+    let (username, email) = unique_suffix("inviteadmin");
+    let (_, body) = register_test_user(app, &username, &email, "AdminPass1!").await;
+    let resp: AuthResponse = serde_json::from_str(&body).unwrap();
+
+    let pool = server::db::create_pool();
+    sqlx::query("UPDATE users SET role = 'admin' WHERE id = $1")
+        .bind(resp.user.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    server::auth::jwt::create_access_token(resp.user.id, &email, "admin", "free").unwrap()
+}
+
+/// Create an invite for `email` as admin via the REST API.
+async fn create_invite(app: &axum::Router, email: &str) -> Invite {
+    let token = admin_token(app).await;
+    let json = serde_json::json!({ "email": email });
+    let (status, body) = post_json_with_auth(app, "/api/invites", &json.to_string(), &token).await;
+    assert_eq!(status, StatusCode::CREATED);
+    serde_json::from_str(&body).unwrap()
+}
+
+async fn register_with_invite(
+    app: &axum::Router,
+    username: &str,
+    email: &str,
+    invite_token: &str,
+) -> (StatusCode, String) {
+    let json = serde_json::json!({
+        "username": username,
+        "email": email,
+        "password": "InvitePass1!",
+        "display_name": format!("Test {}", username),
+        "invite_token": invite_token,
+    });
+    post_json(app, "/api/auth/register", &json.to_string()).await
+}
+
+#[tokio::test]
+async fn invite_mode_registers_with_valid_invite() {
+    let app = test_app_with_registration_mode(RegistrationMode::Invite).await;
+    let admin_app = test_app_with_auth().await;
+    let (username, email) = unique_suffix("invited");
+
+    let invite = create_invite(&admin_app, &email).await;
+    assert_eq!(invite.email, email);
+
+    let (status, body) = register_with_invite(&app, &username, &email, &invite.token).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let resp: AuthResponse = serde_json::from_str(&body).unwrap();
+    assert_eq!(resp.user.email, email);
+}
+
+#[tokio::test]
+async fn invite_mode_rejects_used_invite() {
+    let app = test_app_with_registration_mode(RegistrationMode::Invite).await;
+    let admin_app = test_app_with_auth().await;
+    let (username, email) = unique_suffix("reused");
+
+    let invite = create_invite(&admin_app, &email).await;
+    let (status, _) = register_with_invite(&app, &username, &email, &invite.token).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // Same token again (with a different account) is rejected
+    let (username2, _) = unique_suffix("reused2");
+    let (status, body) = register_with_invite(&app, &username2, &email, &invite.token).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let err: AppError = serde_json::from_str(&body).unwrap();
+    assert_eq!(err.kind, shared_types::AppErrorKind::Forbidden);
+}
+
+#[tokio::test]
+async fn invite_mode_requires_invite() {
+    let app = test_app_with_registration_mode(RegistrationMode::Invite).await;
+    let (username, email) = unique_suffix("uninvited");
+
+    let (status, body) = register_test_user(&app, &username, &email, "NoInvite1!").await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let err: AppError = serde_json::from_str(&body).unwrap();
+    assert_eq!(err.kind, shared_types::AppErrorKind::Forbidden);
+
+    // The rejected signup did not create an account
+    let login_json = serde_json::json!({ "email": email, "password": "NoInvite1!" });
+    let (status, _) = post_json(&app, "/api/auth/login", &login_json.to_string()).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn create_invite_non_admin_returns_403() {
+    let app = test_app_with_auth().await;
+    let (username, email) = unique_suffix("invitenonadmin");
+
+    let (_, body) = register_test_user(&app, &username, &email, "NotAdmin1!").await;
+    let resp: AuthResponse = serde_json::from_str(&body).unwrap();
+
+    let json = serde_json::json!({ "email": "friend@test.com" });
+    let (status, _) =
+        post_json_with_auth(&app, "/api/invites", &json.to_string(), &resp.access_token).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+// ── Preferences ────────────────────────────────────────
+
+#[tokio::test]
+async fn preferences_default_until_saved() {
+    let app = test_app_with_auth().await;
+    let (username, email) = unique_suffix("prefs");
+
+    let (_, body) = register_test_user(&app, &username, &email, "PrefsPass1!").await;
+    let resp: AuthResponse = serde_json::from_str(&body).unwrap();
+
+    let (status, body) = get_with_auth(&app, "/api/users/me/preferences", &resp.access_token).await;
+    assert_eq!(status, StatusCode::OK);
+    let prefs: UserPreferences = serde_json::from_str(&body).unwrap();
+    assert_eq!(prefs, UserPreferences::default());
+
+    let updated = UserPreferences {
+        push_notifications: true,
+        compact_mode: true,
+        ..Default::default()
+    };
+    let (status, _) = put_json_with_auth(
+        &app,
+        "/api/users/me/preferences",
+        &serde_json::to_string(&updated).unwrap(),
+        &resp.access_token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, body) = get_with_auth(&app, "/api/users/me/preferences", &resp.access_token).await;
+    let prefs: UserPreferences = serde_json::from_str(&body).unwrap();
+    assert_eq!(prefs, updated);
+}
+
+#[tokio::test]
+async fn theme_preference_round_trip() {
+    let app = test_app_with_auth().await;
+    let (username, email) = unique_suffix("theme");
+
+    let (_, body) = register_test_user(&app, &username, &email, "ThemePass1!").await;
+    let resp: AuthResponse = serde_json::from_str(&body).unwrap();
+
+    let updated = UserPreferences {
+        theme_family: "solar".to_string(),
+        theme_dark: false,
+        ..Default::default()
+    };
+    let (status, body) = put_json_with_auth(
+        &app,
+        "/api/users/me/preferences",
+        &serde_json::to_string(&updated).unwrap(),
+        &resp.access_token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let saved: UserPreferences = serde_json::from_str(&body).unwrap();
+    assert_eq!(saved, updated);
+
+    // A fresh login (e.g. another device) sees the saved theme
+    let login_json = serde_json::json!({
+        "email": email,
+        "password": "ThemePass1!"
+    });
+    let (_, body) = post_json(&app, "/api/auth/login", &login_json.to_string()).await;
+    let login: AuthResponse = serde_json::from_str(&body).unwrap();
+    let (_, body) = get_with_auth(&app, "/api/users/me/preferences", &login.access_token).await;
+    let prefs: UserPreferences = serde_json::from_str(&body).unwrap();
+    assert_eq!(prefs.theme_family, "solar");
+    assert!(!prefs.theme_dark);
+}
+
+#[tokio::test]
+async fn preferences_require_auth() {
+    let app = test_app_with_auth().await;
+
+    let (status, _) = get(&app, "/api/users/me/preferences").await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _) = put_json(&app, "/api/users/me/preferences", "{}").await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+/// Log in through the REST API from a fixed client IP and user-agent.
+///
+/// The connection comes straight from the client, so the spoofed
+/// `X-Forwarded-For` must be ignored.
+async fn login_from_client(app: &axum::Router, email: &str, password: &str) -> StatusCode {
+    let json = serde_json::json!({ "email": email, "password": password });
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/login")
+                .header(header::CONTENT_TYPE, "application/json")
+                .header("x-forwarded-for", "198.51.100.66")
+                .header(header::USER_AGENT, "audit-test/1.0")
+                .extension(ConnectInfo(SocketAddr::from(([203, 0, 113, 9], 40000))))
+                .body(Body::from(json.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    response.status()
+}
+
+/// Auth events for `user_id` of `event_type`, fetched as admin.
+async fn auth_events_for(app: &axum::Router, user_id: i64, event_type: &str) -> Vec<AuthEvent> {
+    let token = admin_token(app).await;
+    let uri = format!("/api/admin/auth-events?user_id={user_id}&event_type={event_type}");
+    let (status, body) = get_with_auth(app, &uri, &token).await;
+    assert_eq!(status, StatusCode::OK);
+    serde_json::from_str(&body).unwrap()
+}
+
+#[tokio::test]
+async fn failed_login_records_auth_event() {
+    let app = test_app_with_auth().await;
+    let (username, email) = unique_suffix("auditfail");
+    let (_, body) = register_test_user(&app, &username, &email, "RealPass1!").await;
+    let user: AuthResponse = serde_json::from_str(&body).unwrap();
+
+    let status = login_from_client(&app, &email, "WrongPass!").await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let events = auth_events_for(&app, user.user.id, "login_failure").await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].event_type, AuthEventType::LoginFailure);
+    assert_eq!(events[0].user_id, Some(user.user.id));
+    assert_eq!(events[0].ip_address.as_deref(), Some("203.0.113.9"));
+    assert_eq!(events[0].user_agent.as_deref(), Some("audit-test/1.0"));
+}
+
+#[tokio::test]
+async fn successful_login_records_auth_event() {
+    let app = test_app_with_auth().await;
+    let (username, email) = unique_suffix("auditok");
+    let (_, body) = register_test_user(&app, &username, &email, "RealPass1!").await;
+    let user: AuthResponse = serde_json::from_str(&body).unwrap();
+
+    let status = login_from_client(&app, &email, "RealPass1!").await;
+    assert_eq!(status, StatusCode::OK);
+
+    let events = auth_events_for(&app, user.user.id, "login_success").await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].event_type, AuthEventType::LoginSuccess);
+    assert_eq!(events[0].ip_address.as_deref(), Some("203.0.113.9"));
+    assert!(auth_events_for(&app, user.user.id, "login_failure")
+        .await
+        .is_empty());
+}
+
+#[tokio::test]
+async fn auth_events_require_admin() {
+    let app = test_app_with_auth().await;
+    let (username, email) = unique_suffix("auditnonadmin");
+    let (_, body) = register_test_user(&app, &username, &email, "UserPass1!").await;
+    let resp: AuthResponse = serde_json::from_str(&body).unwrap();
+
+    let (status, _) = get_with_auth(&app, "/api/admin/auth-events", &resp.access_token).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn tier_change_writes_one_audit_entry() {
+    let app = test_app_with_auth().await;
+    let admin = admin_token(&app).await;
+    let admin_id = server::auth::jwt::validate_access_token(&admin)
+        .unwrap()
+        .sub;
+    let (username, email) = unique_suffix("audittier");
+    let (_, body) = register_test_user(&app, &username, &email, "TierPass1!").await;
+    let user: AuthResponse = serde_json::from_str(&body).unwrap();
+
+    let (status, _) = put_json_with_auth(
+        &app,
+        &format!("/api/users/{}/tier", user.user.id),
+        r#"{"tier":"premium"}"#,
+        &admin,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let uri = format!("/api/audit?target=user:{}", user.user.id);
+    let (status, body) = get_with_auth(&app, &uri, &admin).await;
+    assert_eq!(status, StatusCode::OK);
+    let page: AuditPage = serde_json::from_str(&body).unwrap();
+    assert_eq!(page.total, 1);
+    assert_eq!(page.entries.len(), 1);
+    let entry = &page.entries[0];
+    assert_eq!(entry.action, AuditAction::TierChange);
+    assert_eq!(entry.actor_id, Some(admin_id));
+    assert_eq!(entry.target, format!("user:{}", user.user.id));
+    assert_eq!(entry.metadata["tier"], "premium");
+}
+
+#[tokio::test]
+async fn audit_log_requires_admin() {
+    let app = test_app_with_auth().await;
+    let (username, email) = unique_suffix("auditlognonadmin");
+    let (_, body) = register_test_user(&app, &username, &email, "UserPass1!").await;
+    let resp: AuthResponse = serde_json::from_str(&body).unwrap();
+
+    let (status, _) = get_with_auth(&app, "/api/audit", &resp.access_token).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn product_history_reflects_create_then_update() {
+    let app = test_app_with_auth().await;
+    let (username, email) = unique_suffix("historyowner");
+    let (_, body) = register_test_user(&app, &username, &email, "OwnerPass1!").await;
+    let owner: AuthResponse = serde_json::from_str(&body).unwrap();
+
+    let (status, body) = post_json_with_auth(
+        &app,
+        "/api/v1/products",
+        r#"{"name":"History Widget","description":"","price":3.0,"category":"Hardware","status":"active"}"#,
+        &owner.access_token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let product: Product = serde_json::from_str(&body).unwrap();
+    let (status, _) = put_json_with_auth(
+        &app,
+        &format!("/api/v1/products/{}", product.id),
+        r#"{"name":"History Widget v2","description":"","price":4.0,"category":"Hardware","status":"active"}"#,
+        &owner.access_token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let uri = format!("/api/v1/products/{}/history", product.id);
+    let (status, body) = get_with_auth(&app, &uri, &owner.access_token).await;
+    assert_eq!(status, StatusCode::OK);
+    let history: ProductHistory = serde_json::from_str(&body).unwrap();
+    assert_eq!(history.owner_id, Some(owner.user.id));
+    assert!(history.updated_at > history.created_at, "{history:?}");
+    let entries = history.entries.expect("owner sees audit entries");
+    let actions: Vec<AuditAction> = entries.iter().map(|e| e.action).collect();
+    assert_eq!(
+        actions,
+        vec![AuditAction::ProductUpdate, AuditAction::ProductCreate]
+    );
+    assert!(entries.iter().all(|e| e.actor_id == Some(owner.user.id)));
+    assert_eq!(entries[0].metadata["name"], "History Widget v2");
+
+    // Other users see only the timestamps.
+    let (username, email) = unique_suffix("historyviewer");
+    let (_, body) = register_test_user(&app, &username, &email, "ViewPass1!").await;
+    let viewer: AuthResponse = serde_json::from_str(&body).unwrap();
+    let (status, body) = get_with_auth(&app, &uri, &viewer.access_token).await;
+    assert_eq!(status, StatusCode::OK);
+    let history: ProductHistory = serde_json::from_str(&body).unwrap();
+    assert_eq!(history.entries, None);
+
+    let (status, body) = get_with_auth(&app, &uri, &admin_token(&app).await).await;
+    assert_eq!(status, StatusCode::OK);
+    let history: ProductHistory = serde_json::from_str(&body).unwrap();
+    assert_eq!(history.entries.map(|e| e.len()), Some(2));
+
+    let (status, _) = get(&app, &uri).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    common::delete(&app, &format!("/api/v1/products/{}", product.id)).await;
+}
+
+#[tokio::test]
+async fn list_products_flags_favorites_only_when_authenticated() {
+    let app = test_app_with_auth().await;
+    let (username, email) = unique_suffix("favorites");
+    let (_, body) = register_test_user(&app, &username, &email, "FavPass1!").await;
+    let token = serde_json::from_str::<AuthResponse>(&body)
+        .unwrap()
+        .access_token;
+
+    let (_, body) = post_json(
+        &app,
+        "/api/products",
+        r#"{"name":"Favorite Widget","description":"","price":5.0,"category":"Hardware","status":"active"}"#,
+    )
+    .await;
+    let product: Product = serde_json::from_str(&body).unwrap();
+    let (status, _) = put_json_with_auth(
+        &app,
+        &format!("/api/products/{}/favorite", product.id),
+        "",
+        &token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    let find = |body: &str| -> Product {
+        serde_json::from_str::<Vec<Product>>(body)
+            .unwrap()
+            .into_iter()
+            .find(|p| p.id == product.id)
+            .unwrap()
+    };
+
+    // Anonymous: no flag at all.
+    let (status, body) = get(&app, "/api/products").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(find(&body).is_favorited, None);
+
+    // Authenticated: flagged.
+    let (status, body) = get_with_auth(&app, "/api/products", &token).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(find(&body).is_favorited, Some(true));
+
+    // Also without the auth middleware, from the token alone.
+    let (status, body) = get_with_auth(&test_app().await, "/api/products", &token).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(find(&body).is_favorited, Some(true));
+
+    // Invalid tokens are treated as anonymous rather than rejected.
+    let (status, body) = get_with_auth(&app, "/api/products", "not-a-token").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(find(&body).is_favorited, None);
+
+    common::delete(&app, &format!("/api/products/{}", product.id)).await;
+}
+
+#[tokio::test]
+async fn only_admins_can_list_deleted_products() {
+    let app = test_app_with_auth().await;
+    let (_, body) = post_json(
+        &app,
+        "/api/products",
+        r#"{"name":"Archived Widget","description":"","price":2.0,"category":"Hardware","status":"active"}"#,
+    )
+    .await;
+    let product: Product = serde_json::from_str(&body).unwrap();
+    common::delete(&app, &format!("/api/products/{}", product.id)).await;
+
+    let (status, _) = get(&app, "/api/products?include_deleted=true").await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (username, email) = unique_suffix("deletedviewer");
+    let (_, body) = register_test_user(&app, &username, &email, "ViewPass1!").await;
+    let user: AuthResponse = serde_json::from_str(&body).unwrap();
+    let (status, _) = get_with_auth(
+        &app,
+        "/api/products?include_deleted=true",
+        &user.access_token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let token = admin_token(&app).await;
+    let (status, body) = get_with_auth(&app, "/api/products?include_deleted=true", &token).await;
+    assert_eq!(status, StatusCode::OK);
+    let products: Vec<Product> = serde_json::from_str(&body).unwrap();
+    let archived = products.iter().find(|p| p.id == product.id).unwrap();
+    assert!(archived.deleted_at.is_some());
+}
+
+/// GET `uri` with a bearer token, returning the status, headers, and body.
+async fn get_response_with_auth(
+    app: &axum::Router,
+    uri: &str,
+    token: &str,
+) -> (StatusCode, axum::http::HeaderMap, String) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(uri)
+                .header(header::AUTHORIZATION, format!("Bearer {token}"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, headers, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn products_export_is_a_csv_download() {
+    let app = test_app_with_auth().await;
+    let (_, body) = post_json(
+        &app,
+        "/api/products",
+        r#"{"name":"Export Widget, Deluxe","description":"For the \"CSV\" test","price":12.5,"category":"Hardware","status":"active"}"#,
+    )
+    .await;
+    let product: Product = serde_json::from_str(&body).unwrap();
+    let token = admin_token(&app).await;
+
+    let (status, headers, body) =
+        get_response_with_auth(&app, "/api/products/export.csv", &token).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        headers[header::CONTENT_DISPOSITION],
+        "attachment; filename=\"products.csv\""
+    );
+    assert!(headers[header::CONTENT_TYPE]
+        .to_str()
+        .unwrap()
+        .starts_with("text/csv"));
+
+    let mut lines = body.lines();
+    assert_eq!(
+        lines.next(),
+        Some("id,name,description,price,category,status,created_at,deleted_at")
+    );
+    let expected = format!(
+        "{},\"Export Widget, Deluxe\",\"For the \"\"CSV\"\" test\",12.50,Hardware,active,",
+        product.id
+    );
+    assert!(lines.any(|line| line.starts_with(&expected)));
+}
+
+#[tokio::test]
+async fn users_export_requires_admin_and_lists_users() {
+    let app = test_app_with_auth().await;
+    let (username, email) = unique_suffix("exportuser");
+    let (_, body) = register_test_user(&app, &username, &email, "ExportPass1!").await;
+    let user: AuthResponse = serde_json::from_str(&body).unwrap();
+
+    let (status, _, _) =
+        get_response_with_auth(&app, "/api/users/export.csv", &user.access_token).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let token = admin_token(&app).await;
+    let (status, _, body) = get_response_with_auth(&app, "/api/users/export.csv", &token).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.starts_with("id,username,display_name,role,tier,created_at,updated_at\r\n"));
+    let expected = format!("{},{username},", user.user.id);
+    assert!(body.lines().any(|line| line.starts_with(&expected)));
+}
+
+#[tokio::test]
+async fn display_name_update_leaves_email_untouched() {
+    let app = test_app_with_auth().await;
+    let (username, email) = unique_suffix("renamer");
+    let (_, body) = register_test_user(&app, &username, &email, "RenamePass1!").await;
+    let registered: AuthResponse = serde_json::from_str(&body).unwrap();
+
+    let (status, body) = put_json_with_auth(
+        &app,
+        "/api/users/me/display-name",
+        r#"{"display_name":"  Neo Anderson "}"#,
+        &registered.access_token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let user: AuthUser = serde_json::from_str(&body).unwrap();
+    assert_eq!(user.display_name, "Neo Anderson");
+    assert_eq!(user.email, registered.user.email);
+
+    // The stored account still signs in with the original email.
+    let json = serde_json::json!({ "email": email, "password": "RenamePass1!" });
+    let (status, body) = post_json(&app, "/api/auth/login", &json.to_string()).await;
+    assert_eq!(status, StatusCode::OK);
+    let login: AuthResponse = serde_json::from_str(&body).unwrap();
+    assert_eq!(login.user.display_name, "Neo Anderson");
+    assert_eq!(login.user.email, email);
+}
+
+#[tokio::test]
+async fn blank_display_name_is_rejected() {
+    let app = test_app_with_auth().await;
+    let (username, email) = unique_suffix("blankname");
+    let (_, body) = register_test_user(&app, &username, &email, "BlankPass1!").await;
+    let registered: AuthResponse = serde_json::from_str(&body).unwrap();
+
+    let (status, body) = put_json_with_auth(
+        &app,
+        "/api/users/me/display-name",
+        r#"{"display_name":"   "}"#,
+        &registered.access_token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let err: AppError = serde_json::from_str(&body).unwrap();
+    assert!(err.field_errors.contains_key("display_name"));
+}
+
+/// Register a user and schedule their account for deletion, returning the
+/// registration response.
+async fn register_and_schedule_deletion(
+    app: &axum::Router,
+    prefix: &str,
+    password: &str,
+) -> AuthResponse {
+    let (username, email) = unique_suffix(prefix);
+    let (_, body) = register_test_user(app, &username, &email, password).await;
+    let registered: AuthResponse = serde_json::from_str(&body).unwrap();
+
+    let (status, body) =
+        post_json_with_auth(app, "/api/users/me/deletion", "", &registered.access_token).await;
+    assert_eq!(status, StatusCode::OK);
+    let _: AccountDeletion = serde_json::from_str(&body).unwrap();
+    registered
+}
+
+async fn login_status(app: &axum::Router, email: &str, password: &str) -> StatusCode {
+    let json = serde_json::json!({ "email": email, "password": password });
+    post_json(app, "/api/auth/login", &json.to_string()).await.0
+}
+
+#[tokio::test]
+async fn scheduled_deletion_blocks_login_but_keeps_the_account() {
+    let app = test_app_with_auth().await;
+    let user = register_and_schedule_deletion(&app, "graceblock", "GracePass1!").await;
+
+    let (status, body) = get_with_auth(&app, "/api/users/me/deletion", &user.access_token).await;
+    assert_eq!(status, StatusCode::OK);
+    let pending: Option<AccountDeletion> = serde_json::from_str(&body).unwrap();
+    assert!(pending.is_some());
+
+    assert_eq!(
+        login_status(&app, &user.user.email, "GracePass1!").await,
+        StatusCode::FORBIDDEN
+    );
+    // Wrong passwords still get the generic error, not the account's state.
+    assert_eq!(
+        login_status(&app, &user.user.email, "WrongPass1!").await,
+        StatusCode::UNAUTHORIZED
+    );
+
+    let (status, _) = get(&app, &format!("/api/users/{}", user.user.id)).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn cancelling_deletion_restores_login() {
+    let app = test_app_with_auth().await;
+    let user = register_and_schedule_deletion(&app, "gracecancel", "GracePass1!").await;
+
+    let (status, _) = delete_with_auth(&app, "/api/users/me/deletion", &user.access_token).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    assert_eq!(
+        login_status(&app, &user.user.email, "GracePass1!").await,
+        StatusCode::OK
+    );
+
+    let (status, _) = delete_with_auth(&app, "/api/users/me/deletion", &user.access_token).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn deletion_can_be_cancelled_with_credentials() {
+    let app = test_app_with_auth().await;
+    let user = register_and_schedule_deletion(&app, "gracecreds", "GracePass1!").await;
+
+    let wrong = serde_json::json!({ "email": user.user.email, "password": "WrongPass1!" });
+    let (status, _) = post_json(&app, "/api/auth/cancel-deletion", &wrong.to_string()).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let json = serde_json::json!({ "email": user.user.email, "password": "GracePass1!" });
+    let (status, _) = post_json(&app, "/api/auth/cancel-deletion", &json.to_string()).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    assert_eq!(
+        login_status(&app, &user.user.email, "GracePass1!").await,
+        StatusCode::OK
+    );
+}
+
+#[tokio::test]
+async fn accounts_past_their_grace_period_are_purged() {
+    let app = test_app_with_auth().await;
+    let user = register_and_schedule_deletion(&app, "gracepurge", "GracePass1!").await;
+
+    let pool = server::db::create_pool();
+    sqlx::query("UPDATE users SET deletion_scheduled_for = NOW() - INTERVAL '1 day' WHERE id = $1")
+        .bind(user.user.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let purged = server::auth::deletion::purge_expired(&pool).await.unwrap();
+    assert!(purged >= 1);
+    let (status, _) = get(&app, &format!("/api/users/{}", user.user.id)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn registering_a_taken_email_is_a_field_error() {
+    let app = test_app_with_auth().await;
+    let (username, email) = unique_suffix("dupemail");
+    let (status, _) = register_test_user(&app, &username, &email, "DupPass123!").await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (other_username, _) = unique_suffix("dupemail2");
+    let (status, body) = register_test_user(&app, &other_username, &email, "DupPass123!").await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let err: AppError = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        err.field_errors.get("email").map(String::as_str),
+        Some("Email already in use")
+    );
+    assert!(!err.field_errors.contains_key("username"));
+}
+
+#[tokio::test]
+async fn registering_a_taken_username_is_a_field_error() {
+    let app = test_app_with_auth().await;
+    let (username, email) = unique_suffix("dupname");
+    let (status, _) = register_test_user(&app, &username, &email, "DupPass123!").await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (_, other_email) = unique_suffix("dupname2");
+    let (status, body) = register_test_user(&app, &username, &other_email, "DupPass123!").await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let err: AppError = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        err.field_errors.get("username").map(String::as_str),
+        Some("Username taken")
+    );
+}
+
+/// Upload `png` as the caller's avatar, returning (status, body).
+async fn upload_avatar(app: &axum::Router, token: &str, png: Vec<u8>) -> (StatusCode, String) {
+    let boundary = "avatar-boundary";
+    let mut payload = format!(
+        "--{boundary}\r\nContent-Disposition: form-data; name=\"avatar\"; filename=\"avatar.png\"\r\nContent-Type: image/png\r\n\r\n"
+    )
+    .into_bytes();
+    payload.extend(png);
+    payload.extend(format!("\r\n--{boundary}--\r\n").into_bytes());
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/users/me/avatar")
+                .header(header::AUTHORIZATION, format!("Bearer {token}"))
+                .header(
+                    header::CONTENT_TYPE,
+                    format!("multipart/form-data; boundary={boundary}"),
+                )
+                .header(header::CONTENT_LENGTH, payload.len())
+                .body(Body::from(payload))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn oversized_avatar_upload_is_a_structured_error() {
+    let app = test_app_with_auth().await;
+    let (username, email) = unique_suffix("bigavatar");
+    let (_, body) = register_test_user(&app, &username, &email, "AvatarPass1!").await;
+    let auth: AuthResponse = serde_json::from_str(&body).unwrap();
+
+    let (status, body) = upload_avatar(&app, &auth.access_token, vec![0u8; 3 * 1024 * 1024]).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let err: AppError = serde_json::from_str(&body).unwrap();
+    assert_eq!(err.message, "File too large");
+    assert!(err.field_errors.contains_key("avatar"));
+}
+
+#[tokio::test]
+async fn replacing_an_avatar_deletes_the_previous_objects() {
+    let objects = common::s3_stub();
+    let app = test_app_with_auth().await;
+    let (username, email) = unique_suffix("avatarswap");
+    let (_, body) = register_test_user(&app, &username, &email, "AvatarPass1!").await;
+    let auth: AuthResponse = serde_json::from_str(&body).unwrap();
+    let png = |name: &str| {
+        server::avatar_fallback::render_fallback_avatar(auth.user.id, name, 64).unwrap()
+    };
+    let stored = |key: &str| {
+        objects
+            .lock()
+            .unwrap()
+            .contains_key(&format!("avatars/{key}"))
+    };
+
+    let (status, body) = upload_avatar(&app, &auth.access_token, png("First Upload")).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let first: AuthUser = serde_json::from_str(&body).unwrap();
+    let first_keys = [first.avatar_url.unwrap(), first.thumbnail_url.unwrap()];
+    assert!(first_keys.iter().all(|key| stored(key)));
+
+    let (status, body) = upload_avatar(&app, &auth.access_token, png("Second Upload")).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let second: AuthUser = serde_json::from_str(&body).unwrap();
+    let second_keys = [second.avatar_url.unwrap(), second.thumbnail_url.unwrap()];
+
+    assert_ne!(first_keys, second_keys);
+    assert!(first_keys.iter().all(|key| !stored(key)));
+    assert!(second_keys.iter().all(|key| stored(key)));
+}
+
+#[tokio::test]
+async fn oauth_sign_up_needs_an_invite_in_invite_only_mode() {
+    use server::auth::oauth::{upsert_oauth_user, OAuthUserInfo};
+
+    let pool = server::db::create_pool();
+    server::db::run_migrations(&pool).await;
+    let (username, email) = unique_suffix("oauthinvite");
+    let info = OAuthUserInfo {
+        provider: shared_types::OAuthProvider::GitHub,
+        provider_id: username,
+        email,
+        email_verified: true,
+        display_name: "Invited Octocat".to_string(),
+        avatar_url: None,
+    };
+
+    let err = upsert_oauth_user(&pool, &info, RegistrationMode::Invite)
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind, shared_types::AppErrorKind::Forbidden);
+
+    // Identities that already have an account keep signing in
+    let account = upsert_oauth_user(&pool, &info, RegistrationMode::Public)
+        .await
+        .unwrap();
+    let again = upsert_oauth_user(&pool, &info, RegistrationMode::Invite)
+        .await
+        .unwrap();
+    assert_eq!(again.id, account.id);
+}
+
+#[tokio::test]
+async fn only_verified_oauth_emails_link_existing_accounts() {
+    use server::auth::oauth::{upsert_oauth_user, OAuthUserInfo};
+    use shared_types::OAuthProvider;
+
+    let pool = server::db::create_pool();
+    server::db::run_migrations(&pool).await;
+    let (username, email) = unique_suffix("oauthlink");
+    let identity = |provider, email: &str, email_verified| OAuthUserInfo {
+        provider,
+        provider_id: format!("{username}-{email_verified}"),
+        email: email.to_string(),
+        email_verified,
+        display_name: "Linked User".to_string(),
+        avatar_url: None,
+    };
+    let owner = upsert_oauth_user(
+        &pool,
+        &identity(OAuthProvider::GitHub, &email, true),
+        RegistrationMode::Public,
+    )
+    .await
+    .unwrap();
+    assert_eq!(owner.email, email);
+
+    // A Microsoft `mail` is not proof of ownership, whatever its case
+    let microsoft = upsert_oauth_user(
+        &pool,
+        &identity(OAuthProvider::Microsoft, &email.to_uppercase(), false),
+        RegistrationMode::Public,
+    )
+    .await
+    .unwrap();
+    assert_ne!(microsoft.id, owner.id);
+    assert_eq!(microsoft.email, "");
+
+    // A verified email links, matched case-insensitively
+    let google = upsert_oauth_user(
+        &pool,
+        &identity(OAuthProvider::Google, &email.to_uppercase(), true),
+        RegistrationMode::Public,
+    )
+    .await
+    .unwrap();
+    assert_eq!(google.id, owner.id);
+    assert_eq!(google.email, email);
+}
+
+#[tokio::test]
+async fn oauth_providers_lists_the_configured_providers() {
+    let app = test_app().await;
+    let (status, body) = get(&app, "/api/v1/auth/providers").await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Read after the app is built, so `.env` has been loaded.
+    let providers: Vec<OAuthProviderInfo> = serde_json::from_str(&body).unwrap();
+    assert_eq!(providers, server::auth::oauth::enabled_providers());
+    for provider in &providers {
+        let (status, _) = get(
+            &app,
+            &format!("/api/v1/auth/oauth/{}/authorize", provider.id),
+        )
+        .await;
+        assert_ne!(status, StatusCode::INTERNAL_SERVER_ERROR, "{}", provider.id);
+    }
+}
+
+/// The outstanding verification token for `user_id`, if any.
+async fn verification_token(pool: &sqlx::PgPool, user_id: i64) -> Option<String> {
+    sqlx::query_scalar("SELECT token FROM email_verifications WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn registration_sends_a_verification_link_that_verifies_the_email() {
+    let app = test_app_with_auth().await;
+    let (username, email) = unique_suffix("verifyme");
+    let (_, body) = register_test_user(&app, &username, &email, "VerifyPass1!").await;
+    let registered: AuthResponse = serde_json::from_str(&body).unwrap();
+    assert!(registered.user.needs_email_verification());
+
+    let pool = server::db::create_pool();
+    let token = verification_token(&pool, registered.user.id).await.unwrap();
+    let json = serde_json::json!({ "token": token });
+    let (status, _) = post_json(&app, "/api/auth/verify-email", &json.to_string()).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    assert!(
+        server::auth::verification::is_verified(&pool, registered.user.id)
+            .await
+            .unwrap()
+    );
+
+    // The link only works once.
+    let (status, _) = post_json(&app, "/api/auth/verify-email", &json.to_string()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn resending_verification_to_a_verified_email_is_a_no_op() {
+    let app = test_app_with_auth().await;
+    let (username, email) = unique_suffix("alreadyverified");
+    let (_, body) = register_test_user(&app, &username, &email, "VerifiedPass1!").await;
+    let registered: AuthResponse = serde_json::from_str(&body).unwrap();
+
+    let pool = server::db::create_pool();
+    sqlx::query("UPDATE users SET email_verified_at = NOW() WHERE id = $1")
+        .bind(registered.user.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM email_verifications WHERE user_id = $1")
+        .bind(registered.user.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let (status, _) = post_json_with_auth(
+        &app,
+        "/api/users/me/verification-email",
+        "",
+        &registered.access_token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    assert_eq!(verification_token(&pool, registered.user.id).await, None);
+}
+
+#[tokio::test]
+async fn verification_resends_are_rate_limited() {
+    let app = test_app_with_auth().await;
+    let (username, email) = unique_suffix("resender");
+    let (_, body) = register_test_user(&app, &username, &email, "ResendPass1!").await;
+    let registered: AuthResponse = serde_json::from_str(&body).unwrap();
+
+    let pool = server::db::create_pool();
+    let mut previous = verification_token(&pool, registered.user.id).await;
+    for _ in 0..server::auth::verification::RESENDS_PER_HOUR {
+        let (status, _) = post_json_with_auth(
+            &app,
+            "/api/users/me/verification-email",
+            "",
+            &registered.access_token,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        // Each resend replaces the previous link.
+        let current = verification_token(&pool, registered.user.id).await;
+        assert!(current.is_some());
+        assert_ne!(current, previous);
+        previous = current;
+    }
+
+    let (status, body) = post_json_with_auth(
+        &app,
+        "/api/users/me/verification-email",
+        "",
+        &registered.access_token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    let err: AppError = serde_json::from_str(&body).unwrap();
+    assert!(err.message.contains("Too many verification emails"));
+}
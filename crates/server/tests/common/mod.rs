@@ -1,9 +1,13 @@
+use std::collections::HashMap;
+
 use axum::{
     body::Body,
-    http::{Request, StatusCode},
+    http::{HeaderMap, Request, StatusCode},
     middleware, Router,
 };
+use server::auth::invites::RegistrationMode;
 use server::db::AppState;
+use server::site::SiteConfig;
 use tower::ServiceExt;
 
 #[allow(dead_code)]
@@ -12,20 +16,48 @@ use tower::ServiceExt;
 pub async fn test_app() -> Router {
     let pool = server::db::create_pool();
     server::db::run_migrations(&pool).await;
-    let state = AppState { pool };
+    let state = AppState::new(pool);
 
     server::rest::rest_router()
+        .merge(server::site::site_router())
         .route("/health", axum::routing::get(server::health::health_check))
         .with_state(state)
 }
 
+#[allow(dead_code)]
+/// Like [`test_app`], with explicit site settings instead of reading
+/// `PUBLIC_BASE_URL`/`ROBOTS_*` (tests share one process environment).
+pub async fn test_app_with_site(site: SiteConfig) -> Router {
+    let pool = server::db::create_pool();
+    server::db::run_migrations(&pool).await;
+    let state = AppState {
+        site,
+        ..AppState::new(pool)
+    };
+
+    server::rest::rest_router()
+        .merge(server::site::site_router())
+        .with_state(state)
+}
+
 #[allow(dead_code)]
 /// Build a test router with auth middleware enabled.
 /// Required for endpoints that use AuthRequired/TierRequired extractors.
 pub async fn test_app_with_auth() -> Router {
+    test_app_with_registration_mode(RegistrationMode::Public).await
+}
+
+#[allow(dead_code)]
+/// Like [`test_app_with_auth`], with an explicit registration mode instead of
+/// reading `REGISTRATION_MODE` (tests share one process environment).
+pub async fn test_app_with_registration_mode(registration_mode: RegistrationMode) -> Router {
     let pool = server::db::create_pool();
     server::db::run_migrations(&pool).await;
-    let state = AppState { pool };
+    let state = AppState {
+        pool,
+        registration_mode,
+        site: SiteConfig::default(),
+    };
 
     server::rest::rest_router()
         .route("/health", axum::routing::get(server::health::health_check))
@@ -69,6 +101,46 @@ pub async fn get(app: &Router, uri: &str) -> (StatusCode, String) {
     (status, String::from_utf8(body.to_vec()).unwrap())
 }
 
+#[allow(dead_code)]
+/// Helper to make a GET request for a binary response and return
+/// (status, headers, body).
+pub async fn get_bytes(app: &Router, uri: &str) -> (StatusCode, HeaderMap, Vec<u8>) {
+    let response = app
+        .clone()
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, headers, body.to_vec())
+}
+
+#[allow(dead_code)]
+/// Helper to make a GET request with Bearer auth and return (status, body).
+pub async fn get_with_auth(app: &Router, uri: &str, token: &str) -> (StatusCode, String) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(uri)
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
 #[allow(dead_code)]
 /// Helper to make a POST request with JSON body.
 pub async fn post_json(app: &Router, uri: &str, json: &str) -> (StatusCode, String) {
@@ -194,3 +266,141 @@ pub async fn delete(app: &Router, uri: &str) -> (StatusCode, String) {
         .unwrap();
     (status, String::from_utf8(body.to_vec()).unwrap())
 }
+
+#[allow(dead_code)]
+/// Helper to make an authenticated DELETE request.
+pub async fn delete_with_auth(app: &Router, uri: &str, token: &str) -> (StatusCode, String) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("DELETE")
+                .uri(uri)
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[allow(dead_code)]
+/// Helper to make a bodyless request with any method and return (status, headers, body).
+pub async fn send(
+    app: &Router,
+    method: &str,
+    uri: &str,
+) -> (StatusCode, axum::http::HeaderMap, String) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, headers, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[allow(dead_code)]
+/// Helper to make a conditional GET with `If-None-Match` and return (status, headers, body).
+pub async fn get_if_none_match(
+    app: &Router,
+    uri: &str,
+    etag: &str,
+) -> (StatusCode, axum::http::HeaderMap, String) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(uri)
+                .header("if-none-match", etag)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, headers, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[allow(dead_code)]
+/// Objects held by [`s3_stub`], keyed by `{bucket}/{key}`.
+pub type S3Objects = std::sync::Arc<std::sync::Mutex<HashMap<String, Vec<u8>>>>;
+
+#[allow(dead_code)]
+/// Start an in-memory S3 stand-in, once per test binary, and point the
+/// `S3_*` settings at it. Returns its objects so tests can check what
+/// handlers wrote and deleted.
+///
+/// It runs on its own thread and runtime, as each `#[tokio::test]` has a
+/// runtime of its own that ends with the test.
+pub fn s3_stub() -> S3Objects {
+    use axum::{body::Bytes, extract::Path, routing::put};
+    use std::sync::OnceLock;
+
+    static OBJECTS: OnceLock<S3Objects> = OnceLock::new();
+    OBJECTS
+        .get_or_init(|| {
+            let objects = S3Objects::default();
+            let (put_objects, get_objects, delete_objects) =
+                (objects.clone(), objects.clone(), objects.clone());
+            let router = Router::new().route(
+                "/{*path}",
+                put(move |Path(path): Path<String>, body: Bytes| async move {
+                    put_objects.lock().unwrap().insert(path, body.to_vec());
+                    StatusCode::OK
+                })
+                .get(move |Path(path): Path<String>| async move {
+                    match get_objects.lock().unwrap().get(&path) {
+                        Some(body) => (StatusCode::OK, body.clone()),
+                        None => (StatusCode::NOT_FOUND, Vec::new()),
+                    }
+                })
+                .delete(move |Path(path): Path<String>| async move {
+                    delete_objects.lock().unwrap().remove(&path);
+                    StatusCode::NO_CONTENT
+                }),
+            );
+
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.set_nonblocking(true).unwrap();
+            let addr = listener.local_addr().unwrap();
+            std::thread::spawn(move || {
+                tokio::runtime::Runtime::new()
+                    .unwrap()
+                    .block_on(async move {
+                        let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+                        axum::serve(listener, router).await.unwrap();
+                    })
+            });
+
+            std::env::set_var("S3_ENDPOINT", format!("http://{addr}"));
+            std::env::set_var("S3_ACCESS_KEY", "test-key");
+            std::env::set_var("S3_SECRET_KEY", "test-secret");
+            std::env::set_var("S3_BUCKET", "avatars");
+            objects
+        })
+        .clone()
+}
@@ -5,23 +5,36 @@ use std::fmt;
 /// Categorization of application errors.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "openapi", schema(example = "ValidationError"))]
 pub enum AppErrorKind {
+    /// The request could not be parsed (malformed JSON, wrong field types).
+    BadRequest,
     NotFound,
+    /// The request parsed but failed validation rules.
     ValidationError,
     DatabaseError,
     Unauthorized,
     Forbidden,
+    MethodNotAllowed,
+    /// The caller hit a rate limit and should retry later.
+    TooManyRequests,
+    /// The server is overloaded and shed the request; retry later.
+    ServiceUnavailable,
     InternalError,
 }
 
 impl fmt::Display for AppErrorKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            AppErrorKind::BadRequest => write!(f, "BadRequest"),
             AppErrorKind::NotFound => write!(f, "NotFound"),
             AppErrorKind::ValidationError => write!(f, "ValidationError"),
             AppErrorKind::DatabaseError => write!(f, "DatabaseError"),
             AppErrorKind::Unauthorized => write!(f, "Unauthorized"),
             AppErrorKind::Forbidden => write!(f, "Forbidden"),
+            AppErrorKind::MethodNotAllowed => write!(f, "MethodNotAllowed"),
+            AppErrorKind::TooManyRequests => write!(f, "TooManyRequests"),
+            AppErrorKind::ServiceUnavailable => write!(f, "ServiceUnavailable"),
             AppErrorKind::InternalError => write!(f, "InternalError"),
         }
     }
@@ -30,6 +43,14 @@ impl fmt::Display for AppErrorKind {
 /// Structured application error used across server and client.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(
+    feature = "openapi",
+    schema(example = json!({
+        "kind": "ValidationError",
+        "message": "Validation failed",
+        "field_errors": { "username": "Username must be at least 3 characters" }
+    }))
+)]
 pub struct AppError {
     pub kind: AppErrorKind,
     pub message: String,
@@ -38,6 +59,14 @@ pub struct AppError {
 }
 
 impl AppError {
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self {
+            kind: AppErrorKind::BadRequest,
+            message: message.into(),
+            field_errors: HashMap::new(),
+        }
+    }
+
     pub fn not_found(message: impl Into<String>) -> Self {
         Self {
             kind: AppErrorKind::NotFound,
@@ -78,6 +107,30 @@ impl AppError {
         }
     }
 
+    pub fn method_not_allowed(message: impl Into<String>) -> Self {
+        Self {
+            kind: AppErrorKind::MethodNotAllowed,
+            message: message.into(),
+            field_errors: HashMap::new(),
+        }
+    }
+
+    pub fn too_many_requests(message: impl Into<String>) -> Self {
+        Self {
+            kind: AppErrorKind::TooManyRequests,
+            message: message.into(),
+            field_errors: HashMap::new(),
+        }
+    }
+
+    pub fn service_unavailable(message: impl Into<String>) -> Self {
+        Self {
+            kind: AppErrorKind::ServiceUnavailable,
+            message: message.into(),
+            field_errors: HashMap::new(),
+        }
+    }
+
     pub fn internal(message: impl Into<String>) -> Self {
         Self {
             kind: AppErrorKind::InternalError,
@@ -131,11 +184,15 @@ impl AppError {
     #[cfg_attr(not(feature = "server"), allow(dead_code))]
     fn status_code_u16(&self) -> u16 {
         match self.kind {
+            AppErrorKind::BadRequest => 400,
             AppErrorKind::NotFound => 404,
             AppErrorKind::ValidationError => 422,
             AppErrorKind::DatabaseError => 500,
             AppErrorKind::Unauthorized => 401,
             AppErrorKind::Forbidden => 403,
+            AppErrorKind::MethodNotAllowed => 405,
+            AppErrorKind::TooManyRequests => 429,
+            AppErrorKind::ServiceUnavailable => 503,
             AppErrorKind::InternalError => 500,
         }
     }
@@ -235,6 +292,7 @@ mod tests {
 
     #[test]
     fn status_code_mapping() {
+        assert_eq!(AppError::bad_request("").status_code_u16(), 400);
         assert_eq!(AppError::not_found("").status_code_u16(), 404);
         assert_eq!(
             AppError::validation("", HashMap::new()).status_code_u16(),
@@ -243,6 +301,8 @@ mod tests {
         assert_eq!(AppError::database("").status_code_u16(), 500);
         assert_eq!(AppError::unauthorized("").status_code_u16(), 401);
         assert_eq!(AppError::forbidden("").status_code_u16(), 403);
+        assert_eq!(AppError::method_not_allowed("").status_code_u16(), 405);
+        assert_eq!(AppError::too_many_requests("").status_code_u16(), 429);
         assert_eq!(AppError::internal("").status_code_u16(), 500);
     }
 
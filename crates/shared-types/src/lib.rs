@@ -1,7 +1,11 @@
 pub mod error;
 pub mod models;
+pub mod money;
+pub mod pagination;
 pub mod requests;
 
 pub use error::*;
 pub use models::*;
+pub use money::*;
+pub use pagination::*;
 pub use requests::*;
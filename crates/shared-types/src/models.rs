@@ -1,4 +1,8 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::{AppError, Cursor, Money};
 
 /// User subscription tier controlling feature access.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
@@ -12,7 +16,7 @@ pub enum UserTier {
 
 impl UserTier {
     /// Numeric rank for tier comparison.
-    fn rank(&self) -> u8 {
+    pub fn rank(&self) -> u8 {
         match self {
             UserTier::Free => 0,
             UserTier::Premium => 1,
@@ -42,6 +46,69 @@ impl UserTier {
             UserTier::Elite => "elite",
         }
     }
+
+    /// Human-readable name for display in UI.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            UserTier::Free => "Free",
+            UserTier::Premium => "Premium",
+            UserTier::Elite => "Elite",
+        }
+    }
+
+    /// Badge style used when rendering this tier.
+    pub fn badge_variant(&self) -> TierBadgeVariant {
+        match self {
+            UserTier::Free => TierBadgeVariant::Secondary,
+            UserTier::Premium => TierBadgeVariant::Primary,
+            UserTier::Elite => TierBadgeVariant::Destructive,
+        }
+    }
+}
+
+/// UI-agnostic badge style for a [`UserTier`].
+///
+/// Mirrors the UI badge variants so the app can map it one-to-one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TierBadgeVariant {
+    Primary,
+    Secondary,
+    Destructive,
+}
+
+/// User role controlling administrative access.
+///
+/// Serialized as a lowercase string, matching the `users.role` column.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum Role {
+    #[default]
+    User,
+    Admin,
+}
+
+impl Role {
+    /// Parse a role string, defaulting to User for unknown values.
+    pub fn from_str_or_default(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "admin" => Role::Admin,
+            _ => Role::User,
+        }
+    }
+
+    /// Serialize to lowercase string for database storage.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::User => "user",
+            Role::Admin => "admin",
+        }
+    }
+
+    /// Whether this role grants administrative privileges.
+    pub fn is_admin(&self) -> bool {
+        matches!(self, Role::Admin)
+    }
 }
 
 /// Supported OAuth identity providers.
@@ -50,6 +117,7 @@ impl UserTier {
 pub enum OAuthProvider {
     Google,
     GitHub,
+    Microsoft,
 }
 
 impl OAuthProvider {
@@ -57,6 +125,7 @@ impl OAuthProvider {
         match self {
             OAuthProvider::Google => "google",
             OAuthProvider::GitHub => "github",
+            OAuthProvider::Microsoft => "microsoft",
         }
     }
 
@@ -64,16 +133,81 @@ impl OAuthProvider {
         match s.to_lowercase().as_str() {
             "google" => Some(OAuthProvider::Google),
             "github" => Some(OAuthProvider::GitHub),
+            "microsoft" => Some(OAuthProvider::Microsoft),
             _ => None,
         }
     }
+
+    /// Name shown to users, e.g. on sign-in buttons.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            OAuthProvider::Google => "Google",
+            OAuthProvider::GitHub => "GitHub",
+            OAuthProvider::Microsoft => "Microsoft",
+        }
+    }
+
+    /// Every supported provider, in the order sign-in buttons are shown.
+    pub fn all() -> [OAuthProvider; 3] {
+        [
+            OAuthProvider::Google,
+            OAuthProvider::GitHub,
+            OAuthProvider::Microsoft,
+        ]
+    }
 }
 
-/// Parameters received from an OAuth callback redirect.
+/// An OAuth provider users can sign in with on this server.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct OAuthProviderInfo {
+    pub provider: OAuthProvider,
+    /// Lowercase key used in URLs, e.g. `github`.
+    pub id: String,
+    /// Name to show on the sign-in button.
+    pub display_name: String,
+    /// Brand mark to show next to the name; clients map it to an icon.
+    pub icon: String,
+}
+
+impl From<OAuthProvider> for OAuthProviderInfo {
+    fn from(provider: OAuthProvider) -> Self {
+        Self {
+            id: provider.as_str().to_string(),
+            display_name: provider.display_name().to_string(),
+            icon: provider.as_str().to_string(),
+            provider,
+        }
+    }
+}
+
+/// The ways the current user can sign in.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LoginMethods {
+    /// Whether the account has a password set.
+    pub has_password: bool,
+    /// OAuth providers linked to the account.
+    pub oauth_providers: Vec<OAuthProvider>,
+}
+
+impl LoginMethods {
+    /// Whether `provider` could be unlinked without leaving the account
+    /// with no way to sign in.
+    pub fn can_unlink(&self, provider: &OAuthProvider) -> bool {
+        self.oauth_providers.contains(provider)
+            && (self.has_password || self.oauth_providers.len() > 1)
+    }
+}
+
+/// Parameters received from an OAuth callback redirect.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema, utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
 pub struct OAuthCallbackParams {
+    /// Authorization code issued by the provider.
     pub code: String,
+    /// CSRF state from the authorize step.
     pub state: String,
 }
 
@@ -86,6 +220,86 @@ pub struct User {
     pub display_name: String,
     pub role: String,
     pub tier: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl User {
+    /// Calendar date (`YYYY-MM-DD`) the user joined, for compact display.
+    pub fn joined_date(&self) -> &str {
+        self.created_at.get(..10).unwrap_or(&self.created_at)
+    }
+}
+
+/// Lifecycle status of a catalog product.
+///
+/// Serialized as a lowercase string so it maps directly onto the `products.status` column.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum ProductStatus {
+    #[default]
+    Active,
+    Draft,
+    Archived,
+}
+
+impl ProductStatus {
+    /// All statuses in display order.
+    pub fn all() -> [ProductStatus; 3] {
+        [
+            ProductStatus::Active,
+            ProductStatus::Draft,
+            ProductStatus::Archived,
+        ]
+    }
+
+    /// Parse a status string, returning None for unknown values.
+    pub fn parse_status(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "active" => Some(ProductStatus::Active),
+            "draft" => Some(ProductStatus::Draft),
+            "archived" => Some(ProductStatus::Archived),
+            _ => None,
+        }
+    }
+
+    /// Parse a status string, defaulting to Active for unknown values.
+    pub fn from_str_or_default(s: &str) -> Self {
+        Self::parse_status(s).unwrap_or_default()
+    }
+
+    /// Serialize to lowercase string for database storage.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProductStatus::Active => "active",
+            ProductStatus::Draft => "draft",
+            ProductStatus::Archived => "archived",
+        }
+    }
+}
+
+impl fmt::Display for ProductStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Message for a status that isn't one of the [`ProductStatus`] values.
+pub(crate) const PRODUCT_STATUS_MESSAGE: &str = "Status must be one of: active, draft, archived";
+
+/// Strict parse used at API boundaries. Unknown values produce a
+/// validation error with a `status` field error.
+impl std::str::FromStr for ProductStatus {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_status(s).ok_or_else(|| {
+            let mut field_errors = HashMap::new();
+            field_errors.insert("status".to_string(), PRODUCT_STATUS_MESSAGE.to_string());
+            AppError::validation("Validation failed", field_errors)
+        })
+    }
 }
 
 /// A product available in the catalog.
@@ -95,10 +309,35 @@ pub struct Product {
     pub id: i64,
     pub name: String,
     pub description: String,
-    pub price: f64,
+    #[cfg_attr(feature = "openapi", schema(value_type = f64))]
+    pub price: Money,
     pub category: String,
-    pub status: String,
+    pub status: ProductStatus,
     pub created_at: String,
+    /// When the product was last modified; changes whenever its fields do.
+    pub updated_at: String,
+    /// S3 object key of the uploaded product image, if any. Load it through
+    /// `GET /api/v1/products/{id}/image`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_url: Option<String>,
+    /// Whether the requesting user has favorited this product; omitted for
+    /// anonymous requests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_favorited: Option<bool>,
+    /// When the product was soft-deleted; only set on deleted products,
+    /// which admins can list with `include_deleted=true`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<String>,
+}
+
+/// Response of the product list: every product, or one page of them when a
+/// `limit` is given.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum ProductList {
+    All(Vec<Product>),
+    Page(Cursor<Product>),
 }
 
 /// Aggregated dashboard statistics.
@@ -153,8 +392,164 @@ pub struct RegisterRequest {
         validate(length(min = 1, message = "Display name is required"))
     )]
     pub display_name: String,
+    /// Invite token; required when the server runs with `REGISTRATION_MODE=invite`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invite_token: Option<String>,
+}
+
+/// A registration invite issued by an admin.
+///
+/// The token is single-use and only valid for registering with `email`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Invite {
+    pub id: i64,
+    pub email: String,
+    pub token: String,
+    pub created_at: String,
+}
+
+/// Kind of authentication event recorded in the audit log.
+///
+/// Serialized in snake_case, matching the `auth_events.event_type` column.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum AuthEventType {
+    LoginSuccess,
+    LoginFailure,
+    Logout,
+    TokenRefresh,
 }
 
+impl AuthEventType {
+    /// Serialize to snake_case string for database storage.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuthEventType::LoginSuccess => "login_success",
+            AuthEventType::LoginFailure => "login_failure",
+            AuthEventType::Logout => "logout",
+            AuthEventType::TokenRefresh => "token_refresh",
+        }
+    }
+
+    /// Parse an event type string, returning None for unknown values.
+    pub fn parse_event_type(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "login_success" => Some(AuthEventType::LoginSuccess),
+            "login_failure" => Some(AuthEventType::LoginFailure),
+            "logout" => Some(AuthEventType::Logout),
+            "token_refresh" => Some(AuthEventType::TokenRefresh),
+            _ => None,
+        }
+    }
+}
+
+/// An entry in the authentication audit log.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AuthEvent {
+    pub id: i64,
+    /// The user involved, when known. Failed logins for unknown emails have none.
+    pub user_id: Option<i64>,
+    pub event_type: AuthEventType,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: String,
+}
+
+/// Privileged action recorded in the admin audit log.
+///
+/// Serialized in snake_case, matching the `audit_log.action` column.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum AuditAction {
+    TierChange,
+    UserDelete,
+    ProductCreate,
+    ProductUpdate,
+    ProductDelete,
+}
+
+impl AuditAction {
+    /// Serialize to snake_case string for database storage.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::TierChange => "tier_change",
+            AuditAction::UserDelete => "user_delete",
+            AuditAction::ProductCreate => "product_create",
+            AuditAction::ProductUpdate => "product_update",
+            AuditAction::ProductDelete => "product_delete",
+        }
+    }
+
+    /// Parse an action string, returning None for unknown values.
+    pub fn parse_action(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "tier_change" => Some(AuditAction::TierChange),
+            "user_delete" => Some(AuditAction::UserDelete),
+            "product_create" => Some(AuditAction::ProductCreate),
+            "product_update" => Some(AuditAction::ProductUpdate),
+            "product_delete" => Some(AuditAction::ProductDelete),
+            _ => None,
+        }
+    }
+}
+
+/// An entry in the admin audit log.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AuditEntry {
+    pub id: i64,
+    /// The signed-in user who acted, if any.
+    pub actor_id: Option<i64>,
+    pub action: AuditAction,
+    /// What was acted on, e.g. `user:42` or `product:7`.
+    pub target: String,
+    /// Action-specific details, such as the new tier.
+    pub metadata: serde_json::Value,
+    pub created_at: String,
+}
+
+/// One page of the admin audit log, newest entries first.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AuditPage {
+    pub entries: Vec<AuditEntry>,
+    pub page: i64,
+    pub per_page: i64,
+    /// Entries matching the query across all pages.
+    pub total: i64,
+}
+
+/// When a product was created and last changed, and by whom.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ProductHistory {
+    pub product_id: i64,
+    /// The user who created the product, if known.
+    pub owner_id: Option<i64>,
+    pub created_at: String,
+    pub updated_at: String,
+    /// The product's audit entries, newest first. Only sent to the product's
+    /// owner and admins; `None` for everyone else.
+    pub entries: Option<Vec<AuditEntry>>,
+}
+
+/// A pending account deletion. Until `scheduled_for` the account keeps its
+/// data but can't be signed in to, and the deletion can be cancelled.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AccountDeletion {
+    /// When the account will be permanently deleted.
+    pub scheduled_for: String,
+}
+
+/// Response header set when the server transparently refreshed the session
+/// tokens while handling a request. Clients watch for it to resync auth state.
+pub const TOKEN_REFRESHED_HEADER: &str = "x-token-refreshed";
+
 /// Authenticated user info (safe to send to client).
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
@@ -163,20 +558,93 @@ pub struct AuthUser {
     pub username: String,
     pub display_name: String,
     pub email: String,
-    pub role: String,
+    pub role: Role,
     pub tier: UserTier,
+    /// S3 object key of the uploaded avatar (or an absolute URL for OAuth
+    /// avatars). Resolve to a loadable URL with `get_avatar_url`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub avatar_url: Option<String>,
+    /// S3 object key of the 128x128 avatar thumbnail, when one was generated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thumbnail_url: Option<String>,
+    /// When the session's access token expires (Unix seconds), so the client
+    /// can refresh ahead of time. Only set by `get_current_user`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+    /// Whether the user has verified their email, when the response says:
+    /// set by `get_current_user` and `register`, `None` elsewhere.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email_verified: Option<bool>,
+}
+
+impl AuthUser {
+    /// This user with the session expiring at `expires_at` (Unix seconds).
+    pub fn with_expiry(self, expires_at: i64) -> Self {
+        Self {
+            expires_at: Some(expires_at),
+            ..self
+        }
+    }
+
+    /// This user with their email verification status.
+    pub fn with_email_verified(self, verified: bool) -> Self {
+        Self {
+            email_verified: Some(verified),
+            ..self
+        }
+    }
+
+    /// Whether the user is known to still have to verify their email.
+    pub fn needs_email_verification(&self) -> bool {
+        self.email_verified == Some(false)
+    }
+}
+
+/// Per-user notification and appearance settings.
+///
+/// Users without a stored row get [`UserPreferences::default`]. Missing fields
+/// in a request body also fall back to their defaults.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct UserPreferences {
+    pub email_notifications: bool,
+    pub push_notifications: bool,
+    pub weekly_digest: bool,
+    pub animations_enabled: bool,
+    pub compact_mode: bool,
+    /// Theme family key, e.g. `cyberpunk` or `solar`.
+    pub theme_family: String,
+    pub theme_dark: bool,
+}
+
+impl Default for UserPreferences {
+    fn default() -> Self {
+        Self {
+            email_notifications: true,
+            push_notifications: false,
+            weekly_digest: true,
+            animations_enabled: true,
+            compact_mode: false,
+            theme_family: "cyberpunk".to_string(),
+            theme_dark: true,
+        }
+    }
 }
 
 /// Premium analytics data returned by the tier-gated endpoint.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct PremiumAnalytics {
-    pub total_revenue: f64,
-    pub avg_product_price: f64,
+    #[cfg_attr(feature = "openapi", schema(value_type = f64))]
+    pub total_revenue: Money,
+    #[cfg_attr(feature = "openapi", schema(value_type = f64))]
+    pub avg_product_price: Money,
     pub products_by_category: Vec<CategoryCount>,
     pub users_last_30_days: i64,
+    /// Revenue from active products in each category, highest first.
+    #[serde(default)]
+    pub revenue_by_category: Vec<CategoryRevenue>,
 }
 
 /// Category name with a count of products in that category.
@@ -187,13 +655,36 @@ pub struct CategoryCount {
     pub count: i64,
 }
 
+/// Category name with the summed price of its active products.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CategoryRevenue {
+    pub category: String,
+    #[cfg_attr(feature = "openapi", schema(value_type = f64))]
+    pub revenue: Money,
+}
+
 /// Refresh token request (used by REST/OpenAPI).
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "validation", derive(validator::Validate))]
 pub struct RefreshRequest {
+    #[cfg_attr(
+        feature = "validation",
+        validate(length(min = 1, message = "Refresh token is required"))
+    )]
     pub refresh_token: String,
 }
 
+/// New access token issued for a refresh token.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RefreshResponse {
+    pub access_token: String,
+    /// Seconds until `access_token` expires.
+    pub expires_in: i64,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -206,6 +697,8 @@ mod tests {
             display_name: "Tyler".into(),
             role: "user".into(),
             tier: "free".into(),
+            created_at: "2025-01-01 00:00:00 UTC".into(),
+            updated_at: "2025-01-02 00:00:00 UTC".into(),
         };
 
         let json = serde_json::to_string(&user).unwrap();
@@ -216,13 +709,38 @@ mod tests {
 
     #[test]
     fn user_deserializes_from_api_json() {
-        let json = r#"{"id": 42, "username": "demo", "display_name": "Demo User", "role": "admin", "tier": "premium"}"#;
+        let json = r#"{"id": 42, "username": "demo", "display_name": "Demo User", "role": "admin", "tier": "premium", "created_at": "2025-03-04 05:06:07 UTC", "updated_at": "2025-03-04 05:06:07 UTC"}"#;
         let user: User = serde_json::from_str(json).unwrap();
 
         assert_eq!(user.id, 42);
         assert_eq!(user.username, "demo");
         assert_eq!(user.role, "admin");
         assert_eq!(user.tier, "premium");
+        assert_eq!(user.joined_date(), "2025-03-04");
+    }
+
+    #[test]
+    fn user_preferences_fill_missing_fields_with_defaults() {
+        let prefs: UserPreferences = serde_json::from_str(r#"{"compact_mode":true}"#).unwrap();
+        assert_eq!(
+            prefs,
+            UserPreferences {
+                compact_mode: true,
+                ..Default::default()
+            }
+        );
+        assert!(prefs.email_notifications);
+        assert!(!prefs.push_notifications);
+        assert_eq!(prefs.theme_family, "cyberpunk");
+        assert!(prefs.theme_dark);
+    }
+
+    #[test]
+    fn premium_analytics_without_revenue_breakdown_still_parses() {
+        let json = r#"{"total_revenue": 10.0, "avg_product_price": 5.0, "products_by_category": [], "users_last_30_days": 3}"#;
+        let analytics: PremiumAnalytics = serde_json::from_str(json).unwrap();
+        assert!(analytics.revenue_by_category.is_empty());
+        assert_eq!(analytics.total_revenue, Money::from_cents(1000));
     }
 
     #[test]
@@ -231,10 +749,14 @@ mod tests {
             id: 1,
             name: "Widget".into(),
             description: "A test widget".into(),
-            price: 29.99,
+            price: Money::from_cents(2999),
             category: "Hardware".into(),
-            status: "active".into(),
+            status: ProductStatus::Active,
             created_at: "2025-01-01T00:00:00Z".into(),
+            updated_at: "2025-01-01T00:00:00Z".into(),
+            image_url: None,
+            is_favorited: None,
+            deleted_at: None,
         };
 
         let json = serde_json::to_string(&product).unwrap();
@@ -243,6 +765,44 @@ mod tests {
         assert_eq!(product, deserialized);
     }
 
+    #[test]
+    fn product_status_serializes_lowercase() {
+        let json = serde_json::to_string(&ProductStatus::Archived).unwrap();
+        assert_eq!(json, r#""archived""#);
+        let parsed: ProductStatus = serde_json::from_str(r#""draft""#).unwrap();
+        assert_eq!(parsed, ProductStatus::Draft);
+    }
+
+    #[test]
+    fn product_status_as_str_roundtrip() {
+        for status in ProductStatus::all() {
+            assert_eq!(ProductStatus::from_str_or_default(status.as_str()), status);
+        }
+    }
+
+    #[test]
+    fn product_status_unknown_falls_to_active() {
+        assert_eq!(
+            ProductStatus::from_str_or_default(""),
+            ProductStatus::Active
+        );
+        assert_eq!(
+            ProductStatus::from_str_or_default("deleted"),
+            ProductStatus::Active
+        );
+    }
+
+    #[test]
+    fn product_status_parse_rejects_unknown_with_field_error() {
+        assert_eq!(
+            "Draft".parse::<ProductStatus>().unwrap(),
+            ProductStatus::Draft
+        );
+        let err = "bogus".parse::<ProductStatus>().unwrap_err();
+        assert_eq!(err.kind, crate::AppErrorKind::ValidationError);
+        assert!(err.field_errors.contains_key("status"));
+    }
+
     #[test]
     fn user_tier_has_access_same_tier() {
         assert!(UserTier::Free.has_access(&UserTier::Free));
@@ -290,6 +850,56 @@ mod tests {
         }
     }
 
+    #[test]
+    fn user_tier_display_names() {
+        assert_eq!(UserTier::Free.display_name(), "Free");
+        assert_eq!(UserTier::Premium.display_name(), "Premium");
+        assert_eq!(UserTier::Elite.display_name(), "Elite");
+    }
+
+    #[test]
+    fn user_tier_badge_variants() {
+        assert_eq!(UserTier::Free.badge_variant(), TierBadgeVariant::Secondary);
+        assert_eq!(UserTier::Premium.badge_variant(), TierBadgeVariant::Primary);
+        assert_eq!(
+            UserTier::Elite.badge_variant(),
+            TierBadgeVariant::Destructive
+        );
+    }
+
+    #[test]
+    fn role_from_str_or_default_known_values() {
+        assert_eq!(Role::from_str_or_default("admin"), Role::Admin);
+        assert_eq!(Role::from_str_or_default("Admin"), Role::Admin);
+        assert_eq!(Role::from_str_or_default("user"), Role::User);
+    }
+
+    #[test]
+    fn role_from_str_or_default_unknown_falls_to_user() {
+        assert_eq!(Role::from_str_or_default(""), Role::User);
+        assert_eq!(Role::from_str_or_default("superuser"), Role::User);
+    }
+
+    #[test]
+    fn role_as_str_roundtrip() {
+        for role in [Role::User, Role::Admin] {
+            assert_eq!(Role::from_str_or_default(role.as_str()), role);
+        }
+    }
+
+    #[test]
+    fn role_is_admin_only_for_admin() {
+        assert!(Role::Admin.is_admin());
+        assert!(!Role::User.is_admin());
+    }
+
+    #[test]
+    fn role_serializes_lowercase() {
+        assert_eq!(serde_json::to_string(&Role::Admin).unwrap(), r#""admin""#);
+        let parsed: Role = serde_json::from_str(r#""user""#).unwrap();
+        assert_eq!(parsed, Role::User);
+    }
+
     #[test]
     fn oauth_provider_parse_valid() {
         assert_eq!(
@@ -308,6 +918,28 @@ mod tests {
             OAuthProvider::parse_provider("GitHub"),
             Some(OAuthProvider::GitHub)
         );
+        assert_eq!(
+            OAuthProvider::parse_provider("microsoft"),
+            Some(OAuthProvider::Microsoft)
+        );
+        assert_eq!(
+            OAuthProvider::parse_provider("Microsoft"),
+            Some(OAuthProvider::Microsoft)
+        );
+    }
+
+    #[test]
+    fn oauth_provider_as_str_round_trips() {
+        for provider in [
+            OAuthProvider::Google,
+            OAuthProvider::GitHub,
+            OAuthProvider::Microsoft,
+        ] {
+            assert_eq!(
+                OAuthProvider::parse_provider(provider.as_str()),
+                Some(provider)
+            );
+        }
     }
 
     #[test]
@@ -316,4 +948,79 @@ mod tests {
         assert_eq!(OAuthProvider::parse_provider(""), None);
         assert_eq!(OAuthProvider::parse_provider("twitter"), None);
     }
+
+    #[test]
+    fn last_login_method_cannot_be_unlinked() {
+        let oauth_only = LoginMethods {
+            has_password: false,
+            oauth_providers: vec![OAuthProvider::Google],
+        };
+        assert!(!oauth_only.can_unlink(&OAuthProvider::Google));
+
+        let with_password = LoginMethods {
+            has_password: true,
+            ..oauth_only
+        };
+        assert!(with_password.can_unlink(&OAuthProvider::Google));
+        assert!(!with_password.can_unlink(&OAuthProvider::GitHub));
+    }
+
+    #[test]
+    fn auth_event_type_roundtrips_through_db_string() {
+        for event in [
+            AuthEventType::LoginSuccess,
+            AuthEventType::LoginFailure,
+            AuthEventType::Logout,
+            AuthEventType::TokenRefresh,
+        ] {
+            assert_eq!(AuthEventType::parse_event_type(event.as_str()), Some(event));
+            assert_eq!(
+                serde_json::to_string(&event).unwrap(),
+                format!("\"{}\"", event.as_str())
+            );
+        }
+        assert_eq!(AuthEventType::parse_event_type("password_reset"), None);
+    }
+
+    #[test]
+    fn audit_action_roundtrips_through_db_string() {
+        for action in [
+            AuditAction::TierChange,
+            AuditAction::UserDelete,
+            AuditAction::ProductCreate,
+            AuditAction::ProductUpdate,
+            AuditAction::ProductDelete,
+        ] {
+            assert_eq!(AuditAction::parse_action(action.as_str()), Some(action));
+            assert_eq!(
+                serde_json::to_string(&action).unwrap(),
+                format!("\"{}\"", action.as_str())
+            );
+        }
+    }
+
+    #[test]
+    fn auth_user_expiry_is_optional_on_the_wire() {
+        let json = r#"{"id":1,"username":"neo","display_name":"Neo","email":"neo@example.com","role":"user","tier":"Free"}"#;
+        let user: AuthUser = serde_json::from_str(json).unwrap();
+        assert_eq!(user.expires_at, None);
+        assert!(!serde_json::to_string(&user).unwrap().contains("expires_at"));
+
+        let user = user.with_expiry(1_700_000_000);
+        let value = serde_json::to_value(&user).unwrap();
+        assert_eq!(value["expires_at"], 1_700_000_000);
+    }
+
+    #[test]
+    fn unknown_verification_status_does_not_prompt() {
+        let json = r#"{"id":1,"username":"neo","display_name":"Neo","email":"neo@example.com","role":"user","tier":"Free"}"#;
+        let user: AuthUser = serde_json::from_str(json).unwrap();
+        assert_eq!(user.email_verified, None);
+        assert!(!user.needs_email_verification());
+        assert!(user
+            .clone()
+            .with_email_verified(false)
+            .needs_email_verification());
+        assert!(!user.with_email_verified(true).needs_email_verification());
+    }
 }
@@ -0,0 +1,258 @@
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// An amount of money in whole cents.
+///
+/// Serialized as a JSON number (`29.99`) so existing clients keep working;
+/// deserialized from a number or a decimal string (`"29.99"`). Fractions of
+/// a cent are rounded to the nearest cent, halves away from zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Money(i64);
+
+impl Money {
+    pub const ZERO: Money = Money(0);
+
+    pub const fn from_cents(cents: i64) -> Self {
+        Self(cents)
+    }
+
+    pub const fn cents(self) -> i64 {
+        self.0
+    }
+
+    /// Round a floating-point amount to the nearest cent.
+    ///
+    /// Used at the database boundary, where prices are still stored as
+    /// `double precision`. Non-finite values saturate (NaN becomes zero).
+    pub fn from_f64(amount: f64) -> Self {
+        Self((amount * 100.0).round() as i64)
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / 100.0
+    }
+
+    /// Parse a decimal amount such as `12`, `12.5`, or `-0.99`.
+    ///
+    /// Digits past the cents are rounded exactly, without going through
+    /// floating point.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let invalid = || format!("Invalid amount: {input:?}");
+        let trimmed = input.trim();
+        let (negative, digits) = match trimmed.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        let all_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        if (whole.is_empty() && fraction.is_empty()) || !all_digits(whole) || !all_digits(fraction)
+        {
+            return Err(invalid());
+        }
+
+        let whole: i64 = if whole.is_empty() {
+            0
+        } else {
+            whole.parse().map_err(|_| invalid())?
+        };
+        let mut fraction = fraction.bytes().map(|b| i64::from(b - b'0'));
+        let tenths = fraction.next().unwrap_or(0);
+        let hundredths = fraction.next().unwrap_or(0);
+        let round_up = fraction.next().is_some_and(|d| d >= 5);
+
+        let cents = whole
+            .checked_mul(100)
+            .and_then(|c| c.checked_add(tenths * 10 + hundredths + i64::from(round_up)))
+            .ok_or_else(invalid)?;
+        Ok(Self(if negative { -cents } else { cents }))
+    }
+
+    /// The amount without a currency sign, e.g. `12.50`, as used in form
+    /// fields and CSV exports.
+    pub fn to_decimal_string(self) -> String {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let cents = self.0.unsigned_abs();
+        format!("{sign}{}.{:02}", cents / 100, cents % 100)
+    }
+}
+
+impl fmt::Display for Money {
+    /// Formats as dollars, e.g. `$12.50` or `-$0.99`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let cents = self.0.unsigned_abs();
+        write!(f, "{sign}${}.{:02}", cents / 100, cents % 100)
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, rhs: Money) -> Money {
+        Money(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, rhs: Money) {
+        self.0 += rhs.0;
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, rhs: Money) -> Money {
+        Money(self.0 - rhs.0)
+    }
+}
+
+impl SubAssign for Money {
+    fn sub_assign(&mut self, rhs: Money) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        Money(-self.0)
+    }
+}
+
+impl Mul<i64> for Money {
+    type Output = Money;
+
+    fn mul(self, quantity: i64) -> Money {
+        Money(self.0 * quantity)
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Money>>(iter: I) -> Money {
+        iter.fold(Money::ZERO, Add::add)
+    }
+}
+
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.to_f64())
+    }
+}
+
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct MoneyVisitor;
+
+        impl Visitor<'_> for MoneyVisitor {
+            type Value = Money;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("an amount as a number or decimal string")
+            }
+
+            fn visit_f64<E: de::Error>(self, value: f64) -> Result<Money, E> {
+                if !value.is_finite() {
+                    return Err(E::custom("amount must be finite"));
+                }
+                Ok(Money::from_f64(value))
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<Money, E> {
+                value
+                    .checked_mul(100)
+                    .map(Money)
+                    .ok_or_else(|| E::custom("amount out of range"))
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Money, E> {
+                i64::try_from(value)
+                    .map_err(|_| E::custom("amount out of range"))
+                    .and_then(|v| self.visit_i64(v))
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Money, E> {
+                Money::parse(value).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(MoneyVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn float_amounts_round_to_the_nearest_cent() {
+        assert_eq!(Money::from_f64(29.99).cents(), 2999);
+        assert_eq!(Money::from_f64(0.1 + 0.2).cents(), 30);
+        assert_eq!(Money::from_f64(-2.5).cents(), -250);
+        assert_eq!(Money::from_f64(f64::NAN), Money::ZERO);
+        assert_eq!(Money::from_cents(2999).to_f64(), 29.99);
+    }
+
+    #[test]
+    fn decimal_strings_round_exactly() {
+        assert_eq!(Money::parse("12").unwrap().cents(), 1200);
+        assert_eq!(Money::parse(" 12.5 ").unwrap().cents(), 1250);
+        assert_eq!(Money::parse(".99").unwrap().cents(), 99);
+        // 0.285 is 0.28499999... as an f64; parsed exactly it rounds up
+        assert_eq!(Money::parse("0.285").unwrap().cents(), 29);
+        assert_eq!(Money::parse("0.2849").unwrap().cents(), 28);
+        assert_eq!(Money::parse("-0.99").unwrap().cents(), -99);
+        for bad in [
+            "",
+            ".",
+            "abc",
+            "1.2.3",
+            "1e3",
+            "$5",
+            "NaN",
+            "99999999999999999999",
+        ] {
+            assert!(Money::parse(bad).is_err(), "{bad:?} should not parse");
+        }
+    }
+
+    #[test]
+    fn arithmetic_stays_in_whole_cents() {
+        let total: Money = std::iter::repeat_n(Money::parse("0.10").unwrap(), 3).sum();
+        assert_eq!(total, Money::parse("0.30").unwrap());
+
+        let mut balance = Money::from_cents(1000);
+        balance -= Money::from_cents(250);
+        balance += Money::from_cents(5);
+        assert_eq!(balance, Money::from_cents(755));
+        assert_eq!(Money::from_cents(199) * 3, Money::from_cents(597));
+        assert_eq!(-Money::from_cents(1), Money::from_cents(-1));
+        assert!(Money::from_cents(100) > Money::from_cents(99));
+    }
+
+    #[test]
+    fn displays_as_dollars() {
+        assert_eq!(Money::from_cents(1250).to_string(), "$12.50");
+        assert_eq!(Money::from_cents(5).to_string(), "$0.05");
+        assert_eq!(Money::from_cents(-99).to_string(), "-$0.99");
+        assert_eq!(Money::from_cents(1250).to_decimal_string(), "12.50");
+    }
+
+    #[test]
+    fn serde_accepts_numbers_and_strings() {
+        let from_number: Money = serde_json::from_str("29.99").unwrap();
+        let from_integer: Money = serde_json::from_str("30").unwrap();
+        let from_string: Money = serde_json::from_str("\"29.99\"").unwrap();
+        assert_eq!(from_number, Money::from_cents(2999));
+        assert_eq!(from_integer, Money::from_cents(3000));
+        assert_eq!(from_string, from_number);
+        assert!(serde_json::from_str::<Money>("\"cheap\"").is_err());
+
+        assert_eq!(serde_json::to_string(&from_number).unwrap(), "29.99");
+        assert_eq!(serde_json::to_string(&from_integer).unwrap(), "30.0");
+    }
+}
@@ -0,0 +1,380 @@
+use serde::{Deserialize, Serialize};
+
+use crate::AppError;
+
+/// Largest page a list endpoint returns, whatever `limit` asks for.
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+/// One page of a keyset-paginated list, newest first.
+///
+/// Pass `next_cursor` back as the same cursor parameter (`before_id` or
+/// `after_id`) to get the following page; it is `None` on the last page.
+/// Unlike offsets, cursors never skip or repeat rows when new ones are
+/// inserted between requests.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Cursor<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<i64>,
+}
+
+impl<T> Cursor<T> {
+    /// Build a page from rows fetched with `LIMIT limit + 1`: the extra row
+    /// only signals that another page exists and is dropped.
+    ///
+    /// `rows` must be in the order they are returned, and `id` gives the key
+    /// the list is ordered by. `next_cursor` is the key of the last row kept,
+    /// which is the lowest for `before_id` pages and the highest (`newer`)
+    /// for `after_id` pages.
+    pub fn from_overfetch(
+        mut rows: Vec<T>,
+        limit: i64,
+        newer: bool,
+        id: impl Fn(&T) -> i64,
+    ) -> Self {
+        let limit = usize::try_from(limit).unwrap_or(0);
+        let has_more = rows.len() > limit;
+        rows.truncate(limit);
+        let next_cursor = if !has_more {
+            None
+        } else if newer {
+            rows.iter().map(&id).max()
+        } else {
+            rows.iter().map(&id).min()
+        };
+        Self {
+            items: rows,
+            next_cursor,
+        }
+    }
+}
+
+/// How a list request pages through rows ordered by id, newest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Paging {
+    /// Every row (no `limit` given).
+    All,
+    /// `limit` rows after skipping `offset`. Kept for clients that jump to a
+    /// page number; prefer cursors for infinite scroll.
+    Offset { limit: i64, offset: i64 },
+    /// `limit` rows with ids below `id`: the next page when scrolling down.
+    Before { limit: i64, id: i64 },
+    /// `limit` rows with ids above `id`: rows newer than the first one seen.
+    After { limit: i64, id: i64 },
+}
+
+impl Paging {
+    /// Resolve list query parameters. `limit` turns paging on and is clamped
+    /// to `1..=MAX_PAGE_SIZE`; at most one of `offset`, `before_id` and
+    /// `after_id` may be given.
+    pub fn resolve(
+        limit: Option<i64>,
+        offset: Option<i64>,
+        before_id: Option<i64>,
+        after_id: Option<i64>,
+    ) -> Result<Self, AppError> {
+        let modes = [offset, before_id, after_id]
+            .iter()
+            .filter(|v| v.is_some())
+            .count();
+        if modes > 1 {
+            return Err(AppError::bad_request(
+                "Use only one of offset, before_id and after_id",
+            ));
+        }
+        let Some(limit) = limit else {
+            if modes > 0 {
+                return Err(AppError::bad_request(
+                    "limit is required with offset, before_id or after_id",
+                ));
+            }
+            return Ok(Self::All);
+        };
+        let limit = limit.clamp(1, MAX_PAGE_SIZE);
+
+        Ok(match (offset, before_id, after_id) {
+            (_, Some(id), _) => Self::Before { limit, id },
+            (_, _, Some(id)) => Self::After { limit, id },
+            (offset, _, _) => Self::Offset {
+                limit,
+                offset: offset.unwrap_or(0).max(0),
+            },
+        })
+    }
+}
+
+/// Direction of a sorted list.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    /// The value used on the wire and bound into queries.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortOrder::Asc => "asc",
+            SortOrder::Desc => "desc",
+        }
+    }
+}
+
+/// Columns a list of users can be sorted by.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum UserSortField {
+    #[default]
+    Id,
+    Username,
+    DisplayName,
+    CreatedAt,
+}
+
+impl UserSortField {
+    /// The value used on the wire and bound into queries.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserSortField::Id => "id",
+            UserSortField::Username => "username",
+            UserSortField::DisplayName => "display_name",
+            UserSortField::CreatedAt => "created_at",
+        }
+    }
+}
+
+/// Page, sort and search parameters for a list, shared by the REST query
+/// string and the server function arguments.
+///
+/// `S` is the resource's sort field enum (e.g. [`UserSortField`]), so an
+/// unknown sort key is rejected when the parameters are parsed rather than
+/// reaching the query.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ListParams<S> {
+    /// Page to return, starting at 1 (default 1).
+    pub page: Option<i64>,
+    /// Rows per page (at most 100). Without it every row is returned.
+    pub page_size: Option<i64>,
+    /// Field to sort by (default `id`).
+    #[serde(default)]
+    pub sort_by: S,
+    /// `asc` (default) or `desc`.
+    #[serde(default)]
+    pub order: SortOrder,
+    /// Only rows matching this text, ignoring case.
+    pub search: Option<String>,
+}
+
+impl<S> ListParams<S> {
+    /// `(limit, offset)` for the requested page, or `None` for every row.
+    /// `page_size` is clamped to `1..=MAX_PAGE_SIZE` and `page` to at least 1.
+    pub fn limit_offset(&self) -> Option<(i64, i64)> {
+        let limit = self.page_size?.clamp(1, MAX_PAGE_SIZE);
+        let page = self.page.unwrap_or(1).max(1);
+        Some((limit, (page - 1).saturating_mul(limit)))
+    }
+
+    /// `search` as an `ILIKE` pattern matching it anywhere, with `%`, `_`
+    /// and `\` escaped; `None` when blank.
+    pub fn search_pattern(&self) -> Option<String> {
+        let search = self.search.as_deref()?.trim();
+        if search.is_empty() {
+            return None;
+        }
+        let escaped: String = search
+            .chars()
+            .flat_map(|c| match c {
+                '%' | '_' | '\\' => vec!['\\', c],
+                c => vec![c],
+            })
+            .collect();
+        Some(format!("%{escaped}%"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Page through `ids` (descending) the way the server does, returning
+    /// every page.
+    fn pages(ids: &[i64], limit: i64, start: Paging) -> Vec<Cursor<i64>> {
+        let mut paging = start;
+        let mut pages = Vec::new();
+        loop {
+            let page = match paging {
+                Paging::Before { limit, id } => {
+                    let rows = ids.iter().copied().filter(|&r| r < id);
+                    Cursor::from_overfetch(
+                        rows.take(limit as usize + 1).collect(),
+                        limit,
+                        false,
+                        |r| *r,
+                    )
+                }
+                Paging::After { limit, id } => {
+                    let mut rows: Vec<i64> =
+                        ids.iter().rev().copied().filter(|&r| r > id).collect();
+                    rows.truncate(limit as usize + 1);
+                    let mut page = Cursor::from_overfetch(rows, limit, true, |r| *r);
+                    page.items.reverse();
+                    page
+                }
+                _ => unreachable!(),
+            };
+            let next = page.next_cursor;
+            pages.push(page);
+            match (next, paging) {
+                (Some(id), Paging::Before { .. }) => paging = Paging::Before { limit, id },
+                (Some(id), Paging::After { .. }) => paging = Paging::After { limit, id },
+                _ => return pages,
+            }
+        }
+    }
+
+    #[test]
+    fn cursor_pages_cover_every_row_exactly_once() {
+        let ids: Vec<i64> = (1..=23).rev().collect();
+        for limit in [1, 5, 10, 23, 50] {
+            let seen: Vec<i64> = pages(
+                &ids,
+                limit,
+                Paging::Before {
+                    limit,
+                    id: i64::MAX,
+                },
+            )
+            .into_iter()
+            .flat_map(|page| page.items)
+            .collect();
+            assert_eq!(seen, ids, "limit {limit}");
+        }
+
+        let mut newer: Vec<i64> = pages(&ids, 4, Paging::After { limit: 4, id: 0 })
+            .into_iter()
+            .flat_map(|page| page.items)
+            .collect();
+        newer.sort_unstable_by(|a, b| b.cmp(a));
+        assert_eq!(newer, ids);
+    }
+
+    #[test]
+    fn last_page_has_no_cursor() {
+        let page = Cursor::from_overfetch(vec![5, 4, 3], 3, false, |r| *r);
+        assert_eq!(page.next_cursor, None);
+        let page = Cursor::from_overfetch(vec![5, 4, 3, 2], 3, false, |r| *r);
+        assert_eq!(page.items, vec![5, 4, 3]);
+        assert_eq!(page.next_cursor, Some(3));
+    }
+
+    #[test]
+    fn paging_is_resolved_from_query_parameters() {
+        assert_eq!(
+            Paging::resolve(None, None, None, None).unwrap(),
+            Paging::All
+        );
+        assert_eq!(
+            Paging::resolve(Some(20), None, None, None).unwrap(),
+            Paging::Offset {
+                limit: 20,
+                offset: 0
+            }
+        );
+        assert_eq!(
+            Paging::resolve(Some(1000), None, Some(42), None).unwrap(),
+            Paging::Before {
+                limit: MAX_PAGE_SIZE,
+                id: 42
+            }
+        );
+        assert_eq!(
+            Paging::resolve(Some(0), None, None, Some(7)).unwrap(),
+            Paging::After { limit: 1, id: 7 }
+        );
+        assert!(Paging::resolve(Some(10), Some(10), Some(42), None).is_err());
+        assert!(Paging::resolve(None, None, Some(42), None).is_err());
+    }
+
+    #[test]
+    fn list_params_round_trip_through_query_strings() {
+        let params = ListParams {
+            page: Some(3),
+            page_size: Some(25),
+            sort_by: UserSortField::DisplayName,
+            order: SortOrder::Desc,
+            search: Some("neo & co".to_string()),
+        };
+        let query = serde_urlencoded::to_string(&params).unwrap();
+        assert_eq!(
+            query,
+            "page=3&page_size=25&sort_by=display_name&order=desc&search=neo+%26+co"
+        );
+        assert_eq!(
+            serde_urlencoded::from_str::<ListParams<UserSortField>>(&query).unwrap(),
+            params
+        );
+    }
+
+    #[test]
+    fn list_params_default_when_omitted() {
+        let params: ListParams<UserSortField> = serde_urlencoded::from_str("").unwrap();
+        assert_eq!(params, ListParams::default());
+        assert_eq!(params.sort_by, UserSortField::Id);
+        assert_eq!(params.order, SortOrder::Asc);
+        assert_eq!(params.limit_offset(), None);
+        assert_eq!(
+            serde_urlencoded::to_string(&params).unwrap(),
+            "sort_by=id&order=asc"
+        );
+
+        let params: ListParams<UserSortField> =
+            serde_urlencoded::from_str("sort_by=created_at").unwrap();
+        assert_eq!(params.sort_by, UserSortField::CreatedAt);
+    }
+
+    #[test]
+    fn unknown_sort_keys_are_rejected() {
+        assert!(
+            serde_urlencoded::from_str::<ListParams<UserSortField>>("sort_by=password").is_err()
+        );
+        assert!(serde_urlencoded::from_str::<ListParams<UserSortField>>("order=sideways").is_err());
+    }
+
+    #[test]
+    fn list_params_resolve_to_limit_and_offset() {
+        let params = |page, page_size| ListParams::<UserSortField> {
+            page,
+            page_size,
+            ..Default::default()
+        };
+        assert_eq!(params(None, Some(20)).limit_offset(), Some((20, 0)));
+        assert_eq!(params(Some(3), Some(20)).limit_offset(), Some((20, 40)));
+        assert_eq!(
+            params(Some(0), Some(1000)).limit_offset(),
+            Some((MAX_PAGE_SIZE, 0))
+        );
+        assert_eq!(params(Some(2), None).limit_offset(), None);
+    }
+
+    #[test]
+    fn search_is_escaped_for_ilike() {
+        let search = |text: &str| ListParams::<UserSortField> {
+            search: Some(text.to_string()),
+            ..Default::default()
+        };
+        assert_eq!(search(" neo ").search_pattern().as_deref(), Some("%neo%"));
+        assert_eq!(
+            search("100%_a\\b").search_pattern().as_deref(),
+            Some("%100\\%\\_a\\\\b%")
+        );
+        assert_eq!(search("  ").search_pattern(), None);
+        assert_eq!(
+            ListParams::<UserSortField>::default().search_pattern(),
+            None
+        );
+    }
+}
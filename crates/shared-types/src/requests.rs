@@ -1,8 +1,22 @@
 use serde::{Deserialize, Serialize};
 
+use crate::{AppError, Money, Paging, ProductStatus};
+
 #[cfg(feature = "validation")]
 use validator::Validate;
 
+/// Canonical form of a request DTO, applied before validation.
+///
+/// Trims surrounding whitespace from text fields and lowercases emails.
+/// Passwords are left untouched.
+pub trait Normalize {
+    fn normalize(self) -> Self;
+}
+
+fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
 /// Request DTO for creating a user.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
@@ -37,6 +51,49 @@ pub struct UpdateUserRequest {
     pub display_name: String,
 }
 
+/// Request DTO for looking up several users at once (at most 100 ids).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "validation", derive(Validate))]
+pub struct BatchGetUsersRequest {
+    #[cfg_attr(
+        feature = "validation",
+        validate(length(max = 100, message = "At most 100 ids per request"))
+    )]
+    pub ids: Vec<i64>,
+}
+
+/// Lowest price a product may have, checked on both the form and the server.
+pub const MIN_PRODUCT_PRICE: Money = Money::ZERO;
+
+const PRICE_MESSAGE: &str = "Price must be a non-negative number";
+
+/// Parse a price typed into a form, applying the same rule as request
+/// validation so bad input is caught before it is sent.
+pub fn parse_price(input: &str) -> Result<Money, String> {
+    match Money::parse(input) {
+        Ok(price) if price >= MIN_PRODUCT_PRICE => Ok(price),
+        _ => Err(PRICE_MESSAGE.to_string()),
+    }
+}
+
+#[cfg(feature = "validation")]
+fn validate_price(price: &Money) -> Result<(), validator::ValidationError> {
+    if *price < MIN_PRODUCT_PRICE {
+        return Err(validator::ValidationError::new("range").with_message(PRICE_MESSAGE.into()));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "validation")]
+fn validate_status(status: &str) -> Result<(), validator::ValidationError> {
+    if ProductStatus::parse_status(status).is_none() {
+        return Err(validator::ValidationError::new("status")
+            .with_message(crate::models::PRODUCT_STATUS_MESSAGE.into()));
+    }
+    Ok(())
+}
+
 /// Request DTO for creating a product.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
@@ -48,16 +105,18 @@ pub struct CreateProductRequest {
     )]
     pub name: String,
     pub description: String,
-    #[cfg_attr(
-        feature = "validation",
-        validate(range(min = 0.0, message = "Price must be non-negative"))
-    )]
-    pub price: f64,
+    #[cfg_attr(feature = "validation", validate(custom(function = "validate_price")))]
+    #[cfg_attr(feature = "openapi", schema(value_type = f64))]
+    pub price: Money,
     #[cfg_attr(
         feature = "validation",
         validate(length(min = 1, message = "Category is required"))
     )]
     pub category: String,
+    /// One of the [`ProductStatus`] values. Kept as text so an unknown value
+    /// is a validation error on `status` rather than a malformed body.
+    #[cfg_attr(feature = "validation", validate(custom(function = "validate_status")))]
+    #[cfg_attr(feature = "openapi", schema(value_type = ProductStatus))]
     pub status: String,
 }
 
@@ -72,19 +131,35 @@ pub struct UpdateProductRequest {
     )]
     pub name: String,
     pub description: String,
-    #[cfg_attr(
-        feature = "validation",
-        validate(range(min = 0.0, message = "Price must be non-negative"))
-    )]
-    pub price: f64,
+    #[cfg_attr(feature = "validation", validate(custom(function = "validate_price")))]
+    #[cfg_attr(feature = "openapi", schema(value_type = f64))]
+    pub price: Money,
     #[cfg_attr(
         feature = "validation",
         validate(length(min = 1, message = "Category is required"))
     )]
     pub category: String,
+    /// One of the [`ProductStatus`] values. Kept as text so an unknown value
+    /// is a validation error on `status` rather than a malformed body.
+    #[cfg_attr(feature = "validation", validate(custom(function = "validate_status")))]
+    #[cfg_attr(feature = "openapi", schema(value_type = ProductStatus))]
     pub status: String,
 }
 
+impl CreateProductRequest {
+    /// The requested status; only meaningful once the request has validated.
+    pub fn product_status(&self) -> ProductStatus {
+        ProductStatus::from_str_or_default(&self.status)
+    }
+}
+
+impl UpdateProductRequest {
+    /// The requested status; only meaningful once the request has validated.
+    pub fn product_status(&self) -> ProductStatus {
+        ProductStatus::from_str_or_default(&self.status)
+    }
+}
+
 /// Request DTO for updating the current user's profile.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
@@ -102,6 +177,27 @@ pub struct UpdateProfileRequest {
     pub email: String,
 }
 
+/// Request DTO for changing only the current user's display name, leaving
+/// the email (and any email-change checks) alone.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "validation", derive(Validate))]
+pub struct UpdateDisplayNameRequest {
+    #[cfg_attr(
+        feature = "validation",
+        validate(length(min = 1, message = "Display name is required"))
+    )]
+    pub display_name: String,
+}
+
+/// Request DTO for confirming an email address with the token from a
+/// verification link.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct VerifyEmailRequest {
+    pub token: String,
+}
+
 /// Response returned after successful authentication (login or register).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
@@ -116,3 +212,252 @@ pub struct AuthResponse {
 pub struct UpdateTierRequest {
     pub tier: String,
 }
+
+/// Request DTO for inviting someone to register (invite-only mode).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "validation", derive(Validate))]
+pub struct CreateInviteRequest {
+    #[cfg_attr(
+        feature = "validation",
+        validate(email(message = "Valid email is required"))
+    )]
+    pub email: String,
+}
+
+/// Query parameters for listing products.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct ProductListQuery {
+    /// Also return soft-deleted products (admin only).
+    #[serde(default)]
+    pub include_deleted: bool,
+    /// Page size (at most 100). Without it every product is returned as a
+    /// plain array; with it the response is a page with a `next_cursor`.
+    pub limit: Option<i64>,
+    /// Products to skip. Prefer `before_id` for infinite scroll: offsets
+    /// skip or repeat products when new ones are added between requests.
+    pub offset: Option<i64>,
+    /// Only products with a lower id, i.e. the page after a cursor.
+    pub before_id: Option<i64>,
+    /// Only products with a higher id, i.e. ones newer than a cursor.
+    pub after_id: Option<i64>,
+}
+
+impl ProductListQuery {
+    /// How the requested products are paged.
+    pub fn paging(&self) -> Result<Paging, AppError> {
+        Paging::resolve(self.limit, self.offset, self.before_id, self.after_id)
+    }
+}
+
+/// Filters for listing authentication events (admin only).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct AuthEventQuery {
+    /// Only events for this user.
+    pub user_id: Option<i64>,
+    /// Only events of this type, e.g. `login_failure`.
+    pub event_type: Option<String>,
+    /// Maximum number of events to return (default 100, at most 500).
+    pub limit: Option<i64>,
+}
+
+/// Which products premium analytics are computed over.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum AnalyticsScope {
+    /// Only products the caller created.
+    Mine,
+    /// The whole catalog (admin only).
+    Global,
+}
+
+/// Query parameters for premium analytics.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct AnalyticsQuery {
+    /// `mine` or `global`; defaults to `global` for admins and `mine` for
+    /// everyone else.
+    pub scope: Option<AnalyticsScope>,
+}
+
+/// Time window for dashboard statistics.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum StatsRange {
+    #[serde(rename = "7d")]
+    Last7Days,
+    #[serde(rename = "30d")]
+    Last30Days,
+    #[default]
+    #[serde(rename = "all")]
+    AllTime,
+}
+
+impl StatsRange {
+    pub const ALL: [StatsRange; 3] = [Self::Last7Days, Self::Last30Days, Self::AllTime];
+
+    /// Length of the window in days, or `None` for all time.
+    pub fn days(self) -> Option<i64> {
+        match self {
+            Self::Last7Days => Some(7),
+            Self::Last30Days => Some(30),
+            Self::AllTime => None,
+        }
+    }
+
+    /// Value used in the `range` query parameter.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Last7Days => "7d",
+            Self::Last30Days => "30d",
+            Self::AllTime => "all",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|range| range.as_str() == value)
+    }
+
+    /// Name shown in the range picker.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Last7Days => "Last 7 days",
+            Self::Last30Days => "Last 30 days",
+            Self::AllTime => "All time",
+        }
+    }
+}
+
+/// Query parameters for dashboard statistics.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct DashboardStatsQuery {
+    /// `7d`, `30d`, or `all` (default). Counts and recent users only include
+    /// records created within the window.
+    pub range: Option<StatsRange>,
+}
+
+/// Filters and paging for the admin audit log.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct AuditLogQuery {
+    /// Only entries for this target, e.g. `user:42`.
+    pub target: Option<String>,
+    /// Page to return, starting at 1 (default 1).
+    pub page: Option<i64>,
+    /// Entries per page (default 50, at most 200).
+    pub per_page: Option<i64>,
+}
+
+impl Normalize for CreateUserRequest {
+    fn normalize(self) -> Self {
+        Self {
+            username: self.username.trim().to_string(),
+            display_name: self.display_name.trim().to_string(),
+        }
+    }
+}
+
+impl Normalize for UpdateProfileRequest {
+    fn normalize(self) -> Self {
+        Self {
+            display_name: self.display_name.trim().to_string(),
+            email: normalize_email(&self.email),
+        }
+    }
+}
+
+impl Normalize for UpdateDisplayNameRequest {
+    fn normalize(self) -> Self {
+        Self {
+            display_name: self.display_name.trim().to_string(),
+        }
+    }
+}
+
+impl Normalize for crate::RegisterRequest {
+    fn normalize(self) -> Self {
+        Self {
+            username: self.username.trim().to_string(),
+            email: normalize_email(&self.email),
+            password: self.password,
+            display_name: self.display_name.trim().to_string(),
+            invite_token: self
+                .invite_token
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty()),
+        }
+    }
+}
+
+impl Normalize for crate::LoginRequest {
+    fn normalize(self) -> Self {
+        Self {
+            email: normalize_email(&self.email),
+            password: self.password,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LoginRequest, RegisterRequest};
+
+    #[test]
+    fn register_request_is_trimmed_and_email_lowercased() {
+        let req = RegisterRequest {
+            username: "  alice ".to_string(),
+            email: " Alice@Example.COM ".to_string(),
+            password: " secret with spaces ".to_string(),
+            display_name: "\tAlice A.\n".to_string(),
+            invite_token: Some("   ".to_string()),
+        }
+        .normalize();
+
+        assert_eq!(req.username, "alice");
+        assert_eq!(req.email, "alice@example.com");
+        assert_eq!(req.password, " secret with spaces ");
+        assert_eq!(req.display_name, "Alice A.");
+        assert_eq!(req.invite_token, None);
+    }
+
+    #[test]
+    fn login_request_email_is_normalized() {
+        let req = LoginRequest {
+            email: "Bob@Test.com ".to_string(),
+            password: "pw".to_string(),
+        }
+        .normalize();
+        assert_eq!(req.email, "bob@test.com");
+    }
+
+    #[test]
+    fn stats_range_round_trips_through_its_query_value() {
+        for range in StatsRange::ALL {
+            assert_eq!(StatsRange::parse(range.as_str()), Some(range));
+            let json = serde_json::to_string(&range).unwrap();
+            assert_eq!(json, format!("\"{}\"", range.as_str()));
+        }
+        assert_eq!(StatsRange::parse("90d"), None);
+        assert_eq!(StatsRange::default(), StatsRange::AllTime);
+    }
+
+    #[test]
+    fn parse_price_matches_request_validation() {
+        assert_eq!(parse_price(" 12.50 "), Ok(Money::from_cents(1250)));
+        assert_eq!(parse_price("0"), Ok(MIN_PRODUCT_PRICE));
+        assert!(parse_price("abc").is_err());
+        assert!(parse_price("-0.01").is_err());
+        assert!(parse_price("NaN").is_err());
+        assert!(parse_price("").is_err());
+    }
+}
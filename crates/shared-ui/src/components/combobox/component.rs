@@ -0,0 +1,319 @@
+use dioxus::prelude::*;
+
+use crate::components::popover::{PopoverContent, PopoverRoot};
+
+/// A selectable entry in a [`Combobox`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComboboxOption<T> {
+    pub value: T,
+    pub label: String,
+}
+
+impl<T> ComboboxOption<T> {
+    pub fn new(value: T, label: impl Into<String>) -> Self {
+        Self {
+            value,
+            label: label.into(),
+        }
+    }
+}
+
+/// Options whose label contains `query` (case-insensitive, surrounding
+/// whitespace ignored). An empty query matches everything.
+pub fn filter_options<T: Clone>(
+    options: &[ComboboxOption<T>],
+    query: &str,
+) -> Vec<ComboboxOption<T>> {
+    let query = query.trim().to_lowercase();
+    options
+        .iter()
+        .filter(|o| query.is_empty() || o.label.to_lowercase().contains(&query))
+        .cloned()
+        .collect()
+}
+
+/// How well `label` matches `query`, case-insensitively; lower is better.
+///
+/// Labels containing the query rank first, best when it starts the label or
+/// a word in it. Otherwise the query's characters must appear in order
+/// (`"gp"` matches "Go to Products"), with each one that neither starts a
+/// word nor follows the previous match costing a point. `None` when the
+/// label doesn't match at all; an empty query matches everything equally.
+pub fn fuzzy_score(label: &str, query: &str) -> Option<u32> {
+    let label = label.to_lowercase();
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Some(0);
+    }
+    let word_start = |i: usize| {
+        !label[..i]
+            .chars()
+            .next_back()
+            .is_some_and(char::is_alphanumeric)
+    };
+
+    if let Some(pos) = label.find(&query) {
+        return Some(match pos {
+            0 => 0,
+            pos if word_start(pos) => 1,
+            _ => 2,
+        });
+    }
+
+    let mut score = 10;
+    let mut after_previous = 0;
+    let mut chars = label.char_indices();
+    for q in query.chars() {
+        let (i, c) = chars.find(|&(_, c)| c == q)?;
+        if i != after_previous && !word_start(i) {
+            score += 1;
+        }
+        after_previous = i + c.len_utf8();
+    }
+    Some(score)
+}
+
+/// Options matching `query`, best first by [`fuzzy_score`]. Equally good
+/// matches keep their original order.
+pub fn rank_options<T: Clone>(
+    options: &[ComboboxOption<T>],
+    query: &str,
+) -> Vec<ComboboxOption<T>> {
+    let mut ranked: Vec<_> = options
+        .iter()
+        .filter_map(|o| fuzzy_score(&o.label, query).map(|score| (score, o)))
+        .collect();
+    ranked.sort_by_key(|(score, _)| *score);
+    ranked.into_iter().map(|(_, o)| o.clone()).collect()
+}
+
+/// A text input with a filterable popover list of options.
+///
+/// Typing filters the options client-side; arrow keys move the highlight and
+/// Enter selects it. With `allow_custom`, Enter on text that matches no option
+/// emits it through `on_custom_value` instead.
+#[component]
+pub fn Combobox<T: Clone + PartialEq + 'static>(
+    options: Vec<ComboboxOption<T>>,
+    /// Selected value; updating it from the parent updates the input.
+    #[props(default)]
+    value: Option<T>,
+    #[props(default)] on_value_change: Option<EventHandler<Option<T>>>,
+    #[props(default)] allow_custom: bool,
+    /// Called with the typed text when `allow_custom` is set and no option matches.
+    #[props(default)]
+    on_custom_value: Option<EventHandler<String>>,
+    #[props(default)] placeholder: String,
+    #[props(default = "No matches".to_string())] empty_message: String,
+    #[props(extends = GlobalAttributes)] attributes: Vec<Attribute>,
+) -> Element {
+    let mut selected = use_signal(|| value.clone());
+    use_effect(use_reactive!(|value| selected.set(value)));
+    let mut query = use_signal(String::new);
+    let mut open = use_signal(|| false);
+    let mut highlighted = use_signal(|| 0usize);
+
+    let selected_label = selected
+        .read()
+        .as_ref()
+        .and_then(|v| options.iter().find(|o| &o.value == v))
+        .map(|o| o.label.clone())
+        .unwrap_or_default();
+    let matches = filter_options(&options, &query.read());
+    let text = query();
+    let show_custom = allow_custom
+        && !text.trim().is_empty()
+        && !matches
+            .iter()
+            .any(|o| o.label.eq_ignore_ascii_case(text.trim()));
+    let item_count = matches.len() + usize::from(show_custom);
+    let active = highlighted().min(item_count.saturating_sub(1));
+
+    let base = vec![Attribute::new("class", "cyber-combobox-input", None, false)];
+    let merged = dioxus_primitives::merge_attributes(vec![base, attributes]);
+
+    let choose = {
+        let matches = matches.clone();
+        move |index: usize| {
+            if let Some(option) = matches.get(index) {
+                selected.set(Some(option.value.clone()));
+                if let Some(handler) = &on_value_change {
+                    handler.call(Some(option.value.clone()));
+                }
+            } else if show_custom {
+                if let Some(handler) = &on_custom_value {
+                    handler.call(query().trim().to_string());
+                }
+            }
+            query.set(String::new());
+            open.set(false);
+        }
+    };
+    let mut choose_key = choose.clone();
+
+    rsx! {
+        document::Link { rel: "stylesheet", href: asset!("./style.css") }
+        div { class: "cyber-combobox",
+            PopoverRoot {
+                open: open(),
+                on_open_change: move |is_open: bool| open.set(is_open),
+                input {
+                    r#type: "text",
+                    role: "combobox",
+                    autocomplete: "off",
+                    "aria-expanded": open(),
+                    "aria-autocomplete": "list",
+                    placeholder: placeholder,
+                    value: if open() { text.clone() } else { selected_label },
+                    onfocus: move |_| open.set(true),
+                    onblur: move |_| {
+                        open.set(false);
+                        query.set(String::new());
+                    },
+                    oninput: move |evt: FormEvent| {
+                        query.set(evt.value());
+                        highlighted.set(0);
+                        open.set(true);
+                    },
+                    onkeydown: move |evt: KeyboardEvent| match evt.key() {
+                        Key::ArrowDown => {
+                            evt.prevent_default();
+                            open.set(true);
+                            if item_count > 0 {
+                                highlighted.set((active + 1) % item_count);
+                            }
+                        }
+                        Key::ArrowUp => {
+                            evt.prevent_default();
+                            open.set(true);
+                            if item_count > 0 {
+                                highlighted.set((active + item_count - 1) % item_count);
+                            }
+                        }
+                        Key::Enter => {
+                            if open() && item_count > 0 {
+                                evt.prevent_default();
+                                choose_key(active);
+                            }
+                        }
+                        Key::Escape => {
+                            open.set(false);
+                            query.set(String::new());
+                        }
+                        _ => {}
+                    },
+                    ..merged,
+                }
+                PopoverContent { class: "cyber-popover-content cyber-combobox-content",
+                    ul { class: "cyber-combobox-list", role: "listbox",
+                        for (i, option) in matches.iter().enumerate() {
+                            {
+                                let mut choose = choose.clone();
+                                let is_selected = selected.read().as_ref() == Some(&option.value);
+                                rsx! {
+                                    li {
+                                        key: "{i}",
+                                        class: "cyber-combobox-item",
+                                        role: "option",
+                                        "aria-selected": is_selected,
+                                        "data-highlighted": i == active,
+                                        // mousedown keeps focus on the input so blur doesn't close first
+                                        onmousedown: move |evt: MouseEvent| {
+                                            evt.prevent_default();
+                                            choose(i);
+                                        },
+                                        onmouseenter: move |_| highlighted.set(i),
+                                        "{option.label}"
+                                    }
+                                }
+                            }
+                        }
+                        if show_custom {
+                            {
+                                let mut choose = choose.clone();
+                                let index = matches.len();
+                                rsx! {
+                                    li {
+                                        class: "cyber-combobox-item cyber-combobox-custom",
+                                        role: "option",
+                                        "data-highlighted": index == active,
+                                        onmousedown: move |evt: MouseEvent| {
+                                            evt.prevent_default();
+                                            choose(index);
+                                        },
+                                        onmouseenter: move |_| highlighted.set(index),
+                                        "Use \"{text.trim()}\""
+                                    }
+                                }
+                            }
+                        }
+                        if item_count == 0 {
+                            li { class: "cyber-combobox-empty", "{empty_message}" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> Vec<ComboboxOption<&'static str>> {
+        vec![
+            ComboboxOption::new("hw", "Hardware"),
+            ComboboxOption::new("sw", "Software"),
+            ComboboxOption::new("svc", "Service"),
+        ]
+    }
+
+    #[test]
+    fn empty_query_matches_all() {
+        assert_eq!(filter_options(&options(), "  ").len(), 3);
+    }
+
+    #[test]
+    fn filter_is_case_insensitive_substring() {
+        let values: Vec<_> = filter_options(&options(), "WARE")
+            .into_iter()
+            .map(|o| o.value)
+            .collect();
+        assert_eq!(values, vec!["hw", "sw"]);
+    }
+
+    #[test]
+    fn filter_with_no_match_is_empty() {
+        assert!(filter_options(&options(), "firmware").is_empty());
+    }
+
+    #[test]
+    fn fuzzy_ranks_prefix_then_word_then_substring_then_scattered() {
+        let options = vec![
+            ComboboxOption::new(1, "Toggle theme"),
+            ComboboxOption::new(2, "Go to Products"),
+            ComboboxOption::new(3, "Reproduce"),
+            ComboboxOption::new(4, "Products"),
+            ComboboxOption::new(5, "Sign out"),
+        ];
+        let values = |query| -> Vec<i32> {
+            rank_options(&options, query)
+                .into_iter()
+                .map(|o| o.value)
+                .collect()
+        };
+        assert_eq!(values("pro"), vec![4, 2, 3]);
+        assert_eq!(values("gtp"), vec![2]);
+        assert_eq!(values("tt"), vec![1, 2]);
+        assert_eq!(values("xyz"), Vec::<i32>::new());
+        assert_eq!(values(""), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn fuzzy_prefers_word_starts_and_runs() {
+        assert_eq!(fuzzy_score("Go to Products", "gp"), Some(10));
+        assert!(fuzzy_score("New Product", "np") < fuzzy_score("Sign up", "np"));
+        assert_eq!(fuzzy_score("Settings", "sgt"), None);
+    }
+}
@@ -0,0 +1,169 @@
+use dioxus::prelude::*;
+
+use crate::components::combobox::{rank_options, ComboboxOption};
+use crate::components::dialog::{DialogContent, DialogRoot, DialogTitle};
+
+/// An action offered by a [`CommandPalette`].
+#[derive(Clone, PartialEq)]
+pub struct Command {
+    pub label: String,
+    /// Shown beside the label, e.g. the command's keyboard shortcut.
+    pub hint: Option<String>,
+    pub run: Callback<()>,
+}
+
+impl Command {
+    /// Create a command. Must be called from within a component.
+    pub fn new(label: impl Into<String>, run: impl FnMut(()) + 'static) -> Self {
+        Self {
+            label: label.into(),
+            hint: None,
+            run: Callback::new(run),
+        }
+    }
+
+    pub fn hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+}
+
+/// Script sending a message each time Cmd+K (Ctrl+K off macOS) is pressed,
+/// even while typing in a field.
+const TOGGLE_LISTENER: &str = r#"
+    if (window.__commandPaletteListener) {
+        document.removeEventListener('keydown', window.__commandPaletteListener);
+    }
+    window.__commandPaletteListener = (event) => {
+        if ((event.metaKey || event.ctrlKey) && !event.altKey && event.key.toLowerCase() === 'k') {
+            event.preventDefault();
+            dioxus.send(true);
+        }
+    };
+    document.addEventListener('keydown', window.__commandPaletteListener);
+"#;
+
+/// A dialog for finding and running a command by typing part of its name.
+///
+/// Cmd/Ctrl+K opens and closes it. The query is matched with
+/// [`rank_options`], so `"gp"` finds "Go to Products"; arrow keys move the
+/// highlight and Enter runs the highlighted command, closing the palette.
+#[component]
+pub fn CommandPalette(
+    commands: Vec<Command>,
+    #[props(default = "Type a command…".to_string())] placeholder: String,
+    #[props(default = "No matching commands".to_string())] empty_message: String,
+) -> Element {
+    let mut open = use_signal(|| false);
+    let mut query = use_signal(String::new);
+    let mut highlighted = use_signal(|| 0usize);
+
+    use_future(move || async move {
+        let mut eval = document::eval(TOGGLE_LISTENER);
+        while eval.recv::<bool>().await.is_ok() {
+            query.set(String::new());
+            highlighted.set(0);
+            open.toggle();
+        }
+    });
+
+    let options: Vec<_> = commands
+        .iter()
+        .enumerate()
+        .map(|(i, command)| ComboboxOption::new(i, command.label.clone()))
+        .collect();
+    let matches = rank_options(&options, &query.read());
+    let active = highlighted().min(matches.len().saturating_sub(1));
+
+    let run = {
+        let matches = matches.clone();
+        let commands = commands.clone();
+        move |index: usize| {
+            let Some(option) = matches.get(index) else {
+                return;
+            };
+            open.set(false);
+            commands[option.value].run.call(());
+        }
+    };
+    let mut run_key = run.clone();
+    let count = matches.len();
+
+    rsx! {
+        document::Link { rel: "stylesheet", href: asset!("./style.css") }
+        DialogRoot {
+            open: open(),
+            on_open_change: move |is_open: bool| open.set(is_open),
+            DialogContent { class: "cyber-dialog-content cyber-command-palette",
+                DialogTitle { class: "cyber-command-palette-title", "Command Palette" }
+                if open() {
+                    input {
+                        class: "cyber-command-palette-input",
+                        r#type: "text",
+                        role: "combobox",
+                        autocomplete: "off",
+                        "aria-expanded": "true",
+                        "aria-autocomplete": "list",
+                        "aria-controls": "command-palette-list",
+                        placeholder: placeholder,
+                        value: query(),
+                        onmounted: move |evt: MountedEvent| {
+                            let element = evt.data();
+                            spawn(async move {
+                                let _ = element.set_focus(true).await;
+                            });
+                        },
+                        oninput: move |evt: FormEvent| {
+                            query.set(evt.value());
+                            highlighted.set(0);
+                        },
+                        onkeydown: move |evt: KeyboardEvent| match evt.key() {
+                            Key::ArrowDown if count > 0 => {
+                                evt.prevent_default();
+                                highlighted.set((active + 1) % count);
+                            }
+                            Key::ArrowUp if count > 0 => {
+                                evt.prevent_default();
+                                highlighted.set((active + count - 1) % count);
+                            }
+                            Key::Enter => {
+                                evt.prevent_default();
+                                run_key(active);
+                            }
+                            _ => {}
+                        },
+                    }
+                    ul {
+                        id: "command-palette-list",
+                        class: "cyber-command-palette-list",
+                        role: "listbox",
+                        for (i, option) in matches.iter().enumerate() {
+                            {
+                                let mut run = run.clone();
+                                let hint = commands[option.value].hint.clone();
+                                rsx! {
+                                    li {
+                                        key: "{option.value}",
+                                        class: "cyber-command-palette-item",
+                                        role: "option",
+                                        "aria-selected": i == active,
+                                        "data-highlighted": i == active,
+                                        onclick: move |_| run(i),
+                                        onmouseenter: move |_| highlighted.set(i),
+                                        span { "{option.label}" }
+                                        if let Some(hint) = hint {
+                                            kbd { class: "cyber-command-palette-hint", "{hint}" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        if matches.is_empty() {
+                            li { class: "cyber-command-palette-empty", "{empty_message}" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,298 @@
+use dioxus::prelude::*;
+
+use crate::components::skeleton::Skeleton;
+
+/// Direction of a sorted [`DataTable`] column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortDirection {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+impl SortDirection {
+    /// The opposite direction.
+    pub fn toggled(self) -> Self {
+        match self {
+            SortDirection::Ascending => SortDirection::Descending,
+            SortDirection::Descending => SortDirection::Ascending,
+        }
+    }
+
+    fn aria(&self) -> &'static str {
+        match self {
+            SortDirection::Ascending => "ascending",
+            SortDirection::Descending => "descending",
+        }
+    }
+
+    fn indicator(&self) -> &'static str {
+        match self {
+            SortDirection::Ascending => "\u{25B2}",
+            SortDirection::Descending => "\u{25BC}",
+        }
+    }
+}
+
+/// The column a [`DataTable`] is sorted by, and in which direction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortState {
+    pub column: String,
+    pub direction: SortDirection,
+}
+
+impl SortState {
+    /// Ascending sort on `column`.
+    pub fn new(column: impl Into<String>) -> Self {
+        Self {
+            column: column.into(),
+            direction: SortDirection::Ascending,
+        }
+    }
+
+    /// Sort state after a header click: flips direction when `column` is
+    /// already sorted, otherwise starts an ascending sort on it.
+    pub fn next(current: Option<&SortState>, column: &str) -> SortState {
+        match current {
+            Some(state) if state.column == column => SortState {
+                column: column.to_string(),
+                direction: state.direction.toggled(),
+            },
+            _ => SortState::new(column),
+        }
+    }
+}
+
+/// Describes one column of a [`DataTable`]: its header and how to render a cell.
+#[derive(Clone, PartialEq)]
+pub struct DataColumn<T: 'static> {
+    /// Stable identifier, reported back in [`SortState::column`].
+    pub key: String,
+    pub header: String,
+    pub sortable: bool,
+    pub render: Callback<T, Element>,
+}
+
+impl<T: 'static> DataColumn<T> {
+    /// Create a column. Must be called from within a component.
+    pub fn new(
+        key: impl Into<String>,
+        header: impl Into<String>,
+        render: impl FnMut(T) -> Element + 'static,
+    ) -> Self {
+        Self {
+            key: key.into(),
+            header: header.into(),
+            sortable: false,
+            render: Callback::new(render),
+        }
+    }
+
+    /// Allow sorting by this column via its header.
+    pub fn sortable(mut self) -> Self {
+        self.sortable = true;
+        self
+    }
+}
+
+#[derive(Props, Clone, PartialEq)]
+pub struct DataTableProps<T: Clone + PartialEq + 'static> {
+    pub rows: Vec<T>,
+    pub columns: Vec<DataColumn<T>>,
+    /// Current sort; rows are rendered in the order given, so the caller sorts.
+    #[props(default)]
+    pub sort: Option<SortState>,
+    /// Called with the next sort state when a sortable header is clicked.
+    #[props(default)]
+    pub on_sort: Option<EventHandler<SortState>>,
+    #[props(default)]
+    pub on_row_click: Option<EventHandler<T>>,
+    /// Stable key per row; defaults to the row index.
+    #[props(default)]
+    pub row_key: Option<Callback<T, String>>,
+    /// Show skeleton rows instead of data.
+    #[props(default = false)]
+    pub loading: bool,
+    #[props(default = 5)]
+    pub skeleton_rows: usize,
+    #[props(default = "No data.".to_string())]
+    pub empty_message: String,
+    #[props(extends = GlobalAttributes)]
+    pub attributes: Vec<Attribute>,
+}
+
+/// A cyberpunk-styled data table rendering a semantic `<table>`.
+///
+/// Columns are described by [`DataColumn`]s, each rendering one cell per row.
+#[component]
+pub fn DataTable<T: Clone + PartialEq + 'static>(props: DataTableProps<T>) -> Element {
+    let base = vec![Attribute::new("class", "data-table", None, false)];
+    let merged = dioxus_primitives::merge_attributes(vec![base, props.attributes]);
+    let column_count = props.columns.len();
+    let clickable = props.on_row_click.is_some();
+
+    rsx! {
+        document::Link { rel: "stylesheet", href: asset!("./style.css") }
+        div { class: "data-table-wrap",
+            table {
+                ..merged,
+                thead {
+                    tr {
+                        for column in props.columns.iter() {
+                            {
+                                let active = props
+                                    .sort
+                                    .as_ref()
+                                    .filter(|s| s.column == column.key)
+                                    .map(|s| s.direction);
+                                let aria_sort = match active {
+                                    Some(direction) => direction.aria(),
+                                    None if column.sortable => "none",
+                                    None => "",
+                                };
+                                let indicator = active.map(|d| d.indicator()).unwrap_or("\u{2195}");
+                                let key = column.key.clone();
+                                let sort = props.sort.clone();
+                                let on_sort = props.on_sort;
+
+                                rsx! {
+                                    th {
+                                        key: "{column.key}",
+                                        scope: "col",
+                                        class: "data-table-header",
+                                        "aria-sort": if !aria_sort.is_empty() { aria_sort },
+                                        if column.sortable {
+                                            button {
+                                                r#type: "button",
+                                                class: "data-table-sort",
+                                                onclick: move |_| {
+                                                    if let Some(handler) = &on_sort {
+                                                        handler.call(SortState::next(sort.as_ref(), &key));
+                                                    }
+                                                },
+                                                "{column.header}"
+                                                span { class: "data-table-sort-indicator", "{indicator}" }
+                                            }
+                                        } else {
+                                            "{column.header}"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                tbody {
+                    if props.loading {
+                        for i in 0..props.skeleton_rows {
+                            tr { key: "skeleton-{i}", class: "data-table-row",
+                                for j in 0..column_count {
+                                    td { key: "{j}", class: "data-table-cell",
+                                        Skeleton { style: "height: 1rem; width: 70%;" }
+                                    }
+                                }
+                            }
+                        }
+                    } else if props.rows.is_empty() {
+                        tr {
+                            td {
+                                class: "data-table-empty",
+                                colspan: "{column_count}",
+                                "{props.empty_message}"
+                            }
+                        }
+                    } else {
+                        for (i, row) in props.rows.iter().enumerate() {
+                            {
+                                let key = props
+                                    .row_key
+                                    .map(|k| k.call(row.clone()))
+                                    .unwrap_or_else(|| i.to_string());
+                                let clicked = row.clone();
+                                let on_row_click = props.on_row_click;
+
+                                rsx! {
+                                    tr {
+                                        key: "{key}",
+                                        class: "data-table-row",
+                                        "data-clickable": clickable,
+                                        onclick: move |_| {
+                                            if let Some(handler) = &on_row_click {
+                                                handler.call(clicked.clone());
+                                            }
+                                        },
+                                        for column in props.columns.iter() {
+                                            td { key: "{column.key}", class: "data-table-cell",
+                                                {column.render.call(row.clone())}
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, PartialEq)]
+    struct Row {
+        id: i64,
+        name: &'static str,
+    }
+
+    #[component]
+    fn Harness(loading: bool) -> Element {
+        let columns = vec![
+            DataColumn::new("id", "ID", |r: Row| rsx! { "{r.id}" }),
+            DataColumn::new("name", "Name", |r: Row| rsx! { "{r.name}" }).sortable(),
+        ];
+        rsx! {
+            DataTable::<Row> {
+                rows: vec![Row { id: 1, name: "Ada" }, Row { id: 2, name: "Grace" }],
+                columns,
+                sort: SortState::new("name"),
+                loading,
+            }
+        }
+    }
+
+    fn render(loading: bool) -> String {
+        let mut dom = VirtualDom::new_with_props(Harness, HarnessProps { loading });
+        dom.rebuild_in_place();
+        dioxus_ssr::render(&dom)
+    }
+
+    #[test]
+    fn renders_headers_and_rows() {
+        let html = render(false);
+        assert!(html.contains("<table"));
+        assert!(html.contains("<th"));
+        assert!(html.contains("Name"));
+        assert!(html.contains("Ada"));
+        assert!(html.contains("Grace"));
+        assert!(html.contains(r#"aria-sort="ascending""#));
+    }
+
+    #[test]
+    fn loading_renders_skeleton_rows() {
+        let html = render(true);
+        assert!(html.contains("skeleton"));
+        assert!(!html.contains("Ada"));
+    }
+
+    #[test]
+    fn sort_state_next_toggles_active_column() {
+        let asc = SortState::new("name");
+        let desc = SortState::next(Some(&asc), "name");
+        assert_eq!(desc.direction, SortDirection::Descending);
+        let other = SortState::next(Some(&desc), "id");
+        assert_eq!(other, SortState::new("id"));
+    }
+}
@@ -1,6 +1,9 @@
+use std::fmt;
+
 use dioxus::prelude::*;
 use dioxus_primitives::calendar::CalendarProps;
 use dioxus_primitives::date_picker as prim;
+use time::{Date, Month};
 
 pub use dioxus_primitives::date_picker::{DateRangePickerContext, DefaultCalendarProps};
 
@@ -82,3 +85,292 @@ pub fn DateRangePickerInput(mut props: prim::DatePickerInputProps) -> Element {
         prim::DateRangePickerInput { ..props }
     }
 }
+
+/// Format typed into a [`DateTextInput`], e.g. `2026-03-01`.
+pub const DATE_INPUT_FORMAT: &str = "YYYY-MM-DD";
+
+/// Why typed text was not accepted as a date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateInputError {
+    /// Not in [`DATE_INPUT_FORMAT`].
+    Format,
+    /// In the right format but not a real day, e.g. `2026-02-30`.
+    NoSuchDay,
+    BeforeMin(Date),
+    AfterMax(Date),
+    /// A day the caller marked unavailable.
+    Unavailable,
+}
+
+impl fmt::Display for DateInputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DateInputError::Format => write!(f, "Enter a date as {DATE_INPUT_FORMAT}"),
+            DateInputError::NoSuchDay => write!(f, "That date doesn't exist"),
+            DateInputError::BeforeMin(min) => {
+                write!(f, "Pick a date on or after {}", format_date_input(*min))
+            }
+            DateInputError::AfterMax(max) => {
+                write!(f, "Pick a date on or before {}", format_date_input(*max))
+            }
+            DateInputError::Unavailable => write!(f, "That date isn't available"),
+        }
+    }
+}
+
+/// Render `date` in [`DATE_INPUT_FORMAT`].
+pub fn format_date_input(date: Date) -> String {
+    format!(
+        "{:04}-{:02}-{:02}",
+        date.year(),
+        u8::from(date.month()),
+        date.day()
+    )
+}
+
+/// Parse text typed as [`DATE_INPUT_FORMAT`]; `/` is accepted in place of
+/// `-`. Blank text is no date.
+pub fn parse_date_input(text: &str) -> Result<Option<Date>, DateInputError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    let parts: Vec<&str> = text.split(['-', '/']).collect();
+    let [year, month, day] = parts.as_slice() else {
+        return Err(DateInputError::Format);
+    };
+    let digits = |part: &str, max_len: usize| {
+        (!part.is_empty() && part.len() <= max_len && part.bytes().all(|b| b.is_ascii_digit()))
+            .then(|| part.parse::<u16>().ok())
+            .flatten()
+    };
+    let (Some(year), Some(month), Some(day)) = (
+        digits(year, 4).filter(|_| year.len() == 4),
+        digits(month, 2),
+        digits(day, 2),
+    ) else {
+        return Err(DateInputError::Format);
+    };
+
+    let month = u8::try_from(month)
+        .ok()
+        .and_then(|m| Month::try_from(m).ok())
+        .ok_or(DateInputError::NoSuchDay)?;
+    let day = u8::try_from(day).map_err(|_| DateInputError::NoSuchDay)?;
+    Date::from_calendar_date(i32::from(year), month, day)
+        .map(Some)
+        .map_err(|_| DateInputError::NoSuchDay)
+}
+
+/// Check `date` against a [`DateTextInput`]'s constraints.
+pub fn check_date_input(
+    date: Date,
+    min: Option<Date>,
+    max: Option<Date>,
+    disabled_dates: &[Date],
+) -> Result<Date, DateInputError> {
+    if let Some(min) = min.filter(|min| date < *min) {
+        return Err(DateInputError::BeforeMin(min));
+    }
+    if let Some(max) = max.filter(|max| date > *max) {
+        return Err(DateInputError::AfterMax(max));
+    }
+    if disabled_dates.contains(&date) {
+        return Err(DateInputError::Unavailable);
+    }
+    Ok(date)
+}
+
+/// Text field for typing a date as [`DATE_INPUT_FORMAT`], for use alongside
+/// a [`DatePicker`] bound to the same `value`.
+///
+/// The typed text is checked when the field is committed (blur or Enter):
+/// valid dates within `min`/`max` and not in `disabled_dates` are passed to
+/// `on_change`; anything else leaves `value` alone and shows the reason
+/// below the field. Clearing the field passes `None`. When `value` changes
+/// elsewhere (e.g. a calendar pick) the field shows the new date.
+#[component]
+pub fn DateTextInput(
+    value: Option<Date>,
+    on_change: EventHandler<Option<Date>>,
+    #[props(default)] min: Option<Date>,
+    #[props(default)] max: Option<Date>,
+    #[props(default)] disabled_dates: Vec<Date>,
+    #[props(default = false)] disabled: bool,
+    #[props(default = "date-input".to_string())] id: String,
+    #[props(extends = GlobalAttributes)] attributes: Vec<Attribute>,
+) -> Element {
+    let mut text = use_signal(|| value.map(format_date_input).unwrap_or_default());
+    let mut error = use_signal(|| None::<DateInputError>);
+
+    // Follow selections made elsewhere, unless the text already says so.
+    use_effect(use_reactive!(|value| {
+        if parse_date_input(&text.peek()).ok() != Some(value) {
+            text.set(value.map(format_date_input).unwrap_or_default());
+            error.set(None);
+        }
+    }));
+
+    let mut commit = move |typed: String| {
+        let checked = parse_date_input(&typed).and_then(|date| {
+            date.map(|d| check_date_input(d, min, max, &disabled_dates))
+                .transpose()
+        });
+        match checked {
+            Ok(date) => {
+                error.set(None);
+                if let Some(date) = date {
+                    text.set(format_date_input(date));
+                }
+                if date != value {
+                    on_change.call(date);
+                }
+            }
+            Err(e) => error.set(Some(e)),
+        }
+    };
+
+    let error_id = format!("{id}-error");
+    let base = vec![Attribute::new(
+        "class",
+        "cyber-date-text-input",
+        None,
+        false,
+    )];
+    let merged = dioxus_primitives::merge_attributes(vec![base, attributes]);
+
+    rsx! {
+        document::Link { rel: "stylesheet", href: asset!("./style.css") }
+        div {
+            ..merged,
+            input {
+                id: "{id}",
+                r#type: "text",
+                class: "input",
+                inputmode: "numeric",
+                autocomplete: "off",
+                placeholder: DATE_INPUT_FORMAT,
+                value: "{text}",
+                disabled,
+                aria_invalid: error().is_some(),
+                aria_describedby: if error().is_some() { Some(error_id.clone()) } else { None },
+                oninput: move |evt: FormEvent| {
+                    text.set(evt.value());
+                    error.set(None);
+                },
+                onchange: {
+                    let mut commit = commit.clone();
+                    move |evt: FormEvent| commit(evt.value())
+                },
+                onkeydown: move |evt: KeyboardEvent| {
+                    if evt.key() == Key::Enter {
+                        evt.prevent_default();
+                        commit(text());
+                    }
+                },
+            }
+            if let Some(err) = error() {
+                div {
+                    id: "{error_id}",
+                    class: "cyber-date-text-input-error",
+                    role: "alert",
+                    "{err}"
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: Month, day: u8) -> Date {
+        Date::from_calendar_date(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn typed_dates_are_parsed() {
+        assert_eq!(
+            parse_date_input("2026-03-01"),
+            Ok(Some(date(2026, Month::March, 1)))
+        );
+        assert_eq!(
+            parse_date_input(" 2024/2/29 "),
+            Ok(Some(date(2024, Month::February, 29)))
+        );
+        assert_eq!(parse_date_input("   "), Ok(None));
+        assert_eq!(format_date_input(date(2026, Month::March, 1)), "2026-03-01");
+    }
+
+    #[test]
+    fn invalid_typed_dates_are_rejected() {
+        for text in [
+            "yesterday",
+            "03/01/2026",
+            "2026-03",
+            "2026-03-01-02",
+            "26-03-01",
+        ] {
+            assert_eq!(
+                parse_date_input(text),
+                Err(DateInputError::Format),
+                "{text}"
+            );
+        }
+        for text in [
+            "2026-02-30",
+            "2025-02-29",
+            "2026-13-01",
+            "2026-00-10",
+            "2026-04-31",
+        ] {
+            assert_eq!(
+                parse_date_input(text),
+                Err(DateInputError::NoSuchDay),
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn constraints_are_enforced() {
+        let min = date(2026, Month::January, 1);
+        let max = date(2026, Month::December, 31);
+        let holiday = date(2026, Month::December, 25);
+        let check = |d| check_date_input(d, Some(min), Some(max), &[holiday]);
+
+        assert_eq!(
+            check(date(2026, Month::June, 1)),
+            Ok(date(2026, Month::June, 1))
+        );
+        assert_eq!(
+            check(date(2025, Month::December, 31)),
+            Err(DateInputError::BeforeMin(min))
+        );
+        assert_eq!(
+            check(date(2027, Month::January, 1)),
+            Err(DateInputError::AfterMax(max))
+        );
+        assert_eq!(check(holiday), Err(DateInputError::Unavailable));
+        assert_eq!(
+            DateInputError::BeforeMin(min).to_string(),
+            "Pick a date on or after 2026-01-01"
+        );
+    }
+
+    #[test]
+    fn renders_the_selected_date_without_an_error() {
+        let html = dioxus_ssr::render_element(rsx! {
+            DateTextInput {
+                value: Some(date(2026, Month::March, 1)),
+                on_change: |_| {},
+                id: "created-after",
+            }
+        });
+        assert!(html.contains(r#"value="2026-03-01""#), "{html}");
+        assert!(html.contains(r#"id="created-after""#), "{html}");
+        assert!(html.contains(r#"placeholder="YYYY-MM-DD""#), "{html}");
+        assert!(!html.contains(r#"role="alert""#), "{html}");
+    }
+}
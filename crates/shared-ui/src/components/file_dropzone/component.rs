@@ -0,0 +1,243 @@
+use std::fmt;
+
+use dioxus::html::FileData;
+use dioxus::prelude::*;
+
+/// A file accepted by a [`FileDropzone`], read into memory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DroppedFile {
+    pub name: String,
+    pub content_type: String,
+    pub bytes: Vec<u8>,
+}
+
+/// Why a [`FileDropzone`] rejected a file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileRejection {
+    TooLarge { max_size: u64 },
+    WrongType { content_type: String },
+    Unreadable,
+}
+
+impl fmt::Display for FileRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileRejection::TooLarge { max_size } => {
+                write!(f, "File must be under {}", format_size(*max_size))
+            }
+            FileRejection::WrongType { content_type } if content_type.is_empty() => {
+                f.write_str("This file type isn't supported")
+            }
+            FileRejection::WrongType { content_type } => {
+                write!(f, "Files of type {content_type} aren't supported")
+            }
+            FileRejection::Unreadable => f.write_str("Failed to read file"),
+        }
+    }
+}
+
+/// Human-readable byte size, e.g. `2 MB` or `512 KB`.
+pub fn format_size(bytes: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = 1024 * KB;
+    if bytes >= MB && bytes % MB == 0 {
+        format!("{} MB", bytes / MB)
+    } else if bytes >= MB {
+        format!("{:.1} MB", bytes as f64 / MB as f64)
+    } else if bytes >= KB {
+        format!("{} KB", bytes / KB)
+    } else {
+        format!("{bytes} B")
+    }
+}
+
+/// Whether a file matches an `accept` list like the HTML attribute:
+/// comma-separated MIME types (`image/png`), wildcards (`image/*`), or
+/// extensions (`.png`). An empty list accepts everything.
+pub fn accepts(accept: &str, name: &str, content_type: &str) -> bool {
+    let content_type = content_type.to_lowercase();
+    let name = name.to_lowercase();
+    let mut patterns = accept
+        .split(',')
+        .map(|p| p.trim().to_lowercase())
+        .filter(|p| !p.is_empty())
+        .peekable();
+    if patterns.peek().is_none() {
+        return true;
+    }
+    patterns.any(|pattern| {
+        if pattern.starts_with('.') {
+            name.ends_with(&pattern)
+        } else if let Some(prefix) = pattern.strip_suffix("/*") {
+            content_type
+                .split_once('/')
+                .is_some_and(|(kind, _)| kind == prefix)
+        } else {
+            content_type == pattern
+        }
+    })
+}
+
+/// Check a file against the dropzone's `accept` list and size limit before
+/// reading it.
+pub fn validate_file(
+    name: &str,
+    content_type: &str,
+    size: u64,
+    accept: &str,
+    max_size: Option<u64>,
+) -> Result<(), FileRejection> {
+    if !accepts(accept, name, content_type) {
+        return Err(FileRejection::WrongType {
+            content_type: content_type.to_string(),
+        });
+    }
+    if let Some(max_size) = max_size.filter(|max| size > *max) {
+        return Err(FileRejection::TooLarge { max_size });
+    }
+    Ok(())
+}
+
+/// A drop target for a single file that also opens the file picker on click.
+///
+/// Files are checked against `accept` and `max_size` before being read;
+/// accepted files are passed to `on_file` with their bytes, rejected ones to
+/// `on_error`. Children are rendered as the prompt inside the zone.
+#[component]
+pub fn FileDropzone(
+    /// Allowed types, in the same format as the `accept` attribute.
+    #[props(default)]
+    accept: String,
+    /// Maximum file size in bytes.
+    #[props(default)]
+    max_size: Option<u64>,
+    #[props(default = false)] disabled: bool,
+    on_file: EventHandler<DroppedFile>,
+    #[props(default)] on_error: Option<EventHandler<FileRejection>>,
+    #[props(extends = GlobalAttributes)] attributes: Vec<Attribute>,
+    children: Element,
+) -> Element {
+    let mut dragging = use_signal(|| false);
+
+    let base = vec![Attribute::new("class", "file-dropzone", None, false)];
+    let merged = dioxus_primitives::merge_attributes(vec![base, attributes]);
+
+    let accept_list = accept.clone();
+    let handle = move |file: FileData| {
+        let accept = accept_list.clone();
+        async move {
+            let name = file.name();
+            let content_type = file.content_type().unwrap_or_default();
+            let checked = validate_file(&name, &content_type, file.size(), &accept, max_size);
+            let result = match checked {
+                Ok(()) => file
+                    .read_bytes()
+                    .await
+                    .map(|bytes| DroppedFile {
+                        name,
+                        content_type,
+                        bytes: bytes.to_vec(),
+                    })
+                    .map_err(|_| FileRejection::Unreadable),
+                Err(rejection) => Err(rejection),
+            };
+            match result {
+                Ok(dropped) => on_file.call(dropped),
+                Err(rejection) => {
+                    if let Some(handler) = &on_error {
+                        handler.call(rejection);
+                    }
+                }
+            }
+        }
+    };
+    let handle_drop = handle.clone();
+
+    rsx! {
+        document::Link { rel: "stylesheet", href: asset!("./style.css") }
+        label {
+            "data-dragging": dragging(),
+            "data-disabled": disabled,
+            ondragenter: move |evt: DragEvent| {
+                evt.prevent_default();
+                if !disabled {
+                    dragging.set(true);
+                }
+            },
+            ondragover: move |evt: DragEvent| evt.prevent_default(),
+            ondragleave: move |_| dragging.set(false),
+            ondrop: move |evt: DragEvent| {
+                evt.prevent_default();
+                dragging.set(false);
+                if disabled {
+                    return;
+                }
+                if let Some(file) = evt.files().into_iter().next() {
+                    spawn(handle_drop.clone()(file));
+                }
+            },
+            ..merged,
+            input {
+                r#type: "file",
+                class: "file-dropzone-input",
+                accept: accept,
+                disabled: disabled,
+                onchange: move |evt: FormEvent| {
+                    if let Some(file) = evt.files().into_iter().next() {
+                        spawn(handle.clone()(file));
+                    }
+                },
+            }
+            {children}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IMAGES: &str = "image/jpeg,image/png,image/webp";
+    const TWO_MB: u64 = 2 * 1024 * 1024;
+
+    #[test]
+    fn accepts_listed_type_within_limit() {
+        assert_eq!(
+            validate_file("me.png", "image/png", 1024, IMAGES, Some(TWO_MB)),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn rejects_oversize_file() {
+        let err = validate_file("me.png", "image/png", TWO_MB + 1, IMAGES, Some(TWO_MB));
+        assert_eq!(err, Err(FileRejection::TooLarge { max_size: TWO_MB }));
+        assert_eq!(err.unwrap_err().to_string(), "File must be under 2 MB");
+    }
+
+    #[test]
+    fn rejects_unlisted_type() {
+        let err = validate_file("notes.pdf", "application/pdf", 10, IMAGES, None);
+        assert_eq!(
+            err,
+            Err(FileRejection::WrongType {
+                content_type: "application/pdf".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn accept_supports_wildcards_and_extensions() {
+        assert!(accepts("image/*", "a.gif", "image/gif"));
+        assert!(!accepts("image/*", "a.txt", "text/plain"));
+        assert!(accepts(".csv, .TSV", "DATA.tsv", ""));
+        assert!(accepts("", "anything.bin", "application/octet-stream"));
+    }
+
+    #[test]
+    fn format_size_picks_unit() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(300 * 1024), "300 KB");
+        assert_eq!(format_size(1536 * 1024), "1.5 MB");
+    }
+}
@@ -1,6 +1,13 @@
 use dioxus::prelude::*;
+use std::time::Duration;
 
 /// A cyberpunk-styled text input component.
+///
+/// With `debounce_ms`, `on_input` fires once typing pauses for that long,
+/// with the latest event; the field itself still updates on every keystroke.
+///
+/// With `mask`, only numbers the mask accepts can be entered, and changes are
+/// reported through `on_value` (with the parsed number) instead of `on_input`.
 #[component]
 pub fn Input(
     #[props(default)] value: String,
@@ -9,11 +16,28 @@ pub fn Input(
     #[props(default)] label: String,
     #[props(default = "text".to_string())] input_type: String,
     #[props(default = false)] disabled: bool,
+    #[props(default)] debounce_ms: Option<u64>,
+    #[props(default)] mask: Option<NumberMask>,
+    #[props(default)] on_value: EventHandler<MaskedValue>,
     #[props(extends = GlobalAttributes)] attributes: Vec<Attribute>,
 ) -> Element {
     let base = vec![Attribute::new("class", "input", None, false)];
     let merged = dioxus_primitives::merge_attributes(vec![base, attributes]);
 
+    // While debouncing or masking, show our own copy of the text rather than
+    // the (lagging or unmasked) prop.
+    let mut draft = use_signal(|| value.clone());
+    use_effect(use_reactive!(|value| draft.set(value)));
+    let mut debouncer = use_signal(Debouncer::<FormEvent>::default);
+
+    let delay = debounce_ms.filter(|ms| *ms > 0).map(Duration::from_millis);
+    let shown = if delay.is_some() || mask.is_some() {
+        draft()
+    } else {
+        value
+    };
+    let input_mode = mask.map(|m| if m.decimals > 0 { "decimal" } else { "numeric" });
+
     rsx! {
         document::Link { rel: "stylesheet", href: asset!("./style.css") }
         div { class: "input-wrapper",
@@ -22,12 +46,259 @@ pub fn Input(
             }
             input {
                 r#type: "{input_type}",
-                value: value,
+                value: shown,
                 placeholder: placeholder,
                 disabled: disabled,
-                oninput: move |evt| on_input.call(evt),
+                inputmode: input_mode,
+                onkeydown: move |evt: KeyboardEvent| {
+                    if let (Some(mask), Key::Character(typed)) = (mask, evt.key()) {
+                        let modified = evt.modifiers().ctrl() || evt.modifiers().meta();
+                        if !modified && !typed.chars().all(|c| mask.accepts_char(c)) {
+                            evt.prevent_default();
+                        }
+                    }
+                },
+                oninput: move |evt: FormEvent| {
+                    if let Some(mask) = mask {
+                        // Pasted or otherwise invalid text: keep the last good value.
+                        let raw = mask.clean(&evt.value()).unwrap_or_else(|| draft());
+                        draft.set(raw.clone());
+                        let parsed = mask.parse(&raw);
+                        on_value.call(MaskedValue { raw, parsed });
+                        return;
+                    }
+                    match delay {
+                        Some(delay) => {
+                            draft.set(evt.value());
+                            let ticket = debouncer.write().push(evt);
+                            spawn(async move {
+                                futures_timer::Delay::new(delay).await;
+                                if let Some(evt) = debouncer.write().settle(ticket) {
+                                    on_input.call(evt);
+                                }
+                            });
+                        }
+                        None => on_input.call(evt),
+                    }
+                },
                 ..merged,
             }
         }
     }
 }
+
+/// Restricts an [`Input`] to numbers, optionally bounded and with a fixed
+/// number of decimal places.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumberMask {
+    /// Digits allowed after the decimal point; 0 for whole numbers.
+    pub decimals: u8,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl NumberMask {
+    /// Whole numbers.
+    pub fn integer() -> Self {
+        Self {
+            decimals: 0,
+            min: None,
+            max: None,
+        }
+    }
+
+    /// Numbers with up to `decimals` decimal places.
+    pub fn decimal(decimals: u8) -> Self {
+        Self {
+            decimals,
+            ..Self::integer()
+        }
+    }
+
+    /// Non-negative amounts with at most two decimal places.
+    pub fn currency() -> Self {
+        Self {
+            min: Some(0.0),
+            ..Self::decimal(2)
+        }
+    }
+
+    pub fn with_min(self, min: f64) -> Self {
+        Self {
+            min: Some(min),
+            ..self
+        }
+    }
+
+    pub fn with_max(self, max: f64) -> Self {
+        Self {
+            max: Some(max),
+            ..self
+        }
+    }
+
+    fn allows_negative(&self) -> bool {
+        self.min.is_none_or(|min| min < 0.0)
+    }
+
+    /// Whether `c` can appear in a value of this mask.
+    pub fn accepts_char(&self, c: char) -> bool {
+        c.is_ascii_digit()
+            || (c == '.' && self.decimals > 0)
+            || (c == '-' && self.allows_negative())
+    }
+
+    /// Strip what the mask doesn't allow from `text`: unknown characters,
+    /// currency symbols and grouping commas, a second decimal point, and
+    /// excess decimal places.
+    ///
+    /// Returns `None` when what's left is above `max`, so the caller can
+    /// keep the previous value instead.
+    pub fn clean(&self, text: &str) -> Option<String> {
+        let mut cleaned = String::with_capacity(text.len());
+        let mut decimals = None;
+        for c in text.trim().chars() {
+            match c {
+                '-' if cleaned.is_empty() && self.allows_negative() => cleaned.push(c),
+                '.' if self.decimals > 0 && decimals.is_none() => {
+                    decimals = Some(0);
+                    cleaned.push(c);
+                }
+                '0'..='9' => match decimals {
+                    Some(n) if n >= self.decimals => {}
+                    Some(n) => {
+                        decimals = Some(n + 1);
+                        cleaned.push(c);
+                    }
+                    None => cleaned.push(c),
+                },
+                _ => {}
+            }
+        }
+
+        match (cleaned.parse::<f64>(), self.max) {
+            (Ok(number), Some(max)) if number > max => None,
+            _ => Some(cleaned),
+        }
+    }
+
+    /// The number in `raw`, if it is complete and within bounds.
+    pub fn parse(&self, raw: &str) -> Option<f64> {
+        let number = raw.parse::<f64>().ok().filter(|n| n.is_finite())?;
+        let in_range =
+            self.min.is_none_or(|min| number >= min) && self.max.is_none_or(|max| number <= max);
+        in_range.then_some(number)
+    }
+}
+
+/// A change to a masked [`Input`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaskedValue {
+    /// The text in the field after masking, e.g. `"12."` while typing.
+    pub raw: String,
+    /// The number it represents, or `None` if it's empty, incomplete, or out
+    /// of range.
+    pub parsed: Option<f64>,
+}
+
+/// Keeps the latest value from a burst of events, releasing it only to the
+/// timer started by the last event in the burst.
+struct Debouncer<T> {
+    latest: Option<T>,
+    ticket: u64,
+}
+
+impl<T> Default for Debouncer<T> {
+    fn default() -> Self {
+        Self {
+            latest: None,
+            ticket: 0,
+        }
+    }
+}
+
+impl<T> Debouncer<T> {
+    /// Record a new event, superseding any pending one. Returns the ticket
+    /// to pass to [`Debouncer::settle`] once the delay has elapsed.
+    fn push(&mut self, value: T) -> u64 {
+        self.ticket += 1;
+        self.latest = Some(value);
+        self.ticket
+    }
+
+    /// The pending event, if `ticket` is still the most recent one.
+    fn settle(&mut self, ticket: u64) -> Option<T> {
+        if ticket == self.ticket {
+            self.latest.take()
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rapid_typing_emits_once_with_last_value() {
+        let mut debouncer = Debouncer::default();
+        let tickets: Vec<u64> = ["q", "qu", "qua", "quan"]
+            .into_iter()
+            .map(|v| debouncer.push(v))
+            .collect();
+
+        // Every keystroke's timer fires in order; only the last one emits.
+        let emitted: Vec<_> = tickets
+            .into_iter()
+            .filter_map(|t| debouncer.settle(t))
+            .collect();
+        assert_eq!(emitted, vec!["quan"]);
+    }
+
+    #[test]
+    fn typing_after_settling_emits_again() {
+        let mut debouncer = Debouncer::default();
+        let first = debouncer.push("a");
+        assert_eq!(debouncer.settle(first), Some("a"));
+        assert_eq!(debouncer.settle(first), None);
+
+        let second = debouncer.push("ab");
+        assert_eq!(debouncer.settle(second), Some("ab"));
+    }
+
+    #[test]
+    fn currency_mask_strips_invalid_characters() {
+        let mask = NumberMask::currency();
+        assert_eq!(mask.clean("$1,299.99").as_deref(), Some("1299.99"));
+        assert_eq!(mask.clean("12abc").as_deref(), Some("12"));
+        assert_eq!(mask.clean("-5").as_deref(), Some("5"));
+        assert_eq!(mask.clean("1.2.3").as_deref(), Some("1.23"));
+        assert_eq!(mask.clean("9.999").as_deref(), Some("9.99"));
+    }
+
+    #[test]
+    fn integer_mask_rejects_decimal_point_and_letters() {
+        let mask = NumberMask::integer();
+        assert!(!mask.accepts_char('.'));
+        assert!(!mask.accepts_char('e'));
+        assert!(mask.accepts_char('-'));
+        assert_eq!(mask.clean("-4.5").as_deref(), Some("-45"));
+    }
+
+    #[test]
+    fn values_above_max_are_rejected() {
+        let mask = NumberMask::decimal(1).with_max(100.0);
+        assert_eq!(mask.clean("100").as_deref(), Some("100"));
+        assert_eq!(mask.clean("100.5"), None);
+    }
+
+    #[test]
+    fn parse_reports_only_complete_in_range_numbers() {
+        let mask = NumberMask::currency().with_min(1.0);
+        assert_eq!(mask.parse("12."), Some(12.0));
+        assert_eq!(mask.parse("0.5"), None);
+        assert_eq!(mask.parse(""), None);
+        assert_eq!(mask.parse("-"), None);
+    }
+}
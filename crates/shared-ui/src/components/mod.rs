@@ -2,6 +2,7 @@
 pub mod badge;
 pub mod button;
 pub mod card;
+pub mod file_dropzone;
 pub mod form;
 pub mod input;
 pub mod sheet;
@@ -50,6 +51,13 @@ pub mod toast;
 // Phase 1 (last): Depends on button, sheet, separator, tooltip
 pub mod sidebar;
 
+// Composite: Depend on skeleton, button, popover, checkbox, dialog
+pub mod combobox;
+pub mod command_palette;
+pub mod data_table;
+pub mod multi_select;
+pub mod pagination;
+
 // Re-exports for convenience
 pub use accordion::*;
 pub use alert_dialog::*;
@@ -61,16 +69,22 @@ pub use calendar::*;
 pub use card::*;
 pub use checkbox::*;
 pub use collapsible::*;
+pub use combobox::*;
+pub use command_palette::*;
 pub use context_menu::*;
+pub use data_table::*;
 pub use date_picker::*;
 pub use dialog::*;
 pub use dropdown_menu::*;
+pub use file_dropzone::*;
 pub use form::*;
 pub use hover_card::*;
 pub use input::*;
 pub use label::*;
 pub use menubar::*;
+pub use multi_select::*;
 pub use navbar::*;
+pub use pagination::*;
 pub use popover::*;
 pub use progress::*;
 pub use radio_group::*;
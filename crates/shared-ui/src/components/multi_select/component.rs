@@ -0,0 +1,270 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use dioxus::prelude::*;
+
+use crate::components::checkbox::{Checkbox, CheckboxIndicator, CheckboxState};
+use crate::components::popover::{PopoverContent, PopoverRoot};
+
+/// A selectable entry in a [`MultiSelect`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiSelectOption<T> {
+    pub value: T,
+    pub label: String,
+}
+
+impl<T> MultiSelectOption<T> {
+    pub fn new(value: T, label: impl Into<String>) -> Self {
+        Self {
+            value,
+            label: label.into(),
+        }
+    }
+}
+
+/// Selection with `value` added if it was absent, or removed if present.
+pub fn toggle_selection<T: Clone + Eq + Hash>(selected: &HashSet<T>, value: &T) -> HashSet<T> {
+    let mut next = selected.clone();
+    if !next.remove(value) {
+        next.insert(value.clone());
+    }
+    next
+}
+
+/// Selection with `value` removed.
+pub fn remove_selection<T: Clone + Eq + Hash>(selected: &HashSet<T>, value: &T) -> HashSet<T> {
+    let mut next = selected.clone();
+    next.remove(value);
+    next
+}
+
+/// Selected options in option order, so chips don't reshuffle as the set changes.
+pub fn selected_options<'a, T: Eq + Hash>(
+    options: &'a [MultiSelectOption<T>],
+    selected: &HashSet<T>,
+) -> Vec<&'a MultiSelectOption<T>> {
+    options
+        .iter()
+        .filter(|o| selected.contains(&o.value))
+        .collect()
+}
+
+/// A dropdown of checkable options; selected values show as removable chips.
+///
+/// The trigger is focusable: arrow keys open the list and move the highlight,
+/// Enter or Space toggles the highlighted option, Escape closes, and Backspace
+/// removes the last chip.
+#[component]
+pub fn MultiSelect<T: Clone + PartialEq + Eq + Hash + 'static>(
+    options: Vec<MultiSelectOption<T>>,
+    /// Selected values; updating them from the parent updates the chips.
+    #[props(default)]
+    value: HashSet<T>,
+    #[props(default)] on_change: Option<EventHandler<HashSet<T>>>,
+    #[props(default)] placeholder: String,
+    #[props(extends = GlobalAttributes)] attributes: Vec<Attribute>,
+) -> Element {
+    let mut selected = use_signal(|| value.clone());
+    use_effect(use_reactive!(|value| selected.set(value)));
+    let mut open = use_signal(|| false);
+    let mut highlighted = use_signal(|| 0usize);
+
+    let option_count = options.len();
+    let active = highlighted().min(option_count.saturating_sub(1));
+    let chips: Vec<MultiSelectOption<T>> = selected_options(&options, &selected.read())
+        .into_iter()
+        .cloned()
+        .collect();
+
+    let base = vec![Attribute::new(
+        "class",
+        "cyber-multi-select-trigger",
+        None,
+        false,
+    )];
+    let merged = dioxus_primitives::merge_attributes(vec![base, attributes]);
+
+    let mut update = move |next: HashSet<T>| {
+        selected.set(next.clone());
+        if let Some(handler) = &on_change {
+            handler.call(next);
+        }
+    };
+    let toggle = {
+        let options = options.clone();
+        move |index: usize| {
+            if let Some(option) = options.get(index) {
+                let next = toggle_selection(&selected.read(), &option.value);
+                update(next);
+            }
+        }
+    };
+    let mut toggle_key = toggle.clone();
+    let last_chip = chips.last().map(|o| o.value.clone());
+
+    rsx! {
+        document::Link { rel: "stylesheet", href: asset!("./style.css") }
+        div { class: "cyber-multi-select",
+            PopoverRoot {
+                open: open(),
+                on_open_change: move |is_open: bool| open.set(is_open),
+                div {
+                    role: "combobox",
+                    tabindex: "0",
+                    "aria-haspopup": "listbox",
+                    "aria-expanded": open(),
+                    onclick: move |_| open.set(!open()),
+                    onblur: move |_| open.set(false),
+                    onkeydown: move |evt: KeyboardEvent| match evt.key() {
+                        Key::ArrowDown => {
+                            evt.prevent_default();
+                            if open() && option_count > 0 {
+                                highlighted.set((active + 1) % option_count);
+                            }
+                            open.set(true);
+                        }
+                        Key::ArrowUp => {
+                            evt.prevent_default();
+                            if open() && option_count > 0 {
+                                highlighted.set((active + option_count - 1) % option_count);
+                            }
+                            open.set(true);
+                        }
+                        Key::Enter => {
+                            evt.prevent_default();
+                            if open() {
+                                toggle_key(active);
+                            } else {
+                                open.set(true);
+                            }
+                        }
+                        Key::Character(ref c) if c == " " => {
+                            evt.prevent_default();
+                            if open() {
+                                toggle_key(active);
+                            } else {
+                                open.set(true);
+                            }
+                        }
+                        Key::Escape => open.set(false),
+                        Key::Backspace => {
+                            if let Some(value) = &last_chip {
+                                let next = remove_selection(&selected.read(), value);
+                                update(next);
+                            }
+                        }
+                        _ => {}
+                    },
+                    ..merged,
+                    if chips.is_empty() {
+                        span { class: "cyber-multi-select-placeholder", "{placeholder}" }
+                    }
+                    for option in chips.iter() {
+                        {
+                            let value = option.value.clone();
+                            rsx! {
+                                span { key: "{option.label}", class: "cyber-multi-select-chip",
+                                    "{option.label}"
+                                    button {
+                                        r#type: "button",
+                                        class: "cyber-multi-select-chip-remove",
+                                        tabindex: "-1",
+                                        "aria-label": "Remove {option.label}",
+                                        onmousedown: move |evt: MouseEvent| evt.prevent_default(),
+                                        onclick: move |evt: MouseEvent| {
+                                            evt.stop_propagation();
+                                            let next = remove_selection(&selected.read(), &value);
+                                            update(next);
+                                        },
+                                        "×"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                PopoverContent { class: "cyber-popover-content cyber-multi-select-content",
+                    ul {
+                        class: "cyber-multi-select-list",
+                        role: "listbox",
+                        "aria-multiselectable": "true",
+                        for (i, option) in options.iter().enumerate() {
+                            {
+                                let mut toggle = toggle.clone();
+                                let is_selected = selected.read().contains(&option.value);
+                                rsx! {
+                                    li {
+                                        key: "{i}",
+                                        class: "cyber-multi-select-item",
+                                        role: "option",
+                                        "aria-selected": is_selected,
+                                        "data-highlighted": i == active,
+                                        // mousedown keeps focus on the trigger so blur doesn't close first
+                                        onmousedown: move |evt: MouseEvent| {
+                                            evt.prevent_default();
+                                            toggle(i);
+                                        },
+                                        onmouseenter: move |_| highlighted.set(i),
+                                        Checkbox {
+                                            checked: Some(if is_selected { CheckboxState::Checked } else { CheckboxState::Unchecked }),
+                                            tabindex: "-1",
+                                            aria_hidden: "true",
+                                            CheckboxIndicator {}
+                                        }
+                                        "{option.label}"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> Vec<MultiSelectOption<&'static str>> {
+        vec![
+            MultiSelectOption::new("active", "Active"),
+            MultiSelectOption::new("draft", "Draft"),
+            MultiSelectOption::new("archived", "Archived"),
+        ]
+    }
+
+    #[test]
+    fn toggle_adds_missing_value() {
+        let next = toggle_selection(&HashSet::new(), &"draft");
+        assert_eq!(next, HashSet::from(["draft"]));
+    }
+
+    #[test]
+    fn toggle_removes_present_value() {
+        let selected = HashSet::from(["active", "draft"]);
+        assert_eq!(
+            toggle_selection(&selected, &"active"),
+            HashSet::from(["draft"])
+        );
+    }
+
+    #[test]
+    fn remove_ignores_missing_value() {
+        let selected = HashSet::from(["active"]);
+        assert_eq!(remove_selection(&selected, &"archived"), selected);
+        assert!(remove_selection(&selected, &"active").is_empty());
+    }
+
+    #[test]
+    fn selected_options_follow_option_order() {
+        let options = options();
+        let selected = HashSet::from(["archived", "active"]);
+        let labels: Vec<_> = selected_options(&options, &selected)
+            .into_iter()
+            .map(|o| o.label.as_str())
+            .collect();
+        assert_eq!(labels, vec!["Active", "Archived"]);
+    }
+}
@@ -0,0 +1,190 @@
+use dioxus::prelude::*;
+
+use crate::components::button::{Button, ButtonVariant};
+
+/// One slot in the pagination control: a page number or a gap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageItem {
+    Page(usize),
+    Ellipsis,
+}
+
+/// Compute the page numbers to show for `current` (1-based) of `total` pages.
+///
+/// Always shows the first and last page plus `siblings` pages on each side of
+/// the current one, collapsing the rest into ellipses, e.g. `1 … 4 5 6 … 20`.
+/// The number of slots stays constant while paging so the buttons don't jump.
+pub fn page_window(current: usize, total: usize, siblings: usize) -> Vec<PageItem> {
+    // first + last + current + siblings + two ellipses
+    let slots = 2 * siblings + 5;
+    if total <= slots {
+        return (1..=total).map(PageItem::Page).collect();
+    }
+
+    let current = current.clamp(1, total);
+    let left = current.saturating_sub(siblings).max(1);
+    let right = (current + siblings).min(total);
+    let show_left_gap = left > 3;
+    let show_right_gap = right + 2 < total;
+    // Pages shown on the non-collapsed side when only one gap is present
+    let edge = slots - 2;
+
+    let mut items = Vec::with_capacity(slots);
+    match (show_left_gap, show_right_gap) {
+        (false, _) => {
+            items.extend((1..=edge).map(PageItem::Page));
+            items.push(PageItem::Ellipsis);
+            items.push(PageItem::Page(total));
+        }
+        (true, false) => {
+            items.push(PageItem::Page(1));
+            items.push(PageItem::Ellipsis);
+            items.extend((total + 1 - edge..=total).map(PageItem::Page));
+        }
+        (true, true) => {
+            items.push(PageItem::Page(1));
+            items.push(PageItem::Ellipsis);
+            items.extend((left..=right).map(PageItem::Page));
+            items.push(PageItem::Ellipsis);
+            items.push(PageItem::Page(total));
+        }
+    }
+    items
+}
+
+/// Page navigation with previous/next buttons and a windowed set of page numbers.
+///
+/// Pages are 1-based. Renders nothing when there is only one page.
+#[component]
+pub fn Pagination(
+    current_page: usize,
+    total_pages: usize,
+    on_page_change: EventHandler<usize>,
+    /// Pages shown on each side of the current page.
+    #[props(default = 1)]
+    siblings: usize,
+    #[props(extends = GlobalAttributes)] attributes: Vec<Attribute>,
+) -> Element {
+    if total_pages <= 1 {
+        return rsx! {};
+    }
+
+    let current = current_page.clamp(1, total_pages);
+    let base = vec![
+        Attribute::new("class", "pagination", None, false),
+        Attribute::new("aria-label", "Pagination", None, false),
+    ];
+    let merged = dioxus_primitives::merge_attributes(vec![base, attributes]);
+
+    rsx! {
+        document::Link { rel: "stylesheet", href: asset!("./style.css") }
+        nav {
+            ..merged,
+            Button {
+                variant: ButtonVariant::Outline,
+                class: "pagination-prev",
+                disabled: current == 1,
+                aria_label: "Previous page",
+                onclick: move |_| on_page_change.call(current - 1),
+                "\u{2039} Prev"
+            }
+            for (i, item) in page_window(current, total_pages, siblings).into_iter().enumerate() {
+                {match item {
+                    PageItem::Page(page) => rsx! {
+                        Button {
+                            key: "page-{page}",
+                            variant: if page == current { ButtonVariant::Primary } else { ButtonVariant::Ghost },
+                            class: "pagination-page",
+                            aria_current: if page == current { "page" } else { "false" },
+                            onclick: move |_| {
+                                if page != current {
+                                    on_page_change.call(page);
+                                }
+                            },
+                            "{page}"
+                        }
+                    },
+                    PageItem::Ellipsis => rsx! {
+                        span {
+                            key: "gap-{i}",
+                            class: "pagination-ellipsis",
+                            "aria-hidden": "true",
+                            "\u{2026}"
+                        }
+                    },
+                }}
+            }
+            Button {
+                variant: ButtonVariant::Outline,
+                class: "pagination-next",
+                disabled: current == total_pages,
+                aria_label: "Next page",
+                onclick: move |_| on_page_change.call(current + 1),
+                "Next \u{203A}"
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use PageItem::{Ellipsis, Page};
+
+    #[test]
+    fn few_pages_are_all_shown() {
+        assert_eq!(page_window(2, 5, 1), (1..=5).map(Page).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn middle_page_has_gaps_on_both_sides() {
+        assert_eq!(
+            page_window(5, 20, 1),
+            vec![
+                Page(1),
+                Ellipsis,
+                Page(4),
+                Page(5),
+                Page(6),
+                Ellipsis,
+                Page(20)
+            ]
+        );
+    }
+
+    #[test]
+    fn edges_keep_a_constant_width() {
+        assert_eq!(
+            page_window(1, 20, 1),
+            vec![
+                Page(1),
+                Page(2),
+                Page(3),
+                Page(4),
+                Page(5),
+                Ellipsis,
+                Page(20)
+            ]
+        );
+        assert_eq!(
+            page_window(20, 20, 1),
+            vec![
+                Page(1),
+                Ellipsis,
+                Page(16),
+                Page(17),
+                Page(18),
+                Page(19),
+                Page(20)
+            ]
+        );
+    }
+
+    #[test]
+    fn single_page_renders_nothing() {
+        let html = dioxus_ssr::render_element(rsx! {
+            Pagination { current_page: 1, total_pages: 1, on_page_change: |_| {} }
+        });
+        assert!(!html.contains("pagination"));
+    }
+}
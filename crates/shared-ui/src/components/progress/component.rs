@@ -23,3 +23,118 @@ pub fn ProgressIndicator(mut props: prim::ProgressIndicatorProps) -> Element {
         prim::ProgressIndicator { ..props }
     }
 }
+
+/// Where a [`TopProgressBar`] is in its cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressPhase {
+    /// Nothing loading; the bar is hidden.
+    Idle,
+    /// Work is pending; the bar creeps towards the end.
+    Loading,
+    /// Work just settled; the bar fills and fades out.
+    Complete,
+}
+
+impl ProgressPhase {
+    /// The phase after the pending state changes to `active`.
+    pub fn next(self, active: bool) -> Self {
+        match (self, active) {
+            (_, true) => ProgressPhase::Loading,
+            (ProgressPhase::Loading, false) => ProgressPhase::Complete,
+            (phase, false) => phase,
+        }
+    }
+
+    fn class(&self) -> &'static str {
+        match self {
+            ProgressPhase::Idle => "idle",
+            ProgressPhase::Loading => "loading",
+            ProgressPhase::Complete => "complete",
+        }
+    }
+}
+
+/// Thin bar pinned to the top of the viewport that animates while `active`
+/// (e.g. a route is waiting on data) and completes once it turns false.
+#[component]
+pub fn TopProgressBar(
+    active: bool,
+    #[props(extends = GlobalAttributes)] attributes: Vec<Attribute>,
+) -> Element {
+    let mut phase = use_signal(|| ProgressPhase::Idle.next(active));
+    use_effect(use_reactive!(|active| {
+        let next = phase.peek().next(active);
+        if next != *phase.peek() {
+            phase.set(next);
+        }
+    }));
+    let shown = if active {
+        ProgressPhase::Loading
+    } else {
+        phase()
+    };
+
+    let base = vec![Attribute::new("class", "top-progress", None, false)];
+    let merged = dioxus_primitives::merge_attributes(vec![base, attributes]);
+
+    rsx! {
+        document::Link { rel: "stylesheet", href: asset!("./style.css") }
+        div {
+            role: "progressbar",
+            aria_label: "Loading",
+            aria_busy: active,
+            aria_hidden: shown == ProgressPhase::Idle,
+            "data-state": shown.class(),
+            ..merged,
+            div { class: "top-progress-bar" }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(active: bool) -> String {
+        dioxus_ssr::render_element(rsx! {
+            TopProgressBar { active }
+        })
+    }
+
+    /// Declarations of the stylesheet rule for `selector`.
+    fn rule<'a>(css: &'a str, selector: &str) -> &'a str {
+        let start = css
+            .find(&format!("{selector} {{"))
+            .unwrap_or_else(|| panic!("no rule for {selector}"));
+        let body = &css[start..];
+        &body[..body.find('}').unwrap()]
+    }
+
+    #[test]
+    fn shows_while_pending_and_hides_when_settled() {
+        let pending = render(true);
+        assert!(pending.contains(r#"data-state="loading""#), "{pending}");
+        assert!(pending.contains(r#"aria-busy="true""#), "{pending}");
+
+        let settled = render(false);
+        assert!(settled.contains(r#"data-state="idle""#), "{settled}");
+        assert!(settled.contains(r#"aria-hidden="true""#), "{settled}");
+
+        let css = include_str!("style.css");
+        assert!(rule(css, r#".top-progress[data-state="idle"]"#).contains("opacity: 0"));
+        assert!(rule(css, r#".top-progress[data-state="complete"]"#).contains("animation"));
+    }
+
+    #[test]
+    fn a_settled_navigation_completes_before_going_idle() {
+        let navigating = ProgressPhase::Idle.next(true);
+        assert_eq!(navigating, ProgressPhase::Loading);
+        assert_eq!(navigating.next(true), ProgressPhase::Loading);
+
+        let settled = navigating.next(false);
+        assert_eq!(settled, ProgressPhase::Complete);
+        assert_eq!(settled.next(false), ProgressPhase::Complete);
+        assert_eq!(settled.next(true), ProgressPhase::Loading);
+        assert_eq!(ProgressPhase::Idle.next(false), ProgressPhase::Idle);
+    }
+}
@@ -11,7 +11,9 @@ pub enum SheetSide {
 }
 
 impl SheetSide {
-    fn class(&self) -> &'static str {
+    /// Value of the panel's `data-side` attribute, which the stylesheet
+    /// keys positioning and animation on.
+    pub fn class(&self) -> &'static str {
         match self {
             SheetSide::Top => "top",
             SheetSide::Right => "right",
@@ -19,20 +21,48 @@ impl SheetSide {
             SheetSide::Left => "left",
         }
     }
+
+    /// Keyframes the panel slides in with (defined in the theme).
+    pub fn animation(&self) -> &'static str {
+        match self {
+            SheetSide::Top => "slide-in-top",
+            SheetSide::Right => "slide-in-right",
+            SheetSide::Bottom => "slide-in-bottom",
+            SheetSide::Left => "slide-in-left",
+        }
+    }
 }
 
 /// A cyberpunk-styled sliding panel overlay.
+///
+/// The panel is a modal dialog: it takes focus when opened, Tab and
+/// Shift+Tab stay inside it, and Escape or a click on the overlay calls
+/// `on_close`. `mobile_side` slides it in from a different edge on small
+/// screens, e.g. a bottom sheet on phones.
 #[component]
 pub fn Sheet(
     open: bool,
     on_close: EventHandler<()>,
     #[props(default)] side: SheetSide,
+    #[props(default)] mobile_side: Option<SheetSide>,
     children: Element,
 ) -> Element {
+    let mut panel: Signal<Option<std::rc::Rc<MountedData>>> = use_signal(|| None);
+
     if !open {
         return rsx! {};
     }
 
+    // Focus guards on either side of the content send focus back to the
+    // panel, so tabbing never leaves the sheet.
+    let refocus = move |_: FocusEvent| {
+        if let Some(panel) = panel() {
+            spawn(async move {
+                let _ = panel.set_focus(true).await;
+            });
+        }
+    };
+
     rsx! {
         document::Link { rel: "stylesheet", href: asset!("./style.css") }
         div {
@@ -41,9 +71,28 @@ pub fn Sheet(
             onclick: move |_| on_close.call(()),
             div {
                 class: "sheet-panel",
+                role: "dialog",
+                "aria-modal": "true",
+                tabindex: "-1",
                 "data-side": side.class(),
+                "data-mobile-side": mobile_side.map(|side| side.class()),
+                onmounted: move |evt: MountedEvent| {
+                    let element = evt.data();
+                    panel.set(Some(element.clone()));
+                    spawn(async move {
+                        let _ = element.set_focus(true).await;
+                    });
+                },
+                onkeydown: move |evt: KeyboardEvent| {
+                    if evt.key() == Key::Escape {
+                        evt.stop_propagation();
+                        on_close.call(());
+                    }
+                },
                 onclick: move |evt| evt.stop_propagation(),
+                div { class: "sheet-focus-guard", tabindex: "0", onfocus: refocus }
                 {children}
+                div { class: "sheet-focus-guard", tabindex: "0", onfocus: refocus }
             }
         }
     }
@@ -147,3 +196,82 @@ pub fn SheetClose(on_close: EventHandler<()>) -> Element {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIDES: [SheetSide; 4] = [
+        SheetSide::Top,
+        SheetSide::Right,
+        SheetSide::Bottom,
+        SheetSide::Left,
+    ];
+
+    fn render(side: SheetSide) -> String {
+        dioxus_ssr::render_element(rsx! {
+            Sheet { open: true, on_close: |_| {}, side, SheetContent { "body" } }
+        })
+    }
+
+    /// Declarations of the stylesheet rule for `selector`.
+    fn rule<'a>(css: &'a str, selector: &str) -> &'a str {
+        let start = css
+            .find(&format!("{selector} {{"))
+            .unwrap_or_else(|| panic!("no rule for {selector}"));
+        let body = &css[start..];
+        &body[..body.find('}').unwrap()]
+    }
+
+    #[test]
+    fn panel_is_marked_with_its_side() {
+        for side in SIDES {
+            let html = render(side);
+            assert!(
+                html.contains(&format!("data-side=\"{}\"", side.class())),
+                "{html}"
+            );
+            assert!(!html.contains("data-mobile-side"), "{html}");
+            assert!(html.contains(r#"role="dialog""#));
+            assert!(html.contains(r#"aria-modal="true""#));
+        }
+
+        let html = dioxus_ssr::render_element(rsx! {
+            Sheet {
+                open: true,
+                on_close: |_| {},
+                side: SheetSide::Right,
+                mobile_side: SheetSide::Bottom,
+                SheetContent { "body" }
+            }
+        });
+        assert!(html.contains(r#"data-side="right""#), "{html}");
+        assert!(html.contains(r#"data-mobile-side="bottom""#), "{html}");
+    }
+
+    #[test]
+    fn each_side_slides_in_from_its_edge() {
+        let css = include_str!("style.css");
+        for side in SIDES {
+            let selector = format!(".sheet-panel[data-side=\"{}\"]", side.class());
+            let declarations = rule(css, &selector);
+            assert!(
+                declarations.contains(&format!("animation: {} ", side.animation())),
+                "{selector}: {declarations}"
+            );
+            let mobile = format!(".sheet-panel[data-mobile-side=\"{}\"]", side.class());
+            assert!(
+                rule(css, &mobile).contains(&format!("animation: {} ", side.animation())),
+                "{mobile}"
+            );
+        }
+    }
+
+    #[test]
+    fn closed_sheet_renders_nothing() {
+        let html = dioxus_ssr::render_element(rsx! {
+            Sheet { open: false, on_close: |_| {}, SheetContent { "body" } }
+        });
+        assert!(!html.contains("sheet-panel"));
+    }
+}
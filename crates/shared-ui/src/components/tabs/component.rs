@@ -1,47 +1,370 @@
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+
 use dioxus::prelude::*;
-use dioxus_primitives::tabs as prim;
 
+/// Shared state between a [`Tabs`] root and its triggers and panels.
+#[derive(Clone, Copy)]
+struct TabsContext {
+    active: Signal<String>,
+    on_value_change: Option<EventHandler<String>>,
+    id: Signal<String>,
+    horizontal: bool,
+    disabled: bool,
+    /// Trigger value and disabled flag, by trigger index.
+    triggers: Signal<BTreeMap<usize, (String, bool)>>,
+    /// Mounted trigger buttons, for moving focus with the keyboard.
+    mounted: Signal<HashMap<String, Rc<MountedData>>>,
+}
+
+impl TabsContext {
+    fn trigger_id(&self, value: &str) -> String {
+        format!("{}-tab-{}", self.id.read(), value)
+    }
+
+    fn panel_id(&self, value: &str) -> String {
+        format!("{}-panel-{}", self.id.read(), value)
+    }
+
+    fn select(&mut self, value: String) {
+        if *self.active.read() == value {
+            return;
+        }
+        self.active.set(value.clone());
+        if let Some(handler) = &self.on_value_change {
+            handler.call(value);
+        }
+    }
+}
+
+/// Where a key press moves focus within a tab list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TabMove {
+    Next,
+    Previous,
+    First,
+    Last,
+}
+
+impl TabMove {
+    /// The move for `key`, using the arrows along the list's orientation.
+    pub fn from_key(key: &Key, horizontal: bool) -> Option<Self> {
+        match (key, horizontal) {
+            (Key::ArrowRight, true) | (Key::ArrowDown, false) => Some(Self::Next),
+            (Key::ArrowLeft, true) | (Key::ArrowUp, false) => Some(Self::Previous),
+            (Key::Home, _) => Some(Self::First),
+            (Key::End, _) => Some(Self::Last),
+            _ => None,
+        }
+    }
+}
+
+/// The tab a keyboard move lands on, skipping disabled tabs.
+///
+/// `tabs` holds each tab's value and whether it is disabled, in display
+/// order. Next and previous wrap around at the ends.
+pub fn tab_after(tabs: &[(String, bool)], current: &str, movement: TabMove) -> Option<String> {
+    let enabled: Vec<&String> = tabs
+        .iter()
+        .filter(|(_, disabled)| !disabled)
+        .map(|(value, _)| value)
+        .collect();
+    let count = enabled.len();
+    if count == 0 {
+        return None;
+    }
+    let position = enabled.iter().position(|value| *value == current);
+    let index = match (movement, position) {
+        (TabMove::First, _) | (TabMove::Next, None) => 0,
+        (TabMove::Last, _) | (TabMove::Previous, None) => count - 1,
+        (TabMove::Next, Some(i)) => (i + 1) % count,
+        (TabMove::Previous, Some(i)) => (i + count - 1) % count,
+    };
+    Some(enabled[index].clone())
+}
+
+/// A set of tabs, each showing one [`TabContent`] panel.
+///
+/// Follows the WAI-ARIA tabs pattern: only the active trigger is in the tab
+/// order, arrow keys (along the orientation) and Home/End move between
+/// triggers and select them, and the active panel is the only one focusable.
 #[component]
-pub fn Tabs(mut props: prim::TabsProps) -> Element {
-    props
-        .attributes
-        .push(Attribute::new("class", "tabs", None, false));
+pub fn Tabs(
+    /// Tab shown first.
+    #[props(default)]
+    default_value: String,
+    /// Controlled active tab; updating it from the parent switches tabs.
+    #[props(default)]
+    value: Option<String>,
+    #[props(default)] on_value_change: Option<EventHandler<String>>,
+    #[props(default = true)] horizontal: bool,
+    #[props(default)] disabled: bool,
+    /// Prefix for the generated trigger and panel ids; give each tab set on a
+    /// page its own.
+    #[props(default = "tabs".to_string())]
+    id_prefix: String,
+    #[props(extends = GlobalAttributes)] attributes: Vec<Attribute>,
+    children: Element,
+) -> Element {
+    let mut active = use_signal(|| value.clone().unwrap_or(default_value));
+    use_effect(use_reactive!(|value| {
+        if let Some(value) = value {
+            active.set(value);
+        }
+    }));
+    let mut tabs_id = use_signal(|| id_prefix.clone());
+    use_effect(use_reactive!(|id_prefix| tabs_id.set(id_prefix)));
+
+    use_context_provider(|| TabsContext {
+        active,
+        on_value_change,
+        id: tabs_id,
+        horizontal,
+        disabled,
+        triggers: Signal::new(BTreeMap::new()),
+        mounted: Signal::new(HashMap::new()),
+    });
+
+    let base = vec![Attribute::new("class", "tabs", None, false)];
+    let merged = dioxus_primitives::merge_attributes(vec![base, attributes]);
 
     rsx! {
         document::Link { rel: "stylesheet", href: asset!("./style.css") }
-        prim::Tabs { ..props }
+        div {
+            "data-orientation": if horizontal { "horizontal" } else { "vertical" },
+            "data-disabled": disabled,
+            ..merged,
+            {children}
+        }
     }
 }
 
 #[component]
-pub fn TabList(mut props: prim::TabListProps) -> Element {
-    props
-        .attributes
-        .push(Attribute::new("class", "tab-list", None, false));
+pub fn TabList(
+    #[props(extends = GlobalAttributes)] attributes: Vec<Attribute>,
+    children: Element,
+) -> Element {
+    let ctx: TabsContext = use_context();
+    let base = vec![Attribute::new("class", "tab-list", None, false)];
+    let merged = dioxus_primitives::merge_attributes(vec![base, attributes]);
 
     rsx! {
-        prim::TabList { ..props }
+        div {
+            role: "tablist",
+            "aria-orientation": if ctx.horizontal { "horizontal" } else { "vertical" },
+            ..merged,
+            {children}
+        }
     }
 }
 
 #[component]
-pub fn TabTrigger(mut props: prim::TabTriggerProps) -> Element {
-    if props.class.is_none() {
-        props.class = Some("tab-trigger".to_string());
-    }
+pub fn TabTrigger(
+    value: String,
+    /// Position of the trigger in its list, used for keyboard order.
+    index: usize,
+    #[props(default)] disabled: bool,
+    #[props(extends = GlobalAttributes)] attributes: Vec<Attribute>,
+    children: Element,
+) -> Element {
+    let mut ctx: TabsContext = use_context();
+
+    use_effect(use_reactive!(|value, index, disabled| {
+        ctx.triggers.write().insert(index, (value, disabled));
+    }));
+    use_drop(move || {
+        ctx.triggers.write().remove(&index);
+    });
+
+    let disabled = disabled || ctx.disabled;
+    let selected = *ctx.active.read() == value;
+    let base = vec![Attribute::new("class", "tab-trigger", None, false)];
+    let merged = dioxus_primitives::merge_attributes(vec![base, attributes]);
+
+    let select_value = value.clone();
+    let key_value = value.clone();
+    let mount_value = value.clone();
 
     rsx! {
-        prim::TabTrigger { ..props }
+        button {
+            r#type: "button",
+            role: "tab",
+            id: ctx.trigger_id(&value),
+            "aria-selected": selected,
+            "aria-controls": ctx.panel_id(&value),
+            tabindex: if selected { "0" } else { "-1" },
+            disabled,
+            "data-state": if selected { "active" } else { "inactive" },
+            "data-disabled": disabled,
+            onmounted: move |evt: MountedEvent| {
+                ctx.mounted.write().insert(mount_value.clone(), evt.data());
+            },
+            onclick: move |_| {
+                if !disabled {
+                    ctx.select(select_value.clone());
+                }
+            },
+            onkeydown: move |evt: KeyboardEvent| {
+                let Some(movement) = TabMove::from_key(&evt.key(), ctx.horizontal) else {
+                    return;
+                };
+                evt.prevent_default();
+                let tabs: Vec<(String, bool)> = ctx.triggers.read().values().cloned().collect();
+                if let Some(next) = tab_after(&tabs, &key_value, movement) {
+                    if let Some(element) = ctx.mounted.read().get(&next).cloned() {
+                        spawn(async move {
+                            let _ = element.set_focus(true).await;
+                        });
+                    }
+                    ctx.select(next);
+                }
+            },
+            ..merged,
+            {children}
+        }
     }
 }
 
 #[component]
-pub fn TabContent(mut props: prim::TabContentProps) -> Element {
-    if props.class.is_none() {
-        props.class = Some("tab-content".to_string());
-    }
+pub fn TabContent(
+    value: String,
+    #[props(extends = GlobalAttributes)] attributes: Vec<Attribute>,
+    children: Element,
+) -> Element {
+    let ctx: TabsContext = use_context();
+    let selected = *ctx.active.read() == value;
+    let base = vec![Attribute::new("class", "tab-content", None, false)];
+    let merged = dioxus_primitives::merge_attributes(vec![base, attributes]);
 
     rsx! {
-        prim::TabContent { ..props }
+        div {
+            role: "tabpanel",
+            id: ctx.panel_id(&value),
+            "aria-labelledby": ctx.trigger_id(&value),
+            tabindex: if selected { "0" },
+            hidden: !selected,
+            "data-state": if selected { "active" } else { "inactive" },
+            ..merged,
+            if selected {
+                {children}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tabs(values: &[(&str, bool)]) -> Vec<(String, bool)> {
+        values
+            .iter()
+            .map(|(value, disabled)| (value.to_string(), *disabled))
+            .collect()
+    }
+
+    #[test]
+    fn arrows_follow_the_orientation() {
+        assert_eq!(
+            TabMove::from_key(&Key::ArrowRight, true),
+            Some(TabMove::Next)
+        );
+        assert_eq!(TabMove::from_key(&Key::ArrowDown, true), None);
+        assert_eq!(
+            TabMove::from_key(&Key::ArrowUp, false),
+            Some(TabMove::Previous)
+        );
+        assert_eq!(TabMove::from_key(&Key::End, false), Some(TabMove::Last));
+    }
+
+    #[test]
+    fn navigation_wraps_and_skips_disabled_tabs() {
+        let tabs = tabs(&[
+            ("all", false),
+            ("active", false),
+            ("draft", true),
+            ("archived", false),
+        ]);
+        let after = |current, movement| tab_after(&tabs, current, movement);
+
+        assert_eq!(after("active", TabMove::Next).as_deref(), Some("archived"));
+        assert_eq!(after("archived", TabMove::Next).as_deref(), Some("all"));
+        assert_eq!(after("all", TabMove::Previous).as_deref(), Some("archived"));
+        assert_eq!(after("active", TabMove::First).as_deref(), Some("all"));
+        assert_eq!(after("all", TabMove::Last).as_deref(), Some("archived"));
+        assert_eq!(tab_after(&[], "all", TabMove::Next), None);
+    }
+
+    #[component]
+    fn Harness(value: String) -> Element {
+        rsx! {
+            Tabs { value: Some(value), id_prefix: "status".to_string(),
+                TabList {
+                    TabTrigger { value: "all".to_string(), index: 0usize, "All" }
+                    TabTrigger { value: "active".to_string(), index: 1usize, "Active" }
+                    TabTrigger { value: "archived".to_string(), index: 2usize, "Archived" }
+                }
+                TabContent { value: "all".to_string(), "every product" }
+                TabContent { value: "active".to_string(), "active products" }
+                TabContent { value: "archived".to_string(), "archived products" }
+            }
+        }
+    }
+
+    fn render(value: &str) -> String {
+        let mut dom = VirtualDom::new_with_props(
+            Harness,
+            HarnessProps {
+                value: value.to_string(),
+            },
+        );
+        dom.rebuild_in_place();
+        dioxus_ssr::render(&dom)
+    }
+
+    /// The opening tag of the element with `id`.
+    fn tag<'a>(html: &'a str, id: &str) -> &'a str {
+        let id = format!("id=\"{id}\"");
+        let at = html
+            .find(&id)
+            .unwrap_or_else(|| panic!("no element with {id}"));
+        let start = html[..at].rfind('<').unwrap();
+        let end = at + html[at..].find('>').unwrap();
+        &html[start..=end]
+    }
+
+    #[test]
+    fn arrow_navigation_moves_selection_and_aria_state() {
+        let order = tabs(&[("all", false), ("active", false), ("archived", false)]);
+        let next = tab_after(&order, "all", TabMove::Next).unwrap();
+        let html = render(&next);
+
+        assert!(html.contains(r#"role="tablist""#));
+        let active = tag(&html, "status-tab-active");
+        assert!(active.contains(r#"aria-selected="true""#), "{active}");
+        assert!(active.contains(r#"aria-controls="status-panel-active""#));
+        assert!(active.contains(r#"tabindex="0""#));
+
+        let all = tag(&html, "status-tab-all");
+        assert!(all.contains(r#"aria-selected="false""#), "{all}");
+        assert!(all.contains(r#"tabindex="-1""#));
+
+        let panel = tag(&html, "status-panel-active");
+        assert!(panel.contains(r#"role="tabpanel""#));
+        assert!(panel.contains(r#"aria-labelledby="status-tab-active""#));
+        assert!(panel.contains(r#"tabindex="0""#));
+        assert!(html.contains("active products"));
+    }
+
+    #[test]
+    fn only_the_active_panel_is_focusable() {
+        let html = render("archived");
+        for hidden in ["status-panel-all", "status-panel-active"] {
+            let panel = tag(&html, hidden);
+            assert!(!panel.contains("tabindex"), "{panel}");
+            assert!(panel.contains("hidden"), "{panel}");
+        }
+        assert!(!html.contains("every product"));
+        assert!(html.contains("archived products"));
     }
 }
@@ -1,23 +1,408 @@
+use std::time::Duration;
+
 use dioxus::prelude::*;
-use dioxus_primitives::toast as prim;
 
-pub use dioxus_primitives::toast::{consume_toast, use_toast, ToastOptions, ToastType, Toasts};
+/// How long a toast stays up unless its options say otherwise.
+pub const DEFAULT_TOAST_DURATION: Duration = Duration::from_secs(5);
+
+/// The kind of message a toast carries, which sets its colour.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ToastType {
+    Success,
+    Error,
+    Warning,
+    #[default]
+    Info,
+}
+
+impl ToastType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ToastType::Success => "success",
+            ToastType::Error => "error",
+            ToastType::Warning => "warning",
+            ToastType::Info => "info",
+        }
+    }
+}
+
+/// The screen corner or edge a toast stacks at.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ToastPosition {
+    TopLeft,
+    TopCenter,
+    TopRight,
+    BottomLeft,
+    BottomCenter,
+    #[default]
+    BottomRight,
+}
+
+impl ToastPosition {
+    pub fn all() -> &'static [ToastPosition] {
+        &[
+            ToastPosition::TopLeft,
+            ToastPosition::TopCenter,
+            ToastPosition::TopRight,
+            ToastPosition::BottomLeft,
+            ToastPosition::BottomCenter,
+            ToastPosition::BottomRight,
+        ]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ToastPosition::TopLeft => "top-left",
+            ToastPosition::TopCenter => "top-center",
+            ToastPosition::TopRight => "top-right",
+            ToastPosition::BottomLeft => "bottom-left",
+            ToastPosition::BottomCenter => "bottom-center",
+            ToastPosition::BottomRight => "bottom-right",
+        }
+    }
+}
+
+/// A button on a toast, e.g. "Undo" after a delete. Clicking it also
+/// dismisses the toast.
+#[derive(Clone, PartialEq)]
+pub struct ToastAction {
+    pub label: String,
+    pub on_click: Callback<()>,
+}
+
+impl std::fmt::Debug for ToastAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ToastAction")
+            .field("label", &self.label)
+            .finish_non_exhaustive()
+    }
+}
+
+/// How a toast is shown, built up from [`ToastOptions::new`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ToastOptions {
+    pub description: Option<String>,
+    /// How long until the toast dismisses itself; `None` keeps it until the
+    /// user closes it.
+    pub duration: Option<Duration>,
+    /// Where to show the toast; `None` uses the [`ToastProvider`]'s position.
+    pub position: Option<ToastPosition>,
+    pub action: Option<ToastAction>,
+}
+
+impl Default for ToastOptions {
+    fn default() -> Self {
+        Self {
+            description: None,
+            duration: Some(DEFAULT_TOAST_DURATION),
+            position: None,
+            action: None,
+        }
+    }
+}
+
+impl ToastOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Secondary text under the title.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    /// Keep the toast until the user closes it.
+    pub fn permanent(mut self) -> Self {
+        self.duration = None;
+        self
+    }
+
+    pub fn position(mut self, position: ToastPosition) -> Self {
+        self.position = Some(position);
+        self
+    }
+
+    /// Add a button running `on_click`. Must be called from within a
+    /// component or event handler.
+    pub fn action(mut self, label: impl Into<String>, on_click: impl FnMut(()) + 'static) -> Self {
+        self.action = Some(ToastAction {
+            label: label.into(),
+            on_click: Callback::new(on_click),
+        });
+        self
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct ToastEntry {
+    id: u64,
+    kind: ToastType,
+    title: String,
+    options: ToastOptions,
+}
+
+/// Handle for showing toasts, from [`use_toast`] or [`consume_toast`].
+#[derive(Clone, Copy, PartialEq)]
+pub struct Toasts {
+    entries: Signal<Vec<ToastEntry>>,
+    next_id: Signal<u64>,
+}
+
+impl Toasts {
+    /// Show a toast, returning its id for [`Toasts::dismiss`].
+    pub fn show(mut self, title: String, kind: ToastType, options: ToastOptions) -> u64 {
+        let id = *self.next_id.peek();
+        self.next_id.set(id + 1);
+        self.entries.write().push(ToastEntry {
+            id,
+            kind,
+            title,
+            options,
+        });
+        id
+    }
+
+    pub fn success(self, title: String, options: ToastOptions) -> u64 {
+        self.show(title, ToastType::Success, options)
+    }
+
+    pub fn error(self, title: String, options: ToastOptions) -> u64 {
+        self.show(title, ToastType::Error, options)
+    }
+
+    pub fn warning(self, title: String, options: ToastOptions) -> u64 {
+        self.show(title, ToastType::Warning, options)
+    }
+
+    pub fn info(self, title: String, options: ToastOptions) -> u64 {
+        self.show(title, ToastType::Info, options)
+    }
+
+    pub fn dismiss(mut self, id: u64) {
+        self.entries.write().retain(|entry| entry.id != id);
+    }
+}
+
+/// The [`Toasts`] of the enclosing [`ToastProvider`].
+pub fn use_toast() -> Toasts {
+    use_context()
+}
+
+/// Like [`use_toast`], for use outside of hooks such as in event handlers.
+pub fn consume_toast() -> Toasts {
+    consume_context()
+}
+
+/// `entries` grouped by where they show, newest last, keeping the newest
+/// `max_toasts` of each group. Positions without toasts are left out.
+fn stacks(
+    entries: &[ToastEntry],
+    default_position: ToastPosition,
+    max_toasts: usize,
+) -> Vec<(ToastPosition, Vec<ToastEntry>)> {
+    ToastPosition::all()
+        .iter()
+        .filter_map(|&stack| {
+            let in_stack: Vec<_> = entries
+                .iter()
+                .filter(|entry| entry.options.position.unwrap_or(default_position) == stack)
+                .cloned()
+                .collect();
+            let skip = in_stack.len().saturating_sub(max_toasts);
+            (!in_stack.is_empty()).then(|| (stack, in_stack.into_iter().skip(skip).collect()))
+        })
+        .collect()
+}
 
+/// Shows toasts raised anywhere below it, stacked at their position.
+///
+/// `position` is where toasts go when their options don't say; each stack
+/// keeps at most `max_toasts`, dropping the oldest.
 #[component]
-pub fn ToastProvider(props: prim::ToastProviderProps) -> Element {
+pub fn ToastProvider(
+    #[props(default)] position: ToastPosition,
+    #[props(default = 5)] max_toasts: usize,
+    children: Element,
+) -> Element {
+    let toasts = use_context_provider(|| Toasts {
+        entries: Signal::new(Vec::new()),
+        next_id: Signal::new(0),
+    });
+
+    let stacks = stacks(&toasts.entries.read(), position, max_toasts);
+
     rsx! {
         document::Link { rel: "stylesheet", href: asset!("./style.css") }
-        prim::ToastProvider { ..props }
+        {children}
+        for (stack, in_stack) in stacks {
+            div {
+                key: "{stack.as_str()}",
+                class: "toast-container",
+                "data-position": stack.as_str(),
+                role: "region",
+                "aria-label": "Notifications",
+                ol { class: "toast-list",
+                    for entry in in_stack {
+                        li { key: "{entry.id}", class: "toast-item",
+                            Toast { entry, toasts }
+                        }
+                    }
+                }
+            }
+        }
     }
 }
 
 #[component]
-pub fn Toast(mut props: prim::ToastProps) -> Element {
-    props
-        .attributes
-        .push(Attribute::new("class", "cyber-toast", None, false));
+fn Toast(entry: ToastEntry, toasts: Toasts) -> Element {
+    let id = entry.id;
+    let duration = entry.options.duration;
+    use_future(move || async move {
+        if let Some(duration) = duration {
+            futures_timer::Delay::new(duration).await;
+            toasts.dismiss(id);
+        }
+    });
+
+    let role = match entry.kind {
+        ToastType::Error | ToastType::Warning => "alert",
+        ToastType::Success | ToastType::Info => "status",
+    };
 
     rsx! {
-        prim::Toast { ..props }
+        div {
+            class: "toast cyber-toast",
+            role,
+            "data-type": entry.kind.as_str(),
+            div { class: "toast-content",
+                div { class: "toast-title", "{entry.title}" }
+                if let Some(description) = &entry.options.description {
+                    div { class: "toast-description", "{description}" }
+                }
+            }
+            if let Some(action) = entry.options.action.clone() {
+                button {
+                    class: "toast-action",
+                    r#type: "button",
+                    onclick: move |_| {
+                        toasts.dismiss(id);
+                        action.on_click.call(());
+                    },
+                    "{action.label}"
+                }
+            }
+            button {
+                class: "toast-close",
+                r#type: "button",
+                "aria-label": "Dismiss",
+                onclick: move |_| toasts.dismiss(id),
+                "×"
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn options_default_to_a_timed_toast_at_the_provider_position() {
+        let options = ToastOptions::new();
+        assert_eq!(options, ToastOptions::default());
+        assert_eq!(options.duration, Some(DEFAULT_TOAST_DURATION));
+        assert_eq!(options.position, None);
+        assert_eq!(options.description, None);
+        assert!(options.action.is_none());
+        assert_eq!(ToastPosition::default(), ToastPosition::BottomRight);
+    }
+
+    #[test]
+    fn options_builder_sets_each_field() {
+        let options = ToastOptions::new()
+            .description("3 items")
+            .duration(Duration::from_secs(10))
+            .position(ToastPosition::TopCenter);
+        assert_eq!(options.description.as_deref(), Some("3 items"));
+        assert_eq!(options.duration, Some(Duration::from_secs(10)));
+        assert_eq!(options.position, Some(ToastPosition::TopCenter));
+        assert_eq!(options.permanent().duration, None);
+    }
+
+    fn entry(id: u64, position: Option<ToastPosition>) -> ToastEntry {
+        ToastEntry {
+            id,
+            kind: ToastType::Info,
+            title: format!("toast {id}"),
+            options: ToastOptions {
+                position,
+                ..ToastOptions::new()
+            },
+        }
+    }
+
+    #[test]
+    fn stacks_group_by_position_and_keep_the_newest() {
+        let entries = vec![
+            entry(0, None),
+            entry(1, Some(ToastPosition::TopCenter)),
+            entry(2, None),
+            entry(3, Some(ToastPosition::BottomRight)),
+        ];
+        let ids =
+            |stacks: Vec<(ToastPosition, Vec<ToastEntry>)>| -> Vec<(ToastPosition, Vec<u64>)> {
+                stacks
+                    .into_iter()
+                    .map(|(position, stack)| (position, stack.iter().map(|e| e.id).collect()))
+                    .collect()
+            };
+        assert_eq!(
+            ids(stacks(&entries, ToastPosition::BottomRight, 2)),
+            vec![
+                (ToastPosition::TopCenter, vec![1]),
+                (ToastPosition::BottomRight, vec![2, 3]),
+            ]
+        );
+        assert_eq!(
+            ids(stacks(&entries[..1], ToastPosition::TopLeft, 5)),
+            vec![(ToastPosition::TopLeft, vec![0])]
+        );
+    }
+
+    #[component]
+    fn Harness() -> Element {
+        let toasts = use_hook(|| Toasts {
+            entries: Signal::new(Vec::new()),
+            next_id: Signal::new(0),
+        });
+        let entry = ToastEntry {
+            id: 7,
+            kind: ToastType::Success,
+            title: "Product deleted".to_string(),
+            options: ToastOptions::new()
+                .description("Widget")
+                .action("Undo", |_| {}),
+        };
+        rsx! {
+            Toast { entry, toasts }
+        }
+    }
+
+    #[test]
+    fn toast_renders_type_description_and_action() {
+        let mut dom = VirtualDom::new(Harness);
+        dom.rebuild_in_place();
+        let html = dioxus_ssr::render(&dom);
+        assert!(html.contains(r#"data-type="success""#), "{html}");
+        assert!(html.contains(r#"role="status""#));
+        assert!(html.contains("Widget"));
+        assert!(html.contains(r#"class="toast-action""#));
+        assert!(html.contains("Undo"));
     }
 }
@@ -1,15 +1,98 @@
+use std::collections::HashSet;
+
 use dioxus::prelude::*;
 use dioxus_primitives::toggle_group as prim;
 
+/// How many items of a [`ToggleGroup`] can be pressed at once.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ToggleSelection {
+    /// Exactly one item is pressed; pressing it again leaves it on.
+    Single,
+    /// Items toggle independently, and none need be pressed.
+    #[default]
+    Multiple,
+}
+
+/// Items pressed before any interaction. Single mode keeps the lowest of
+/// `default_pressed`, or the first item when it is empty.
+pub fn initial_pressed(
+    selection: ToggleSelection,
+    default_pressed: HashSet<usize>,
+) -> HashSet<usize> {
+    match selection {
+        ToggleSelection::Multiple => default_pressed,
+        ToggleSelection::Single => HashSet::from([default_pressed.into_iter().min().unwrap_or(0)]),
+    }
+}
+
+/// Pressed items after the group reports `next`, given the `current` ones.
+///
+/// In single mode the newly pressed item replaces the current one, and
+/// un-pressing the current item is ignored.
+pub fn resolve_pressed(
+    selection: ToggleSelection,
+    current: &HashSet<usize>,
+    next: HashSet<usize>,
+) -> HashSet<usize> {
+    match selection {
+        ToggleSelection::Multiple => next,
+        ToggleSelection::Single => match next.difference(current).min().or(next.iter().min()) {
+            Some(&index) => HashSet::from([index]),
+            None => current.clone(),
+        },
+    }
+}
+
+/// A row of toggle buttons.
+///
+/// With [`ToggleSelection::Multiple`] (the default) changes are reported
+/// through `on_pressed_change`; with [`ToggleSelection::Single`] through
+/// `on_select`, with the index of the pressed item.
 #[component]
-pub fn ToggleGroup(mut props: prim::ToggleGroupProps) -> Element {
-    props
-        .attributes
-        .push(Attribute::new("class", "toggle-group", None, false));
+pub fn ToggleGroup(
+    #[props(default)] selection: ToggleSelection,
+    #[props(default)] default_pressed: HashSet<usize>,
+    #[props(default)] on_pressed_change: Option<EventHandler<HashSet<usize>>>,
+    #[props(default)] on_select: Option<EventHandler<usize>>,
+    #[props(default)] horizontal: bool,
+    #[props(default)] disabled: bool,
+    #[props(extends = GlobalAttributes)] attributes: Vec<Attribute>,
+    children: Element,
+) -> Element {
+    let mut pressed = use_signal(|| initial_pressed(selection, default_pressed));
+
+    let base = vec![Attribute::new("class", "toggle-group", None, false)];
+    let merged = dioxus_primitives::merge_attributes(vec![base, attributes]);
 
     rsx! {
         document::Link { rel: "stylesheet", href: asset!("./style.css") }
-        prim::ToggleGroup { ..props }
+        prim::ToggleGroup {
+            pressed: Some(pressed()),
+            allow_multiple_pressed: selection == ToggleSelection::Multiple,
+            horizontal,
+            disabled,
+            on_pressed_change: move |next: HashSet<usize>| {
+                let next = resolve_pressed(selection, &pressed.read(), next);
+                if next == *pressed.read() {
+                    return;
+                }
+                pressed.set(next.clone());
+                match selection {
+                    ToggleSelection::Multiple => {
+                        if let Some(handler) = &on_pressed_change {
+                            handler.call(next);
+                        }
+                    }
+                    ToggleSelection::Single => {
+                        if let (Some(handler), Some(&index)) = (&on_select, next.iter().next()) {
+                            handler.call(index);
+                        }
+                    }
+                }
+            },
+            attributes: merged,
+            {children}
+        }
     }
 }
 
@@ -23,3 +106,80 @@ pub fn ToggleGroupItem(mut props: prim::ToggleItemProps) -> Element {
         prim::ToggleItem { ..props }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ToggleSelection::{Multiple, Single};
+
+    #[test]
+    fn single_mode_starts_with_exactly_one_pressed() {
+        assert_eq!(initial_pressed(Single, HashSet::new()), HashSet::from([0]));
+        assert_eq!(
+            initial_pressed(Single, HashSet::from([2, 1])),
+            HashSet::from([1])
+        );
+    }
+
+    #[test]
+    fn single_mode_switches_to_the_pressed_item() {
+        let current = HashSet::from([0]);
+        assert_eq!(
+            resolve_pressed(Single, &current, HashSet::from([1])),
+            HashSet::from([1])
+        );
+        // A group that reports the old item alongside the new one
+        assert_eq!(
+            resolve_pressed(Single, &current, HashSet::from([0, 2])),
+            HashSet::from([2])
+        );
+    }
+
+    #[test]
+    fn single_mode_keeps_the_item_when_it_is_unpressed() {
+        let current = HashSet::from([1]);
+        assert_eq!(resolve_pressed(Single, &current, HashSet::new()), current);
+    }
+
+    #[test]
+    fn multiple_mode_passes_the_set_through() {
+        assert_eq!(initial_pressed(Multiple, HashSet::new()), HashSet::new());
+        let current = HashSet::from([0, 1]);
+        assert_eq!(
+            resolve_pressed(Multiple, &current, HashSet::from([0, 1, 2])),
+            HashSet::from([0, 1, 2])
+        );
+        assert_eq!(
+            resolve_pressed(Multiple, &current, HashSet::new()),
+            HashSet::new()
+        );
+    }
+
+    fn pressed_items(html: &str) -> usize {
+        html.matches("data-state=\"on\"").count()
+    }
+
+    #[test]
+    fn renders_the_pressed_items() {
+        let single = dioxus_ssr::render_element(rsx! {
+            ToggleGroup {
+                selection: ToggleSelection::Single,
+                default_pressed: HashSet::from([1, 2]),
+                ToggleGroupItem { index: 0usize, "Grid" }
+                ToggleGroupItem { index: 1usize, "List" }
+                ToggleGroupItem { index: 2usize, "Table" }
+            }
+        });
+        assert_eq!(pressed_items(&single), 1);
+
+        let multiple = dioxus_ssr::render_element(rsx! {
+            ToggleGroup {
+                default_pressed: HashSet::from([1, 2]),
+                ToggleGroupItem { index: 0usize, "Bold" }
+                ToggleGroupItem { index: 1usize, "Italic" }
+                ToggleGroupItem { index: 2usize, "Underline" }
+            }
+        });
+        assert_eq!(pressed_items(&multiple), 2);
+    }
+}
@@ -0,0 +1,226 @@
+use dioxus::prelude::*;
+
+use crate::form_state::{FormValues, UseForm};
+
+/// Storage key for the draft saved under `key`.
+fn storage_key(key: &str) -> String {
+    format!("draft:{key}")
+}
+
+/// Stored fields that still exist on the form, to restore into it.
+///
+/// Fields the form no longer has (e.g. after a release renamed one) are
+/// dropped, as are values identical to the current ones.
+pub fn restorable_fields(current: &FormValues, stored: FormValues) -> Vec<(String, String)> {
+    stored
+        .into_iter()
+        .filter(|(field, value)| current.get(field).is_some_and(|v| v != value))
+        .collect()
+}
+
+/// Where drafts are kept between visits: `localStorage` in the browser.
+#[cfg(target_arch = "wasm32")]
+mod store {
+    use dioxus::prelude::*;
+
+    use crate::form_state::FormValues;
+
+    fn js_string(value: &str) -> String {
+        serde_json::to_string(value).unwrap_or_else(|_| "\"\"".to_string())
+    }
+
+    pub async fn load(key: &str) -> Option<FormValues> {
+        let raw = document::eval(&format!(
+            "return localStorage.getItem({});",
+            js_string(&super::storage_key(key))
+        ))
+        .join::<Option<String>>()
+        .await
+        .ok()
+        .flatten()?;
+        serde_json::from_str(&raw).ok()
+    }
+
+    pub fn save(key: &str, values: &FormValues) {
+        let Ok(raw) = serde_json::to_string(values) else {
+            return;
+        };
+        document::eval(&format!(
+            "try {{ localStorage.setItem({}, {}); }} catch(e) {{}}",
+            js_string(&super::storage_key(key)),
+            js_string(&raw)
+        ));
+    }
+
+    pub fn clear(key: &str) {
+        document::eval(&format!(
+            "localStorage.removeItem({});",
+            js_string(&super::storage_key(key))
+        ));
+    }
+}
+
+/// Where drafts are kept between visits: a file per draft in the temp
+/// directory on desktop and mobile.
+#[cfg(not(target_arch = "wasm32"))]
+mod store {
+    use std::path::PathBuf;
+
+    use crate::form_state::FormValues;
+
+    fn path(key: &str) -> PathBuf {
+        let name: String = super::storage_key(key)
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        std::env::temp_dir()
+            .join("shared-ui-drafts")
+            .join(format!("{name}.json"))
+    }
+
+    pub async fn load(key: &str) -> Option<FormValues> {
+        let raw = std::fs::read_to_string(path(key)).ok()?;
+        serde_json::from_str(&raw).ok()
+    }
+
+    pub fn save(key: &str, values: &FormValues) {
+        let path = path(key);
+        let result = serde_json::to_string(values)
+            .map_err(std::io::Error::other)
+            .and_then(|raw| {
+                if let Some(dir) = path.parent() {
+                    std::fs::create_dir_all(dir)?;
+                }
+                std::fs::write(&path, raw)
+            });
+        if let Err(e) = result {
+            dioxus::logger::tracing::warn!(error = %e, "failed to save draft");
+        }
+    }
+
+    pub fn clear(key: &str) {
+        let _ = std::fs::remove_file(path(key));
+    }
+}
+
+pub use store::{clear as clear_draft, load as load_draft, save as save_draft};
+
+/// Handle returned by [`use_draft`].
+#[derive(Clone, Copy, PartialEq)]
+pub struct Draft {
+    key: &'static str,
+}
+
+impl Draft {
+    /// Forget the saved draft, e.g. once the form was submitted.
+    pub fn clear(&self) {
+        clear_draft(self.key);
+    }
+}
+
+/// Keep `form`'s unsaved input as a draft under `key`, so it survives the
+/// user navigating away.
+///
+/// Each time `active` turns true the saved draft is restored into the form,
+/// which is then saved on every edit; reverting all edits deletes it. Call
+/// [`Draft::clear`] after a successful submit. `active` lets one form be
+/// shared between, say, creating and editing records while only the create
+/// flow keeps a draft.
+pub fn use_draft<T: 'static>(
+    key: &'static str,
+    form: UseForm<T>,
+    active: impl Readable<Target = bool> + 'static,
+) -> Draft {
+    let mut restored = use_signal(|| false);
+    let mut form = form;
+
+    use_effect(move || {
+        if !*active.read() {
+            if *restored.peek() {
+                restored.set(false);
+            }
+            return;
+        }
+        if !restored() {
+            spawn(async move {
+                if let Some(stored) = load_draft(key).await {
+                    let current = form.model().values().clone();
+                    for (field, value) in restorable_fields(&current, stored) {
+                        form.set(&field, value);
+                    }
+                }
+                restored.set(true);
+            });
+            return;
+        }
+
+        let model = form.model();
+        if model.is_dirty() {
+            save_draft(key, model.values());
+        } else {
+            clear_draft(key);
+        }
+    });
+
+    Draft { key }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(fields: &[(&str, &str)]) -> FormValues {
+        fields
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn only_known_changed_fields_are_restored() {
+        let current = values(&[("name", ""), ("price", "1.00")]);
+        let stored = values(&[("name", "Widget"), ("price", "1.00"), ("colour", "red")]);
+        assert_eq!(
+            restorable_fields(&current, stored),
+            vec![("name".to_string(), "Widget".to_string())]
+        );
+    }
+
+    #[test]
+    fn draft_is_saved_restored_and_cleared() {
+        let key = format!("test-{}", std::process::id());
+        let draft = values(&[("name", "Half-typed"), ("price", "12.5")]);
+
+        save_draft(&key, &draft);
+        let restored = block_on(load_draft(&key));
+        assert_eq!(restored, Some(draft.clone()));
+
+        save_draft(&key, &values(&[("name", "Edited")]));
+        assert_eq!(
+            block_on(load_draft(&key)),
+            Some(values(&[("name", "Edited")]))
+        );
+
+        clear_draft(&key);
+        assert_eq!(block_on(load_draft(&key)), None);
+    }
+
+    /// The native store never actually awaits, so a single poll completes it.
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        use std::task::{Context, Poll, Waker};
+        let mut future = std::pin::pin!(future);
+        match future
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()))
+        {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("draft store should not block"),
+        }
+    }
+}
@@ -0,0 +1,352 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+
+use dioxus::prelude::*;
+
+/// Per-field error messages, keyed by field name (same shape as
+/// `AppError::field_errors`).
+pub type FieldErrors = HashMap<String, String>;
+
+/// Current form values, keyed by field name.
+pub type FormValues = BTreeMap<String, String>;
+
+/// Form state without any reactivity: values, the values they started from,
+/// touched fields, per-field errors, and submit progress.
+///
+/// [`UseForm`] wraps this in a signal; keeping it plain makes the rules easy
+/// to test.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FormModel {
+    initial: FormValues,
+    values: FormValues,
+    touched: HashSet<String>,
+    errors: FieldErrors,
+    submitting: bool,
+    submit_count: u32,
+}
+
+impl FormModel {
+    pub fn new<K: Into<String>, V: Into<String>>(fields: impl IntoIterator<Item = (K, V)>) -> Self {
+        let values: FormValues = fields
+            .into_iter()
+            .map(|(k, v)| (k.into(), v.into()))
+            .collect();
+        Self {
+            initial: values.clone(),
+            values,
+            ..Default::default()
+        }
+    }
+
+    pub fn values(&self) -> &FormValues {
+        &self.values
+    }
+
+    /// Value of `field`, or an empty string for unknown fields.
+    pub fn value(&self, field: &str) -> &str {
+        self.values.get(field).map(String::as_str).unwrap_or("")
+    }
+
+    /// Update a field and clear its error, since it no longer describes the value.
+    pub fn set_value(&mut self, field: &str, value: impl Into<String>) {
+        self.values.insert(field.to_string(), value.into());
+        self.errors.remove(field);
+    }
+
+    /// Mark a field as visited, typically on blur.
+    pub fn touch(&mut self, field: &str) {
+        self.touched.insert(field.to_string());
+    }
+
+    pub fn is_touched(&self, field: &str) -> bool {
+        self.touched.contains(field)
+    }
+
+    /// Whether `field` differs from the value it started with.
+    pub fn is_field_dirty(&self, field: &str) -> bool {
+        self.values.get(field) != self.initial.get(field)
+    }
+
+    /// Whether any field differs from the values the form started with.
+    pub fn is_dirty(&self) -> bool {
+        self.values != self.initial
+    }
+
+    pub fn error(&self, field: &str) -> Option<&str> {
+        self.errors.get(field).map(String::as_str)
+    }
+
+    pub fn errors(&self) -> &FieldErrors {
+        &self.errors
+    }
+
+    pub fn set_errors(&mut self, errors: FieldErrors) {
+        self.errors = errors;
+    }
+
+    pub fn is_submitting(&self) -> bool {
+        self.submitting
+    }
+
+    /// Number of submit attempts since the last reset.
+    pub fn submit_count(&self) -> u32 {
+        self.submit_count
+    }
+
+    /// Start over from `fields`, e.g. when the form is reopened for another record.
+    pub fn reset<K: Into<String>, V: Into<String>>(
+        &mut self,
+        fields: impl IntoIterator<Item = (K, V)>,
+    ) {
+        *self = Self::new(fields);
+    }
+
+    /// Begin a submit attempt. Returns `false` if one is already in flight.
+    ///
+    /// Every field is marked touched so errors show even on untouched inputs.
+    pub fn begin_submit(&mut self) -> bool {
+        if self.submitting {
+            return false;
+        }
+        self.submitting = true;
+        self.submit_count += 1;
+        self.errors.clear();
+        self.touched.extend(self.values.keys().cloned());
+        true
+    }
+
+    /// Finish a submit attempt. On success the submitted values become the new
+    /// baseline, so the form is no longer dirty; on failure the errors are kept.
+    pub fn finish_submit(&mut self, result: Result<(), FieldErrors>) {
+        self.submitting = false;
+        match result {
+            Ok(()) => self.initial = self.values.clone(),
+            Err(errors) => self.errors = errors,
+        }
+    }
+}
+
+type Validator<T> = Rc<dyn Fn(FormValues) -> Pin<Box<dyn Future<Output = Result<T, FieldErrors>>>>>;
+
+/// Handle returned by [`use_form`]. Cheap to copy into event handlers.
+///
+/// `T` is what the validator turns the raw string values into; it is what
+/// [`UseForm::submit`] hands to the save callback.
+pub struct UseForm<T: 'static> {
+    model: Signal<FormModel>,
+    validator: CopyValue<Validator<T>>,
+}
+
+impl<T: 'static> Clone for UseForm<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: 'static> Copy for UseForm<T> {}
+
+impl<T: 'static> PartialEq for UseForm<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.model == other.model && self.validator == other.validator
+    }
+}
+
+/// Bindings for a single text field, returned by [`UseForm::register`].
+#[derive(Clone, PartialEq)]
+pub struct FieldBinding {
+    pub value: String,
+    pub error: Option<String>,
+    pub on_input: EventHandler<FormEvent>,
+    pub on_blur: EventHandler<FocusEvent>,
+}
+
+impl<T: 'static> UseForm<T> {
+    /// Snapshot of the whole form state.
+    pub fn model(&self) -> FormModel {
+        self.model.read().clone()
+    }
+
+    pub fn value(&self, field: &str) -> String {
+        self.model.read().value(field).to_string()
+    }
+
+    pub fn set(&mut self, field: &str, value: impl Into<String>) {
+        self.model.write().set_value(field, value);
+    }
+
+    pub fn touch(&mut self, field: &str) {
+        self.model.write().touch(field);
+    }
+
+    /// Error for `field`, shown only once the field is touched.
+    pub fn error(&self, field: &str) -> Option<String> {
+        let model = self.model.read();
+        model
+            .error(field)
+            .filter(|_| model.is_touched(field))
+            .map(str::to_string)
+    }
+
+    pub fn set_errors(&mut self, errors: FieldErrors) {
+        self.model.write().set_errors(errors);
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.model.read().is_dirty()
+    }
+
+    pub fn is_submitting(&self) -> bool {
+        self.model.read().is_submitting()
+    }
+
+    pub fn reset<K: Into<String>, V: Into<String>>(
+        &mut self,
+        fields: impl IntoIterator<Item = (K, V)>,
+    ) {
+        self.model.write().reset(fields);
+    }
+
+    /// Value, error, and input/blur handlers for a text input bound to `field`.
+    pub fn register(&self, field: &'static str) -> FieldBinding {
+        let mut form = *self;
+        FieldBinding {
+            value: self.value(field),
+            error: self.error(field),
+            on_input: EventHandler::new(move |evt: FormEvent| form.set(field, evt.value())),
+            on_blur: EventHandler::new(move |_: FocusEvent| form.touch(field)),
+        }
+    }
+
+    /// Validate the current values and, if they pass, hand the result to
+    /// `on_valid`. Errors from either step are stored per field.
+    ///
+    /// Ignored while a previous submit is still running.
+    pub fn submit<F, Fut>(&self, on_valid: F)
+    where
+        F: FnOnce(T) -> Fut + 'static,
+        Fut: Future<Output = Result<(), FieldErrors>> + 'static,
+    {
+        let mut model = self.model;
+        if !model.write().begin_submit() {
+            return;
+        }
+        let values = model.read().values().clone();
+        let validator = self.validator.read().clone();
+        spawn(async move {
+            let result = match validator(values).await {
+                Ok(parsed) => on_valid(parsed).await,
+                Err(errors) => Err(errors),
+            };
+            model.write().finish_submit(result);
+        });
+    }
+}
+
+/// Manage a form's values, touched/dirty state, per-field errors, and submit
+/// progress in one place.
+///
+/// `initial` lists the fields and their starting values. `validate` turns the
+/// raw values into `T` or returns field errors; it runs on every submit and
+/// may be async (e.g. to check uniqueness on the server).
+pub fn use_form<T, K, V, F, Fut>(initial: impl FnOnce() -> Vec<(K, V)>, validate: F) -> UseForm<T>
+where
+    T: 'static,
+    K: Into<String>,
+    V: Into<String>,
+    F: Fn(FormValues) -> Fut + 'static,
+    Fut: Future<Output = Result<T, FieldErrors>> + 'static,
+{
+    let model = use_signal(|| FormModel::new(initial()));
+    let validator = use_hook(|| {
+        let validator: Validator<T> = Rc::new(move |values| Box::pin(validate(values)));
+        CopyValue::new(validator)
+    });
+    UseForm { model, validator }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn form() -> FormModel {
+        FormModel::new([("name", "Widget"), ("price", "9.99")])
+    }
+
+    #[test]
+    fn new_form_is_clean() {
+        let form = form();
+        assert!(!form.is_dirty());
+        assert_eq!(form.value("name"), "Widget");
+        assert_eq!(form.value("missing"), "");
+    }
+
+    #[test]
+    fn editing_marks_field_dirty_until_reverted() {
+        let mut form = form();
+        form.set_value("name", "Gadget");
+        assert!(form.is_dirty());
+        assert!(form.is_field_dirty("name"));
+        assert!(!form.is_field_dirty("price"));
+
+        form.set_value("name", "Widget");
+        assert!(!form.is_dirty());
+    }
+
+    #[test]
+    fn editing_clears_that_fields_error() {
+        let mut form = form();
+        form.set_errors(FieldErrors::from([
+            ("name".to_string(), "Required".to_string()),
+            ("price".to_string(), "Invalid".to_string()),
+        ]));
+        form.set_value("name", "Gadget");
+        assert_eq!(form.error("name"), None);
+        assert_eq!(form.error("price"), Some("Invalid"));
+    }
+
+    #[test]
+    fn begin_submit_touches_all_fields_and_blocks_resubmit() {
+        let mut form = form();
+        assert!(form.begin_submit());
+        assert!(form.is_submitting());
+        assert!(form.is_touched("name") && form.is_touched("price"));
+        assert!(!form.begin_submit());
+        assert_eq!(form.submit_count(), 1);
+    }
+
+    #[test]
+    fn failed_submit_keeps_errors_and_dirty_state() {
+        let mut form = form();
+        form.set_value("price", "abc");
+        form.begin_submit();
+        form.finish_submit(Err(FieldErrors::from([(
+            "price".to_string(),
+            "Price must be a number".to_string(),
+        )])));
+        assert!(!form.is_submitting());
+        assert!(form.is_dirty());
+        assert_eq!(form.error("price"), Some("Price must be a number"));
+    }
+
+    #[test]
+    fn successful_submit_resets_dirty_baseline() {
+        let mut form = form();
+        form.set_value("price", "12.50");
+        form.begin_submit();
+        form.finish_submit(Ok(()));
+        assert!(!form.is_submitting());
+        assert!(!form.is_dirty());
+        assert!(form.errors().is_empty());
+    }
+
+    #[test]
+    fn reset_replaces_values_and_state() {
+        let mut form = form();
+        form.set_value("name", "Gadget");
+        form.begin_submit();
+        form.reset([("name", "Other")]);
+        assert_eq!(form, FormModel::new([("name", "Other")]));
+    }
+}
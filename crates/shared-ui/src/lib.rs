@@ -1,4 +1,8 @@
 pub mod components;
+pub mod draft;
+pub mod form_state;
 pub mod theme;
 
 pub use components::*;
+pub use draft::*;
+pub use form_state::*;
@@ -8,14 +8,25 @@ pub enum ThemeFamily {
     #[default]
     Cyberpunk,
     Solar,
+    Matrix,
 }
 
 impl ThemeFamily {
+    /// Every family, in the order they are offered in the UI.
+    pub fn all() -> &'static [ThemeFamily] {
+        &[
+            ThemeFamily::Cyberpunk,
+            ThemeFamily::Solar,
+            ThemeFamily::Matrix,
+        ]
+    }
+
     /// Internal key used for storage and Select values.
     pub fn as_str(&self) -> &'static str {
         match self {
             ThemeFamily::Cyberpunk => "cyberpunk",
             ThemeFamily::Solar => "solar",
+            ThemeFamily::Matrix => "matrix",
         }
     }
 
@@ -24,6 +35,7 @@ impl ThemeFamily {
         match self {
             ThemeFamily::Cyberpunk => "Cyberpunk",
             ThemeFamily::Solar => "Solarized",
+            ThemeFamily::Matrix => "Matrix",
         }
     }
 
@@ -31,6 +43,7 @@ impl ThemeFamily {
     pub fn from_key(s: &str) -> Self {
         match s {
             "solar" => ThemeFamily::Solar,
+            "matrix" => ThemeFamily::Matrix,
             _ => ThemeFamily::Cyberpunk,
         }
     }
@@ -42,6 +55,23 @@ impl ThemeFamily {
             (ThemeFamily::Cyberpunk, false) => "light",
             (ThemeFamily::Solar, true) => "solar",
             (ThemeFamily::Solar, false) => "solar-light",
+            (ThemeFamily::Matrix, true) => "matrix",
+            (ThemeFamily::Matrix, false) => "matrix-light",
+        }
+    }
+
+    /// Split a `data-theme` value back into its family and dark flag.
+    ///
+    /// The inverse of [`ThemeFamily::resolve`]; unknown values fall back to
+    /// dark Cyberpunk.
+    pub fn from_theme(theme: &str) -> (Self, bool) {
+        match theme {
+            "light" => (ThemeFamily::Cyberpunk, false),
+            "solar" => (ThemeFamily::Solar, true),
+            "solar-light" => (ThemeFamily::Solar, false),
+            "matrix" => (ThemeFamily::Matrix, true),
+            "matrix-light" => (ThemeFamily::Matrix, false),
+            _ => (ThemeFamily::Cyberpunk, true),
         }
     }
 }
@@ -57,6 +87,20 @@ pub struct ThemeState {
 }
 
 impl ThemeState {
+    pub fn new() -> Self {
+        Self {
+            family: Signal::new(ThemeFamily::default().as_str().to_string()),
+            is_dark: Signal::new(true),
+        }
+    }
+
+    /// Switch to `family` in the given mode and apply it.
+    pub fn set(&mut self, family: ThemeFamily, is_dark: bool) {
+        self.family.set(family.as_str().to_string());
+        self.is_dark.set(is_dark);
+        self.apply();
+    }
+
     /// Apply the current family + mode to the document.
     pub fn apply(&self) {
         let family = ThemeFamily::from_key(&self.family.read());
@@ -65,26 +109,71 @@ impl ThemeState {
     }
 }
 
+impl Default for ThemeState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Read the `data-theme` currently on `<html>`, i.e. what [`ThemeSeed`]
+/// applied from the cookie or the last [`set_theme`] call.
+pub async fn applied_theme() -> Option<String> {
+    document::eval("return document.documentElement.getAttribute('data-theme');")
+        .join::<Option<String>>()
+        .await
+        .ok()
+        .flatten()
+}
+
+/// Default name of the cookie that persists the theme.
+pub const DEFAULT_THEME_COOKIE: &str = "theme";
+
+/// Inline script that applies the persisted theme cookie to `<html>`.
+///
+/// It also records the cookie name on `<html>` as `data-theme-cookie`, where
+/// [`set_theme`] picks it up. `cookie` must be a plain cookie name (letters,
+/// digits, `-` and `_`).
+fn theme_seed_script(cookie: &str) -> String {
+    format!(
+        r#"
+(function() {{
+    document.documentElement.setAttribute('data-theme-cookie', '{cookie}');
+    var match = document.cookie.match(/(?:^|;\s*){cookie}=([^;]*)/);
+    var theme = match ? match[1] : 'cyberpunk';
+    document.documentElement.setAttribute('data-theme', theme);
+}})();
+"#
+    )
+}
+
 /// Seed the theme on application startup.
 ///
 /// Reads the persisted theme from a cookie and applies it to the document root.
 /// Call this once in your top-level App component.
+///
+/// When a CSP `nonce` is supplied the seed is rendered as an inline
+/// `<script nonce=...>` during SSR, so a strict `script-src 'nonce-...'`
+/// policy allows it. Without one (desktop/mobile) it falls back to `eval`.
+///
+/// `cookie` names the theme cookie (default `theme`), for deployments that
+/// share a domain with other apps.
 #[component]
-pub fn ThemeSeed() -> Element {
-    use_effect(|| {
-        // Read theme cookie and apply data-theme attribute to <html>
-        document::eval(
-            r#"
-            (function() {
-                var match = document.cookie.match(/(?:^|;\s*)theme=([^;]*)/);
-                var theme = match ? match[1] : 'cyberpunk';
-                document.documentElement.setAttribute('data-theme', theme);
-            })();
-            "#,
-        );
+pub fn ThemeSeed(nonce: Option<String>, cookie: Option<String>) -> Element {
+    let script = theme_seed_script(cookie.as_deref().unwrap_or(DEFAULT_THEME_COOKIE));
+    let has_nonce = nonce.is_some();
+    let eval_script = script.clone();
+    use_effect(move || {
+        if !has_nonce {
+            document::eval(&eval_script);
+        }
     });
 
-    rsx! {}
+    match nonce {
+        Some(nonce) => rsx! {
+            script { nonce: "{nonce}", dangerous_inner_html: script }
+        },
+        None => rsx! {},
+    }
 }
 
 /// Set the active theme, persisting to a cookie and updating the document.
@@ -94,7 +183,8 @@ pub fn set_theme(theme: &str) {
     document::eval(&format!(
         r#"
         (function() {{
-            document.cookie = 'theme={theme};path=/;max-age=2592000;SameSite=Lax';
+            var name = document.documentElement.getAttribute('data-theme-cookie') || '{DEFAULT_THEME_COOKIE}';
+            document.cookie = name + '={theme};path=/;max-age=2592000;SameSite=Lax';
             document.documentElement.setAttribute('data-theme', '{theme}');
             try {{
                 var bc = new BroadcastChannel('theme-sync');
@@ -110,6 +200,13 @@ pub fn set_theme(theme: &str) {
 mod tests {
     use super::*;
 
+    #[test]
+    fn seed_script_reads_the_named_cookie() {
+        let script = theme_seed_script("shop_theme");
+        assert!(script.contains(r"(?:^|;\s*)shop_theme=([^;]*)"));
+        assert!(script.contains("'data-theme-cookie', 'shop_theme'"));
+    }
+
     #[test]
     fn theme_family_default_is_cyberpunk() {
         assert_eq!(ThemeFamily::default(), ThemeFamily::Cyberpunk);
@@ -119,12 +216,14 @@ mod tests {
     fn theme_family_as_str_values() {
         assert_eq!(ThemeFamily::Cyberpunk.as_str(), "cyberpunk");
         assert_eq!(ThemeFamily::Solar.as_str(), "solar");
+        assert_eq!(ThemeFamily::Matrix.as_str(), "matrix");
     }
 
     #[test]
     fn theme_family_from_key() {
         assert_eq!(ThemeFamily::from_key("cyberpunk"), ThemeFamily::Cyberpunk);
         assert_eq!(ThemeFamily::from_key("solar"), ThemeFamily::Solar);
+        assert_eq!(ThemeFamily::from_key("matrix"), ThemeFamily::Matrix);
         assert_eq!(ThemeFamily::from_key("unknown"), ThemeFamily::Cyberpunk);
     }
 
@@ -134,5 +233,65 @@ mod tests {
         assert_eq!(ThemeFamily::Cyberpunk.resolve(false), "light");
         assert_eq!(ThemeFamily::Solar.resolve(true), "solar");
         assert_eq!(ThemeFamily::Solar.resolve(false), "solar-light");
+        assert_eq!(ThemeFamily::Matrix.resolve(true), "matrix");
+        assert_eq!(ThemeFamily::Matrix.resolve(false), "matrix-light");
+    }
+
+    #[test]
+    fn theme_family_all_covers_every_variant() {
+        // Exhaustive on purpose: a new variant won't compile here until it
+        // is listed, and the assert then fails if `all()` misses it.
+        let every = |family: ThemeFamily| match family {
+            ThemeFamily::Cyberpunk | ThemeFamily::Solar | ThemeFamily::Matrix => family,
+        };
+        for family in [
+            ThemeFamily::Cyberpunk,
+            ThemeFamily::Solar,
+            ThemeFamily::Matrix,
+        ] {
+            assert!(ThemeFamily::all().contains(&every(family)));
+        }
+        assert_eq!(ThemeFamily::all().len(), 3);
+    }
+
+    #[test]
+    fn theme_family_all_round_trips_through_keys() {
+        for family in ThemeFamily::all() {
+            assert_eq!(ThemeFamily::from_key(family.as_str()), *family);
+            assert!(!family.display_name().is_empty());
+        }
+    }
+
+    #[test]
+    fn theme_family_from_theme_inverts_resolve() {
+        for &family in ThemeFamily::all() {
+            for is_dark in [true, false] {
+                assert_eq!(
+                    ThemeFamily::from_theme(family.resolve(is_dark)),
+                    (family, is_dark)
+                );
+            }
+        }
+        assert_eq!(
+            ThemeFamily::from_theme("unknown"),
+            (ThemeFamily::Cyberpunk, true)
+        );
+    }
+
+    #[test]
+    fn theme_seed_script_carries_nonce() {
+        let html = dioxus_ssr::render_element(rsx! {
+            ThemeSeed { nonce: "abc123".to_string() }
+        });
+        assert!(html.contains(r#"nonce="abc123""#));
+        assert!(html.contains("data-theme"));
+    }
+
+    #[test]
+    fn theme_seed_without_nonce_renders_nothing() {
+        let html = dioxus_ssr::render_element(rsx! {
+            ThemeSeed {}
+        });
+        assert!(!html.contains("<script"));
     }
 }
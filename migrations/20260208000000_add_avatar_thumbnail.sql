@@ -0,0 +1,2 @@
+-- Object key of the 128x128 avatar thumbnail generated on upload
+ALTER TABLE users ADD COLUMN IF NOT EXISTS thumbnail_url TEXT;
@@ -0,0 +1,4 @@
+-- Theme family and dark/light mode, so the chosen theme follows the user across devices
+ALTER TABLE user_preferences
+    ADD COLUMN IF NOT EXISTS theme_family TEXT NOT NULL DEFAULT 'cyberpunk',
+    ADD COLUMN IF NOT EXISTS theme_dark BOOLEAN NOT NULL DEFAULT TRUE;
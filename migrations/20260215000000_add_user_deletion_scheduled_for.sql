@@ -0,0 +1,7 @@
+-- Accounts pending deletion: sign-in is blocked until this time, after which
+-- the account is purged. NULL means the account is active.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS deletion_scheduled_for TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_users_deletion_scheduled_for
+    ON users(deletion_scheduled_for)
+    WHERE deletion_scheduled_for IS NOT NULL;
@@ -0,0 +1,9 @@
+-- Usernames are unique. Earlier rows could share one, so later duplicates
+-- get their id appended before the index is built.
+UPDATE users u
+SET username = u.username || '_' || u.id
+WHERE EXISTS (
+    SELECT 1 FROM users o WHERE o.username = u.username AND o.id < u.id
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS users_username_key ON users(username);
@@ -0,0 +1,4 @@
+-- Products record who created them, so analytics can be scoped to a user's
+-- own catalog. Existing products have no owner.
+ALTER TABLE products ADD COLUMN IF NOT EXISTS owner_id BIGINT REFERENCES users(id) ON DELETE SET NULL;
+CREATE INDEX IF NOT EXISTS idx_products_owner_id ON products(owner_id);
@@ -0,0 +1,3 @@
+-- SHA-256 of the uploaded avatar, so re-uploading the same file reuses the
+-- stored objects instead of writing new ones.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS avatar_hash TEXT;
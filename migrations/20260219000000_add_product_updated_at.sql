@@ -0,0 +1,3 @@
+-- Last modification time of a product, used to derive ETags for
+-- conditional GETs.
+ALTER TABLE products ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
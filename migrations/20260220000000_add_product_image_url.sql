@@ -0,0 +1,3 @@
+-- S3 object key of an uploaded product image, served through
+-- GET /api/v1/products/{id}/image.
+ALTER TABLE products ADD COLUMN IF NOT EXISTS image_url TEXT;
@@ -0,0 +1,5 @@
+-- Soft delete for users: deleted accounts are hidden and can't sign in, but
+-- can be restored until the purger removes them
+ALTER TABLE users ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_users_deleted_at ON users(deleted_at) WHERE deleted_at IS NOT NULL;